@@ -0,0 +1,88 @@
+// Parsing rustdoc JSON at each level of detail, on a synthetic crate shaped
+// like the big generated ones (`windows`, `windows-sys`): many functions,
+// each with a span, links and a full signature. Point `ZDOC_BENCH_JSON` at a
+// real file (e.g. `target/doc/windows.json`) to measure that instead.
+//
+//     cargo bench --bench parse
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use serde_json::{Value, json};
+use std::hint::black_box;
+
+#[allow(dead_code)]
+#[path = "../src/rustdoc_json.rs"]
+mod rustdoc_json;
+
+use rustdoc_json::Detail;
+
+const ITEMS: usize = 20_000;
+
+fn synthetic_crate() -> Vec<u8> {
+    let mut index = serde_json::Map::new();
+    for i in 0..ITEMS {
+        let param = |name: &str| json!([name, { "resolved_path": { "path": "HANDLE", "id": 1, "args": null } }]);
+        let item = json!({
+            "id": i,
+            "crate_id": 0,
+            "name": format!("CreateThing{}", i),
+            "span": {
+                "filename": "src/Windows/Win32/Foundation/mod.rs",
+                "begin": [i, 0],
+                "end": [i + 3, 1],
+            },
+            "visibility": "public",
+            "docs": format!("Creates thing number {}. See [`CloseThing{}`].", i, i),
+            "links": { format!("`CloseThing{}`", i): i + 1 },
+            "attrs": [],
+            "deprecation": null,
+            "inner": { "function": {
+                "sig": {
+                    "inputs": [param("handle"), param("flags"), param("reserved")],
+                    "output": { "primitive": "u32" },
+                    "is_c_variadic": false,
+                },
+                "generics": { "params": [], "where_predicates": [] },
+                "header": { "is_const": false, "is_unsafe": true, "is_async": false, "abi": "Rust" },
+                "has_body": true,
+            }},
+        });
+        index.insert(i.to_string(), item);
+    }
+    let document = json!({
+        "root": 0,
+        "crate_version": "0.1.0",
+        "includes_private": false,
+        "index": index,
+        "paths": {},
+        "external_crates": {},
+        "format_version": 45,
+    });
+    serde_json::to_vec(&document).expect("the fixture serializes")
+}
+
+fn parse(c: &mut Criterion) {
+    let bytes = match std::env::var_os("ZDOC_BENCH_JSON") {
+        Some(path) => std::fs::read(path).expect("ZDOC_BENCH_JSON is readable"),
+        None => synthetic_crate(),
+    };
+    let mut group = c.benchmark_group("parse");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(bytes.len() as u64));
+    for (name, detail) in [
+        ("full", Detail::Full),
+        ("api", Detail::Api),
+        ("search", Detail::Search),
+    ] {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                let (json, _): (Value, u64) =
+                    rustdoc_json::read_hashed_as(black_box(&bytes[..]), detail)
+                        .expect("the fixture parses");
+                json
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
@@ -0,0 +1,123 @@
+// `diff --resolve-aliases`: type aliases written out as the types they stand
+// for, so changing `type Handle = u32` to `u64` changes every signature that
+// mentions `Handle`, not just the alias. Only aliases defined in the crate
+// are known; ones from dependencies (`io::Result`) keep their names.
+//
+// `format_type` has no access to the crate being read, so the aliases of the
+// crate whose API is being extracted are kept here for the duration.
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::items;
+
+// An alias's type parameters, in order, and the type it names
+struct Alias {
+    params: Vec<(String, Option<Value>)>,
+    target: Value,
+}
+
+thread_local! {
+    static ALIASES: RefCell<Option<HashMap<String, Alias>>> = const { RefCell::new(None) };
+}
+
+// Run `extract` with the type aliases of `json` resolved in signatures
+pub fn resolving<T>(json: &Value, extract: impl FnOnce() -> T) -> T {
+    ALIASES.with(|aliases| *aliases.borrow_mut() = Some(collect(json)));
+    let result = extract();
+    ALIASES.with(|aliases| *aliases.borrow_mut() = None);
+    result
+}
+
+fn collect(json: &Value) -> HashMap<String, Alias> {
+    let mut aliases = HashMap::new();
+    let Some(index) = json.get("index").and_then(|v| v.as_object()) else {
+        return aliases;
+    };
+    for (id, item) in index {
+        if items::item_kind(item) != Some("type_alias") {
+            continue;
+        }
+        let Some(inner) = items::item_inner(item) else {
+            continue;
+        };
+        let Some(target) = inner.get("type") else {
+            continue;
+        };
+        let params = inner
+            .get("generics")
+            .and_then(|generics| generics.get("params"))
+            .and_then(|v| v.as_array())
+            .map(|params| {
+                params
+                    .iter()
+                    .filter_map(|param| {
+                        let bounds = param.get("kind")?.get("type")?;
+                        let name = param.get("name")?.as_str()?;
+                        let default = bounds.get("default").filter(|v| !v.is_null()).cloned();
+                        Some((name.to_string(), default))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        aliases.insert(
+            id.clone(),
+            Alias {
+                params,
+                target: target.clone(),
+            },
+        );
+    }
+    aliases
+}
+
+// The type a `resolved_path` to an alias stands for, with the alias's type
+// arguments put in place of its parameters. None when it isn't an alias or
+// aliases aren't being resolved.
+pub fn resolve(resolved_path: &Value) -> Option<Value> {
+    let id = resolved_path.get("id").and_then(items::id_key)?;
+    ALIASES.with(|aliases| {
+        let aliases = aliases.borrow();
+        let alias = aliases.as_ref()?.get(&id)?;
+        let args: Vec<&Value> = resolved_path
+            .get("args")
+            .and_then(|args| args.get("angle_bracketed"))
+            .and_then(|args| args.get("args"))
+            .and_then(|v| v.as_array())
+            .map(|args| args.iter().filter_map(|arg| arg.get("type")).collect())
+            .unwrap_or_default();
+        let mut substitutions = HashMap::new();
+        for (i, (name, default)) in alias.params.iter().enumerate() {
+            if let Some(arg) = args.get(i).copied().or(default.as_ref()) {
+                substitutions.insert(name.as_str(), arg);
+            }
+        }
+        Some(substitute(&alias.target, &substitutions))
+    })
+}
+
+// `ty` with each generic parameter named in `substitutions` replaced
+fn substitute(ty: &Value, substitutions: &HashMap<&str, &Value>) -> Value {
+    match ty {
+        Value::Object(fields) => {
+            if let Some(name) = fields.get("generic").and_then(|v| v.as_str())
+                && let Some(arg) = substitutions.get(name)
+            {
+                return (*arg).clone();
+            }
+            Value::Object(
+                fields
+                    .iter()
+                    .map(|(key, value)| (key.clone(), substitute(value, substitutions)))
+                    .collect(),
+            )
+        }
+        Value::Array(values) => Value::Array(
+            values
+                .iter()
+                .map(|value| substitute(value, substitutions))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
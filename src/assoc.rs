@@ -0,0 +1,299 @@
+// `zdoc assoc`: associated types and consts of a trait, or the concrete
+// assignments a type's impls make for them
+use anyhow::Result;
+use serde_json::Value;
+
+use crate::items::{self, ResolvedItem};
+
+// Render an associated type or const as a declaration. In a trait the value
+// is the default; in an impl it's the concrete assignment.
+fn render_assoc_item(item: &Value) -> Option<String> {
+    let name = items::item_name(item)?;
+    let inner = items::item_inner(item)?;
+    // Current formats call the value `type`/`value`; older ones `default`
+    let value = |key: &str| {
+        inner
+            .get(key)
+            .or_else(|| inner.get("default"))
+            .filter(|v| !v.is_null())
+    };
+
+    match items::item_kind(item)? {
+        "assoc_type" => {
+            let generics = inner.get("generics");
+            let mut decl = format!("type {}{}", name, crate::format_generic_params(generics));
+            if let Some(bounds) = inner
+                .get("bounds")
+                .and_then(|v| v.as_array())
+                .filter(|b| !b.is_empty())
+            {
+                decl.push_str(&format!(": {}", crate::format_bounds(bounds)));
+            }
+            // A GAT's where clause follows the assigned type, as rustc prefers
+            if let Some(ty) = value("type") {
+                decl.push_str(&format!(" = {}", crate::format_type(ty)));
+            }
+            decl.push_str(&crate::format_where_clause(generics));
+            Some(format!("{};", decl))
+        }
+        "assoc_const" => {
+            let ty = inner
+                .get("type")
+                .map(crate::format_type)
+                .unwrap_or_else(|| "?".to_string());
+            let mut decl = format!("const {}: {}", name, ty);
+            if let Some(default) = value("value").and_then(|v| v.as_str()) {
+                decl.push_str(&format!(" = {}", default));
+            }
+            Some(format!("{};", decl))
+        }
+        _ => None,
+    }
+}
+
+fn assoc_members(json: &Value, ids: &[String]) -> Vec<String> {
+    ids.iter()
+        .filter_map(|id| items::lookup(json, id))
+        .filter_map(render_assoc_item)
+        .collect()
+}
+
+fn display_trait_assoc(json: &Value, target: &ResolvedItem) {
+    let ids = items::item_inner(target.item)
+        .map(items::own_member_ids)
+        .unwrap_or_default();
+    let members = assoc_members(json, &ids);
+
+    println!("\nAssociated items of trait {}:\n", target.full_path());
+    if members.is_empty() {
+        println!("  (No associated types or consts)");
+    }
+    for member in members {
+        println!("  {}", member);
+    }
+}
+
+fn display_type_assoc(json: &Value, target: &ResolvedItem) {
+    println!("\nAssociated items of {}:", target.full_path());
+
+    let mut sections = Vec::new();
+    for impl_id in items::impl_ids(target.item) {
+        let Some(impl_item) = items::lookup(json, &impl_id) else {
+            continue;
+        };
+        let Some(inner) = items::item_inner(impl_item) else {
+            continue;
+        };
+        // Blanket and auto-trait impls only restate generic definitions
+        let shared = inner.get("blanket_impl").is_some_and(|v| !v.is_null())
+            || inner.get("is_synthetic").and_then(|v| v.as_bool()) == Some(true);
+        if shared {
+            continue;
+        }
+
+        let members = assoc_members(json, &items::impl_member_ids(impl_item));
+        if members.is_empty() {
+            continue;
+        }
+
+        let generics = inner.get("generics");
+        let self_type = inner
+            .get("for")
+            .map(crate::format_type)
+            .unwrap_or_else(|| "?".to_string());
+        let header = match inner.get("trait").filter(|v| !v.is_null()) {
+            Some(trait_path) => format!(
+                "impl{} {} for {}{}",
+                crate::format_generic_params(generics),
+                crate::format_type(&serde_json::json!({ "resolved_path": trait_path })),
+                self_type,
+                crate::format_where_clause(generics)
+            ),
+            None => format!(
+                "impl{} {}{}",
+                crate::format_generic_params(generics),
+                self_type,
+                crate::format_where_clause(generics)
+            ),
+        };
+        sections.push((header, members));
+    }
+
+    if sections.is_empty() {
+        println!("\n  (No associated types or consts)");
+        return;
+    }
+
+    sections.sort();
+    for (header, members) in sections {
+        println!("\n{}", header);
+        for member in members {
+            println!("  {}", member);
+        }
+    }
+}
+
+// Handler for `zdoc assoc`
+pub fn run(metadata: &cargo_metadata::Metadata, item_path: &str) -> Result<()> {
+    let located = items::locate(metadata, item_path)?;
+    let target = located.resolved();
+
+    match items::item_kind(target.item) {
+        Some("trait") => display_trait_assoc(&located.json, &target),
+        Some("struct" | "enum" | "union" | "primitive") => {
+            display_type_assoc(&located.json, &target)
+        }
+        Some(kind) => anyhow::bail!(
+            "'{}' is a {}; `assoc` works on traits and types",
+            target.full_path(),
+            kind
+        ),
+        None => anyhow::bail!("'{}' has no kind information", target.full_path()),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // Associated items as rustdoc (format 57) writes them for
+    //
+    //     pub trait Container {
+    //         type Item: Clone;
+    //         type Iter<'a>: Iterator<Item = &'a Self::Item> where Self: 'a;
+    //         type Keyed<K: Ord>;
+    //         const LIMIT: usize = 8;
+    //     }
+    //     impl Container for Bag {
+    //         type Item = u8;
+    //         type Iter<'a> = std::slice::Iter<'a, u8> where Self: 'a;
+    //         type Keyed<K: Ord> = BTreeSet<K>;
+    //     }
+    fn assoc(name: &str, inner: Value) -> Value {
+        json!({ "name": name, "inner": inner })
+    }
+
+    fn lifetime_param() -> Value {
+        json!({ "params": [{ "name": "'a", "kind": { "lifetime": { "outlives": [] } } }],
+                "where_predicates": [{ "bound_predicate": {
+                    "type": { "generic": "Self" }, "bounds": [{ "outlives": "'a" }], "generic_params": [] } }] })
+    }
+
+    fn ord_param() -> Value {
+        json!({ "params": [{ "name": "K", "kind": { "type": {
+                    "bounds": [{ "trait_bound": { "trait": { "path": "Ord", "id": 6, "args": null },
+                                                  "generic_params": [], "modifier": "none" } }],
+                    "default": null, "is_synthetic": false } } }],
+                "where_predicates": [] })
+    }
+
+    fn no_generics() -> Value {
+        json!({ "params": [], "where_predicates": [] })
+    }
+
+    #[test]
+    fn plain_associated_types() {
+        let declared = assoc(
+            "Item",
+            json!({ "assoc_type": { "generics": no_generics(), "type": null, "bounds": [
+                { "trait_bound": { "trait": { "path": "Clone", "id": 1, "args": null },
+                                   "generic_params": [], "modifier": "none" } }] } }),
+        );
+        let assigned = assoc(
+            "Item",
+            json!({ "assoc_type": { "generics": no_generics(), "bounds": [], "type": { "primitive": "u8" } } }),
+        );
+        assert_eq!(
+            render_assoc_item(&declared).as_deref(),
+            Some("type Item: Clone;")
+        );
+        assert_eq!(
+            render_assoc_item(&assigned).as_deref(),
+            Some("type Item = u8;")
+        );
+    }
+
+    #[test]
+    fn generic_associated_types() {
+        let declared = assoc(
+            "Iter",
+            json!({ "assoc_type": { "generics": lifetime_param(), "type": null, "bounds": [
+                { "trait_bound": { "generic_params": [], "modifier": "none", "trait": {
+                    "path": "Iterator", "id": 3, "args": { "angle_bracketed": { "args": [], "constraints": [{
+                        "name": "Item", "args": null, "binding": { "equality": { "type": { "borrowed_ref": {
+                            "lifetime": "'a", "is_mutable": false, "type": { "qualified_path": {
+                                "name": "Item", "args": null, "self_type": { "generic": "Self" },
+                                "trait": { "path": "", "id": 4, "args": null } } } } } } } }] } } } } }] } }),
+        );
+        let assigned = assoc(
+            "Iter",
+            json!({ "assoc_type": { "generics": lifetime_param(), "bounds": [], "type": { "resolved_path": {
+                "path": "Iter", "id": 11, "args": { "angle_bracketed": {
+                    "args": [{ "lifetime": "'a" }, { "type": { "primitive": "u8" } }], "constraints": [] } } } } } }),
+        );
+        assert_eq!(
+            render_assoc_item(&declared).as_deref(),
+            Some("type Iter<'a>: Iterator<Item = &'a Self::Item> where Self: 'a;")
+        );
+        assert_eq!(
+            render_assoc_item(&assigned).as_deref(),
+            Some("type Iter<'a> = Iter<'a, u8> where Self: 'a;")
+        );
+    }
+
+    #[test]
+    fn generic_associated_types_with_type_params() {
+        let declared = assoc(
+            "Keyed",
+            json!({ "assoc_type": { "generics": ord_param(), "bounds": [], "type": null } }),
+        );
+        let assigned = assoc(
+            "Keyed",
+            json!({ "assoc_type": { "generics": ord_param(), "bounds": [], "type": { "resolved_path": {
+                "path": "BTreeSet", "id": 13, "args": { "angle_bracketed": {
+                    "args": [{ "type": { "generic": "K" } }], "constraints": [] } } } } } }),
+        );
+        assert_eq!(
+            render_assoc_item(&declared).as_deref(),
+            Some("type Keyed<K: Ord>;")
+        );
+        assert_eq!(
+            render_assoc_item(&assigned).as_deref(),
+            Some("type Keyed<K: Ord> = BTreeSet<K>;")
+        );
+    }
+
+    #[test]
+    fn associated_consts() {
+        let current = assoc(
+            "LIMIT",
+            json!({ "assoc_const": { "type": { "primitive": "usize" }, "value": "8" } }),
+        );
+        let older = assoc(
+            "LIMIT",
+            json!({ "assoc_const": { "type": { "primitive": "usize" }, "default": "8" } }),
+        );
+        let required = assoc(
+            "LIMIT",
+            json!({ "assoc_const": { "type": { "primitive": "usize" }, "value": null } }),
+        );
+        assert_eq!(
+            render_assoc_item(&current).as_deref(),
+            Some("const LIMIT: usize = 8;")
+        );
+        assert_eq!(render_assoc_item(&older), render_assoc_item(&current));
+        assert_eq!(
+            render_assoc_item(&required).as_deref(),
+            Some("const LIMIT: usize;")
+        );
+    }
+
+    #[test]
+    fn other_items_are_skipped() {
+        let method = assoc("iter", json!({ "function": {} }));
+        assert_eq!(render_assoc_item(&method), None);
+    }
+}
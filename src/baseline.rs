@@ -0,0 +1,95 @@
+// `zdoc baseline` and `zdoc verify`: save a workspace crate's public API to a
+// file, then check later builds against it, failing on breaking changes
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::{ApiItem, DiffFormat, DiffReport, JsonStyle};
+
+// The saved API of one crate
+#[derive(Serialize, Deserialize)]
+struct Baseline {
+    #[serde(rename = "crate")]
+    crate_name: String,
+    version: Option<String>,
+    items: Vec<ApiItem>,
+}
+
+// The crate's current API from freshly generated local docs. Blanket impl
+// members are left out: they follow from the traits, not the crate.
+fn current_api(metadata: &cargo_metadata::Metadata, crate_name: &str) -> Result<Baseline> {
+    crate::generate_json_docs(metadata)?;
+    let local = crate::DocSource::Local { metadata };
+    let json = crate::doc_source::read_crate_json(crate_name, local)?.json;
+    let mut items = crate::extract_api_items(&json)?;
+    items.retain(|item| !item.is_pathless_member());
+    items.sort_by_key(|item| (item.full_path(), item.item_type.clone()));
+    Ok(Baseline {
+        crate_name: crate_name.to_string(),
+        version: json
+            .get("crate_version")
+            .and_then(|v| v.as_str())
+            .map(String::from),
+        items,
+    })
+}
+
+// Handler for `zdoc baseline`: print the API as JSON, sorted so that
+// committed baselines diff cleanly
+pub fn run_baseline(metadata: &cargo_metadata::Metadata, crate_name: &str) -> Result<()> {
+    let baseline = current_api(metadata, crate_name)?;
+    let text =
+        serde_json::to_string_pretty(&baseline).context("Failed to serialize the baseline")?;
+    println!("{}", text);
+    Ok(())
+}
+
+// Handler for `zdoc verify`
+pub fn run_verify(
+    metadata: &cargo_metadata::Metadata,
+    crate_name: Option<&str>,
+    baseline_path: &Path,
+    format: DiffFormat,
+    json_style: JsonStyle,
+) -> Result<()> {
+    let text = fs::read_to_string(baseline_path)
+        .with_context(|| format!("Failed to read {}", baseline_path.display()))?;
+    let baseline: Baseline = serde_json::from_str(&text)
+        .with_context(|| format!("{} is not a zdoc baseline", baseline_path.display()))?;
+
+    let crate_name = match crate_name {
+        Some(name) => name.to_string(),
+        None => baseline.crate_name.clone(),
+    };
+    let current = current_api(metadata, &crate_name)?;
+
+    let old_label = match &baseline.version {
+        Some(version) => format!("baseline ({})", version),
+        None => "baseline".to_string(),
+    };
+    // Ignored paths aren't covered by the baseline's promises, so changes
+    // there are only counted
+    let ignore = crate::ignore::Ignore::load(metadata.workspace_root.as_std_path())?;
+    // Baselines saved before blanket impl members were left out still list
+    // them; dropping them here keeps those from showing up as removed
+    let mut saved = baseline.items;
+    saved.retain(|item| !item.is_pathless_member());
+    let (old_items, old_ignored) = ignore.partition(saved, ApiItem::full_path);
+    let (new_items, new_ignored) = ignore.partition(current.items, ApiItem::full_path);
+    let changes = crate::compare_api_items(old_items, new_items);
+    let report = DiffReport::new(&crate_name, &old_label, "local", changes)
+        .with_ignored(old_ignored, new_ignored);
+    crate::emit_diff(format, json_style, &report)?;
+
+    let breaking = report.breaking_count();
+    if breaking > 0 {
+        anyhow::bail!(
+            "{} breaking change{} against {}",
+            breaking,
+            if breaking == 1 { "" } else { "s" },
+            baseline_path.display()
+        );
+    }
+    Ok(())
+}
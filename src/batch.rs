@@ -0,0 +1,232 @@
+// `zdoc diff --batch <file>`: diff many crates at once, e.g. every bump in a
+// lockfile update. Each line of the file is `crate old new`; blank lines and
+// `#` comments are skipped. Pairs are fetched and compared concurrently, a
+// few at a time so docs.rs isn't flooded, then reported in file order with
+// a verdict per crate and a summary.
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::registry;
+use crate::table::Table;
+use crate::{DiffFormat, DiffOptions, DiffReport, JsonStyle};
+
+// Pairs fetched at once. Local feature builds share a target directory, so
+// they go one at a time.
+const MAX_CONCURRENT: usize = 4;
+
+pub struct Pair {
+    pub crate_name: String,
+    pub old: String,
+    pub new: String,
+}
+
+fn parse(text: &str, path: &Path) -> Result<Vec<Pair>> {
+    let mut pairs = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [crate_name, old, new] = fields[..] else {
+            anyhow::bail!(
+                "{}:{}: expected `crate old new`, found '{}'",
+                path.display(),
+                number + 1,
+                line
+            );
+        };
+        pairs.push(Pair {
+            crate_name: crate_name.to_string(),
+            old: old.to_string(),
+            new: new.to_string(),
+        });
+    }
+    Ok(pairs)
+}
+
+// How one pair came out
+fn verdict(outcome: &Result<DiffReport, String>) -> &'static str {
+    match outcome {
+        Err(_) => "failed",
+        Ok(report) if report.breaking_count() > 0 => "breaking",
+        Ok(report) if report.change_count() > 0 => "minor",
+        Ok(_) => "unchanged",
+    }
+}
+
+async fn diff_pair(pair: &Pair, options: &DiffOptions) -> Result<DiffReport> {
+    let features = options.features.as_ref();
+    let old_version = registry::resolve_version(&pair.crate_name, &pair.old, options.pre).await?;
+    let new_version = registry::resolve_version(&pair.crate_name, &pair.new, options.pre).await?;
+    let old = crate::diff_source_docs(&pair.crate_name, &old_version, features).await?;
+    let new = crate::diff_source_docs(&pair.crate_name, &new_version, features).await?;
+    crate::warn_same_release(&old_version, &new_version, &old, &new);
+    crate::diff_report(
+        &pair.crate_name,
+        &old_version,
+        &new_version,
+        &old,
+        &new,
+        options,
+    )
+}
+
+pub async fn run(path: &Path, options: &DiffOptions, json_style: JsonStyle) -> Result<()> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let pairs = parse(&text, path)?;
+    if pairs.is_empty() {
+        anyhow::bail!("{} lists no `crate old new` pairs", path.display());
+    }
+    run_pairs(pairs, options, json_style, "Batch summary").await
+}
+
+// Diff `pairs` and report them, headed by `heading` in text output
+pub async fn run_pairs(
+    pairs: Vec<Pair>,
+    options: &DiffOptions,
+    json_style: JsonStyle,
+    heading: &str,
+) -> Result<()> {
+    if options.dry_run {
+        for pair in &pairs {
+            crate::diff_docs(&pair.crate_name, &pair.old, &pair.new, options, json_style).await?;
+        }
+        return Ok(());
+    }
+
+    let limit = if options.features.is_some() {
+        1
+    } else {
+        MAX_CONCURRENT
+    };
+    let permits = Arc::new(Semaphore::new(limit));
+    let options = Arc::new(options.clone());
+    let pairs = Arc::new(pairs);
+    let mut tasks = JoinSet::new();
+    for i in 0..pairs.len() {
+        let (permits, options, pairs) = (permits.clone(), options.clone(), pairs.clone());
+        tasks.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let outcome = diff_pair(&pairs[i], &options)
+                .await
+                .map_err(|e| format!("{:#}", e));
+            (i, outcome)
+        });
+    }
+    let mut outcomes: Vec<Option<Result<DiffReport, String>>> =
+        (0..pairs.len()).map(|_| None).collect();
+    while let Some(joined) = tasks.join_next().await {
+        let (i, outcome) = joined.context("A batch diff task panicked")?;
+        outcomes[i] = Some(outcome);
+    }
+    let outcomes: Vec<(&Pair, Result<DiffReport, String>)> = pairs
+        .iter()
+        .zip(outcomes)
+        .filter_map(|(pair, outcome)| Some((pair, outcome?)))
+        .collect();
+
+    let count = |wanted: &str| {
+        outcomes
+            .iter()
+            .filter(|(_, outcome)| verdict(outcome) == wanted)
+            .count()
+    };
+    let failed = count("failed");
+
+    if options.format == DiffFormat::Json {
+        let diffs: Vec<Value> = outcomes
+            .iter()
+            .map(|(pair, outcome)| match outcome {
+                Ok(report) => {
+                    let mut diff = crate::render_diff_json(report);
+                    diff["verdict"] = verdict(outcome).into();
+                    diff
+                }
+                Err(error) => serde_json::json!({
+                    "crate": pair.crate_name,
+                    "from": pair.old,
+                    "to": pair.new,
+                    "verdict": "failed",
+                    "error": error,
+                }),
+            })
+            .collect();
+        crate::print_json(
+            &serde_json::json!({
+                "diffs": diffs,
+                "summary": {
+                    "crates": outcomes.len(),
+                    "breaking": count("breaking"),
+                    "minor": count("minor"),
+                    "unchanged": count("unchanged"),
+                    "failed": failed,
+                },
+            }),
+            json_style,
+        )?;
+    } else {
+        for (pair, outcome) in &outcomes {
+            match outcome {
+                Ok(report) => crate::emit_diff(options.format, json_style, report)?,
+                Err(error) if options.format == DiffFormat::Github => print!(
+                    "{}",
+                    crate::github::failure(
+                        &format!("{} {}...{} failed", pair.crate_name, pair.old, pair.new),
+                        error
+                    )
+                ),
+                Err(_) => {}
+            }
+        }
+
+        let mut table = Table::new(&["Crate", "From", "To", "Verdict", "Changes"]);
+        for (pair, outcome) in &outcomes {
+            let changes = match outcome {
+                Ok(report) => format!(
+                    "+{} / -{} / ~{} ({} breaking)",
+                    report.added.len(),
+                    report.removed.len(),
+                    report.modified.len(),
+                    report.breaking_count()
+                ),
+                Err(error) => error.clone(),
+            };
+            table.add_row(vec![
+                pair.crate_name.clone(),
+                pair.old.clone(),
+                pair.new.clone(),
+                verdict(outcome).to_string(),
+                changes,
+            ]);
+        }
+        let github = options.format == DiffFormat::Github;
+        if github {
+            println!("::group::{}", heading);
+        } else {
+            println!("\n{}:\n", heading);
+        }
+        print!("{}", table.render());
+        println!(
+            "\n{} crates: {} breaking, {} minor, {} unchanged, {} failed",
+            outcomes.len(),
+            count("breaking"),
+            count("minor"),
+            count("unchanged"),
+            failed
+        );
+        if github {
+            println!("::endgroup::");
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{} of {} diffs failed", failed, outcomes.len());
+    }
+    Ok(())
+}
@@ -0,0 +1,176 @@
+// Trait bounds in rendered signatures, so diffs can tell a bound that was
+// dropped (callers and implementors can do more: minor) from one that was
+// added (code that compiled may not: breaking). Works on the text
+// `extract_signature` produces, which is also what baselines store.
+use std::collections::BTreeSet;
+
+// The end of the bracketed group `text` starts with, skipping `->` arrows
+pub fn group_end(text: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut previous = ' ';
+    for (i, c) in text.char_indices() {
+        match c {
+            '<' | '(' | '[' | '{' => depth += 1,
+            '>' if previous == '-' => {}
+            '>' | ')' | ']' | '}' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+        previous = c;
+    }
+    None
+}
+
+// Where `separator` first appears outside brackets
+pub fn find_top_level(text: &str, separator: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut previous = ' ';
+    for (i, c) in text.char_indices() {
+        match c {
+            '<' | '(' | '[' | '{' => depth += 1,
+            '>' if previous == '-' => {}
+            '>' | ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            _ if depth == 0 && text[i..].starts_with(separator) => return Some(i),
+            _ => {}
+        }
+        previous = c;
+    }
+    None
+}
+
+// Split at `separator`s that aren't nested in brackets
+pub fn split_at_top_level<'a>(mut text: &'a str, separator: &str) -> Vec<&'a str> {
+    let mut parts = Vec::new();
+    while let Some(i) = find_top_level(text, separator) {
+        parts.push(text[..i].trim());
+        text = &text[i + separator.len()..];
+    }
+    parts.push(text.trim());
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
+// Split at commas that aren't nested in brackets
+pub fn split_top_level(text: &str) -> Vec<&str> {
+    split_at_top_level(text, ",")
+}
+
+// `T: Clone + Send` as (`T`, its bounds); None for a predicate without any.
+// `::` in paths never has a space after it, so `: ` separates the two.
+fn predicate(text: &str) -> Option<(&str, Vec<&str>)> {
+    let colon = find_top_level(text, ": ")?;
+    Some((
+        &text[..colon],
+        split_at_top_level(&text[colon + 2..], " + "),
+    ))
+}
+
+// A signature split into its bounds, as (bounded type, bound) pairs, and
+// everything else with the bounds taken out. The rest is only for comparing,
+// so whitespace is dropped from it rather than kept tidy.
+pub fn split(signature: &str) -> (String, BTreeSet<(String, String)>) {
+    let mut bounds = BTreeSet::new();
+    let mut skeleton = String::new();
+
+    // Function qualifiers lead, before the generic parameters
+    let (qualifiers, signature) = crate::signature::split_qualifiers(signature);
+    for qualifier in qualifiers {
+        skeleton.push_str(qualifier);
+    }
+    let mut rest = signature;
+    if signature.starts_with('<')
+        && let Some(end) = group_end(signature)
+    {
+        let mut params = Vec::new();
+        for param in split_top_level(&signature[1..end]) {
+            // Const params have a type, not bounds
+            if param.starts_with("const ") {
+                params.push(param.to_string());
+                continue;
+            }
+            let (declared, default) = match find_top_level(param, " = ") {
+                Some(i) => param.split_at(i),
+                None => (param, ""),
+            };
+            match predicate(declared) {
+                Some((name, param_bounds)) => {
+                    for bound in param_bounds {
+                        bounds.insert((name.to_string(), bound.to_string()));
+                    }
+                    params.push(format!("{}{}", name, default));
+                }
+                None => params.push(param.to_string()),
+            }
+        }
+        skeleton.push_str(&format!("<{}>", params.join(", ")));
+        rest = &signature[end + 1..];
+    }
+
+    // Bounds right after the parameters bound the item itself: a trait's
+    // supertraits (`: Display + Send`, the same as `where Self: Display +
+    // Send`) or an associated type's bounds
+    if let Some(own) = rest.strip_prefix(": ") {
+        let end = [" where ", " { ", " = "]
+            .iter()
+            .filter_map(|next| find_top_level(own, next))
+            .min()
+            .unwrap_or(own.len());
+        for bound in split_at_top_level(&own[..end], " + ") {
+            bounds.insert(("Self".to_string(), bound.to_string()));
+        }
+        rest = &own[end..];
+    }
+
+    // A where clause runs to the end of a function signature, and up to the
+    // body (` { 2 fields }` or ` = Target`) of a definition
+    let (before, clause) = match find_top_level(rest, " where ") {
+        Some(start) => (&rest[..start], &rest[start + " where ".len()..]),
+        // A where clause with no generic parameters before it
+        None => match rest.strip_prefix("where ") {
+            Some(clause) => ("", clause),
+            None => (rest, ""),
+        },
+    };
+    skeleton.push_str(before);
+    let body_start = [" { ", " = "]
+        .iter()
+        .filter_map(|body| find_top_level(clause, body))
+        .min()
+        .unwrap_or(clause.len());
+    for text in split_top_level(&clause[..body_start]) {
+        if let Some((subject, predicate_bounds)) = predicate(text) {
+            for bound in predicate_bounds {
+                bounds.insert((subject.to_string(), bound.to_string()));
+            }
+        }
+    }
+    skeleton.push_str(&clause[body_start..]);
+    skeleton.retain(|c| !c.is_whitespace());
+    (skeleton, bounds)
+}
+
+// Whether the only difference between two signatures is bounds that were
+// dropped, or `?Sized` relaxations that were added
+pub fn only_loosened(old: &str, new: &str) -> bool {
+    let (old_skeleton, old_bounds) = split(old);
+    let (new_skeleton, new_bounds) = split(new);
+    let relaxes = |(_, bound): &&(String, String)| bound.starts_with('?');
+    old_skeleton == new_skeleton
+        && old_bounds != new_bounds
+        && !old_bounds.difference(&new_bounds).any(|b| relaxes(&b))
+        && new_bounds.difference(&old_bounds).all(|b| relaxes(&b))
+}
+
+// Bounds `Self` lost between two signatures: on a trait they act as
+// supertraits, which generic code over the trait may rely on
+pub fn lost_self_bounds(old: &str, new: &str) -> bool {
+    let (_, old_bounds) = split(old);
+    let (_, new_bounds) = split(new);
+    old_bounds
+        .difference(&new_bounds)
+        .any(|(subject, _)| subject == "Self")
+}
@@ -0,0 +1,381 @@
+// On-disk cache of rustdoc JSON downloaded from docs.rs, and `zdoc cache`
+// to inspect and clear it. Only exact versions are cached: `latest` or `1.0`
+// can resolve to a newer release later, so those are always fetched.
+//
+// Entries live at `<root>/docs-rs/<crate>/<version>.json`. Writes go to a
+// temporary file that is renamed into place, and clearing renames an entry
+// away before deleting it, so concurrent zdoc processes never read a partial
+// file; an entry vanishing mid-read is treated as a miss.
+use anyhow::{Context, Result};
+use cargo_metadata::semver::Version;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::table::Table;
+
+const DOCS_RS_DIR: &str = "docs-rs";
+
+// `$ZDOC_CACHE_DIR`, else the platform cache dir (`$XDG_CACHE_HOME/zdoc` on
+// Linux), else a directory under the system temp dir
+pub fn root() -> PathBuf {
+    if let Some(dir) = std::env::var_os("ZDOC_CACHE_DIR").filter(|dir| !dir.is_empty()) {
+        return PathBuf::from(dir);
+    }
+    dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("zdoc")
+}
+
+fn entry_path(root: &Path, crate_name: &str, version: &str) -> PathBuf {
+    root.join(DOCS_RS_DIR)
+        .join(crate_name)
+        .join(format!("{}.json", version))
+}
+
+// The cached JSON for an exact version, opened for reading, if there is one
+pub fn open(crate_name: &str, version: &str) -> Result<Option<fs::File>> {
+    let path = entry_path(&root(), crate_name, version);
+    match fs::File::open(&path) {
+        Ok(file) => Ok(Some(file)),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err).with_context(|| format!("Failed to read {}", path.display())),
+    }
+}
+
+pub fn is_cached(crate_name: &str, version: &str) -> bool {
+    entry_path(&root(), crate_name, version).exists()
+}
+
+// A temporary file in the crate's cache directory to download into. Once
+// it's known to hold an exact release, `keep` moves it into place atomically;
+// otherwise dropping it deletes it.
+pub fn staging_file(crate_name: &str) -> Result<tempfile::NamedTempFile> {
+    let dir = root().join(DOCS_RS_DIR).join(crate_name);
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    tempfile::NamedTempFile::new_in(&dir)
+        .with_context(|| format!("Failed to create a temporary file in {}", dir.display()))
+}
+
+// Make a staged download the entry for an exact version
+pub fn keep(staged: tempfile::NamedTempFile, crate_name: &str, version: &str) -> Result<()> {
+    let path = entry_path(&root(), crate_name, version);
+    staged
+        .persist(&path)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+struct Entry {
+    crate_name: String,
+    version: String,
+    path: PathBuf,
+    size: u64,
+    modified: SystemTime,
+}
+
+impl Entry {
+    fn age(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(self.modified)
+            .unwrap_or_default()
+    }
+}
+
+// Every entry under `root`, sorted by crate then version. Files that
+// disappear while listing are skipped.
+fn entries(root: &Path) -> Result<Vec<Entry>> {
+    let docs_dir = root.join(DOCS_RS_DIR);
+    let crate_dirs = match fs::read_dir(&docs_dir) {
+        Ok(dirs) => dirs,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(err).with_context(|| format!("Failed to read {}", docs_dir.display()));
+        }
+    };
+
+    let mut found = Vec::new();
+    for crate_dir in crate_dirs.flatten() {
+        let crate_name = crate_dir.file_name().to_string_lossy().into_owned();
+        let Ok(files) = fs::read_dir(crate_dir.path()) else {
+            continue;
+        };
+        for file in files.flatten() {
+            let path = file.path();
+            // Temporary files from in-progress writes and clears are skipped
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let Ok(metadata) = file.metadata() else {
+                continue;
+            };
+            let version = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            found.push(Entry {
+                crate_name: crate_name.clone(),
+                version,
+                path,
+                size: metadata.len(),
+                modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            });
+        }
+    }
+    // In semver order, so `1.0.0-beta.2` lists before `1.0.0` and `0.10.0`
+    // after `0.9.0`
+    let semver = |entry: &Entry| Version::parse(&entry.version).ok();
+    found.sort_by(|a, b| {
+        a.crate_name
+            .cmp(&b.crate_name)
+            .then_with(|| semver(a).cmp(&semver(b)))
+            .then_with(|| a.version.cmp(&b.version))
+    });
+    Ok(found)
+}
+
+// The crates with anything cached, for suggesting names
+pub fn crate_names() -> Vec<String> {
+    let Ok(dirs) = fs::read_dir(root().join(DOCS_RS_DIR)) else {
+        return Vec::new();
+    };
+    dirs.flatten()
+        .map(|dir| dir.file_name().to_string_lossy().into_owned())
+        .collect()
+}
+
+// Remove an entry: rename it out of the way first so no other process can
+// open a file that is being deleted. Returns false if it was already gone.
+fn remove(entry: &Entry) -> Result<bool> {
+    let doomed = entry
+        .path
+        .with_extension(format!("json.removing-{}", std::process::id()));
+    match fs::rename(&entry.path, &doomed) {
+        Ok(()) => {}
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(false),
+        Err(err) => {
+            return Err(err).with_context(|| format!("Failed to remove {}", entry.path.display()));
+        }
+    }
+    match fs::remove_file(&doomed) {
+        Ok(()) => Ok(true),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(true),
+        Err(err) => Err(err).with_context(|| format!("Failed to remove {}", doomed.display())),
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+// The largest whole unit: `45s`, `12m`, `3h`, `9d`
+fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    match secs {
+        0..60 => format!("{}s", secs),
+        60..3600 => format!("{}m", secs / 60),
+        3600..86400 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
+}
+
+pub fn list() -> Result<()> {
+    let entries = entries(&root())?;
+    if entries.is_empty() {
+        println!("The cache is empty ({})", root().display());
+        return Ok(());
+    }
+    let mut table = Table::new(&["Crate", "Version", "Size", "Age"]);
+    for entry in &entries {
+        table.add_row(vec![
+            entry.crate_name.clone(),
+            entry.version.clone(),
+            format_size(entry.size),
+            format_age(entry.age()),
+        ]);
+    }
+    print!("{}", table.render());
+    Ok(())
+}
+
+pub fn stats() -> Result<()> {
+    let root = root();
+    let entries = entries(&root)?;
+    let total: u64 = entries.iter().map(|entry| entry.size).sum();
+    let mut crates: Vec<&str> = entries.iter().map(|e| e.crate_name.as_str()).collect();
+    crates.dedup();
+
+    println!("Cache root: {}", root.display());
+    println!("Entries:    {} ({} crates)", entries.len(), crates.len());
+    println!("Total size: {}", format_size(total));
+    if let Some(oldest) = entries.iter().max_by_key(|entry| entry.age()) {
+        println!(
+            "Oldest:     {} {} ({} old)",
+            oldest.crate_name,
+            oldest.version,
+            format_age(oldest.age())
+        );
+    }
+    Ok(())
+}
+
+// Remove the entries under `root` matching the filters, returning how many
+// were removed and their total size
+fn clear_in(
+    root: &Path,
+    crate_name: Option<&str>,
+    older_than: Option<Duration>,
+) -> Result<(usize, u64)> {
+    let mut removed = 0;
+    let mut freed = 0;
+    for entry in entries(root)? {
+        if crate_name.is_some_and(|name| name != entry.crate_name) {
+            continue;
+        }
+        if older_than.is_some_and(|limit| entry.age() < limit) {
+            continue;
+        }
+        if remove(&entry)? {
+            removed += 1;
+            freed += entry.size;
+        }
+    }
+    Ok((removed, freed))
+}
+
+pub fn clear(crate_name: Option<&str>, older_than: Option<Duration>) -> Result<()> {
+    let (removed, freed) = clear_in(&root(), crate_name, older_than)?;
+    println!("Removed {} cache entries ({})", removed, format_size(freed));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: Duration = Duration::from_secs(86400);
+
+    // Cache `crate_name` `version`, last written `age` ago
+    fn cache(root: &Path, crate_name: &str, version: &str, age: Duration) {
+        let path = entry_path(root, crate_name, version);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "{}").unwrap();
+        let file = fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() - age).unwrap();
+    }
+
+    fn listed(root: &Path) -> Vec<String> {
+        entries(root)
+            .unwrap()
+            .iter()
+            .map(|entry| format!("{} {}", entry.crate_name, entry.version))
+            .collect()
+    }
+
+    #[test]
+    fn lists_entries_in_semver_order() {
+        let root = tempfile::tempdir().unwrap();
+        for version in ["0.10.0", "1.0.0", "0.9.0", "1.0.0-beta.2"] {
+            cache(root.path(), "serde", version, Duration::ZERO);
+        }
+        cache(root.path(), "anyhow", "1.0.0", Duration::ZERO);
+        assert_eq!(
+            listed(root.path()),
+            [
+                "anyhow 1.0.0",
+                "serde 0.9.0",
+                "serde 0.10.0",
+                "serde 1.0.0-beta.2",
+                "serde 1.0.0"
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_what_isnt_an_entry() {
+        let root = tempfile::tempdir().unwrap();
+        cache(root.path(), "serde", "1.0.0", Duration::ZERO);
+        let crate_dir = root.path().join(DOCS_RS_DIR).join("serde");
+        // A download in progress and a clear in progress
+        fs::write(crate_dir.join(".tmpA1b2C3"), "{").unwrap();
+        fs::write(crate_dir.join("0.9.0.json.removing-42"), "{}").unwrap();
+        // A stray file where a crate directory would be
+        fs::write(root.path().join(DOCS_RS_DIR).join("notes.txt"), "").unwrap();
+        assert_eq!(listed(root.path()), ["serde 1.0.0"]);
+    }
+
+    #[test]
+    fn missing_cache_is_empty() {
+        let root = tempfile::tempdir().unwrap();
+        assert!(listed(&root.path().join("never-created")).is_empty());
+        assert_eq!(clear_in(root.path(), None, None).unwrap(), (0, 0));
+    }
+
+    #[test]
+    fn clears_one_crate() {
+        let root = tempfile::tempdir().unwrap();
+        cache(root.path(), "serde", "1.0.0", Duration::ZERO);
+        cache(root.path(), "serde", "1.0.1", Duration::ZERO);
+        cache(root.path(), "anyhow", "1.0.0", Duration::ZERO);
+        assert_eq!(clear_in(root.path(), Some("serde"), None).unwrap(), (2, 4));
+        assert_eq!(listed(root.path()), ["anyhow 1.0.0"]);
+    }
+
+    #[test]
+    fn clears_entries_older_than_a_limit() {
+        let root = tempfile::tempdir().unwrap();
+        cache(root.path(), "serde", "1.0.0", 30 * DAY);
+        cache(root.path(), "serde", "1.0.1", DAY);
+        cache(root.path(), "anyhow", "1.0.0", 10 * DAY);
+        assert_eq!(clear_in(root.path(), None, Some(7 * DAY)).unwrap().0, 2);
+        assert_eq!(listed(root.path()), ["serde 1.0.1"]);
+    }
+
+    #[test]
+    fn combines_filters() {
+        let root = tempfile::tempdir().unwrap();
+        cache(root.path(), "serde", "1.0.0", 30 * DAY);
+        cache(root.path(), "serde", "1.0.1", DAY);
+        cache(root.path(), "anyhow", "1.0.0", 30 * DAY);
+        assert_eq!(
+            clear_in(root.path(), Some("serde"), Some(7 * DAY))
+                .unwrap()
+                .0,
+            1
+        );
+        assert_eq!(listed(root.path()), ["anyhow 1.0.0", "serde 1.0.1"]);
+    }
+
+    #[test]
+    fn tolerates_entries_removed_meanwhile() {
+        let root = tempfile::tempdir().unwrap();
+        cache(root.path(), "serde", "1.0.0", Duration::ZERO);
+        let found = entries(root.path()).unwrap();
+        // Another process clears the entry after this one listed it
+        fs::remove_file(&found[0].path).unwrap();
+        assert!(!remove(&found[0]).unwrap());
+        assert_eq!(clear_in(root.path(), None, None).unwrap(), (0, 0));
+    }
+
+    #[test]
+    fn formats_sizes_and_ages() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0 GiB");
+        assert_eq!(format_age(Duration::from_secs(45)), "45s");
+        assert_eq!(format_age(Duration::from_secs(3 * 3600 + 5)), "3h");
+        assert_eq!(format_age(9 * DAY), "9d");
+    }
+}
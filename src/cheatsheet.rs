@@ -0,0 +1,471 @@
+// `zdoc cheatsheet`: a crate's most important API on a screen or two, to
+// print or keep open beside the editor. Types come with their one-line
+// summaries and their best few constructors and methods, followed by free
+// functions, traits and macros, each section cut to a budget.
+//
+// Items are ranked by a score: points lost for every module between the
+// item and the one summarized (re-exports count at their shortest path),
+// points for docs and for a Rust example in them, points for constructors,
+// and a penalty for deprecation. The weights and budgets come from
+// `[cheatsheet]` in `zdoc.toml`:
+//
+//     [cheatsheet]
+//     examples = 4.0
+//     methods = 6
+use anyhow::{Result, bail};
+use colored::Colorize;
+use serde_json::Value;
+use std::collections::HashMap;
+use tracing::warn;
+
+use crate::ApiItem;
+use crate::config::{CheatsheetConfig, Config};
+use crate::items;
+use crate::layout;
+use crate::style::{self, Role};
+
+const TYPE_KINDS: &[&str] = &["struct", "enum", "union", "type_alias"];
+const FUNCTION_KINDS: &[&str] = &["function", "extern fn"];
+const TRAIT_KINDS: &[&str] = &["trait", "trait_alias"];
+const MACRO_KINDS: &[&str] = &["macro", "proc_macro"];
+// Spaces between columns
+const GUTTER: usize = 3;
+// Indent of section entries, and of methods under their type
+const INDENT: usize = 2;
+const METHOD_INDENT: usize = 4;
+
+// The `[cheatsheet]` settings of the nearest `zdoc.toml`; a broken file is
+// warned about and the defaults used
+fn settings() -> CheatsheetConfig {
+    std::env::current_dir()
+        .ok()
+        .and_then(|cwd| Config::find(&cwd))
+        .map(|root| Config::load(&root))
+        .transpose()
+        .unwrap_or_else(|e| {
+            warn!(
+                "Ignoring the cheatsheet settings in {}: {:#}",
+                crate::config::CONFIG_FILE,
+                e
+            );
+            None
+        })
+        .map(|config| config.cheatsheet)
+        .unwrap_or_default()
+}
+
+// One item on the sheet
+struct Entry {
+    // The name, or the path from the summarized module for deeper items
+    name: String,
+    kind: String,
+    // How it's written: `fn connect(cfg: &Config) -> Client`, `vec!`
+    usage: String,
+    summary: String,
+    score: f64,
+    // A type's best constructors and methods
+    methods: Vec<Entry>,
+}
+
+struct Section {
+    heading: &'static str,
+    // Types and traits are listed by name with their summaries, functions
+    // and macros by how they're written
+    by_name: bool,
+    entries: Vec<Entry>,
+}
+
+pub struct Cheatsheet {
+    title: String,
+    sections: Vec<Section>,
+}
+
+// Whether a function takes `self`, so isn't a constructor
+fn has_receiver(item: &Value) -> bool {
+    items::item_inner(item)
+        .and_then(|inner| inner.get("sig"))
+        .and_then(|sig| sig.get("inputs"))
+        .and_then(|v| v.as_array())
+        .and_then(|inputs| inputs.first())
+        .and_then(|input| input.get(0))
+        .and_then(|v| v.as_str())
+        == Some("self")
+}
+
+// A method without a receiver that's named like a constructor or returns
+// its type (also in a `Result` or `Option`)
+fn is_constructor(json: &Value, item: &ApiItem) -> bool {
+    if item.item_type != "method" || items::lookup(json, &item.id).is_none_or(has_receiver) {
+        return false;
+    }
+    let name = item.name.as_str();
+    if matches!(name, "new" | "default" | "builder")
+        || ["new_", "with_", "from_"]
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+    {
+        return true;
+    }
+    let owner = item.path.last().map(String::as_str).unwrap_or_default();
+    let Some(arrow) = crate::bounds::find_top_level(&item.signature, "->") else {
+        return false;
+    };
+    let output = &item.signature[arrow + 2..];
+    let output = output.split(" where ").next().unwrap_or(output);
+    output
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .any(|word| word == "Self" || word == owner)
+}
+
+fn score(json: &Value, item: &ApiItem, depth: usize, weights: &CheatsheetConfig) -> f64 {
+    let docs = item.docs.as_deref().unwrap_or_default();
+    let mut score = -(depth as f64) * weights.depth;
+    if !docs.trim().is_empty() {
+        score += weights.docs;
+    }
+    if items::split_docs(docs)
+        .1
+        .iter()
+        .any(|block| block.is_rust())
+    {
+        score += weights.examples;
+    }
+    if item.stability.contains(&"deprecated") {
+        score -= weights.deprecated;
+    }
+    if is_constructor(json, item) {
+        score += weights.constructor;
+    }
+    score
+}
+
+// How a macro is invoked: `vec!`, `#[derive(Serialize)]`, `#[tokio::main]`-style
+fn macro_usage(item: &ApiItem) -> String {
+    match item.signature.split_whitespace().next() {
+        Some("derive") if item.item_type == "proc_macro" => format!("#[derive({})]", item.name),
+        Some("attribute") if item.item_type == "proc_macro" => format!("#[{}]", item.name),
+        _ => format!("{}!", item.name),
+    }
+}
+
+// A function as declared, its qualifiers in front: `async fn get(url: &str)`
+fn function_usage(item: &ApiItem) -> String {
+    let signature = item.signature.as_str();
+    let start = signature.find(['(', '<']).unwrap_or(signature.len());
+    let qualifiers = signature[..start].trim();
+    let space = if qualifiers.is_empty() { "" } else { " " };
+    format!(
+        "{}{}fn {}{}",
+        qualifiers,
+        space,
+        item.name,
+        &signature[start..]
+    )
+}
+
+fn entry(
+    json: &Value,
+    item: &ApiItem,
+    name: String,
+    depth: usize,
+    weights: &CheatsheetConfig,
+) -> Entry {
+    let kind = item.item_type.as_str();
+    let usage = if MACRO_KINDS.contains(&kind) {
+        macro_usage(item)
+    } else if kind == "method" || FUNCTION_KINDS.contains(&kind) {
+        function_usage(item)
+    } else {
+        items::lookup(json, &item.id)
+            .map(items::declaration)
+            .unwrap_or_else(|| format!("{} {}", kind, item.name))
+    };
+    Entry {
+        name,
+        kind: item.item_type.clone(),
+        usage: usage.split_whitespace().collect::<Vec<_>>().join(" "),
+        summary: item.docs.as_deref().map(items::summary).unwrap_or_default(),
+        score: score(json, item, depth, weights),
+        methods: Vec::new(),
+    }
+}
+
+// Best first; ties go to the shorter, then the alphabetically first name
+fn rank(entries: &mut Vec<Entry>, budget: usize) {
+    entries.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.name.len().cmp(&b.name.len()))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    entries.truncate(budget);
+}
+
+impl Cheatsheet {
+    // The sheet for the crate `json` documents, or for its module at
+    // `module` (`net::http`, with or without the crate's name in front)
+    pub fn build(crate_name: &str, json: &Value, module: Option<&str>) -> Result<Self> {
+        Self::build_with(crate_name, json, module, &settings())
+    }
+
+    // `build`, ranked by `weights` instead of the configured ones
+    fn build_with(
+        crate_name: &str,
+        json: &Value,
+        module: Option<&str>,
+        weights: &CheatsheetConfig,
+    ) -> Result<Self> {
+        let api = crate::extract_api_items(json)?;
+        let root = crate_name.replace('-', "_");
+        let mut scope = vec![root.clone()];
+        if let Some(module) = module {
+            let segments = module.trim_matches(':').split("::");
+            scope.extend(segments.map(String::from));
+            if scope.get(1) == Some(&root) {
+                scope.remove(0);
+            }
+        }
+        let scope_path = scope.join("::");
+        let is_module = |item: &ApiItem| {
+            item.item_type == "module" && item.public_paths().contains(&scope_path)
+        };
+        if module.is_some() && !api.iter().any(is_module) {
+            bail!("No public module {} in {}", scope_path, crate_name);
+        }
+
+        // Where each item can be named from within the scope: the shortest
+        // of its public paths there, relative to the scope
+        let relative = |item: &ApiItem| {
+            item.public_paths()
+                .iter()
+                .filter_map(|path| {
+                    let segments: Vec<&str> = path.split("::").collect();
+                    segments
+                        .starts_with(&scope.iter().map(String::as_str).collect::<Vec<_>>())
+                        .then(|| segments[scope.len()..].join("::"))
+                })
+                .filter(|path| !path.is_empty())
+                .min_by_key(|path| (path.matches("::").count(), path.len()))
+        };
+        let listed = |item: &&ApiItem| !item.stability.contains(&"doc(hidden)");
+
+        let mut methods: HashMap<String, Vec<Entry>> = HashMap::new();
+        for item in api.iter().filter(listed) {
+            if item.item_type == "method" && item.parent_kind.as_deref() == Some("impl") {
+                let entry = entry(json, item, item.name.clone(), 0, weights);
+                methods.entry(item.path.join("::")).or_default().push(entry);
+            }
+        }
+
+        let mut sections = Vec::new();
+        for (heading, kinds, budget, by_name) in [
+            ("Types", TYPE_KINDS, weights.types, true),
+            ("Functions", FUNCTION_KINDS, weights.functions, false),
+            ("Traits", TRAIT_KINDS, weights.traits, true),
+            ("Macros", MACRO_KINDS, weights.macros, false),
+        ] {
+            let mut entries: Vec<Entry> = api
+                .iter()
+                .filter(listed)
+                .filter(|item| kinds.contains(&item.item_type.as_str()))
+                .filter_map(|item| {
+                    let name = relative(item)?;
+                    let depth = name.matches("::").count();
+                    let mut entry = entry(json, item, name, depth, weights);
+                    if let Some(mut own) = methods.remove(&item.full_path()) {
+                        rank(&mut own, weights.methods);
+                        entry.methods = own;
+                    }
+                    Some(entry)
+                })
+                .collect();
+            rank(&mut entries, budget);
+            if !entries.is_empty() {
+                sections.push(Section {
+                    heading,
+                    by_name,
+                    entries,
+                });
+            }
+        }
+
+        let version = json
+            .get("crate_version")
+            .and_then(|v| v.as_str())
+            .map(|version| format!(" {}", version))
+            .unwrap_or_default();
+        let title = match module {
+            Some(_) => format!("{} ({}{})", scope_path, crate_name, version),
+            None => format!("{}{}", crate_name, version),
+        };
+        Ok(Cheatsheet { title, sections })
+    }
+
+    pub fn render_text(&self) -> String {
+        self.render_text_in(layout::width())
+    }
+
+    // The sheet laid out in `width` columns
+    fn render_text_in(&self, width: usize) -> String {
+        let mut out = format!("{}\n", format!("Cheatsheet: {}", self.title).bold());
+        for section in &self.sections {
+            out.push_str(&format!("\n{}\n", section.heading.bold()));
+            match section.by_name {
+                true => {
+                    let name_width = section
+                        .entries
+                        .iter()
+                        .map(|entry| entry.name.chars().count())
+                        .max()
+                        .unwrap_or(0)
+                        .min(width / 3);
+                    for entry in &section.entries {
+                        let name = fit(&entry.name, name_width);
+                        // Padded only when a summary follows
+                        let shown = match entry.summary.is_empty() {
+                            true => name,
+                            false => format!("{:name_width$}{}", name, " ".repeat(GUTTER)),
+                        };
+                        let shown = match style::kind_role(&entry.kind) {
+                            Some(role) => style::paint(role, &shown),
+                            None => shown.normal(),
+                        };
+                        let room = width.saturating_sub(INDENT + name_width + GUTTER);
+                        let summary = fit(&entry.summary, room);
+                        out.push_str(&format!(
+                            "{}{}{}\n",
+                            " ".repeat(INDENT),
+                            shown,
+                            style::paint(Role::Muted, &summary)
+                        ));
+                        let usages: Vec<String> = entry
+                            .methods
+                            .iter()
+                            .map(|method| method.usage.clone())
+                            .collect();
+                        for line in grid(&usages, METHOD_INDENT, width) {
+                            out.push_str(&format!("{}\n", line));
+                        }
+                    }
+                }
+                false => {
+                    let usages: Vec<String> = section
+                        .entries
+                        .iter()
+                        .map(|entry| entry.usage.clone())
+                        .collect();
+                    for line in grid(&usages, INDENT, width) {
+                        out.push_str(&format!("{}\n", line));
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    pub fn render_markdown(&self) -> String {
+        let mut out = format!("# Cheatsheet: {}\n", self.title);
+        for section in &self.sections {
+            out.push_str(&format!("\n## {}\n\n", section.heading));
+            for entry in &section.entries {
+                let shown = match section.by_name {
+                    true => &entry.name,
+                    false => &entry.usage,
+                };
+                out.push_str(&format!("- `{}`", shown));
+                if !entry.summary.is_empty() {
+                    out.push_str(&format!(": {}", entry.summary));
+                }
+                out.push('\n');
+                for method in &entry.methods {
+                    out.push_str(&format!("  - `{}`\n", method.usage));
+                }
+            }
+        }
+        out
+    }
+}
+
+// `text` in at most `width` columns, cut with an ellipsis
+fn fit(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(width.saturating_sub(1)).collect();
+    format!("{}…", kept.trim_end())
+}
+
+// `cells` laid out like `ls` does, down then across, in as many columns as
+// fit in `width` after `indent`; with a single column, cells are cut to fit
+fn grid(cells: &[String], indent: usize, width: usize) -> Vec<String> {
+    let room = width.saturating_sub(indent);
+    let widths: Vec<usize> = cells.iter().map(|cell| cell.chars().count()).collect();
+    let layout = (1..=cells.len()).rev().find_map(|columns| {
+        let rows = cells.len().div_ceil(columns);
+        let column_widths: Vec<usize> = widths
+            .chunks(rows)
+            .map(|column| column.iter().copied().max().unwrap_or(0))
+            .collect();
+        let total = column_widths.iter().sum::<usize>() + GUTTER * (column_widths.len() - 1);
+        (total <= room).then_some((rows, column_widths))
+    });
+    let pad = " ".repeat(indent);
+    let Some((rows, column_widths)) = layout else {
+        return cells
+            .iter()
+            .map(|cell| format!("{}{}", pad, fit(cell, room)))
+            .collect();
+    };
+    (0..rows)
+        .map(|row| {
+            let line: Vec<String> = column_widths
+                .iter()
+                .enumerate()
+                .filter_map(|(column, column_width)| {
+                    let cell = cells.get(column * rows + row)?;
+                    Some(format!("{:column_width$}", cell))
+                })
+                .collect();
+            format!("{}{}", pad, line.join(&" ".repeat(GUTTER)).trim_end())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    fn widgets(weights: &CheatsheetConfig) -> Cheatsheet {
+        // Snapshots are plain text, whatever the terminal
+        colored::control::set_override(false);
+        Cheatsheet::build_with("widgets", &fixtures::load("widgets"), None, weights).unwrap()
+    }
+
+    #[test]
+    fn cheatsheet_at_80_columns() {
+        insta::assert_snapshot!(widgets(&CheatsheetConfig::default()).render_text_in(80));
+    }
+
+    #[test]
+    fn cheatsheet_at_40_columns() {
+        insta::assert_snapshot!(widgets(&CheatsheetConfig::default()).render_text_in(40));
+    }
+
+    #[test]
+    fn cheatsheet_as_markdown() {
+        insta::assert_snapshot!(widgets(&CheatsheetConfig::default()).render_markdown());
+    }
+
+    // Budgets cut each list to its best entries: constructors first, and the
+    // deprecated `Widget::grow` last
+    #[test]
+    fn budgets_keep_the_best() {
+        let weights = CheatsheetConfig {
+            types: 1,
+            methods: 3,
+            ..CheatsheetConfig::default()
+        };
+        insta::assert_snapshot!(widgets(&weights).render_markdown());
+    }
+}
@@ -0,0 +1,127 @@
+// System clipboard access for `--copy` flags. arboard picks the platform's
+// clipboard (X11 on Linux, where Wayland sessions are served through
+// XWayland). Headless machines and SSH sessions have none, so `copy` prints
+// the text instead of failing the command.
+//
+// The clipboard goes through `Provider`, so the fallback can run against a
+// fake.
+use anyhow::{Context, Result};
+use std::io::Write;
+
+pub trait Provider {
+    fn set_text(&self, text: &str) -> Result<()>;
+}
+
+// The platform's clipboard
+pub struct System;
+
+impl Provider for System {
+    fn set_text(&self, text: &str) -> Result<()> {
+        let mut clipboard = arboard::Clipboard::new().context("No clipboard provider available")?;
+        clipboard
+            .set_text(text.to_string())
+            .context("Failed to write to the clipboard")?;
+        Ok(())
+    }
+}
+
+// Copy `text`, described as `what` in the confirmation. Without a clipboard
+// the text goes to stdout after a note on stderr, so it can be copied by hand.
+pub fn copy(text: &str, what: &str) {
+    copy_with(
+        &System,
+        text,
+        what,
+        &mut std::io::stdout(),
+        &mut std::io::stderr(),
+    );
+}
+
+fn copy_with(
+    provider: &impl Provider,
+    text: &str,
+    what: &str,
+    out: &mut impl Write,
+    err: &mut impl Write,
+) {
+    // There's nowhere left to report a failed write to the terminal
+    match provider.set_text(text) {
+        Ok(()) => {
+            let _ = writeln!(out, "Copied {} to the clipboard", what);
+        }
+        Err(e) => {
+            let _ = writeln!(err, "Couldn't copy {} ({:#}); here it is instead:", what, e);
+            let _ = writeln!(out, "{}", text);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use std::cell::RefCell;
+
+    // Keeps what's copied, or fails like a headless machine
+    struct Fake {
+        copied: RefCell<Option<String>>,
+        works: bool,
+    }
+
+    impl Fake {
+        fn new(works: bool) -> Self {
+            Fake {
+                copied: RefCell::new(None),
+                works,
+            }
+        }
+    }
+
+    impl Provider for Fake {
+        fn set_text(&self, text: &str) -> Result<()> {
+            if !self.works {
+                return Err(anyhow!("X11 server connection timed out"))
+                    .context("No clipboard provider available");
+            }
+            *self.copied.borrow_mut() = Some(text.to_string());
+            Ok(())
+        }
+    }
+
+    // What `copy_with` wrote to stdout and stderr
+    fn run(provider: &Fake, text: &str, what: &str) -> (String, String) {
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        copy_with(provider, text, what, &mut out, &mut err);
+        (
+            String::from_utf8(out).unwrap(),
+            String::from_utf8(err).unwrap(),
+        )
+    }
+
+    #[test]
+    fn confirms_what_was_copied() {
+        let provider = Fake::new(true);
+        let (out, err) = run(&provider, "serde_json::from_str", "`serde_json::from_str`");
+        assert_eq!(out, "Copied `serde_json::from_str` to the clipboard\n");
+        assert_eq!(err, "");
+        assert_eq!(
+            provider.copied.borrow().as_deref(),
+            Some("serde_json::from_str")
+        );
+    }
+
+    #[test]
+    fn prints_the_text_without_a_clipboard() {
+        let provider = Fake::new(false);
+        let text = "pub fn from_str<'a, T>(s: &'a str) -> Result<T>\nwhere\n    T: Deserialize<'a>";
+        let (out, err) = run(&provider, text, "the signature");
+        // The text alone on stdout, so piping it somewhere still works
+        assert_eq!(out, format!("{}\n", text));
+        assert_eq!(
+            err,
+            "Couldn't copy the signature (No clipboard provider available: \
+             X11 server connection timed out); here it is instead:\n"
+        );
+        assert_eq!(*provider.copied.borrow(), None);
+    }
+}
@@ -0,0 +1,274 @@
+// `zdoc compare`: two crates side by side, for choosing between
+// alternatives. Only counts, no verdicts: the size of each newest release's
+// public API by kind, its features, MSRV and dependencies (from the registry
+// index), how much of its API is async or unsafe and documented (the same
+// tally `stats` makes), and the top-level names both crates define.
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use tracing::info;
+
+use crate::ignore::Ignore;
+use crate::stats::{self, Tally};
+use crate::table::Table;
+use crate::{ApiItem, JsonStyle, SearchFormat, http, packaging, registry, sources};
+
+// Kinds of crate-root items compared by name
+const TOP_LEVEL_KINDS: &[&str] = &[
+    "struct",
+    "enum",
+    "union",
+    "trait",
+    "function",
+    "type_alias",
+    "macro",
+];
+
+// One crate's newest release, summed up
+struct Side {
+    crate_name: String,
+    version: String,
+    tally: Tally,
+    features: usize,
+    default_features: Vec<String>,
+    msrv: Option<String>,
+    dependencies: usize,
+    optional_dependencies: usize,
+    top_level: BTreeSet<String>,
+}
+
+async fn side(crate_name: &str, ignore: &Ignore) -> Result<Side> {
+    let source = sources::for_crate(crate_name);
+    let releases = registry::releases(&http::client()?, &source, crate_name)
+        .await
+        .with_context(|| format!("Failed to look up the releases of {}", crate_name))?;
+    let release = registry::newest_release(&releases, false)
+        .or_else(|| registry::newest_release(&releases, true))
+        .with_context(|| format!("{} has no releases", crate_name))?;
+    info!("Reading the docs of {} v{}...", crate_name, release.vers);
+    let items = stats::public_items(crate_name, &release.vers, ignore).await?;
+    Ok(summarize(crate_name, release, &items))
+}
+
+// A release's index entry and public items, summed up
+fn summarize(crate_name: &str, release: &registry::Release, items: &[ApiItem]) -> Side {
+    let features = packaging::release_features(release);
+    let normal: Vec<&registry::Dependency> = release
+        .deps
+        .iter()
+        .filter(|dep| dep.kind.as_deref().is_none_or(|kind| kind == "normal"))
+        .collect();
+    Side {
+        crate_name: crate_name.to_string(),
+        version: release.vers.clone(),
+        tally: Tally::of(items),
+        features: features.keys().filter(|name| *name != "default").count(),
+        default_features: features.get("default").cloned().unwrap_or_default(),
+        msrv: release.rust_version.clone(),
+        dependencies: normal.len(),
+        optional_dependencies: normal.iter().filter(|dep| dep.optional).count(),
+        top_level: top_level_names(items),
+    }
+}
+
+// Names defined at the crate root, where an alternative's equivalents live
+fn top_level_names(items: &[ApiItem]) -> BTreeSet<String> {
+    items
+        .iter()
+        .filter(|item| item.path.len() == 1 && TOP_LEVEL_KINDS.contains(&item.item_type.as_str()))
+        .map(|item| item.name.clone())
+        .collect()
+}
+
+fn to_json(side: &Side) -> Value {
+    serde_json::json!({
+        "crate": side.crate_name,
+        "version": side.version,
+        "items": side.tally.items,
+        "items_by_kind": side.tally.by_kind,
+        "features": side.features,
+        "default_features": side.default_features,
+        "msrv": side.msrv,
+        "dependencies": side.dependencies,
+        "optional_dependencies": side.optional_dependencies,
+        "async_fns": side.tally.async_fns,
+        "unsafe_fns": side.tally.unsafe_fns,
+        "documented_percent": (side.tally.documented_percent() * 10.0).round() / 10.0,
+    })
+}
+
+fn print_table(a: &Side, b: &Side) {
+    let mut table = Table::new(&[
+        "",
+        &format!("{} v{}", a.crate_name, a.version),
+        &format!("{} v{}", b.crate_name, b.version),
+    ]);
+    let mut row = |label: &str, value: &dyn Fn(&Side) -> String| {
+        table.add_row(vec![label.to_string(), value(a), value(b)]);
+    };
+    row("Public items", &|side| side.tally.items.to_string());
+    let kinds: BTreeSet<&String> = a
+        .tally
+        .by_kind
+        .keys()
+        .chain(b.tally.by_kind.keys())
+        .collect();
+    for kind in kinds {
+        row(&format!("  {}", kind), &|side| {
+            side.tally
+                .by_kind
+                .get(kind)
+                .copied()
+                .unwrap_or(0)
+                .to_string()
+        });
+    }
+    row("Documented", &|side| {
+        format!("{:.1}%", side.tally.documented_percent())
+    });
+    row("Async fns", &|side| side.tally.async_fns.to_string());
+    row("Unsafe fns", &|side| side.tally.unsafe_fns.to_string());
+    row("Features", &|side| {
+        format!(
+            "{} ({} default)",
+            side.features,
+            side.default_features.len()
+        )
+    });
+    row("Default features", &|side| {
+        if side.default_features.is_empty() {
+            "—".to_string()
+        } else {
+            side.default_features.join(", ")
+        }
+    });
+    row("MSRV", &|side| {
+        side.msrv.clone().unwrap_or_else(|| "—".to_string())
+    });
+    row("Dependencies", &|side| {
+        format!(
+            "{} ({} optional)",
+            side.dependencies, side.optional_dependencies
+        )
+    });
+    println!();
+    print!("{}", table.render());
+}
+
+// Handler for `zdoc compare`
+pub async fn run(
+    crate_a: &str,
+    crate_b: &str,
+    format: SearchFormat,
+    json_style: JsonStyle,
+) -> Result<()> {
+    let ignore = Ignore::load_nearest()?;
+    let (a, b) = tokio::try_join!(side(crate_a, &ignore), side(crate_b, &ignore))?;
+    let shared: Vec<&String> = a.top_level.intersection(&b.top_level).collect();
+
+    if format == SearchFormat::Json {
+        return crate::print_json(
+            &serde_json::json!({
+                "crates": [to_json(&a), to_json(&b)],
+                "shared_names": shared,
+            }),
+            json_style,
+        );
+    }
+
+    print_table(&a, &b);
+    if shared.is_empty() {
+        println!("\nNo top-level names in common.");
+    } else {
+        let names: Vec<&str> = shared.iter().map(|name| name.as_str()).collect();
+        println!(
+            "\nTop-level names in both ({}): {}",
+            shared.len(),
+            names.join(", ")
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    fn items(fixture: &str) -> Vec<ApiItem> {
+        stats::public_api(&fixtures::load(fixture), &Ignore::default()).unwrap()
+    }
+
+    fn release() -> registry::Release {
+        serde_json::from_value(serde_json::json!({
+            "name": "widgets",
+            "vers": "0.3.1",
+            "deps": [
+                { "name": "bitflags", "req": "^2", "kind": "normal" },
+                { "name": "serde", "req": "^1", "optional": true },
+                { "name": "png", "req": "^0.17", "optional": true, "kind": "normal" },
+                { "name": "rayon", "req": "^1", "optional": true },
+                { "name": "insta", "req": "^1", "kind": "dev" },
+                { "name": "cc", "req": "^1", "kind": "build" },
+            ],
+            "features": { "default": ["std"], "std": [] },
+            "features2": { "serde": ["dep:serde"], "images": ["dep:png"] },
+            "rust_version": "1.70",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn summarizes_a_release() {
+        let side = summarize("widgets", &release(), &items("widgets"));
+        assert_eq!(side.version, "0.3.1");
+        assert_eq!(side.tally.items, 24);
+        // `std`, `serde`, `images` and the implicit `rayon`; `default` only
+        // picks among them
+        assert_eq!(side.features, 4);
+        assert_eq!(side.default_features, ["std"]);
+        assert_eq!(side.msrv.as_deref(), Some("1.70"));
+        // Dev and build dependencies aren't anyone else's
+        assert_eq!((side.dependencies, side.optional_dependencies), (4, 3));
+    }
+
+    #[test]
+    fn top_level_names_skip_members_and_modules() {
+        assert_eq!(
+            top_level_names(&items("widgets")),
+            [
+                "Canvas", "Draw", "Shape", "Widget", "clear", "draw", "widget"
+            ]
+            .map(String::from)
+            .into()
+        );
+    }
+
+    #[test]
+    fn names_shared_between_releases() {
+        let old = top_level_names(&items("tasks-old"));
+        let new = top_level_names(&items("tasks-new"));
+        let shared: Vec<&String> = old.intersection(&new).collect();
+        assert_eq!(
+            shared,
+            [
+                "BoxFuture",
+                "JoinHandle",
+                "Service",
+                "boxed",
+                "for_each",
+                "poll_once",
+                "ready",
+                "sleep",
+                "spawn"
+            ]
+        );
+    }
+
+    #[test]
+    fn json_rounds_the_documented_share() {
+        let json = to_json(&summarize("widgets", &release(), &items("widgets")));
+        assert_eq!(json["items_by_kind"]["method"], 9);
+        assert_eq!(json["documented_percent"], 78.3);
+    }
+}
@@ -0,0 +1,191 @@
+// Project settings read from `zdoc.toml` at the workspace root, so a team
+// can check them in alongside the code
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::lint_docs::Level;
+
+pub const CONFIG_FILE: &str = "zdoc.toml";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    // Rule name -> level for `zdoc lint-docs`
+    pub lint_docs: BTreeMap<String, Level>,
+    pub ignore: IgnoreConfig,
+    // Role (or `preset`) -> style, for `style`
+    pub theme: BTreeMap<String, String>,
+    pub sources: SourcesConfig,
+    pub outdated: OutdatedConfig,
+    pub public_deps: PublicDepsConfig,
+    pub rustdoc: RustdocConfig,
+    pub cheatsheet: CheatsheetConfig,
+}
+
+// How docs are generated, for `cargo doc` runs (written by `zdoc setup`)
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RustdocConfig {
+    // The rustup toolchain to document with (`nightly`, `nightly-2025-06-01`)
+    pub toolchain: Option<String>,
+}
+
+// How `zdoc cheatsheet` ranks items and how many of each it keeps
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CheatsheetConfig {
+    // Points lost per module below the one summarized
+    pub depth: f64,
+    // Points for having docs, and for having a code example in them
+    pub docs: f64,
+    pub examples: f64,
+    // Points lost by deprecated items
+    pub deprecated: f64,
+    // Points for methods that make the type (`new`, `with_*`, returning `Self`)
+    pub constructor: f64,
+    // Most items shown per section, and methods per type
+    pub types: usize,
+    pub methods: usize,
+    pub functions: usize,
+    pub traits: usize,
+    pub macros: usize,
+}
+
+impl Default for CheatsheetConfig {
+    fn default() -> Self {
+        CheatsheetConfig {
+            depth: 3.0,
+            docs: 1.0,
+            examples: 2.0,
+            deprecated: 10.0,
+            constructor: 2.0,
+            types: 12,
+            methods: 4,
+            functions: 10,
+            traits: 8,
+            macros: 12,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IgnoreConfig {
+    // Globs over full item paths to leave out of reports
+    pub paths: Vec<String>,
+}
+
+// The newer-dependencies footer, for `outdated`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutdatedConfig {
+    // End commands with a line saying how many direct dependencies are behind
+    pub notify: bool,
+}
+
+// Dependencies the public API mustn't expose, for `public-deps --check`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PublicDepsConfig {
+    // Package names
+    pub deny: Vec<String>,
+}
+
+// Where docs and releases are fetched from, for `sources`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct SourcesConfig {
+    // Instead of docs.rs and the crates.io index, for every crate
+    pub docs_url: Option<String>,
+    pub registry_api: Option<String>,
+    // A bearer token for those hosts, or the environment variable holding it
+    pub token: Option<String>,
+    pub token_env: Option<String>,
+    // Name -> a private registry with its own docs server
+    pub registries: BTreeMap<String, RegistryConfig>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct RegistryConfig {
+    // The registry's index as the lockfile names it (`sparse+https://...`)
+    pub index: Option<String>,
+    pub docs_url: String,
+    // The sparse index to look releases up in (default: `index`)
+    pub registry_api: Option<String>,
+    // A bearer token for its docs server and index, or the environment
+    // variable holding it
+    pub token: Option<String>,
+    pub token_env: Option<String>,
+    // Globs over crate names served by this registry, for crates outside
+    // the project's lockfile
+    pub crates: Vec<String>,
+}
+
+impl Config {
+    // A missing file means the defaults; an unreadable or invalid one is an error
+    pub fn load(workspace_root: &Path) -> Result<Self> {
+        let path = workspace_root.join(CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+        let text = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&text).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    // Set `[rustdoc] toolchain` in the config file in `dir`, creating either
+    // as needed and leaving the rest of the file as it is
+    pub fn set_toolchain(dir: &Path, toolchain: &str) -> Result<PathBuf> {
+        let path = dir.join(CONFIG_FILE);
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(err) => {
+                return Err(err).with_context(|| format!("Failed to read {}", path.display()));
+            }
+        };
+        let setting = format!("toolchain = {:?}", toolchain);
+        let mut lines: Vec<String> = text.lines().map(String::from).collect();
+        let section = lines.iter().position(|line| line.trim() == "[rustdoc]");
+        match section {
+            Some(start) => {
+                let end = lines[start + 1..]
+                    .iter()
+                    .position(|line| line.trim_start().starts_with('['))
+                    .map_or(lines.len(), |i| start + 1 + i);
+                let existing = lines[start + 1..end]
+                    .iter()
+                    .position(|line| line.split('=').next().map(str::trim) == Some("toolchain"));
+                match existing {
+                    Some(i) => lines[start + 1 + i] = setting,
+                    None => lines.insert(start + 1, setting),
+                }
+            }
+            None => {
+                if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+                    lines.push(String::new());
+                }
+                lines.push("[rustdoc]".to_string());
+                lines.push(setting);
+            }
+        }
+        let mut text = lines.join("\n");
+        text.push('\n');
+        // Don't write a file that would no longer load
+        toml::from_str::<Config>(&text)
+            .with_context(|| format!("Failed to update {}", path.display()))?;
+        fs::write(&path, text).with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(path)
+    }
+
+    // The nearest directory at or above `dir` with a config file
+    pub fn find(dir: &Path) -> Option<PathBuf> {
+        dir.ancestors()
+            .find(|dir| dir.join(CONFIG_FILE).is_file())
+            .map(Path::to_path_buf)
+    }
+}
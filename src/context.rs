@@ -0,0 +1,415 @@
+// `zdoc context`: a single markdown blob describing one item, meant as
+// grounding for an assistant prompt.
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::HashSet;
+
+use crate::items::{self, ResolvedItem};
+
+// A type referenced (directly or transitively) from the primary item's signature
+struct RelatedItem {
+    path: String,
+    declaration: String,
+    docs: Option<String>,
+}
+
+pub struct ContextBundle {
+    path: String,
+    declaration: String,
+    docs: String,
+    examples: Vec<String>,
+    related: Vec<RelatedItem>,
+    owner: Option<String>,
+    siblings: Vec<String>,
+}
+
+impl ContextBundle {
+    // Gather everything for `target`, following signature types `depth` levels deep
+    pub fn build(json: &Value, target: &ResolvedItem, depth: usize) -> Self {
+        let (docs, blocks) = items::split_docs(items::item_docs(target.item).unwrap_or(""));
+        let examples = blocks
+            .iter()
+            .filter(|block| block.is_rust())
+            .map(|block| block.visible_code())
+            .collect();
+
+        // Breadth-first over the types mentioned in signatures. Types from other
+        // crates have no index entry and are skipped.
+        let mut related = Vec::new();
+        let mut seen: HashSet<String> = HashSet::from([target.id.clone()]);
+        let mut frontier = vec![target.item];
+        for _ in 0..depth {
+            let mut next = Vec::new();
+            for item in frontier {
+                for id in signature_type_ids(json, item) {
+                    if !seen.insert(id.clone()) {
+                        continue;
+                    }
+                    let Some(type_item) = items::lookup(json, &id) else {
+                        continue;
+                    };
+                    let path = items::canonical_path(json, &id)
+                        .map(|path| path.join("::"))
+                        .unwrap_or_else(|| items::item_name(type_item).unwrap_or("_").to_string());
+                    related.push(RelatedItem {
+                        path,
+                        declaration: items::declaration(type_item),
+                        docs: items::item_docs(type_item).map(String::from),
+                    });
+                    next.push(type_item);
+                }
+            }
+            frontier = next;
+        }
+
+        // The other methods of the type a method belongs to
+        let mut owner = None;
+        let mut siblings = Vec::new();
+        if let Some(owner_item) = target
+            .owner
+            .as_deref()
+            .and_then(|id| items::lookup(json, id))
+            && items::item_kind(target.item) == Some("function")
+        {
+            owner = items::item_name(owner_item).map(String::from);
+            for impl_id in items::impl_ids(owner_item) {
+                let Some(impl_item) = items::lookup(json, &impl_id) else {
+                    continue;
+                };
+                if !items::is_inherent_impl(impl_item) {
+                    continue;
+                }
+                for member_id in items::impl_member_ids(impl_item) {
+                    if member_id == target.id {
+                        continue;
+                    }
+                    if let Some(member) = items::lookup(json, &member_id)
+                        && items::item_kind(member) == Some("function")
+                    {
+                        siblings.push(items::declaration(member));
+                    }
+                }
+            }
+            siblings.sort();
+        }
+
+        ContextBundle {
+            path: target.full_path(),
+            declaration: items::declaration(target.item),
+            docs,
+            examples,
+            related,
+            owner,
+            siblings,
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = format!("# `{}`\n\n```rust\n{}\n```\n", self.path, self.declaration);
+
+        if !self.docs.is_empty() {
+            out.push_str(&format!("\n{}\n", self.docs));
+        }
+
+        if !self.examples.is_empty() {
+            out.push_str("\n## Examples\n");
+            for example in &self.examples {
+                out.push_str(&format!("\n```rust\n{}\n```\n", example));
+            }
+        }
+
+        if !self.related.is_empty() {
+            out.push_str("\n## Referenced types\n");
+            for item in &self.related {
+                out.push_str(&format!(
+                    "\n### `{}`\n\n```rust\n{}\n```\n",
+                    item.path, item.declaration
+                ));
+                if let Some(docs) = &item.docs {
+                    out.push_str(&format!("\n{}\n", docs));
+                }
+            }
+        }
+
+        if !self.siblings.is_empty() {
+            let owner = self.owner.as_deref().unwrap_or("this type");
+            out.push_str(&format!("\n## Other methods on `{}`\n\n", owner));
+            for sibling in &self.siblings {
+                out.push_str(&format!("- `{}`\n", sibling));
+            }
+        }
+
+        out
+    }
+
+    // Shrink the bundle until it renders within `max_chars`. Content is removed
+    // in a fixed order, least important first, one unit at a time:
+    //   1. referenced types' docs are cut to their first paragraph (last type first)
+    //   2. referenced types' docs are dropped (last type first)
+    //   3. sibling methods are dropped (last first)
+    //   4. referenced types are dropped (last first)
+    //   5. examples are dropped (last first)
+    //   6. the item's own docs are truncated
+    // The item's path and declaration are never removed, so the result can
+    // still exceed a very small budget.
+    pub fn fit_to_budget(&mut self, max_chars: usize) {
+        loop {
+            let len = self.render().chars().count();
+            if len <= max_chars || !self.shrink_once(len - max_chars) {
+                break;
+            }
+        }
+    }
+
+    fn shrink_once(&mut self, overflow: usize) -> bool {
+        if let Some(item) = self.related.iter_mut().rev().find(|item| {
+            item.docs
+                .as_deref()
+                .is_some_and(|docs| items::first_paragraph(docs) != docs)
+        }) {
+            let docs = item.docs.take().unwrap_or_default();
+            item.docs = Some(items::first_paragraph(&docs).to_string());
+            return true;
+        }
+        if let Some(item) = self
+            .related
+            .iter_mut()
+            .rev()
+            .find(|item| item.docs.is_some())
+        {
+            item.docs = None;
+            return true;
+        }
+        if self.siblings.pop().is_some() || self.related.pop().is_some() {
+            return true;
+        }
+        if self.examples.pop().is_some() {
+            return true;
+        }
+        if self.docs.is_empty() {
+            return false;
+        }
+
+        // Leave room for the ellipsis marker
+        let keep = self.docs.chars().count().saturating_sub(overflow + 1);
+        self.docs = self
+            .docs
+            .chars()
+            .take(keep)
+            .collect::<String>()
+            .trim_end()
+            .to_string();
+        if !self.docs.is_empty() {
+            self.docs.push('…');
+        }
+        true
+    }
+}
+
+// Ids of the types an item's signature mentions, including those of its fields
+// and variants (which are separate index entries)
+fn signature_type_ids(json: &Value, item: &Value) -> Vec<String> {
+    let mut ids = Vec::new();
+    let Some(inner) = items::item_inner(item) else {
+        return ids;
+    };
+    items::referenced_type_ids(inner, &mut ids);
+
+    let mut children = items::field_ids(inner).unwrap_or_default();
+    if let Some(variants) = inner.get("variants").and_then(|v| v.as_array()) {
+        children.extend(variants.iter().filter_map(items::id_key));
+    }
+    for child_id in children {
+        let Some(child) = items::lookup(json, &child_id) else {
+            continue;
+        };
+        for id in signature_type_ids(json, child) {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+    }
+    ids
+}
+
+// Handler for `zdoc context`
+pub fn run(
+    metadata: &cargo_metadata::Metadata,
+    item_path: &str,
+    depth: usize,
+    max_chars: usize,
+    copy: bool,
+) -> Result<()> {
+    let located = items::locate(metadata, item_path)?;
+    let target = located.resolved();
+
+    let mut bundle = ContextBundle::build(&located.json, &target, depth);
+    bundle.fit_to_budget(max_chars);
+    let text = bundle.render();
+
+    if copy {
+        crate::clipboard::copy(
+            text.trim_end(),
+            &format!(
+                "context for {} ({} chars)",
+                target.full_path(),
+                text.chars().count()
+            ),
+        );
+    } else {
+        print!("{}", text);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn related(path: &str, docs: Option<&str>) -> RelatedItem {
+        RelatedItem {
+            path: path.to_string(),
+            declaration: format!("pub struct {};", path),
+            docs: docs.map(String::from),
+        }
+    }
+
+    fn bundle() -> ContextBundle {
+        ContextBundle {
+            path: "demo::Widget::new".to_string(),
+            declaration: "pub fn new(config: Config) -> Widget".to_string(),
+            docs: "Makes a widget.\n\nThe widget starts hidden.".to_string(),
+            examples: vec![
+                "let w = Widget::new(Config::default());".to_string(),
+                "let w = Widget::new(Config::quiet());".to_string(),
+            ],
+            related: vec![
+                related("demo::Config", Some("Settings.\n\nAll optional.")),
+                related("demo::Widget", Some("A widget.\n\nDraws itself.")),
+            ],
+            owner: Some("Widget".to_string()),
+            siblings: vec![
+                "pub fn show(&mut self)".to_string(),
+                "pub fn hide(&mut self)".to_string(),
+            ],
+        }
+    }
+
+    // Every state `fit_to_budget` passes through, one per unit removed
+    fn shrink_steps(mut bundle: ContextBundle) -> Vec<String> {
+        let mut steps = vec![bundle.render()];
+        while bundle.shrink_once(1) {
+            steps.push(bundle.render());
+        }
+        steps
+    }
+
+    #[test]
+    fn fits_without_changes_when_under_budget() {
+        let mut fitted = bundle();
+        fitted.fit_to_budget(usize::MAX);
+        assert_eq!(fitted.render(), bundle().render());
+    }
+
+    #[test]
+    fn removes_content_in_documented_order() {
+        let mut shrinking = bundle();
+        let mut step = || {
+            assert!(shrinking.shrink_once(1));
+            (
+                shrinking
+                    .related
+                    .iter()
+                    .map(|item| item.docs.clone())
+                    .collect::<Vec<_>>(),
+                shrinking.siblings.len(),
+                shrinking.related.len(),
+                shrinking.examples.len(),
+            )
+        };
+        let some = |docs: &str| Some(docs.to_string());
+
+        // 1. Referenced types' docs cut to their first paragraph, last first
+        assert_eq!(
+            step(),
+            (
+                vec![some("Settings.\n\nAll optional."), some("A widget.")],
+                2,
+                2,
+                2
+            )
+        );
+        assert_eq!(
+            step(),
+            (vec![some("Settings."), some("A widget.")], 2, 2, 2)
+        );
+        // 2. Then dropped, last first
+        assert_eq!(step(), (vec![some("Settings."), None], 2, 2, 2));
+        assert_eq!(step(), (vec![None, None], 2, 2, 2));
+        // 3. Sibling methods, 4. referenced types, 5. examples, last first
+        assert_eq!(step(), (vec![None, None], 1, 2, 2));
+        assert_eq!(step(), (vec![None, None], 0, 2, 2));
+        assert_eq!(step(), (vec![None], 0, 1, 2));
+        assert_eq!(step(), (vec![], 0, 0, 2));
+        assert_eq!(step(), (vec![], 0, 0, 1));
+        assert_eq!(step(), (vec![], 0, 0, 0));
+        // 6. The item's own docs last
+        assert_eq!(
+            shrinking.docs,
+            "Makes a widget.\n\nThe widget starts hidden."
+        );
+        assert!(shrinking.shrink_once(10));
+        assert!(shrinking.docs.ends_with('…'));
+        assert!(shrinking.docs.chars().count() < 40);
+    }
+
+    #[test]
+    fn each_step_renders_shorter() {
+        let steps = shrink_steps(bundle());
+        for pair in steps.windows(2) {
+            assert!(pair[1].chars().count() < pair[0].chars().count());
+        }
+    }
+
+    #[test]
+    fn fits_within_a_reachable_budget() {
+        for max_chars in [400, 300, 200, 150] {
+            let mut fitted = bundle();
+            fitted.fit_to_budget(max_chars);
+            let text = fitted.render();
+            assert!(
+                text.chars().count() <= max_chars,
+                "{} chars over a budget of {}:\n{}",
+                text.chars().count(),
+                max_chars,
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn keeps_path_and_declaration_under_any_budget() {
+        let mut fitted = bundle();
+        fitted.fit_to_budget(0);
+        assert_eq!(
+            fitted.render(),
+            "# `demo::Widget::new`\n\n```rust\npub fn new(config: Config) -> Widget\n```\n"
+        );
+        assert!(!fitted.shrink_once(1));
+    }
+
+    #[test]
+    fn truncates_docs_by_characters() {
+        let mut fitted = bundle();
+        fitted.docs = "Crée un widget à partir de la configuration donnée.".to_string();
+        fitted.examples.clear();
+        fitted.related.clear();
+        fitted.siblings.clear();
+        let full = fitted.render().chars().count();
+        fitted.fit_to_budget(full - 10);
+        assert!(fitted.render().chars().count() <= full - 10);
+        assert!(fitted.docs.starts_with("Crée un widget"));
+        assert!(fitted.docs.ends_with('…'));
+    }
+}
@@ -0,0 +1,252 @@
+// Where a crate's rustdoc JSON comes from, and the one way commands load
+// it: generated in the project by `cargo doc`, fetched from docs.rs (through
+// the cache), read from a file, or taken from the cache alone. Whatever the
+// source, the file goes through `FetchedDocs::read`, so the memory limit,
+// the content hash, the check that it's rustdoc JSON at all and the error
+// messages are the same everywhere.
+use anyhow::{Context, Result};
+use cargo_metadata::Metadata;
+use flate2::read::GzDecoder;
+use serde_json::Value;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tracing::{debug, info, warn};
+
+use crate::{cache, http, rustdoc_json, sources, timing};
+
+#[derive(Clone, Copy)]
+pub enum DocSource<'a> {
+    // `cargo doc` output in the project's doc directory
+    Local { metadata: &'a Metadata },
+    // docs.rs, or the crate's registry's docs server; exact versions are
+    // cached, and with `--offline` only the cache is used
+    DocsRs { version: &'a str },
+    // A rustdoc JSON file, e.g. from a `cargo doc` run of zdoc's own
+    File { path: &'a Path },
+    // The cached docs of an exact version, never fetched
+    Cache { version: &'a str },
+}
+
+// Rustdoc JSON, along with a hash of the raw payload
+pub struct FetchedDocs {
+    pub json: Value,
+    pub content_hash: u64,
+}
+
+impl FetchedDocs {
+    // Parse a JSON file, hashing it as it's read
+    fn read(file: fs::File, what: &str) -> Result<Self> {
+        let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+        rustdoc_json::check_memory(size, what)?;
+
+        let mut phase = timing::phase(format!("parse {}", what));
+        let (json, content_hash) = rustdoc_json::read_hashed(file)
+            .with_context(|| format!("Failed to parse the JSON of {}", what))?;
+        phase.items(index_len(&json));
+        debug!(
+            "Parsed {} ({} KB) in {:.2?}",
+            what,
+            size / 1024,
+            phase.elapsed()
+        );
+        check_format(&json, what)?;
+        Ok(FetchedDocs { json, content_hash })
+    }
+
+    // The concrete version docs.rs resolved the request to (e.g. `1.0` -> `1.0.5`)
+    pub fn crate_version(&self) -> Option<&str> {
+        self.json.get("crate_version").and_then(|v| v.as_str())
+    }
+}
+
+fn index_len(json: &Value) -> usize {
+    json.get("index")
+        .and_then(|v| v.as_object())
+        .map_or(0, |index| index.len())
+}
+
+// Rustdoc JSON of any format version has a `format_version` and an `index`;
+// anything else is some other JSON file, which would otherwise just look
+// like a crate without items
+fn check_format(json: &Value, what: &str) -> Result<()> {
+    let Some(version) = json.get("format_version").and_then(|v| v.as_u64()) else {
+        anyhow::bail!("{} isn't rustdoc JSON (it has no `format_version`)", what);
+    };
+    if json.get("index").is_none_or(|index| !index.is_object()) {
+        anyhow::bail!("{} isn't rustdoc JSON (it has no item index)", what);
+    }
+    debug!("{} is rustdoc JSON format {}", what, version);
+    Ok(())
+}
+
+// Rustdoc names the file after the crate, which uses underscores
+pub fn crate_json_path(doc_dir: &Path, crate_name: &str) -> PathBuf {
+    doc_dir.join(format!("{}.json", crate_name.replace('-', "_")))
+}
+
+// Whether `cargo doc` generated docs for the crate in the project
+pub fn is_generated(metadata: &Metadata, crate_name: &str) -> bool {
+    crate_json_path(&crate::target_dir::doc_dir(metadata), crate_name).exists()
+}
+
+// The error for a crate with no generated docs, which is most often a typo
+pub fn missing_docs(metadata: &Metadata, crate_name: &str) -> anyhow::Error {
+    anyhow::anyhow!(
+        "No JSON docs were generated for '{}'{}",
+        crate_name,
+        crate::selection::did_you_mean(metadata, crate_name)
+    )
+}
+
+// Load a crate's rustdoc JSON from `source`
+pub async fn load_crate_json(crate_name: &str, source: DocSource<'_>) -> Result<FetchedDocs> {
+    match source {
+        DocSource::DocsRs { version } if http::is_offline() => {
+            read_crate_json(crate_name, DocSource::Cache { version })
+        }
+        DocSource::DocsRs { version } => fetch(crate_name, version).await,
+        source => read_crate_json(crate_name, source),
+    }
+}
+
+// `load_crate_json` for the sources on disk, for code that can't wait on
+// the network
+pub fn read_crate_json(crate_name: &str, source: DocSource<'_>) -> Result<FetchedDocs> {
+    match source {
+        DocSource::Local { metadata } => {
+            let path = crate_json_path(&crate::target_dir::doc_dir(metadata), crate_name);
+            let file = open(&path)?.ok_or_else(|| missing_docs(metadata, crate_name))?;
+            FetchedDocs::read(file, crate_name)
+        }
+        DocSource::File { path } => {
+            let file = open(path)?.with_context(|| {
+                format!("No rustdoc JSON for {} at {}", crate_name, path.display())
+            })?;
+            FetchedDocs::read(file, &path.display().to_string())
+        }
+        DocSource::Cache { version } => {
+            let what = format!("{} v{}", crate_name, version);
+            let file = cache::open(crate_name, version)?.with_context(|| {
+                format!(
+                    "The docs of {} aren't cached{}",
+                    what,
+                    if http::is_offline() {
+                        ", and --offline keeps them from being fetched"
+                    } else {
+                        ""
+                    }
+                )
+            })?;
+            info!("Using cached documentation for {}", what);
+            FetchedDocs::read(file, &what)
+        }
+        DocSource::DocsRs { version } => anyhow::bail!(
+            "The docs of {} v{} have to be fetched from {}",
+            crate_name,
+            version,
+            sources::for_crate(crate_name).docs_host()
+        ),
+    }
+}
+
+// A file to read, or None if there is none
+fn open(path: &Path) -> Result<Option<fs::File>> {
+    match fs::File::open(path) {
+        Ok(file) => Ok(Some(file)),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err).with_context(|| format!("Failed to read {}", path.display())),
+    }
+}
+
+// Fetch rustdoc JSON from docs.rs (or the crate's registry's docs server),
+// or from the cache for exact versions
+async fn fetch(crate_name: &str, version: &str) -> Result<FetchedDocs> {
+    let what = format!("{} v{}", crate_name, version);
+    if let Some(file) = cache::open(crate_name, version)? {
+        info!("Using cached documentation for {}", what);
+        return FetchedDocs::read(file, &what);
+    }
+    debug!("Cache miss for {} v{}", crate_name, version);
+
+    let source = sources::for_crate(crate_name);
+    let url = source.json_url(crate_name, version);
+
+    info!("Fetching documentation for {} v{}...", crate_name, version);
+    let phase = timing::phase(format!("fetch {}", what));
+
+    let started = Instant::now();
+    let response = source
+        .get(&http::client()?, &url)
+        .send()
+        .await
+        .context(format!("Failed to fetch docs from {}", url))?;
+    debug!(
+        "GET {} -> {} in {:.2?}",
+        url,
+        response.status(),
+        started.elapsed()
+    );
+
+    if matches!(response.status().as_u16(), 401 | 403) {
+        anyhow::bail!(
+            "Failed to fetch docs for {} v{}: HTTP {}. {} needs a token: set `token-env` for it in \
+             the `[sources]` of {} (or `ZDOC_TOKEN`)",
+            crate_name,
+            version,
+            response.status(),
+            source.docs_host(),
+            crate::config::CONFIG_FILE
+        );
+    }
+    if !response.status().is_success() {
+        anyhow::bail!(
+            "Failed to fetch docs for {} v{}: HTTP {}. Make sure the version exists on {} and has JSON docs available (added to docs.rs in May 2025).{}",
+            crate_name,
+            version,
+            response.status(),
+            source.docs_host(),
+            // A crate that doesn't exist at all is most often a typo
+            if response.status() == reqwest::StatusCode::NOT_FOUND {
+                crate::selection::did_you_mean_remote(crate_name)
+            } else {
+                String::new()
+            }
+        );
+    }
+
+    let compressed_bytes = response
+        .bytes()
+        .await
+        .context("Failed to read response body")?;
+
+    debug!("Downloaded {} KB compressed", compressed_bytes.len() / 1024);
+
+    // Decompress to disk rather than into memory; the file becomes the
+    // cache entry if it turns out to be an exact release
+    let mut staged = cache::staging_file(crate_name).or_else(|err| {
+        warn!("Failed to use the cache: {:#}", err);
+        tempfile::NamedTempFile::new().context("Failed to create a temporary file")
+    })?;
+    std::io::copy(
+        &mut GzDecoder::new(&compressed_bytes[..]),
+        staged.as_file_mut(),
+    )
+    .context("Failed to decompress gzip data")?;
+    drop(compressed_bytes);
+    drop(phase);
+
+    let file = staged
+        .reopen()
+        .context("Failed to read the downloaded docs")?;
+    let docs = FetchedDocs::read(file, &what)?;
+    // Only cache requests naming the exact release they resolved to
+    if docs.crate_version() == Some(version) {
+        match cache::keep(staged, crate_name, version) {
+            Ok(()) => debug!("Cached {}", what),
+            Err(err) => warn!("Failed to cache the docs: {:#}", err),
+        }
+    }
+    Ok(docs)
+}
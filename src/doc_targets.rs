@@ -0,0 +1,130 @@
+// The targets whose JSON docs `search` reads. Besides each package's
+// library, `cargo doc` documents a workspace member's binaries, each into a
+// JSON file named after the binary, and with `--examples` its examples too.
+// A binary named like its package's library would share the library's file,
+// so cargo documents only the library and the binary is left out.
+//
+// Binaries are always documented with their private items, since that's
+// most of what they have; `--private` documents libraries and examples the
+// same way.
+use cargo_metadata::{Metadata, Package, Target, TargetKind};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static PRIVATE: AtomicBool = AtomicBool::new(false);
+static EXAMPLES: AtomicBool = AtomicBool::new(false);
+
+// Document private items of libraries and examples (`--private`)
+pub fn set_private(private: bool) {
+    PRIVATE.store(private, Ordering::Relaxed);
+}
+
+// Document and search examples too (`--examples`)
+pub fn set_examples(examples: bool) {
+    EXAMPLES.store(examples, Ordering::Relaxed);
+}
+
+pub fn examples() -> bool {
+    EXAMPLES.load(Ordering::Relaxed)
+}
+
+// The extra arguments every `cargo doc` run gets
+pub fn cargo_args() -> Vec<&'static str> {
+    if PRIVATE.load(Ordering::Relaxed) {
+        vec!["--document-private-items"]
+    } else {
+        Vec::new()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Lib,
+    Bin,
+    Example,
+}
+
+pub struct DocTarget {
+    // The package the target belongs to, which results are listed under
+    pub package: String,
+    // The crate name rustdoc documents it as
+    pub name: String,
+    pub kind: Kind,
+    // Whether the package is a workspace member, documented as zdoc asks
+    // rather than as a dependency
+    pub local: bool,
+}
+
+impl DocTarget {
+    pub fn json_path(&self, doc_dir: &Path) -> PathBuf {
+        crate::doc_source::crate_json_path(doc_dir, &self.name)
+    }
+
+    // What results from this target are tagged with: `bin: server`, or
+    // nothing for a library
+    pub fn label(&self) -> Option<String> {
+        match self.kind {
+            Kind::Lib => None,
+            Kind::Bin => Some(format!("bin: {}", self.name)),
+            Kind::Example => Some(format!("example: {}", self.name)),
+        }
+    }
+
+    // Whether this target's JSON should include private items, for the
+    // ones it depends on the flags for
+    pub fn wants_private(&self) -> Option<bool> {
+        match self.kind {
+            Kind::Bin => None,
+            _ if !self.local => None,
+            _ => Some(PRIVATE.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+pub fn is_library(target: &Target) -> bool {
+    target.kind.iter().any(|kind| {
+        matches!(
+            kind,
+            TargetKind::Lib
+                | TargetKind::RLib
+                | TargetKind::DyLib
+                | TargetKind::CDyLib
+                | TargetKind::StaticLib
+                | TargetKind::ProcMacro
+        )
+    })
+}
+
+// The targets of `packages` that have JSON docs: every library, plus the
+// binaries (and with `--examples`, the examples) of workspace members.
+// Binaries needing features zdoc doesn't enable are left out.
+pub fn of_packages(metadata: &Metadata, packages: &[&Package]) -> Vec<DocTarget> {
+    let mut targets = Vec::new();
+    for package in packages {
+        let local = metadata.workspace_members.contains(&package.id);
+        let library = package.targets.iter().find(|target| is_library(target));
+        let target = |name: &str, kind| DocTarget {
+            package: package.name.to_string(),
+            name: name.to_string(),
+            kind,
+            local,
+        };
+        if let Some(library) = library {
+            targets.push(target(&library.name, Kind::Lib));
+        }
+        if !local {
+            continue;
+        }
+        for candidate in &package.targets {
+            if library.is_some_and(|library| library.name == candidate.name) {
+                continue;
+            }
+            if candidate.is_bin() && candidate.doc && candidate.required_features.is_empty() {
+                targets.push(target(&candidate.name, Kind::Bin));
+            } else if candidate.is_example() && examples() {
+                targets.push(target(&candidate.name, Kind::Example));
+            }
+        }
+    }
+    targets
+}
@@ -0,0 +1,297 @@
+// How docs.rs built a release's docs, from `[package.metadata.docs.rs]` in
+// its `Cargo.toml`: the features it enabled (`features`, `all-features`,
+// `no-default-features`), `rustdoc-args` and `default-target`. When two
+// releases were built differently, a diff of their docs.rs JSON shows items
+// appearing and vanishing with the settings rather than the code, so
+// `zdoc diff` compares the two and says so up front.
+//
+// The manifest is read out of the release's `.crate` file, downloaded the
+// way cargo does: from the `dl` URL in the registry index's `config.json`.
+use anyhow::{Context, Result};
+use colored::Colorize;
+use flate2::read::GzDecoder;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::io::Read;
+use tracing::{debug, warn};
+
+use crate::http;
+use crate::sources;
+use crate::style::{self, Role};
+
+// A tar header, and the unit tar pads file contents to
+const BLOCK: usize = 512;
+
+// The docs.rs settings that change what a release's docs contain
+#[derive(Debug, Default, PartialEq, Eq)]
+struct BuildConfig {
+    features: BTreeSet<String>,
+    all_features: bool,
+    no_default_features: bool,
+    rustdoc_args: Vec<String>,
+    default_target: Option<String>,
+}
+
+impl BuildConfig {
+    // The settings in a `Cargo.toml`; docs.rs also accepts the table as
+    // `[package.metadata.docsrs]`
+    fn from_manifest(manifest: &str) -> Result<Self> {
+        let manifest: toml::Table = toml::from_str(manifest).context("Invalid Cargo.toml")?;
+        let metadata = manifest
+            .get("package")
+            .and_then(|package| package.get("metadata"));
+        let Some(table) = metadata.and_then(|metadata| {
+            metadata
+                .get("docs")
+                .and_then(|docs| docs.get("rs"))
+                .or_else(|| metadata.get("docsrs"))
+        }) else {
+            return Ok(BuildConfig::default());
+        };
+        let strings = |key: &str| -> Vec<String> {
+            table
+                .get(key)
+                .and_then(|v| v.as_array())
+                .map(|values| {
+                    values
+                        .iter()
+                        .filter_map(|v| v.as_str())
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let flag = |key: &str| table.get(key).and_then(|v| v.as_bool()) == Some(true);
+        Ok(BuildConfig {
+            features: strings("features").into_iter().collect(),
+            all_features: flag("all-features"),
+            no_default_features: flag("no-default-features"),
+            rustdoc_args: strings("rustdoc-args"),
+            default_target: table
+                .get("default-target")
+                .and_then(|v| v.as_str())
+                .map(String::from),
+        })
+    }
+}
+
+// How the build settings of two releases differ, one line per setting
+pub struct Changes {
+    ver1: String,
+    ver2: String,
+    lines: Vec<String>,
+}
+
+fn list(values: &[String]) -> String {
+    if values.is_empty() {
+        "none".to_string()
+    } else {
+        values.join(" ")
+    }
+}
+
+impl Changes {
+    fn between(ver1: &str, ver2: &str, old: &BuildConfig, new: &BuildConfig) -> Option<Self> {
+        let mut lines = Vec::new();
+        let added: Vec<&str> = new
+            .features
+            .difference(&old.features)
+            .map(String::as_str)
+            .collect();
+        let removed: Vec<&str> = old
+            .features
+            .difference(&new.features)
+            .map(String::as_str)
+            .collect();
+        if !added.is_empty() || !removed.is_empty() {
+            let mut parts: Vec<String> = added.iter().map(|name| format!("+{}", name)).collect();
+            parts.extend(removed.iter().map(|name| format!("-{}", name)));
+            lines.push(format!("features: {}", parts.join(" ")));
+        }
+        if old.all_features != new.all_features {
+            lines.push(format!(
+                "all-features: {} -> {}",
+                old.all_features, new.all_features
+            ));
+        }
+        if old.no_default_features != new.no_default_features {
+            lines.push(format!(
+                "no-default-features: {} -> {}",
+                old.no_default_features, new.no_default_features
+            ));
+        }
+        if old.rustdoc_args != new.rustdoc_args {
+            lines.push(format!(
+                "rustdoc-args: {} -> {}",
+                list(&old.rustdoc_args),
+                list(&new.rustdoc_args)
+            ));
+        }
+        if old.default_target != new.default_target {
+            let target =
+                |target: &Option<String>| target.clone().unwrap_or_else(|| "host".to_string());
+            lines.push(format!(
+                "default-target: {} -> {}",
+                target(&old.default_target),
+                target(&new.default_target)
+            ));
+        }
+        (!lines.is_empty()).then(|| Changes {
+            ver1: ver1.to_string(),
+            ver2: ver2.to_string(),
+            lines,
+        })
+    }
+
+    // The notice heading a text diff, blaming the settings for as many of
+    // the `added` and `removed` items as they could account for
+    pub fn print(&self, added: usize, removed: usize) {
+        let heading = format!(
+            "Note: docs.rs built v{} and v{} with different settings:",
+            self.ver1, self.ver2
+        );
+        println!("{}", style::paint(Role::Modified, &heading).bold());
+        for line in &self.lines {
+            println!("  {}", style::paint(Role::Modified, line));
+        }
+        let note = format!(
+            "Some of the {} added and {} removed items may come from these settings rather than \
+             from code changes; rerun with --features to document both versions locally with the \
+             same features.",
+            added, removed
+        );
+        println!("{}\n", style::paint(Role::Muted, &note));
+    }
+
+    pub fn to_json(&self) -> Value {
+        serde_json::json!({
+            "from": self.ver1,
+            "to": self.ver2,
+            "changes": self.lines,
+        })
+    }
+}
+
+// The file at `path` in a `.crate` file (a gzipped tarball), reading only
+// as far as it. Cargo puts `Cargo.toml` near the start.
+fn read_from_crate(bytes: &[u8], path: &str) -> Result<Option<String>> {
+    let mut tar = GzDecoder::new(bytes);
+    let mut header = [0u8; BLOCK];
+    loop {
+        if tar.read_exact(&mut header).is_err() || header.iter().all(|&b| b == 0) {
+            return Ok(None);
+        }
+        let field = |start: usize, end: usize| {
+            let raw = &header[start..end];
+            let len = raw.iter().position(|&b| b == 0).unwrap_or(raw.len());
+            String::from_utf8_lossy(&raw[..len]).into_owned()
+        };
+        // ustar keeps long paths' directories in a separate prefix field
+        let (name, prefix) = (field(0, 100), field(345, 500));
+        let name = if prefix.is_empty() {
+            name
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        let size =
+            usize::from_str_radix(field(124, 136).trim(), 8).context("Malformed .crate file")?;
+        if name == path {
+            let mut contents = vec![0u8; size];
+            tar.read_exact(&mut contents)
+                .context("Truncated .crate file")?;
+            return Ok(Some(String::from_utf8_lossy(&contents).into_owned()));
+        }
+        let padded = size.div_ceil(BLOCK) * BLOCK;
+        std::io::copy(&mut (&mut tar).take(padded as u64), &mut std::io::sink())
+            .context("Truncated .crate file")?;
+    }
+}
+
+// Where the registry serves `.crate` files: its index's `dl` template, with
+// `{crate}` and `{version}` markers or as a base URL cargo appends
+// `/{crate}/{version}/download` to
+async fn download_template(client: &reqwest::Client, crate_name: &str) -> Result<String> {
+    let source = sources::for_crate(crate_name);
+    let index = source
+        .index_url()
+        .with_context(|| format!("No sparse index is configured for {}", crate_name))?;
+    let url = format!("{}/config.json", index);
+    let body = source
+        .get(client, &url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .with_context(|| format!("Failed to fetch {}", url))?
+        .bytes()
+        .await
+        .with_context(|| format!("Failed to download {}", url))?;
+    let config: Value =
+        serde_json::from_slice(&body).with_context(|| format!("Invalid {}", url))?;
+    config
+        .get("dl")
+        .and_then(|v| v.as_str())
+        .map(String::from)
+        .with_context(|| format!("{} has no `dl` URL", url))
+}
+
+async fn fetch(
+    client: &reqwest::Client,
+    dl: &str,
+    crate_name: &str,
+    version: &str,
+) -> Result<BuildConfig> {
+    let url = if dl.contains("{crate}") || dl.contains("{version}") {
+        dl.replace("{crate}", crate_name)
+            .replace("{version}", version)
+    } else {
+        format!(
+            "{}/{}/{}/download",
+            dl.trim_end_matches('/'),
+            crate_name,
+            version
+        )
+    };
+    let source = sources::for_crate(crate_name);
+    let response = source
+        .get(client, &url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .with_context(|| format!("Failed to fetch {}", url))?;
+    debug!("GET {} -> {}", url, response.status());
+    let bytes = response
+        .bytes()
+        .await
+        .with_context(|| format!("Failed to download {}", url))?;
+    let path = format!("{}-{}/Cargo.toml", crate_name, version);
+    let manifest =
+        read_from_crate(&bytes, &path)?.with_context(|| format!("{} has no {}", url, path))?;
+    BuildConfig::from_manifest(&manifest)
+}
+
+// How docs.rs built `ver1` and `ver2` differently, if it did. This is only
+// a notice, so failing to find out (offline, or a registry without
+// downloads) is logged and the diff goes on without it.
+pub async fn compare(crate_name: &str, ver1: &str, ver2: &str) -> Option<Changes> {
+    if http::is_offline() {
+        debug!("Not comparing docs.rs build settings with --offline");
+        return None;
+    }
+    let configs = async {
+        let client = http::client()?;
+        let dl = download_template(&client, crate_name).await?;
+        let old = fetch(&client, &dl, crate_name, ver1).await?;
+        let new = fetch(&client, &dl, crate_name, ver2).await?;
+        anyhow::Ok((old, new))
+    };
+    match configs.await {
+        Ok((old, new)) => Changes::between(ver1, ver2, &old, &new),
+        Err(e) => {
+            warn!(
+                "Couldn't compare how docs.rs built the two versions: {:#}",
+                e
+            );
+            None
+        }
+    }
+}
@@ -0,0 +1,290 @@
+// Requests zdoc answers from a long-running process, and the docs it keeps
+// parsed between them. Frontends turn their input into a `Request` and
+// write out the `Value` `Engine::handle` answers with; `zdoc batch` (below)
+// reads them as JSON lines on stdin.
+//
+// Local docs are generated once, on the first request that needs them, and
+// every crate's JSON is read once per process, so a session of lookups in
+// one project costs one `cargo doc` and one parse per crate.
+use anyhow::{Context, Result, bail};
+use cargo_metadata::Metadata;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use crate::doc_source::{self, DocSource};
+use crate::ignore::Ignore;
+use crate::{items, reexports, show, signature};
+
+#[derive(Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Request {
+    // `zdoc search`: `crate` narrows it to one crate (required with
+    // `version`), else every workspace crate is searched
+    Search {
+        query: String,
+        #[serde(rename = "crate")]
+        crate_name: Option<String>,
+        version: Option<String>,
+        limit: Option<usize>,
+    },
+    // `zdoc show`, as JSON; with `version`, the path starts with the crate
+    Show {
+        path: String,
+        version: Option<String>,
+    },
+    // `zdoc signature`
+    Signature {
+        path: String,
+        version: Option<String>,
+    },
+    // Ends the session
+    Shutdown,
+}
+
+pub struct Engine {
+    // The project's metadata, or why there is none (local docs need it)
+    metadata: Result<Metadata, String>,
+    generated: bool,
+    // Parsed docs by crate and version (None for local docs)
+    docs: HashMap<(String, Option<String>), Rc<Value>>,
+    resolver: reexports::Resolver,
+    ignore: Ignore,
+}
+
+impl Engine {
+    pub fn new(metadata: Result<Metadata>) -> Result<Self> {
+        let (doc_dir, ignore) = match &metadata {
+            Ok(metadata) => (
+                crate::target_dir::doc_dir(metadata),
+                Ignore::load(metadata.workspace_root.as_std_path())?,
+            ),
+            Err(_) => (PathBuf::new(), Ignore::load_nearest()?),
+        };
+        Ok(Engine {
+            metadata: metadata.map_err(|e| format!("{:#}", e)),
+            generated: false,
+            docs: HashMap::new(),
+            resolver: reexports::Resolver::new(&doc_dir),
+            ignore,
+        })
+    }
+
+    fn metadata(&self) -> Result<&Metadata> {
+        self.metadata
+            .as_ref()
+            .map_err(|e| anyhow::anyhow!("{} (give a `version` to use docs.rs docs instead)", e))
+    }
+
+    // Generate the local docs, the first time any are needed
+    fn generate(&mut self) -> Result<()> {
+        if !self.generated {
+            crate::generate_json_docs(self.metadata()?)?;
+            self.generated = true;
+        }
+        Ok(())
+    }
+
+    async fn docs(&mut self, crate_name: &str, version: Option<&str>) -> Result<Rc<Value>> {
+        let key = (crate_name.to_string(), version.map(String::from));
+        if let Some(json) = self.docs.get(&key) {
+            return Ok(Rc::clone(json));
+        }
+        let json = match version {
+            Some(version) => {
+                doc_source::load_crate_json(crate_name, DocSource::DocsRs { version })
+                    .await?
+                    .json
+            }
+            None => {
+                self.generate()?;
+                let local = DocSource::Local {
+                    metadata: self.metadata()?,
+                };
+                doc_source::read_crate_json(crate_name, local)?.json
+            }
+        };
+        let json = Rc::new(json);
+        self.docs.insert(key, Rc::clone(&json));
+        Ok(json)
+    }
+
+    // The one item `path` names, with the docs it's in
+    async fn locate(
+        &mut self,
+        path: &str,
+        version: Option<&str>,
+    ) -> Result<(Rc<Value>, String, Vec<String>)> {
+        let (crate_names, segments) = match version {
+            // Docs.rs docs are one crate's, so the path has to say which
+            Some(_) => {
+                let mut segments: Vec<String> = path.split("::").map(String::from).collect();
+                let crate_name = segments[0].clone();
+                segments[0] = crate_name.replace('-', "_");
+                (vec![crate_name], segments)
+            }
+            None => {
+                // Which crates have docs is only known once they're generated
+                self.generate()?;
+                let metadata = self.metadata()?;
+                let scope = items::path_scope(metadata, path);
+                let crate_names = scope
+                    .crate_names
+                    .into_iter()
+                    .filter(|crate_name| doc_source::is_generated(metadata, crate_name))
+                    .collect();
+                (crate_names, scope.segments)
+            }
+        };
+        let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+        let mut found = Vec::new();
+        for crate_name in crate_names {
+            let json = self.docs(&crate_name, version).await?;
+            let ids: Vec<(String, Vec<String>)> = items::resolve_path(&json, &segments)
+                .into_iter()
+                .map(|resolved| (resolved.id, resolved.path))
+                .collect();
+            found.extend(
+                ids.into_iter()
+                    .map(|(id, path)| (Rc::clone(&json), id, path)),
+            );
+        }
+        match found.len() {
+            0 => bail!("No item matching '{}'", path),
+            1 => Ok(found.remove(0)),
+            _ => {
+                let candidates: Vec<String> =
+                    found.iter().map(|(_, _, path)| path.join("::")).collect();
+                bail!(
+                    "'{}' is ambiguous; candidates: {}",
+                    path,
+                    candidates.join(", ")
+                )
+            }
+        }
+    }
+
+    async fn search(
+        &mut self,
+        query: &str,
+        crate_name: Option<&str>,
+        version: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<Value> {
+        let crate_names: Vec<String> = match (crate_name, version) {
+            (Some(crate_name), _) => vec![crate_name.to_string()],
+            (None, Some(_)) => bail!("`version` needs `crate` too"),
+            (None, None) => {
+                let metadata = self.metadata()?;
+                metadata
+                    .workspace_packages()
+                    .iter()
+                    .map(|package| package.name.to_string())
+                    .collect()
+            }
+        };
+        let matcher = crate::NameMatcher::fuzzy(true, crate::Weights::default());
+        let mut results = Vec::new();
+        for crate_name in &crate_names {
+            let json = self.docs(crate_name, version).await?;
+            results.extend(
+                crate::search_json(&json, crate_name, query, &matcher, &mut self.resolver)?
+                    .into_iter()
+                    .filter(|result| !result.hidden),
+            );
+        }
+        let (mut results, _) = self
+            .ignore
+            .partition(results, |result| result.full_path.clone());
+        results.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.name.cmp(&b.name)));
+        results.truncate(limit.unwrap_or(crate::DEFAULT_SEARCH_RESULTS));
+        let results: Vec<Value> = results.iter().map(|result| result.to_json()).collect();
+        Ok(serde_json::json!({ "results": results }))
+    }
+
+    // The answer to one request; Shutdown is the frontend's to act on
+    pub async fn handle(&mut self, request: &Request) -> Result<Value> {
+        match request {
+            Request::Search {
+                query,
+                crate_name,
+                version,
+                limit,
+            } => {
+                self.search(query, crate_name.as_deref(), version.as_deref(), *limit)
+                    .await
+            }
+            Request::Show { path, version } => {
+                let (json, id, path) = self.locate(path, version.as_deref()).await?;
+                let target = items::ResolvedItem {
+                    item: items::lookup(&json, &id).context("located ids come from the index")?,
+                    id,
+                    path,
+                    owner: None,
+                };
+                Ok(show::to_json(&json, &target))
+            }
+            Request::Signature { path, version } => {
+                let (json, id, path) = self.locate(path, version.as_deref()).await?;
+                let item = items::lookup(&json, &id).context("located ids come from the index")?;
+                Ok(serde_json::json!({
+                    "path": path.join("::"),
+                    "signature": signature::render(&json, item),
+                }))
+            }
+            Request::Shutdown => Ok(Value::Null),
+        }
+    }
+}
+
+// One response line: the request's `id` if it had one, then the result or
+// the error
+fn response(id: Option<&Value>, outcome: Result<Value>) -> Value {
+    let mut response = match outcome {
+        Ok(result) => serde_json::json!({ "ok": true, "result": result }),
+        Err(e) => serde_json::json!({ "ok": false, "error": format!("{:#}", e) }),
+    };
+    if let Some(id) = id {
+        response["id"] = id.clone();
+    }
+    response
+}
+
+// Handler for `zdoc batch`: one JSON request per line on stdin, one JSON
+// response per line on stdout, until `{"cmd":"shutdown"}` or the end of
+// input. A request that can't be parsed or fails gets an error response;
+// the session goes on.
+pub async fn run_stdio(metadata: Result<Metadata>) -> Result<()> {
+    let mut engine = Engine::new(metadata)?;
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout();
+    for line in stdin.lock().lines() {
+        let line = line.context("Failed to read a request from stdin")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let message: Result<Value> = serde_json::from_str(&line).context("Invalid JSON");
+        let id = message.as_ref().ok().and_then(|m| m.get("id")).cloned();
+        let request = message.and_then(|mut message| {
+            if let Some(fields) = message.as_object_mut() {
+                fields.remove("id");
+            }
+            serde_json::from_value::<Request>(message).context("Invalid request")
+        });
+        let shutdown = matches!(request, Ok(Request::Shutdown));
+        let outcome = match request {
+            Ok(request) => engine.handle(&request).await,
+            Err(e) => Err(e),
+        };
+        writeln!(stdout, "{}", response(id.as_ref(), outcome))
+            .and_then(|()| stdout.flush())
+            .context("Failed to write a response")?;
+        if shutdown {
+            break;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,94 @@
+// Rustdoc JSON for a published crate built with chosen features. docs.rs
+// builds each release once, with the features in its `[package.metadata.docs.rs]`,
+// so other feature sets have to be documented locally: a throwaway crate
+// depends on the release with those features and `cargo doc` documents it.
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::PathBuf;
+
+// The features to enable, as cargo takes them
+#[derive(Clone)]
+pub struct Features {
+    pub features: Vec<String>,
+    pub no_default_features: bool,
+}
+
+impl Features {
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.features.is_empty() {
+            parts.push(format!("--features {}", self.features.join(",")));
+        }
+        if self.no_default_features {
+            parts.push("--no-default-features".to_string());
+        }
+        parts.join(" ")
+    }
+
+    // The dependency table entry for `version`
+    fn dependency_spec(&self, version: &str) -> String {
+        // `latest` means whatever the registry has; cargo spells that `*`
+        let requirement = if version == "latest" {
+            "*".to_string()
+        } else {
+            format!("={}", version)
+        };
+        let features: Vec<String> = self.features.iter().map(|f| format!("{:?}", f)).collect();
+        format!(
+            "{{ version = {:?}, features = [{}], default-features = {} }}",
+            requirement,
+            features.join(", "),
+            !self.no_default_features
+        )
+    }
+}
+
+// Build dir shared between runs so dependencies are only compiled once
+fn target_dir() -> PathBuf {
+    std::env::temp_dir().join("zdoc-feature-docs")
+}
+
+// Document `crate_name` at `version` with `features` and return the JSON's path
+pub fn build(crate_name: &str, version: &str, features: &Features) -> Result<PathBuf> {
+    tracing::info!(
+        "Documenting {} v{} locally with {}...",
+        crate_name,
+        version,
+        features.describe()
+    );
+
+    let manifest = format!(
+        "[package]\nname = \"zdoc-feature-docs\"\nversion = \"0.0.0\"\nedition = \"2021\"\n\n\
+         [dependencies]\n{} = {}\n\n[workspace]\n",
+        crate_name,
+        features.dependency_spec(version)
+    );
+    let dir = tempfile::Builder::new()
+        .prefix("zdoc-features-")
+        .tempdir()
+        .context("Failed to create a temporary project")?;
+    fs::create_dir_all(dir.path().join("src")).context("Failed to create src/")?;
+    fs::write(dir.path().join("Cargo.toml"), manifest).context("Failed to write Cargo.toml")?;
+    fs::write(dir.path().join("src/lib.rs"), "").context("Failed to write src/lib.rs")?;
+
+    let target_dir = target_dir();
+    let status = crate::toolchain::cargo_doc()
+        .args(["--no-deps", "--quiet", "-p", crate_name])
+        .args(crate::target_dir::cargo_args_under(&target_dir))
+        .current_dir(dir.path())
+        .status()
+        .context("Failed to run `cargo doc`")?;
+    if !status.success() {
+        anyhow::bail!(
+            "Failed to document {} v{} with {}",
+            crate_name,
+            version,
+            features.describe()
+        );
+    }
+
+    Ok(crate::doc_source::crate_json_path(
+        &crate::target_dir::doc_dir_under(&target_dir),
+        crate_name,
+    ))
+}
@@ -0,0 +1,81 @@
+// `zdoc features`: a package's features as a column, each with what it
+// enables. Features on by default (named in `default`, directly or through
+// other features) are colored, and those the resolved build enables, by
+// default or because some crate in the graph asks for them, are checked.
+use cargo_metadata::{Metadata, Package};
+use std::collections::BTreeSet;
+
+use crate::style::{self, Role};
+
+// The features `default` turns on, following features that enable others
+fn default_features(package: &Package) -> BTreeSet<&str> {
+    let mut on = BTreeSet::new();
+    let mut pending = vec!["default"];
+    while let Some(name) = pending.pop() {
+        for value in package.features.get(name).into_iter().flatten() {
+            if package.features.contains_key(value) && on.insert(value.as_str()) {
+                pending.push(value);
+            }
+        }
+    }
+    on
+}
+
+// The features cargo resolved for `package` in this workspace's build
+fn enabled_features<'a>(metadata: &'a Metadata, package: &Package) -> BTreeSet<&'a str> {
+    metadata
+        .resolve
+        .as_ref()
+        .and_then(|resolve| resolve.nodes.iter().find(|node| node.id == package.id))
+        .map(|node| {
+            node.features
+                .iter()
+                .map(|feature| feature.as_str())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn print(metadata: &Metadata, package: &Package) {
+    println!("Features for {} (v{}):", package.name, package.version);
+    if package.features.is_empty() {
+        println!("  (No features defined)");
+        return;
+    }
+
+    let defaults = default_features(package);
+    let enabled = enabled_features(metadata, package);
+    // Pad before painting, since color codes would count toward the width
+    let width = package
+        .features
+        .keys()
+        .map(|name| name.chars().count())
+        .max()
+        .unwrap_or(0);
+    for (name, values) in &package.features {
+        let marker = if enabled.contains(name.as_str()) {
+            style::paint(Role::Path, "[x]")
+        } else {
+            style::paint(Role::Muted, "[ ]")
+        };
+        let role = if name == "default" || defaults.contains(name.as_str()) {
+            Role::Added
+        } else {
+            Role::Path
+        };
+        if values.is_empty() {
+            println!("  {} {}", marker, style::paint(role, name));
+        } else {
+            let padded = format!("{:<width$}", name, width = width);
+            let values = format!("-> {}", values.join(", "));
+            println!(
+                "  {} {} {}",
+                marker,
+                style::paint(role, &padded),
+                style::paint(Role::Muted, &values)
+            );
+        }
+    }
+    let legend = "[x] enabled in this workspace's build; default features are highlighted";
+    println!("\n{}", style::paint(Role::Muted, legend));
+}
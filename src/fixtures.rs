@@ -0,0 +1,24 @@
+// Rustdoc JSON of the fixture crates in `tests/fixtures`, for tests. Each
+// `<name>/` crate is documented into `<name>.json` by
+// `tests/fixtures/regenerate.sh`, which needs rerunning when a fixture's
+// source changes.
+use serde_json::Value;
+use std::path::PathBuf;
+
+use crate::rustdoc_json::{self, Detail};
+
+pub fn path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(format!("{}.json", name))
+}
+
+// The fixture's docs, parsed as zdoc parses any docs
+pub fn load(name: &str) -> Value {
+    let path = path(name);
+    let file = std::fs::File::open(&path)
+        .unwrap_or_else(|e| panic!("can't open {}: {}", path.display(), e));
+    rustdoc_json::read_hashed_as(std::io::BufReader::new(file), Detail::Full)
+        .unwrap_or_else(|e| panic!("can't parse {}: {:#}", path.display(), e))
+        .0
+}
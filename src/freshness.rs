@@ -0,0 +1,148 @@
+// Whether the JSON in `target/doc` still matches the sources. `cargo doc`
+// is skipped when it's newer than every source file; when it's older and
+// can't be rebuilt (the build fails, or `--no-generate`), whatever was there
+// is read with a warning, since a renamed or removed item would otherwise
+// show up as a ghost result. `--clean` deletes the JSON before regenerating.
+// JSON documented with private items when they weren't asked for, or the
+// other way around, is out of date too.
+use anyhow::{Context, Result};
+use cargo_metadata::Metadata;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::SystemTime;
+use tracing::{info, warn};
+
+use crate::doc_targets::DocTarget;
+use crate::{rustdoc_json, target_dir};
+
+static CLEAN: AtomicBool = AtomicBool::new(false);
+
+// Delete the generated JSON before the next `cargo doc` (`--clean`)
+pub fn set_clean(clean: bool) {
+    CLEAN.store(clean, Ordering::Relaxed);
+}
+
+// Whether `--clean` is still to happen, for `--dry-run`
+pub fn clean_pending() -> bool {
+    CLEAN.load(Ordering::Relaxed)
+}
+
+// The newest modification time of the files under `dir`, skipping build
+// output (`target`, or wherever `target_dir` points) and hidden directories
+fn newest_source_time(dir: &Path, target_dir: &Path) -> Option<SystemTime> {
+    let mut newest = None;
+    for entry in fs::read_dir(dir).ok()?.flatten() {
+        let name = entry.file_name();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let modified = if file_type.is_dir() {
+            if name == "target"
+                || name.to_string_lossy().starts_with('.')
+                || entry.path() == target_dir
+            {
+                continue;
+            }
+            newest_source_time(&entry.path(), target_dir)
+        } else {
+            entry.metadata().and_then(|m| m.modified()).ok()
+        };
+        newest = newest.max(modified);
+    }
+    newest
+}
+
+// Whether a target's JSON has private items when they weren't asked for, or
+// lacks them when they were (`--private`)
+fn private_mismatch(target: &DocTarget, json_path: &Path) -> bool {
+    target.wants_private().is_some_and(|private| {
+        rustdoc_json::includes_private(json_path).is_some_and(|included| included != private)
+    })
+}
+
+// Whether a target's JSON is newer than what it was built from: the
+// package's own files and the workspace's `Cargo.lock`. Registry and git
+// dependencies never change in place, so for them only a missing file counts.
+fn is_fresh(metadata: &Metadata, target: &DocTarget) -> bool {
+    let json_path = target.json_path(&target_dir::doc_dir(metadata));
+    let Ok(built) = fs::metadata(&json_path).and_then(|m| m.modified()) else {
+        return false;
+    };
+    if private_mismatch(target, &json_path) {
+        return false;
+    }
+    let build_dir = target_dir::target_dir(metadata);
+    let lockfile = fs::metadata(metadata.workspace_root.join("Cargo.lock"))
+        .and_then(|m| m.modified())
+        .ok();
+    let sources = metadata
+        .packages
+        .iter()
+        .filter(|p| p.name.as_str() == target.package && p.source.is_none())
+        .filter_map(|p| p.manifest_path.parent())
+        .filter_map(|dir| newest_source_time(dir.as_std_path(), &build_dir))
+        .max();
+    sources.max(lockfile).is_none_or(|changed| changed <= built)
+}
+
+// Whether every target's JSON is current, so `cargo doc` can be skipped
+pub fn all_fresh(metadata: &Metadata, targets: &[DocTarget]) -> bool {
+    targets.iter().all(|target| is_fresh(metadata, target))
+}
+
+// Warn about each target whose JSON exists but is out of date: what's read
+// from it may list items that are gone. `why` says how it got that way.
+pub fn warn_stale(metadata: &Metadata, targets: &[DocTarget], why: &str) {
+    let doc_dir = target_dir::doc_dir(metadata);
+    for target in targets {
+        let json_path = target.json_path(&doc_dir);
+        if !json_path.exists() {
+            continue;
+        }
+        if private_mismatch(target, &json_path) {
+            let (state, wanted) = if target.wants_private() == Some(true) {
+                ("lack", "which --private asked for")
+            } else {
+                ("include", "which weren't asked for")
+            };
+            warn!(
+                "The JSON docs of {} {} private items, {} ({})",
+                target.name, state, wanted, why
+            );
+        } else if !is_fresh(metadata, target) {
+            warn!(
+                "The JSON docs of {} are older than its sources ({}), so they may list items \
+                 that no longer exist; --clean deletes and regenerates them",
+                target.name, why
+            );
+        }
+    }
+}
+
+// Delete the generated JSON in `doc_dir`, once per run, when `--clean` asked
+// for it. Rendered HTML is left alone.
+pub fn clean_if_asked(doc_dir: &Path) -> Result<()> {
+    if !CLEAN.swap(false, Ordering::Relaxed) {
+        return Ok(());
+    }
+    let Ok(entries) = fs::read_dir(doc_dir) else {
+        return Ok(());
+    };
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "json") && path.is_file() {
+            fs::remove_file(&path)
+                .with_context(|| format!("Failed to delete {}", path.display()))?;
+            removed += 1;
+        }
+    }
+    info!(
+        "Deleted {} generated JSON file{} from {}",
+        removed,
+        if removed == 1 { "" } else { "s" },
+        doc_dir.display()
+    );
+    Ok(())
+}
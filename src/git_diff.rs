@@ -0,0 +1,211 @@
+// `zdoc diff --git <ref>`: what the working tree changes about a workspace
+// crate's public API compared with a git ref, e.g. everything a branch
+// changed since `main`. The ref is checked out into a temporary worktree,
+// so the working tree (uncommitted changes included) is never touched, and
+// documented there into a target directory of its own, kept between runs so
+// dependencies are only compiled once. The worktree is removed afterwards.
+use anyhow::{Context, Result};
+use cargo_metadata::Metadata;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tracing::{debug, info, warn};
+
+use crate::doc_source::{self, DocSource, FetchedDocs};
+use crate::selection::PackageSelection;
+use crate::{DiffOptions, JsonStyle, target_dir};
+
+// Run git in `dir`, returning its trimmed stdout
+fn git<I, S>(dir: &Path, args: I) -> Result<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let args: Vec<S> = args.into_iter().collect();
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(&args)
+        .output()
+        .context("Failed to run git. Make sure it's installed.")?;
+    if !output.status.success() {
+        let shown: Vec<String> = args
+            .iter()
+            .map(|arg| arg.as_ref().to_string_lossy().into_owned())
+            .collect();
+        anyhow::bail!(
+            "`git {}` failed: {}",
+            shown.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// A checkout of a commit in a temporary directory, removed when dropped
+struct Worktree {
+    repo: PathBuf,
+    path: PathBuf,
+    // Holds the directory the worktree is in
+    _dir: tempfile::TempDir,
+}
+
+impl Worktree {
+    fn add(repo: &Path, commit: &str) -> Result<Self> {
+        let dir = tempfile::Builder::new()
+            .prefix("zdoc-git-")
+            .tempdir()
+            .context("Failed to create a temporary directory")?;
+        // git wants to create the worktree's directory itself
+        let path = dir.path().join("tree");
+        git(
+            repo,
+            [
+                OsStr::new("worktree"),
+                OsStr::new("add"),
+                OsStr::new("--detach"),
+                OsStr::new("--quiet"),
+                path.as_os_str(),
+                OsStr::new(commit),
+            ],
+        )?;
+        debug!("Checked out {} into {}", commit, path.display());
+        Ok(Worktree {
+            repo: repo.to_path_buf(),
+            path,
+            _dir: dir,
+        })
+    }
+}
+
+impl Drop for Worktree {
+    fn drop(&mut self) {
+        let removed = git(
+            &self.repo,
+            [
+                OsStr::new("worktree"),
+                OsStr::new("remove"),
+                OsStr::new("--force"),
+                self.path.as_os_str(),
+            ],
+        );
+        if let Err(e) = removed {
+            warn!(
+                "Failed to remove the worktree at {}: {:#}; `git worktree prune` cleans it up",
+                self.path.display(),
+                e
+            );
+        }
+    }
+}
+
+// The `cargo doc` run that documents the crate in the worktree
+fn cargo_doc(manifest: &Path, crate_name: &str, target_dir: &Path) -> Command {
+    let mut command = crate::toolchain::cargo_doc();
+    command
+        .args(["--no-deps", "--quiet", "-p", crate_name])
+        .arg("--manifest-path")
+        .arg(manifest)
+        .args(target_dir::cargo_args_under(target_dir));
+    command
+}
+
+// A workspace crate's docs as the working tree has it, documented afresh
+// if they're stale
+pub fn working_tree_docs(metadata: &Metadata, crate_name: &str) -> Result<FetchedDocs> {
+    crate::generate_json_docs(metadata)?;
+    doc_source::read_crate_json(crate_name, DocSource::Local { metadata })
+}
+
+pub async fn run(
+    metadata: &Metadata,
+    crate_name: Option<&str>,
+    git_ref: &str,
+    options: &DiffOptions,
+    json_style: JsonStyle,
+) -> Result<()> {
+    let package = PackageSelection::default().resolve_one(metadata, crate_name, "diff --git")?;
+    if !metadata.workspace_members.contains(&package.id) {
+        anyhow::bail!(
+            "{} isn't a workspace member; --git compares this project's own crates",
+            package.name
+        );
+    }
+    let crate_name = package.name.to_string();
+
+    let workspace_root = metadata.workspace_root.as_std_path();
+    let repo = PathBuf::from(
+        git(workspace_root, ["rev-parse", "--show-toplevel"])
+            .context("--git needs the project to be in a git repository")?,
+    );
+    let commit = git(
+        workspace_root,
+        ["rev-parse", "--verify", &format!("{}^{{commit}}", git_ref)],
+    )
+    .with_context(|| format!("'{}' isn't a commit, branch or tag", git_ref))?;
+    // Where the package's manifest is in a checkout of the repository
+    let repo = fs::canonicalize(&repo).unwrap_or(repo);
+    let manifest = fs::canonicalize(&package.manifest_path)
+        .unwrap_or_else(|_| package.manifest_path.clone().into());
+    let relative_manifest = manifest
+        .strip_prefix(&repo)
+        .with_context(|| format!("{} is outside {}", manifest.display(), repo.display()))?
+        .to_path_buf();
+    let ref_target_dir = target_dir::target_dir(metadata).join("zdoc-git");
+
+    if options.dry_run {
+        println!(
+            "Would check out {} ({}) into a temporary worktree and run:",
+            git_ref,
+            &commit[..commit.len().min(12)]
+        );
+        let command = cargo_doc(
+            &Path::new("<worktree>").join(&relative_manifest),
+            &crate_name,
+            &ref_target_dir,
+        );
+        // What follows `doc`, which `describe` spells out with the toolchain
+        let args: Vec<String> = command
+            .get_args()
+            .skip_while(|arg| *arg != "doc")
+            .skip(1)
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+        println!("  {} {}", crate::toolchain::describe(), args.join(" "));
+        println!("Would document the working tree as usual and compare the two");
+        return Ok(());
+    }
+
+    // The ref's side
+    let old = {
+        let json_path =
+            doc_source::crate_json_path(&target_dir::doc_dir_under(&ref_target_dir), &crate_name);
+        // Another ref's docs mustn't pass for these if the crate has none here
+        let _ = fs::remove_file(&json_path);
+        let worktree = Worktree::add(&repo, &commit)?;
+        info!("Documenting {} at {}...", crate_name, git_ref);
+        let status = cargo_doc(
+            &worktree.path.join(&relative_manifest),
+            &crate_name,
+            &ref_target_dir,
+        )
+        .status()
+        .context("Failed to run `cargo doc`")?;
+        if !status.success() {
+            anyhow::bail!("Failed to document {} at {}", crate_name, git_ref);
+        }
+        if !json_path.exists() {
+            anyhow::bail!(
+                "No JSON docs were generated for {} at {}; does it exist there?",
+                crate_name,
+                git_ref
+            );
+        }
+        doc_source::read_crate_json(&crate_name, DocSource::File { path: &json_path })?
+    };
+
+    let new = working_tree_docs(metadata, &crate_name)?;
+    let report = crate::diff_report(&crate_name, git_ref, "working tree", &old, &new, options)?;
+    crate::print_diff(&report, options, json_style)
+}
@@ -0,0 +1,324 @@
+// `--format github`: GitHub Actions workflow commands, so a failing check
+// points at the problem instead of leaving it in the log. Breaking changes
+// and denied lint findings are `::error` annotations, minor changes and lint
+// warnings `::warning`, and the totals sit in a collapsible `::group::`.
+//
+// Inside Actions (`GITHUB_ACTIONS=true`), a markdown report is also appended
+// to the job summary file named by `$GITHUB_STEP_SUMMARY`, in any format.
+use anyhow::{Context, Result};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+
+use crate::lint_docs::{Finding, Level};
+use crate::{ApiItem, DiffReport, Severity};
+
+// A message: `%` and line breaks are percent-encoded so the runner decodes
+// them back, and a multi-line message stays one command
+fn escape_data(text: &str) -> String {
+    text.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+// A property value, which also can't contain the `:` and `,` that end it
+// (`title=a::b` would otherwise cut the title at `a`)
+fn escape_property(text: &str) -> String {
+    escape_data(text).replace(':', "%3A").replace(',', "%2C")
+}
+
+// One `::level key=value,...::message` line
+fn annotation(level: &str, properties: &[(&str, String)], message: &str) -> String {
+    let properties: Vec<String> = properties
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, escape_property(value)))
+        .collect();
+    let separator = if properties.is_empty() { "" } else { " " };
+    format!(
+        "::{}{}{}::{}\n",
+        level,
+        separator,
+        properties.join(","),
+        escape_data(message)
+    )
+}
+
+// Plain lines wrapped in a collapsible group
+fn group(title: &str, lines: &[String]) -> String {
+    let mut out = format!("::group::{}\n", escape_data(title));
+    for line in lines {
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.push_str("::endgroup::\n");
+    out
+}
+
+fn diff_title(report: &DiffReport) -> String {
+    format!(
+        "API diff for {} ({}...{})",
+        report.crate_name, report.ver1, report.ver2
+    )
+}
+
+// Diff changes as (change, item, severity, detail), in display order
+fn changes(report: &DiffReport) -> Vec<(&'static str, &ApiItem, Severity, String)> {
+    let mut changes = Vec::new();
+    for item in &report.removed {
+        changes.push(("removed", item, Severity::Breaking, item.signature.clone()));
+    }
+    for item in &report.added {
+        let severity = crate::added_severity(item);
+        changes.push(("added", item, severity, item.signature.clone()));
+    }
+    for (old, new) in &report.modified {
+        let detail = format!(
+            "- {}\n+ {}",
+            old.signature_with_tags(),
+            new.signature_with_tags()
+        );
+        changes.push(("changed", new, crate::modified_severity(old, new), detail));
+    }
+    changes
+}
+
+// Breaking changes as errors and minor changes to existing items as
+// warnings. Items read with source spans (a local crate's, for `--git`,
+// `--local` and `verify`) are annotated at their definition, or for removed
+// ones where they were; spans with absolute paths are outside the workspace,
+// so those items, and all of a docs.rs diff's, are annotated by name only.
+// Non-breaking additions are only counted: they're not something to review.
+pub fn render_diff(report: &DiffReport) -> String {
+    let mut out = String::new();
+    for (change, item, severity, detail) in changes(report) {
+        let level = match (severity, change) {
+            (Severity::Breaking, _) => "error",
+            (Severity::Minor, "added") => continue,
+            (Severity::Minor, _) => "warning",
+        };
+        let mut properties = Vec::new();
+        if let Some((file, line)) = &item.location
+            && Path::new(file).is_relative()
+        {
+            properties.push(("file", file.clone()));
+            properties.push(("line", line.to_string()));
+        }
+        let title = format!("{} {}: {}", report.crate_name, change, item.full_path());
+        properties.push(("title", title));
+        let message = format!("{} {}\n{}", change, item.display_string(), detail);
+        out.push_str(&annotation(level, &properties, message.trim_end()));
+    }
+
+    out.push_str(&group(
+        &diff_title(report),
+        &[
+            format!("Added: {}", report.added.len()),
+            format!("Removed: {}", report.removed.len()),
+            format!("Modified: {}", report.modified.len()),
+            format!("Breaking: {}", report.breaking_count()),
+        ],
+    ));
+    out
+}
+
+// An error that isn't about any one item, such as a diff that couldn't run
+pub fn failure(title: &str, message: &str) -> String {
+    annotation("error", &[("title", title.to_string())], message)
+}
+
+// Text for a markdown table cell
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', "<br>")
+}
+
+// The step summary for a diff: totals, then every change
+pub fn diff_summary(report: &DiffReport) -> String {
+    let mut out = format!(
+        "### {}\n\n{} added, {} removed, {} modified, **{} breaking**\n",
+        diff_title(report),
+        report.added.len(),
+        report.removed.len(),
+        report.modified.len(),
+        report.breaking_count()
+    );
+    let changes = changes(report);
+    if !changes.is_empty() {
+        out.push_str("\n| Change | Item | Severity |\n| --- | --- | --- |\n");
+        for (change, item, severity, _) in changes {
+            out.push_str(&format!(
+                "| {} | `{}` | {} |\n",
+                change,
+                cell(&item.display_string()),
+                severity.label()
+            ));
+        }
+    }
+    out.push('\n');
+    out
+}
+
+// Lint findings, each anchored at the item it's about
+pub fn render_findings(findings: &[Finding]) -> String {
+    let mut out = String::new();
+    for finding in findings {
+        let level = match finding.level {
+            Level::Deny => "error",
+            _ => "warning",
+        };
+        out.push_str(&annotation(
+            level,
+            &[
+                ("file", finding.file.clone()),
+                ("line", finding.line.to_string()),
+                ("col", finding.column.to_string()),
+                ("title", format!("lint-docs({})", finding.rule)),
+            ],
+            &format!("{}: {}", finding.path, finding.message),
+        ));
+    }
+    let errors = findings.iter().filter(|f| f.level == Level::Deny).count();
+    out.push_str(&group(
+        "lint-docs summary",
+        &[format!(
+            "{} warnings, {} errors",
+            findings.len() - errors,
+            errors
+        )],
+    ));
+    out
+}
+
+// The step summary for lint findings
+pub fn findings_summary(findings: &[Finding]) -> String {
+    let errors = findings.iter().filter(|f| f.level == Level::Deny).count();
+    let mut out = format!(
+        "### Doc lints\n\n{} warnings, **{} errors**\n",
+        findings.len() - errors,
+        errors
+    );
+    if !findings.is_empty() {
+        out.push_str("\n| Location | Rule | Item | Problem |\n| --- | --- | --- | --- |\n");
+        for finding in findings {
+            out.push_str(&format!(
+                "| {}:{} | {} | `{}` | {} |\n",
+                cell(&finding.file),
+                finding.line,
+                finding.rule,
+                cell(&finding.path),
+                cell(&finding.message)
+            ));
+        }
+    }
+    out.push('\n');
+    out
+}
+
+// Append `markdown` to the job summary. That happens inside Actions, or
+// always with `--format github` (`forced`); either way only when the runner
+// named a summary file.
+pub fn write_step_summary(markdown: &str, forced: bool) -> Result<()> {
+    let in_actions = std::env::var("GITHUB_ACTIONS").is_ok_and(|v| v == "true");
+    if !in_actions && !forced {
+        return Ok(());
+    }
+    let Some(path) = std::env::var_os("GITHUB_STEP_SUMMARY").filter(|path| !path.is_empty()) else {
+        return Ok(());
+    };
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open the step summary {:?}", path))?;
+    file.write_all(markdown.as_bytes())
+        .with_context(|| format!("Failed to write the step summary {:?}", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{compare_api_items, extract_api_items, fixtures};
+
+    #[test]
+    fn escapes_messages() {
+        let cases = [
+            ("plain", "plain"),
+            ("100% safe", "100%25 safe"),
+            // Already-encoded text isn't decoded by the runner
+            ("%0A", "%250A"),
+            ("- fn a()\n+ fn b()", "- fn a()%0A+ fn b()"),
+            ("line\r\n", "line%0D%0A"),
+            // Only properties end at `::` and `,`
+            (
+                "widgets::draw, widgets::clear",
+                "widgets::draw, widgets::clear",
+            ),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(escape_data(text), expected, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn escapes_properties() {
+        let cases = [
+            ("widgets::Widget", "widgets%3A%3AWidget"),
+            ("<A as From<B, C>>", "<A as From<B%2C C>>"),
+            ("100%", "100%25"),
+            ("a\nb", "a%0Ab"),
+            ("src/lib.rs", "src/lib.rs"),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(escape_property(text), expected, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn annotation_keeps_one_line() {
+        assert_eq!(
+            annotation(
+                "error",
+                &[
+                    ("file", "src/lib.rs".to_string()),
+                    ("title", "a::b, c".to_string())
+                ],
+                "removed a::b\n100%"
+            ),
+            "::error file=src/lib.rs,title=a%3A%3Ab%2C c::removed a::b%0A100%25\n"
+        );
+        assert_eq!(annotation("warning", &[], "minor"), "::warning::minor\n");
+    }
+
+    // The widgets fixture, read with spans as local docs are, with `clear`
+    // removed and `Widget::area` changed
+    #[test]
+    fn local_changes_point_at_the_source() {
+        let old = extract_api_items(&fixtures::load("widgets")).unwrap();
+        let mut new = old.clone();
+        new.retain(|item| item.full_path() != "widgets::clear");
+        for item in &mut new {
+            if item.full_path() == "widgets::Widget::area" {
+                item.signature = "(&self) -> u64".to_string();
+            }
+            // Where a dependency would be
+            if item.full_path() == "widgets::Widget::new" {
+                item.signature = "(width: u64, height: u64) -> Self".to_string();
+                item.location = Some(("/home/ci/.cargo/registry/src/lib.rs".to_string(), 1));
+            }
+        }
+        let report = DiffReport::new("widgets", "main", "HEAD", compare_api_items(old, new));
+        let out = render_diff(&report);
+        let annotations: Vec<&str> = out
+            .lines()
+            .filter(|line| line.starts_with("::error") || line.starts_with("::warning"))
+            .map(|line| line.split("::").nth(1).unwrap_or_default())
+            .collect();
+        assert_eq!(
+            annotations,
+            [
+                "error file=src/lib.rs,line=93,title=widgets removed%3A widgets%3A%3Aclear",
+                "error file=src/lib.rs,line=43,title=widgets changed%3A widgets%3A%3AWidget%3A%3Aarea",
+                "error title=widgets changed%3A widgets%3A%3AWidget%3A%3Anew",
+            ]
+        );
+    }
+}
@@ -0,0 +1,70 @@
+// The HTTP client used for docs.rs downloads. Proxies are configured
+// explicitly rather than left to reqwest's system detection: `--proxy`
+// wins, else `HTTPS_PROXY`/`HTTP_PROXY` (or their lowercase forms) by URL
+// scheme, and `NO_PROXY` lists the hosts that bypass either.
+use anyhow::{Context, Result};
+use reqwest::{NoProxy, Proxy};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::debug;
+
+static PROXY_OVERRIDE: OnceLock<String> = OnceLock::new();
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+// Never touch the network for the rest of the run (`--offline`)
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+// Set the `--proxy` override for the rest of the run
+pub fn set_proxy(url: String) {
+    let _ = PROXY_OVERRIDE.set(url);
+}
+
+// The first non-empty one of `names`, with the variable it came from
+fn env_var(names: &[&'static str]) -> Option<(&'static str, String)> {
+    names.iter().find_map(|&name| {
+        std::env::var(name)
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+            .map(|value| (name, value))
+    })
+}
+
+pub fn client() -> Result<reqwest::Client> {
+    if is_offline() {
+        anyhow::bail!("Not connecting to the network with --offline; only cached docs can be used");
+    }
+    let mut builder = reqwest::Client::builder().no_proxy();
+
+    if let Some(url) = PROXY_OVERRIDE.get() {
+        let proxy = Proxy::all(url.as_str())
+            .with_context(|| format!("Invalid --proxy URL '{}'", url))?
+            .no_proxy(NoProxy::from_env());
+        debug!("Using the --proxy proxy for all requests");
+        builder = builder.proxy(proxy);
+    } else {
+        if let Some((name, url)) = env_var(&["HTTPS_PROXY", "https_proxy"]) {
+            let proxy = Proxy::https(url.as_str())
+                .with_context(|| format!("Invalid proxy URL in {}", name))?
+                .no_proxy(NoProxy::from_env());
+            debug!("Using the {} proxy for https requests", name);
+            builder = builder.proxy(proxy);
+        }
+        // `http_proxy` is only read lowercase by most tools, since CGI sets
+        // `HTTP_PROXY` from a request header; both are accepted here
+        if let Some((name, url)) = env_var(&["HTTP_PROXY", "http_proxy"]) {
+            let proxy = Proxy::http(url.as_str())
+                .with_context(|| format!("Invalid proxy URL in {}", name))?
+                .no_proxy(NoProxy::from_env());
+            debug!("Using the {} proxy for http requests", name);
+            builder = builder.proxy(proxy);
+        }
+    }
+
+    builder.build().context("Failed to create the HTTP client")
+}
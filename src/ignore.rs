@@ -0,0 +1,97 @@
+// Paths left out of reports: the `[ignore]` section of `zdoc.toml` lists
+// globs matched against full item paths (`mycrate::internal`,
+// `*::__private`). A pattern matching a module or type also covers
+// everything under it. Diff, verify, list, lint-docs and search all filter
+// through here; `--no-ignore` turns it off for a run.
+use anyhow::{Context, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::config::Config;
+
+static DISABLED: AtomicBool = AtomicBool::new(false);
+
+// Report everything this run, whatever the config says (`--no-ignore`)
+pub fn set_disabled(disabled: bool) {
+    DISABLED.store(disabled, Ordering::Relaxed);
+}
+
+#[derive(Clone, Default)]
+pub struct Ignore {
+    globs: Option<GlobSet>,
+}
+
+impl Ignore {
+    pub fn new(patterns: &[String]) -> Result<Self> {
+        if patterns.is_empty() || DISABLED.load(Ordering::Relaxed) {
+            return Ok(Ignore::default());
+        }
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            let glob = Glob::new(pattern)
+                .with_context(|| format!("Invalid [ignore] pattern '{}'", pattern))?;
+            builder.add(glob);
+        }
+        let globs = builder
+            .build()
+            .context("Failed to compile the [ignore] patterns")?;
+        Ok(Ignore { globs: Some(globs) })
+    }
+
+    // The patterns of the `zdoc.toml` in `workspace_root`
+    pub fn load(workspace_root: &Path) -> Result<Self> {
+        Ignore::new(&Config::load(workspace_root)?.ignore.paths)
+    }
+
+    // The patterns of the nearest `zdoc.toml` at or above the current
+    // directory, for commands that don't need a project (`diff`)
+    pub fn load_nearest() -> Result<Self> {
+        let Ok(cwd) = std::env::current_dir() else {
+            return Ok(Ignore::default());
+        };
+        match Config::find(&cwd) {
+            Some(root) => Ignore::load(&root),
+            None => Ok(Ignore::default()),
+        }
+    }
+
+    // Whether `path` or a module or type above it matches a pattern
+    pub fn is_ignored(&self, path: &str) -> bool {
+        let Some(globs) = &self.globs else {
+            return false;
+        };
+        let mut end = path.len();
+        loop {
+            if globs.is_match(&path[..end]) {
+                return true;
+            }
+            match path[..end].rfind("::") {
+                Some(i) => end = i,
+                None => return false,
+            }
+        }
+    }
+
+    // Split `items` into those reported and those ignored
+    pub fn partition<T>(&self, items: Vec<T>, path: impl Fn(&T) -> String) -> (Vec<T>, Vec<T>) {
+        if self.globs.is_none() {
+            return (items, Vec::new());
+        }
+        items
+            .into_iter()
+            .partition(|item| !self.is_ignored(&path(item)))
+    }
+}
+
+// The note printed under a report when ignored paths hid some `what`s
+pub fn note(count: usize, what: &str) -> Option<String> {
+    (count > 0).then(|| {
+        format!(
+            "{} {}{} under ignored paths not shown (--no-ignore to include)",
+            count,
+            what,
+            if count == 1 { "" } else { "s" }
+        )
+    })
+}
@@ -0,0 +1,190 @@
+// `zdoc impls`: which types implement a trait
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::collections::HashSet;
+
+use crate::items;
+
+// One `impl Trait for Type` block
+pub struct Implementor {
+    pub self_type: String,
+    // `<T: Clone>` and ` where T: Send`, or empty
+    pub generics: String,
+    pub where_clause: String,
+    pub trait_path: String,
+    pub is_blanket: bool,
+    pub is_synthetic: bool,
+}
+
+// Which generated impls to list besides the ones written for a type. Both
+// are off by default: blanket impls repeat for every type they cover, and
+// auto-trait impls (`Send`, `Sync`, ...) for nearly every type there is.
+#[derive(Clone, Copy)]
+pub struct Included {
+    pub blanket: bool,
+    pub auto: bool,
+}
+
+impl Implementor {
+    fn included(&self, included: Included) -> bool {
+        (included.blanket || !self.is_blanket) && (included.auto || !self.is_synthetic)
+    }
+}
+
+// Ids of the traits whose path ends with `segments`, wherever they're defined
+fn matching_trait_ids(json: &Value, segments: &[&str]) -> HashSet<String> {
+    let Some(paths) = json.get("paths").and_then(|v| v.as_object()) else {
+        return HashSet::new();
+    };
+    paths
+        .iter()
+        .filter(|(_, summary)| summary.get("kind").and_then(|v| v.as_str()) == Some("trait"))
+        .filter(|(_, summary)| {
+            let path: Vec<&str> = summary
+                .get("path")
+                .and_then(|v| v.as_array())
+                .map(|path| path.iter().filter_map(|s| s.as_str()).collect())
+                .unwrap_or_default();
+            path.len() >= segments.len() && path[path.len() - segments.len()..] == *segments
+        })
+        .map(|(id, _)| id.clone())
+        .collect()
+}
+
+// Walk every impl block in the crate and keep those implementing the trait
+pub fn find_implementors(json: &Value, trait_path: &str) -> Result<Vec<Implementor>> {
+    let segments: Vec<&str> = trait_path.split("::").collect();
+    let trait_ids = matching_trait_ids(json, &segments);
+    if trait_ids.is_empty() {
+        anyhow::bail!(
+            "No trait matching '{}' is known to this crate's docs",
+            trait_path
+        );
+    }
+
+    let index = json
+        .get("index")
+        .and_then(|v| v.as_object())
+        .context("Missing or invalid 'index' field in JSON")?;
+
+    let mut implementors = Vec::new();
+    for item in index.values() {
+        if items::item_kind(item) != Some("impl") {
+            continue;
+        }
+        let Some(inner) = items::item_inner(item) else {
+            continue;
+        };
+        let Some(trait_ref) = inner.get("trait").filter(|v| !v.is_null()) else {
+            continue;
+        };
+        let implements = trait_ref
+            .get("id")
+            .and_then(items::id_key)
+            .is_some_and(|id| trait_ids.contains(&id));
+        // Negative impls (`impl !Send for T`) say the opposite
+        let negative = inner.get("is_negative").and_then(|v| v.as_bool()) == Some(true);
+        if !implements || negative {
+            continue;
+        }
+
+        let trait_name = trait_ref
+            .get("path")
+            .or_else(|| trait_ref.get("name"))
+            .and_then(|v| v.as_str())
+            .unwrap_or(trait_path);
+        implementors.push(Implementor {
+            self_type: inner
+                .get("for")
+                .map(crate::format_type)
+                .unwrap_or_else(|| "?".to_string()),
+            generics: crate::format_generic_params(inner.get("generics")),
+            where_clause: crate::format_where_clause(inner.get("generics")),
+            trait_path: trait_name.to_string(),
+            // Rustdoc copies a blanket impl onto each type it covers; the
+            // original is an impl for a bare type parameter
+            is_blanket: inner.get("blanket_impl").is_some_and(|v| !v.is_null())
+                || inner.get("for").and_then(|ty| ty.get("generic")).is_some(),
+            is_synthetic: inner.get("is_synthetic").and_then(|v| v.as_bool()) == Some(true),
+        });
+    }
+
+    implementors.sort_by(|a, b| a.self_type.cmp(&b.self_type));
+    implementors.dedup_by(|a, b| a.self_type == b.self_type && a.trait_path == b.trait_path);
+    Ok(implementors)
+}
+
+pub fn display_implementors(
+    crate_name: &str,
+    trait_path: &str,
+    implementors: &[Implementor],
+    included: Included,
+) {
+    let count = |wanted: fn(&Implementor) -> bool| {
+        implementors
+            .iter()
+            .filter(|i| wanted(i) && !i.included(included))
+            .count()
+    };
+    let hidden_blanket = count(|i| i.is_blanket);
+    let hidden_auto = count(|i| i.is_synthetic && !i.is_blanket);
+    let implementors: Vec<&Implementor> = implementors
+        .iter()
+        .filter(|i| i.included(included))
+        .collect();
+
+    if implementors.is_empty() {
+        println!(
+            "No implementations of '{}' found in {}",
+            trait_path, crate_name
+        );
+    } else {
+        println!(
+            "\nImplementors of {} in {} ({}):\n",
+            trait_path,
+            crate_name,
+            implementors.len()
+        );
+    }
+    for implementor in &implementors {
+        print_implementor(implementor);
+    }
+
+    let mut hidden = Vec::new();
+    if hidden_blanket > 0 {
+        hidden.push(format!(
+            "{} blanket impls (--include-blanket)",
+            hidden_blanket
+        ));
+    }
+    if hidden_auto > 0 {
+        hidden.push(format!("{} auto-trait impls (--include-auto)", hidden_auto));
+    }
+    if !hidden.is_empty() {
+        println!("\nNot shown: {}", hidden.join(", "));
+    }
+}
+
+fn print_implementor(implementor: &Implementor) {
+    let mut notes = Vec::new();
+    if implementor.is_blanket {
+        notes.push("blanket");
+    }
+    if implementor.is_synthetic {
+        notes.push("auto");
+    }
+    // Generic impls read like their declaration: `impl<T: Clone> Vec<T>`
+    let declared = if implementor.generics.is_empty() {
+        format!("{}{}", implementor.self_type, implementor.where_clause)
+    } else {
+        format!(
+            "impl{} {}{}",
+            implementor.generics, implementor.self_type, implementor.where_clause
+        )
+    };
+    if notes.is_empty() {
+        println!("  {}", declared);
+    } else {
+        println!("  {} ({})", declared, notes.join(", "));
+    }
+}
@@ -0,0 +1,477 @@
+// `zdoc diff --item <path>`: how one item changed between two versions,
+// rather than the whole crate. Only the item and its members (a type's
+// fields, variants and methods, a trait's items, a module's contents) are
+// compared, and the report shows the old and new signature with the changed
+// tokens highlighted, stability and tag changes, whether the docs changed
+// (`--docs` for a line diff of them) and the members' changes.
+//
+// The path can be given in full or without its leading modules
+// (`Client::connect`). An item found in one version but not the other is
+// looked for by name and kind instead, so one that moved between modules is
+// still compared, with a note saying where it was.
+use anyhow::Result;
+use colored::Colorize;
+use serde_json::Value;
+
+use crate::style::{self, Role};
+use crate::{ApiItem, DiffFormat, DiffReport, JsonStyle};
+
+// Line diffs bigger than this (old lines times new lines) show as all
+// removed and all added rather than being worked out
+const MAX_DIFF_CELLS: usize = 1_000_000;
+
+pub struct Focus {
+    // The path asked for
+    pub query: String,
+    pub old: Option<ApiItem>,
+    pub new: Option<ApiItem>,
+    // Where the item was in the old version, when it has moved since
+    pub moved_from: Option<String>,
+}
+
+// Whether `item` is a member of `parent`, directly or further down: its
+// path continues past the parent's module with the parent's name, or an
+// impl of it (`Client<T>`, `<Client as Display>`)
+fn is_member(parent: &ApiItem, item: &ApiItem) -> bool {
+    let depth = parent.path.len();
+    if item.path.len() <= depth || item.path[..depth] != parent.path[..] {
+        return false;
+    }
+    let segment = &item.path[depth];
+    let name = &parent.name;
+    segment == name
+        || segment.starts_with(&format!("{}<", name))
+        || segment.starts_with(&format!("<{} as ", name))
+        || segment.starts_with(&format!("<{}<", name))
+}
+
+// The one item `query` names: its full path, else the only item whose path
+// ends with it
+fn find<'a>(items: &'a [ApiItem], query: &str) -> Result<Option<&'a ApiItem>> {
+    let suffix = format!("::{}", query);
+    let exact: Vec<&ApiItem> = items
+        .iter()
+        .filter(|item| item.full_path() == query)
+        .collect();
+    let matches = if exact.is_empty() {
+        items
+            .iter()
+            .filter(|item| item.full_path().ends_with(&suffix))
+            .collect()
+    } else {
+        exact
+    };
+    match matches.as_slice() {
+        [] => Ok(None),
+        [item] => Ok(Some(item)),
+        _ => {
+            let names: Vec<String> = matches.iter().map(|item| item.display_string()).collect();
+            anyhow::bail!(
+                "'{}' matches {} items; name one by its full path:\n  {}",
+                query,
+                matches.len(),
+                names.join("\n  ")
+            )
+        }
+    }
+}
+
+// The only item in `items` with the name and kind of `item`, wherever it is
+fn find_moved<'a>(items: &'a [ApiItem], item: &ApiItem) -> Option<&'a ApiItem> {
+    let mut candidates = items
+        .iter()
+        .filter(|other| other.name == item.name && other.kind_family() == item.kind_family());
+    let found = candidates.next()?;
+    candidates.next().is_none().then_some(found)
+}
+
+// Narrow both versions down to the item `query` names and its members.
+// When the item moved, the old version's paths are rewritten to the new
+// ones, so the members still pair up.
+pub fn focus(
+    query: &str,
+    old_items: Vec<ApiItem>,
+    new_items: Vec<ApiItem>,
+) -> Result<(Focus, Vec<ApiItem>, Vec<ApiItem>)> {
+    let query = query.trim_start_matches("::");
+    let mut old = find(&old_items, query)?.cloned();
+    let mut new = find(&new_items, query)?.cloned();
+    match (&old, &new) {
+        (None, None) => anyhow::bail!("No item '{}' in either version", query),
+        (Some(item), None) => new = find_moved(&new_items, item).cloned(),
+        (None, Some(item)) => old = find_moved(&old_items, item).cloned(),
+        _ => {}
+    }
+    let moved_from = match (&old, &new) {
+        (Some(old), Some(new)) if old.path != new.path => Some(old.full_path()),
+        _ => None,
+    };
+
+    let keep = |items: Vec<ApiItem>, parent: &Option<ApiItem>| -> Vec<ApiItem> {
+        let Some(parent) = parent else {
+            return Vec::new();
+        };
+        items
+            .into_iter()
+            .filter(|item| item.diff_key() == parent.diff_key() || is_member(parent, item))
+            .collect()
+    };
+    let mut old_kept = keep(old_items, &old);
+    let new_kept = keep(new_items, &new);
+    if let (Some(from), Some(to)) = (&old, &new)
+        && moved_from.is_some()
+    {
+        for item in &mut old_kept {
+            let rest = item.path.split_off(from.path.len());
+            item.path = to.path.iter().cloned().chain(rest).collect();
+        }
+    }
+    tracing::debug!(
+        "Comparing {} and {} items under {}",
+        old_kept.len(),
+        new_kept.len(),
+        query
+    );
+    Ok((
+        Focus {
+            query: query.to_string(),
+            old,
+            new,
+            moved_from,
+        },
+        old_kept,
+        new_kept,
+    ))
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Op {
+    // Indices into the old and new sequence
+    Same(usize, usize),
+    Removed(usize),
+    Added(usize),
+}
+
+// A longest-common-subsequence diff of `old` and `new`, as the operations
+// that turn one into the other
+fn diff<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Op> {
+    if old.len().saturating_mul(new.len()) > MAX_DIFF_CELLS {
+        let removed = (0..old.len()).map(Op::Removed);
+        return removed.chain((0..new.len()).map(Op::Added)).collect();
+    }
+    // common[i][j]: the longest common subsequence of old[i..] and new[j..]
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut ops = Vec::new();
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            ops.push(Op::Same(i, j));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || common[i + 1][j] >= common[i][j + 1]) {
+            ops.push(Op::Removed(i));
+            i += 1;
+        } else {
+            ops.push(Op::Added(j));
+            j += 1;
+        }
+    }
+    ops
+}
+
+// Identifiers, runs of spaces and single punctuation characters
+fn tokens(text: &str) -> Vec<&str> {
+    let class = |c: char| {
+        if c.is_alphanumeric() || c == '_' {
+            0
+        } else if c.is_whitespace() {
+            1
+        } else {
+            2
+        }
+    };
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut previous = None;
+    for (i, c) in text.char_indices() {
+        let current = class(c);
+        if i > start && (current == 2 || previous != Some(current)) {
+            tokens.push(&text[start..i]);
+            start = i;
+        }
+        previous = Some(current);
+    }
+    if start < text.len() {
+        tokens.push(&text[start..]);
+    }
+    tokens
+}
+
+// `- old` and `+ new`, with the tokens that differ emphasized
+fn highlight(old: &str, new: &str) -> (String, String) {
+    let (old_tokens, new_tokens) = (tokens(old), tokens(new));
+    let mut old_line = style::paint(Role::Removed, "- ").to_string();
+    let mut new_line = style::paint(Role::Added, "+ ").to_string();
+    let emphasized = |role, token| style::paint(role, token).bold().underline().to_string();
+    for op in diff(&old_tokens, &new_tokens) {
+        match op {
+            Op::Same(i, j) => {
+                old_line.push_str(&style::paint(Role::Removed, old_tokens[i]).to_string());
+                new_line.push_str(&style::paint(Role::Added, new_tokens[j]).to_string());
+            }
+            Op::Removed(i) => old_line.push_str(&emphasized(Role::Removed, old_tokens[i])),
+            Op::Added(j) => new_line.push_str(&emphasized(Role::Added, new_tokens[j])),
+        }
+    }
+    (old_line, new_line)
+}
+
+// Unchanged doc lines kept around each changed one
+const DOCS_CONTEXT: usize = 2;
+
+// A unified diff of two doc comments: changed lines with `DOCS_CONTEXT`
+// unchanged ones around them, and `…` for the ones skipped
+fn docs_diff(old: &str, new: &str) -> Vec<(Op, String)> {
+    let (old_lines, new_lines): (Vec<&str>, Vec<&str>) =
+        (old.lines().collect(), new.lines().collect());
+    let ops = diff(&old_lines, &new_lines);
+    let near_change = |at: usize| {
+        let from = at.saturating_sub(DOCS_CONTEXT);
+        let to = (at + DOCS_CONTEXT + 1).min(ops.len());
+        ops[from..to].iter().any(|op| !matches!(op, Op::Same(..)))
+    };
+    let mut lines = Vec::new();
+    let mut skipping = false;
+    for (at, op) in ops.iter().enumerate() {
+        let line = match *op {
+            Op::Same(..) if !near_change(at) => {
+                if !skipping {
+                    lines.push((*op, "…".to_string()));
+                }
+                skipping = true;
+                continue;
+            }
+            Op::Same(i, _) | Op::Removed(i) => old_lines[i],
+            Op::Added(j) => new_lines[j],
+        };
+        skipping = false;
+        lines.push((*op, line.to_string()));
+    }
+    lines
+}
+
+// What happened to the docs, in a few words
+fn docs_change(old: Option<&str>, new: Option<&str>) -> &'static str {
+    match (old, new) {
+        (None, None) => "none in either version",
+        (None, Some(_)) => "added",
+        (Some(_), None) => "removed",
+        (Some(old), Some(new)) if old == new => "unchanged",
+        _ => "changed",
+    }
+}
+
+// Stability markers the item gained (`+`) or lost (`-`)
+fn stability_changes(old: &ApiItem, new: &ApiItem) -> Vec<String> {
+    let gained = new
+        .stability
+        .iter()
+        .filter(|marker| !old.stability.contains(marker))
+        .map(|marker| format!("+{}", marker));
+    let lost = old
+        .stability
+        .iter()
+        .filter(|marker| !new.stability.contains(marker))
+        .map(|marker| format!("-{}", marker));
+    gained.chain(lost).collect()
+}
+
+fn print_text(report: &DiffReport, focus: &Focus, show_docs: bool) {
+    let item = focus.new.as_ref().or(focus.old.as_ref());
+    let title = item.map_or(focus.query.clone(), |item| {
+        format!("{} {}", item.item_type, item.full_path())
+    });
+    println!(
+        "\n{} in {} ({}...{}):\n",
+        title.bold(),
+        report.crate_name,
+        report.ver1,
+        report.ver2
+    );
+
+    match (&focus.old, &focus.new) {
+        (Some(old), Some(new)) => {
+            if let Some(from) = &focus.moved_from {
+                println!("  Moved from {}", style::paint(Role::Path, from));
+            }
+            if old.signature == new.signature {
+                println!("  Signature unchanged: {}", new.signature);
+            } else {
+                let (old_line, new_line) = highlight(&old.signature, &new.signature);
+                println!("  {}\n  {}", old_line, new_line);
+                let severity = crate::modified_severity(old, new);
+                println!("  This change is {}", severity.label());
+                if let Some(change) = crate::async_change(old, new) {
+                    let line = format!("  The function is {}", change);
+                    println!("{}", style::paint(Role::Removed, &line));
+                }
+                if let Some(wrapping) = crate::return_wrapping(old, new) {
+                    let line = format!("  The {}", wrapping.describe());
+                    println!("{}", style::paint(Role::Removed, &line));
+                }
+            }
+            if old.tags != new.tags {
+                println!(
+                    "  Markers: [{}] -> [{}]",
+                    old.tags.join(", "),
+                    new.tags.join(", ")
+                );
+            }
+            let stability = stability_changes(old, new);
+            if !stability.is_empty() {
+                println!("  Stability: {}", stability.join(", "));
+            }
+            let (old_docs, new_docs) = (old.docs.as_deref(), new.docs.as_deref());
+            println!("  Docs: {}", docs_change(old_docs, new_docs));
+            if show_docs && old_docs != new_docs {
+                for (op, line) in docs_diff(old_docs.unwrap_or(""), new_docs.unwrap_or("")) {
+                    let line = match op {
+                        Op::Same(..) => {
+                            style::paint(Role::Muted, format!("    {}", line).trim_end())
+                        }
+                        Op::Removed(_) => {
+                            style::paint(Role::Removed, format!("  - {}", line).trim_end())
+                        }
+                        Op::Added(_) => {
+                            style::paint(Role::Added, format!("  + {}", line).trim_end())
+                        }
+                    };
+                    println!("  {}", line);
+                }
+            }
+        }
+        (None, Some(new)) => {
+            let line = format!("+ {}", new.signature);
+            println!("  Added in {}", report.ver2);
+            println!("  {}", style::paint(Role::Added, &line));
+        }
+        (Some(old), None) => {
+            let line = format!("- {}", old.signature);
+            println!("  Removed in {}", report.ver2);
+            println!("  {}", style::paint(Role::Removed, &line));
+        }
+        (None, None) => {}
+    }
+
+    // The members' changes, the item's own left out
+    let own = item.map(ApiItem::diff_key);
+    let is_member = |item: &&ApiItem| Some(item.diff_key()) != own;
+    let added: Vec<&ApiItem> = report.added.iter().filter(is_member).collect();
+    let removed: Vec<&ApiItem> = report.removed.iter().filter(is_member).collect();
+    let modified: Vec<&(ApiItem, ApiItem)> = report
+        .modified
+        .iter()
+        .filter(|(old, _)| is_member(&old))
+        .collect();
+    if added.is_empty() && removed.is_empty() && modified.is_empty() {
+        return;
+    }
+    println!(
+        "\n{}",
+        format!(
+            "Members: +{} / -{} / ~{}",
+            added.len(),
+            removed.len(),
+            modified.len()
+        )
+        .bold()
+    );
+    for item in removed {
+        let line = format!("- {} {}", item.display_string(), item.shown_signature());
+        println!("  {}", style::paint(Role::Removed, &line));
+    }
+    for item in added {
+        let line = format!("+ {} {}", item.display_string(), item.shown_signature());
+        println!("  {}", style::paint(Role::Added, &line));
+    }
+    for (old, new) in modified {
+        let heading = format!("~ {}", old.display_string());
+        println!(
+            "  {} ({})",
+            style::paint(Role::Modified, &heading),
+            crate::modified_severity(old, new).label()
+        );
+        if old.signature != new.signature {
+            let (old_line, new_line) = highlight(&old.signature, &new.signature);
+            println!("    {}\n    {}", old_line, new_line);
+        }
+    }
+}
+
+// The usual JSON diff of the item and its members, with an `item` object
+// describing the item itself
+fn render_json(report: &DiffReport, focus: &Focus, show_docs: bool) -> Value {
+    let side = |item: &Option<ApiItem>| {
+        item.as_ref().map(|item| {
+            serde_json::json!({
+                "path": item.full_path(),
+                "kind": item.item_type,
+                "signature": item.signature,
+                "tags": item.tags,
+                "stability": item.stability,
+                "docs": item.docs,
+            })
+        })
+    };
+    let mut item = serde_json::json!({
+        "query": focus.query,
+        "old": side(&focus.old),
+        "new": side(&focus.new),
+        "moved_from": focus.moved_from,
+    });
+    if let (Some(old), Some(new)) = (&focus.old, &focus.new) {
+        let (old_docs, new_docs) = (old.docs.as_deref(), new.docs.as_deref());
+        item["docs"] = docs_change(old_docs, new_docs).into();
+        if show_docs && old_docs != new_docs {
+            let lines: Vec<String> = docs_diff(old_docs.unwrap_or(""), new_docs.unwrap_or(""))
+                .into_iter()
+                .map(|(op, line)| match op {
+                    Op::Same(..) => format!(" {}", line),
+                    Op::Removed(_) => format!("-{}", line),
+                    Op::Added(_) => format!("+{}", line),
+                })
+                .collect();
+            item["docs_diff"] = lines.into();
+        }
+    }
+    let mut json = crate::render_diff_json(report);
+    json["item"] = item;
+    json
+}
+
+// Report an item diff in text or JSON; the other formats are for whole crates
+pub fn print(
+    report: &DiffReport,
+    focus: &Focus,
+    show_docs: bool,
+    format: DiffFormat,
+    json_style: JsonStyle,
+) -> Result<()> {
+    match format {
+        DiffFormat::Text => {
+            print_text(report, focus, show_docs);
+            Ok(())
+        }
+        DiffFormat::Json => crate::print_json(&render_json(report, focus, show_docs), json_style),
+        _ => anyhow::bail!("--item prints text or JSON (--format text|json)"),
+    }
+}
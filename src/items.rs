@@ -0,0 +1,900 @@
+// Helpers for navigating the rustdoc JSON index: item lookup by path, member
+// resolution through impl blocks, and doc comment code blocks.
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+// An item located in the index, with the path it was resolved under
+pub struct ResolvedItem<'a> {
+    pub id: String,
+    pub item: &'a Value,
+    pub path: Vec<String>,
+    // For members (methods, variants, fields), the id of the owning type
+    pub owner: Option<String>,
+}
+
+impl ResolvedItem<'_> {
+    pub fn full_path(&self) -> String {
+        self.path.join("::")
+    }
+}
+
+// Info-string attributes rustdoc understands on Rust code blocks
+const RUSTDOC_ATTRS: &[&str] = &[
+    "ignore",
+    "should_panic",
+    "no_run",
+    "compile_fail",
+    "test_harness",
+    "standalone_crate",
+];
+
+// An item resolved from a user-supplied path, owning the docs it came from
+pub struct LocatedItem {
+    // The package whose docs the item was found in
+    pub crate_name: String,
+    pub json: Value,
+    pub id: String,
+    pub path: Vec<String>,
+    pub owner: Option<String>,
+}
+
+impl LocatedItem {
+    pub fn resolved(&self) -> ResolvedItem<'_> {
+        ResolvedItem {
+            id: self.id.clone(),
+            item: lookup(&self.json, &self.id).expect("located ids come from the index"),
+            path: self.path.clone(),
+            owner: self.owner.clone(),
+        }
+    }
+}
+
+// A fenced code block from a doc comment
+pub struct CodeBlock {
+    pub info: String,
+    pub code: String,
+    // Line of the opening fence within the docs, counting from 1
+    pub line: usize,
+    // False when the docs end before the closing fence
+    pub closed: bool,
+}
+
+impl CodeBlock {
+    // Like rustdoc, a block is Rust unless its info string names another language
+    pub fn is_rust(&self) -> bool {
+        self.info
+            .split(',')
+            .map(str::trim)
+            .filter(|attr| !attr.is_empty())
+            .all(|attr| {
+                attr == "rust" || RUSTDOC_ATTRS.contains(&attr) || attr.starts_with("edition")
+            })
+    }
+
+    // Whether the info string carries an attribute such as `no_run`
+    pub fn has_attr(&self, attr: &str) -> bool {
+        self.info.split(',').any(|part| part.trim() == attr)
+    }
+
+    // The code as compiled by rustdoc: hidden `# ` lines included with the
+    // marker removed, and `##` unescaped to `#`
+    pub fn compiled_code(&self) -> String {
+        self.code
+            .lines()
+            .map(|line| {
+                let indent = &line[..line.len() - line.trim_start().len()];
+                let trimmed = line.trim_start();
+                if trimmed == "#" {
+                    String::new()
+                } else if let Some(rest) = trimmed.strip_prefix("##") {
+                    format!("{}#{}", indent, rest)
+                } else if let Some(rest) = trimmed.strip_prefix("# ") {
+                    format!("{}{}", indent, rest)
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    // The code as rendered by rustdoc, without the hidden `# ` lines
+    pub fn visible_code(&self) -> String {
+        if !self.is_rust() {
+            return self.code.clone();
+        }
+        self.code
+            .lines()
+            .filter(|line| {
+                let trimmed = line.trim_start();
+                trimmed != "#" && !trimmed.starts_with("# ")
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+// Ids are numbers in current rustdoc JSON and strings in older formats
+pub fn id_key(id: &Value) -> Option<String> {
+    match id {
+        Value::Number(n) => Some(n.to_string()),
+        Value::String(s) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+// The item kind: the single key of the "inner" object
+pub fn item_kind(item: &Value) -> Option<&str> {
+    let inner = item.get("inner")?;
+    // Kinds without data (`extern_type`) are a bare string
+    inner
+        .as_str()
+        .or_else(|| inner.as_object()?.keys().next().map(String::as_str))
+}
+
+// The kind-specific payload under "inner"
+pub fn item_inner(item: &Value) -> Option<&Value> {
+    let kind = item_kind(item)?;
+    item.get("inner")?.get(kind)
+}
+
+pub fn item_name(item: &Value) -> Option<&str> {
+    item.get("name").and_then(|v| v.as_str())
+}
+
+pub fn item_docs(item: &Value) -> Option<&str> {
+    item.get("docs")
+        .and_then(|v| v.as_str())
+        .filter(|docs| !docs.trim().is_empty())
+}
+
+// Where an item starts: the file rustdoc named and the first line, when the
+// docs were read with source spans
+pub fn item_location(item: &Value) -> Option<(String, usize)> {
+    let span = item.get("span")?;
+    let file = span.get("filename").and_then(|v| v.as_str())?;
+    let line = span.get("begin")?.get(0).and_then(|v| v.as_u64())?;
+    Some((file.to_string(), line as usize))
+}
+
+pub fn lookup<'a>(json: &'a Value, id: &str) -> Option<&'a Value> {
+    json.get("index")?.get(id)
+}
+
+// The canonical path recorded for an id in the "paths" table
+pub fn canonical_path(json: &Value, id: &str) -> Option<Vec<String>> {
+    json.get("paths")?
+        .get(id)?
+        .get("path")?
+        .as_array()
+        .map(|segments| {
+            segments
+                .iter()
+                .filter_map(|s| s.as_str().map(String::from))
+                .collect()
+        })
+}
+
+// Ids of the impl blocks attached to a type (or the implementations of a trait)
+pub fn impl_ids(item: &Value) -> Vec<String> {
+    let inner = match item_inner(item) {
+        Some(inner) => inner,
+        None => return Vec::new(),
+    };
+    inner
+        .get("impls")
+        .or_else(|| inner.get("implementations"))
+        .and_then(|v| v.as_array())
+        .map(|ids| ids.iter().filter_map(id_key).collect())
+        .unwrap_or_default()
+}
+
+// Ids of the items an impl block contributes
+pub fn impl_member_ids(impl_item: &Value) -> Vec<String> {
+    item_inner(impl_item)
+        .and_then(|inner| inner.get("items"))
+        .and_then(|v| v.as_array())
+        .map(|ids| ids.iter().filter_map(id_key).collect())
+        .unwrap_or_default()
+}
+
+// Inherent impls have no trait
+pub fn is_inherent_impl(impl_item: &Value) -> bool {
+    item_inner(impl_item)
+        .and_then(|inner| inner.get("trait"))
+        .is_none_or(Value::is_null)
+}
+
+// Direct members of a type or trait: variants, fields, trait items and
+// everything its impl blocks provide
+pub fn member_ids(json: &Value, item: &Value) -> Vec<String> {
+    let mut ids = item_inner(item).map(own_member_ids).unwrap_or_default();
+    for impl_id in impl_ids(item) {
+        if let Some(impl_item) = lookup(json, &impl_id) {
+            ids.extend(impl_member_ids(impl_item));
+        }
+    }
+    ids
+}
+
+// Members declared in the item itself (variants, fields, trait items)
+pub fn own_member_ids(inner: &Value) -> Vec<String> {
+    let mut ids = Vec::new();
+    for key in ["variants", "items"] {
+        if let Some(list) = inner.get(key).and_then(|v| v.as_array()) {
+            ids.extend(list.iter().filter_map(id_key));
+        }
+    }
+    if let Some(fields) = field_ids(inner) {
+        ids.extend(fields);
+    }
+    ids
+}
+
+// Field ids of a struct, union or variant, across the plain/tuple/struct encodings
+pub fn field_ids(inner: &Value) -> Option<Vec<String>> {
+    let fields = inner
+        .get("kind")
+        .and_then(|kind| {
+            kind.get("plain")
+                .or_else(|| kind.get("struct"))
+                .and_then(|named| named.get("fields"))
+                .or_else(|| kind.get("tuple"))
+        })
+        .or_else(|| inner.get("fields"))?
+        .as_array()?;
+    // Tuple fields hidden from the docs show up as nulls
+    Some(fields.iter().filter_map(id_key).collect())
+}
+
+// Resolve a `::`-separated path to candidate items. Paths may omit leading
+// segments (`Client::connect` matches `mycrate::client::Client::connect`), and
+// the last segment may name a member reachable through the parent's impls.
+pub fn resolve_path<'a>(json: &'a Value, segments: &[&str]) -> Vec<ResolvedItem<'a>> {
+    let mut candidates = resolve_defined(json, segments);
+
+    if candidates.is_empty() && segments.len() > 1 {
+        let (member_name, parent) = segments.split_last().expect("non-empty path");
+        for owner in resolve_defined(json, parent) {
+            for member_id in member_ids(json, owner.item) {
+                let member = match lookup(json, &member_id) {
+                    Some(member) => member,
+                    None => continue,
+                };
+                if item_name(member) == Some(*member_name) {
+                    let mut path = owner.path.clone();
+                    path.push(member_name.to_string());
+                    candidates.push(ResolvedItem {
+                        id: member_id,
+                        item: member,
+                        path,
+                        owner: Some(owner.id.clone()),
+                    });
+                }
+            }
+        }
+    }
+
+    candidates.sort_by(|a, b| a.path.cmp(&b.path));
+    candidates.dedup_by(|a, b| a.id == b.id);
+    candidates
+}
+
+// Where a user-supplied item path is looked up: a leading crate name (a
+// workspace member or a dependency) narrows the search to that crate;
+// otherwise every workspace crate is searched
+pub struct PathScope {
+    pub crate_names: Vec<String>,
+    // The path as the docs spell it, with the crate's underscored name
+    pub segments: Vec<String>,
+    pub named_crate: bool,
+}
+
+pub fn path_scope(metadata: &cargo_metadata::Metadata, item_path: &str) -> PathScope {
+    let mut segments: Vec<String> = item_path.split("::").map(String::from).collect();
+    let named_crate = metadata
+        .packages
+        .iter()
+        .find(|package| package.name.replace('-', "_") == segments[0].replace('-', "_"));
+    if named_crate.is_some() {
+        segments[0] = segments[0].replace('-', "_");
+    }
+    let crate_names: Vec<String> = match named_crate {
+        Some(package) => vec![package.name.to_string()],
+        None => metadata
+            .workspace_packages()
+            .iter()
+            .map(|package| package.name.to_string())
+            .collect(),
+    };
+    PathScope {
+        crate_names,
+        segments,
+        named_crate: named_crate.is_some(),
+    }
+}
+
+// Generate local docs and resolve `item_path` to exactly one item (see
+// `path_scope`). Ambiguous paths list their candidates on stderr.
+pub fn locate(metadata: &cargo_metadata::Metadata, item_path: &str) -> Result<LocatedItem> {
+    crate::generate_json_docs(metadata)?;
+
+    let scope = path_scope(metadata, item_path);
+    let segments: Vec<&str> = scope.segments.iter().map(String::as_str).collect();
+    let mut candidates = Vec::new();
+    for crate_name in scope.crate_names {
+        if !crate::doc_source::is_generated(metadata, &crate_name) {
+            continue;
+        }
+        let local = crate::DocSource::Local { metadata };
+        let json = crate::doc_source::read_crate_json(&crate_name, local)?.json;
+        let found: Vec<(String, Vec<String>, Option<String>)> = resolve_path(&json, &segments)
+            .into_iter()
+            .map(|resolved| (resolved.id, resolved.path, resolved.owner))
+            .collect();
+        if found.is_empty() {
+            continue;
+        }
+        candidates.push((crate_name, json, found));
+    }
+
+    let total: usize = candidates.iter().map(|(_, _, found)| found.len()).sum();
+    match total {
+        // A leading segment that's nearly a crate name is probably meant as
+        // one, as is a lone name (`zdoc show tokoi`)
+        0 if !scope.named_crate => anyhow::bail!(
+            "No item matching '{}' found in the local docs{}",
+            item_path,
+            crate::selection::did_you_mean(metadata, segments[0])
+        ),
+        0 => anyhow::bail!("No item matching '{}' found in the local docs", item_path),
+        1 => {
+            let (crate_name, json, mut found) = candidates.remove(0);
+            let (id, path, owner) = found.remove(0);
+            Ok(LocatedItem {
+                crate_name,
+                json,
+                id,
+                path,
+                owner,
+            })
+        }
+        _ => {
+            eprintln!("'{}' is ambiguous; candidates:", item_path);
+            for (crate_name, _, found) in &candidates {
+                for (_, path, _) in found {
+                    eprintln!("  {} ({})", path.join("::"), crate_name);
+                }
+            }
+            anyhow::bail!("Ambiguous item path '{}'", item_path);
+        }
+    }
+}
+
+// Items with their own entry in the "paths" table, matched by path suffix
+fn resolve_defined<'a>(json: &'a Value, segments: &[&str]) -> Vec<ResolvedItem<'a>> {
+    let paths = match json.get("paths").and_then(|v| v.as_object()) {
+        Some(paths) => paths,
+        None => return Vec::new(),
+    };
+
+    let mut candidates = Vec::new();
+    for (id, summary) in paths {
+        let path: Vec<String> = match summary.get("path").and_then(|v| v.as_array()) {
+            Some(path) => path
+                .iter()
+                .filter_map(|s| s.as_str().map(String::from))
+                .collect(),
+            None => continue,
+        };
+        if !ends_with_segments(&path, segments) {
+            continue;
+        }
+        // Only items documented in this crate have index entries
+        if let Some(item) = lookup(json, id) {
+            candidates.push(ResolvedItem {
+                id: id.clone(),
+                item,
+                path,
+                owner: None,
+            });
+        }
+    }
+    candidates
+}
+
+fn ends_with_segments(path: &[String], segments: &[&str]) -> bool {
+    path.len() >= segments.len()
+        && path[path.len() - segments.len()..]
+            .iter()
+            .zip(segments)
+            .all(|(a, b)| a == b)
+}
+
+// The keyword a declaration of this kind starts with
+pub fn kind_keyword(kind: &str) -> &str {
+    match kind {
+        "function" => "fn",
+        "type_alias" | "assoc_type" => "type",
+        "constant" | "assoc_const" => "const",
+        "module" => "mod",
+        "macro" => "macro_rules!",
+        "struct_field" => "field",
+        other => other,
+    }
+}
+
+// A one-line declaration such as `fn connect(addr: &str) -> Client`
+pub fn declaration(item: &Value) -> String {
+    let kind = item_kind(item).unwrap_or("unknown");
+    let name = item_name(item).unwrap_or("_");
+    let signature = crate::extract_signature(kind, item_inner(item));
+    let separator = if signature.is_empty() || signature.starts_with(['(', '<']) {
+        ""
+    } else {
+        " "
+    };
+    format!("{} {}{}{}", kind_keyword(kind), name, separator, signature)
+}
+
+// Collect the ids of every resolved path mentioned anywhere in a JSON subtree
+pub fn referenced_type_ids(value: &Value, out: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            if let Some(id) = map
+                .get("resolved_path")
+                .and_then(|path| path.get("id"))
+                .and_then(id_key)
+                && !out.contains(&id)
+            {
+                out.push(id);
+            }
+            for child in map.values() {
+                referenced_type_ids(child, out);
+            }
+        }
+        Value::Array(values) => {
+            for child in values {
+                referenced_type_ids(child, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+// Split doc text into prose and fenced code blocks
+pub fn split_docs(docs: &str) -> (String, Vec<CodeBlock>) {
+    let (prose, blocks) = parse_docs(docs);
+    let prose: Vec<&str> = prose.into_iter().map(|(_, line)| line).collect();
+    (prose.join("\n").trim().to_string(), blocks)
+}
+
+// Like `split_docs`, but keeps the (1-based) line number of each prose line
+pub fn parse_docs(docs: &str) -> (Vec<(usize, &str)>, Vec<CodeBlock>) {
+    let mut prose = Vec::new();
+    let mut blocks = Vec::new();
+    let mut current: Option<(String, String, usize, Vec<&str>)> = None;
+
+    for (number, line) in docs.lines().enumerate() {
+        let trimmed = line.trim_start();
+        match current.as_mut() {
+            Some((fence, _, _, lines)) => {
+                if closes_fence(trimmed, fence) {
+                    let (_, info, start, lines) = current.take().expect("open fence");
+                    blocks.push(CodeBlock {
+                        info,
+                        code: lines.join("\n"),
+                        line: start,
+                        closed: true,
+                    });
+                } else {
+                    lines.push(line);
+                }
+            }
+            None => {
+                if let Some(fence) = opening_fence(trimmed) {
+                    let info = trimmed[fence.len()..].trim().to_string();
+                    current = Some((fence, info, number + 1, Vec::new()));
+                } else {
+                    prose.push((number + 1, line));
+                }
+            }
+        }
+    }
+
+    // An unclosed fence runs to the end of the docs, as in CommonMark
+    if let Some((_, info, start, lines)) = current {
+        blocks.push(CodeBlock {
+            info,
+            code: lines.join("\n"),
+            line: start,
+            closed: false,
+        });
+    }
+
+    (prose, blocks)
+}
+
+// The fence marker (three or more backticks or tildes) opening a code block
+pub fn opening_fence(line: &str) -> Option<String> {
+    let marker = line.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let fence: String = line.chars().take_while(|c| *c == marker).collect();
+    (fence.len() >= 3).then_some(fence)
+}
+
+// A closing fence uses the same marker, at least as long, with nothing after it
+pub fn closes_fence(line: &str, fence: &str) -> bool {
+    opening_fence(line)
+        .is_some_and(|closing| closing.starts_with(&fence[..1]) && closing.len() >= fence.len())
+        && line.trim_start_matches(&fence[..1]).trim().is_empty()
+}
+
+// The first paragraph of a doc comment, used for one-line summaries
+pub fn first_paragraph(docs: &str) -> &str {
+    docs.trim().split("\n\n").next().unwrap_or("").trim()
+}
+
+// The first paragraph on a single line, for listings and search previews.
+// Markdown links keep only their text.
+pub fn summary(docs: &str) -> String {
+    let paragraph = docs
+        .trim()
+        .split("\n\n")
+        .find(|p| !p.trim_start().starts_with('#') && opening_fence(p.trim_start()).is_none())
+        .unwrap_or("");
+    let line = paragraph.split_whitespace().collect::<Vec<_>>().join(" ");
+    strip_links(&line)
+}
+
+// `[text](url)` and `[text][ref]` become `text`; intra-doc links `[`Foo`]` become `Foo`
+pub fn strip_links(text: &str) -> String {
+    let link = regex::Regex::new(r"\[([^\]]+)\](?:\([^)]*\)|\[[^\]]*\])|\[(`[^`\]]+`)\]")
+        .expect("valid regex");
+    link.replace_all(text, |caps: &regex::Captures| {
+        caps.get(1)
+            .or_else(|| caps.get(2))
+            .map_or("", |m| m.as_str())
+            .to_string()
+    })
+    .into_owned()
+}
+
+// Attributes as source-like text without the `#[...]` wrapper. Older formats
+// store source strings (`#[non_exhaustive]`); newer ones store bare names or
+// tagged objects (`{"must_use": {...}}`, `{"other": "#[doc(hidden)]"}`).
+pub fn attr_texts(item: &Value) -> Vec<String> {
+    let attrs = match item.get("attrs").and_then(|v| v.as_array()) {
+        Some(attrs) => attrs,
+        None => return Vec::new(),
+    };
+    attrs
+        .iter()
+        .filter_map(|attr| match attr {
+            Value::String(text) => Some(text.clone()),
+            Value::Object(map) => map.iter().next().map(|(key, value)| match value.as_str() {
+                Some(text) if key == "other" => text.to_string(),
+                _ => key.clone(),
+            }),
+            _ => None,
+        })
+        .map(|text| {
+            let text = text.trim();
+            text.strip_prefix("#[")
+                .and_then(|t| t.strip_suffix(']'))
+                .unwrap_or(text)
+                .trim()
+                .to_string()
+        })
+        .collect()
+}
+
+// The names `#[doc(alias = "...")]` and `#[doc(alias("...", "..."))]` give
+// the item, for search to find it by. Newer formats list each alias as an
+// attribute of its own; older ones keep the attributes as written.
+pub fn doc_aliases(item: &Value) -> Vec<String> {
+    let alias = regex::Regex::new(r#"alias\s*(?:=\s*"([^"]*)"|\(([^)]*)\))"#).expect("valid regex");
+    let quoted = regex::Regex::new(r#""([^"]*)""#).expect("valid regex");
+    let mut aliases = Vec::new();
+    for text in attr_texts(item) {
+        let Some(args) = text.strip_prefix("doc").map(str::trim_start) else {
+            continue;
+        };
+        if !args.starts_with('(') {
+            continue;
+        }
+        for caps in alias.captures_iter(args) {
+            if let Some(single) = caps.get(1) {
+                aliases.push(single.as_str().to_string());
+            } else if let Some(list) = caps.get(2) {
+                let names = quoted.captures_iter(list.as_str());
+                aliases.extend(names.map(|name| name[1].to_string()));
+            }
+        }
+    }
+    aliases
+}
+
+// Whether the item carries `#[name]`, `#[name(...)]` or `#[name = ...]`
+pub fn has_attr(item: &Value, name: &str) -> bool {
+    attr_texts(item).iter().any(|text| {
+        text.strip_prefix(name)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(['(', ' ', '=']))
+    })
+}
+
+// Ids reachable from the crate root through public modules and re-exports
+pub fn reachable_ids(json: &Value) -> HashSet<String> {
+    reachable_without(json, |_| false)
+}
+
+// The same, without entering the items `skip` matches
+fn reachable_without(json: &Value, skip: impl Fn(&Value) -> bool) -> HashSet<String> {
+    let mut reachable = HashSet::new();
+    let mut queue: Vec<String> = json.get("root").and_then(id_key).into_iter().collect();
+
+    while let Some(id) = queue.pop() {
+        if reachable.contains(&id) {
+            continue;
+        }
+        let Some(item) = lookup(json, &id) else {
+            reachable.insert(id);
+            continue;
+        };
+        if skip(item) {
+            continue;
+        }
+        reachable.insert(id);
+        let inner = item_inner(item);
+        match item_kind(item) {
+            Some("module") => {
+                if let Some(children) = inner
+                    .and_then(|inner| inner.get("items"))
+                    .and_then(|v| v.as_array())
+                {
+                    queue.extend(children.iter().filter_map(id_key));
+                }
+            }
+            Some("use") | Some("import") => {
+                if let Some(target) = inner.and_then(|inner| inner.get("id")).and_then(id_key) {
+                    queue.push(target);
+                }
+            }
+            _ => {}
+        }
+    }
+    reachable
+}
+
+fn is_doc_hidden(item: &Value) -> bool {
+    has_attr(item, "doc(hidden)")
+}
+
+// Ids of `#[doc(hidden)]` items and of the items only reachable through one
+// (the contents of a hidden module). Rustdoc leaves these out unless run with
+// `--document-hidden-items`, but hidden items re-exported publicly and docs
+// built with that flag still have them. Members of hidden types are up to the
+// caller, which knows their parents.
+pub fn hidden_ids(json: &Value) -> HashSet<String> {
+    let visible = reachable_without(json, is_doc_hidden);
+    let mut hidden: HashSet<String> = reachable_ids(json)
+        .into_iter()
+        .filter(|id| !visible.contains(id))
+        .collect();
+    if let Some(index) = json.get("index").and_then(|v| v.as_object()) {
+        hidden.extend(
+            index
+                .iter()
+                .filter(|(_, item)| is_doc_hidden(item))
+                .map(|(id, _)| id.clone()),
+        );
+    }
+    hidden
+}
+
+// `hidden_ids` and the members of hidden types, traits and impls (variants,
+// fields, trait items, methods), which are hidden with them. Members of
+// blanket and synthetic impls are shared with every other type, so they
+// aren't.
+pub fn hidden_items(json: &Value) -> HashSet<String> {
+    let mut hidden = hidden_ids(json);
+    let mut queue: Vec<String> = hidden.iter().cloned().collect();
+    while let Some(id) = queue.pop() {
+        let Some(item) = lookup(json, &id) else {
+            continue;
+        };
+        let Some(inner) = item_inner(item) else {
+            continue;
+        };
+        let members = match item_kind(item) {
+            Some("trait" | "variant") => own_member_ids(inner),
+            Some("struct" | "enum" | "union") => {
+                let mut members = own_member_ids(inner);
+                for impl_item in impl_ids(item).iter().filter_map(|id| lookup(json, id)) {
+                    let shared = item_inner(impl_item).is_some_and(|inner| {
+                        inner.get("blanket_impl").is_some_and(|v| !v.is_null())
+                            || inner.get("is_synthetic").and_then(|v| v.as_bool()) == Some(true)
+                    });
+                    if !shared {
+                        members.extend(impl_member_ids(impl_item));
+                    }
+                }
+                members
+            }
+            Some("impl") => impl_member_ids(item),
+            _ => continue,
+        };
+        for member in members {
+            if hidden.insert(member.clone()) {
+                queue.push(member);
+            }
+        }
+    }
+    hidden
+}
+
+// Items gated behind an unstable feature: std-style `#[unstable(...)]`, or a
+// `#[cfg(feature = "unstable...")]` cargo feature (also in `doc(cfg(...))`,
+// and in the `CfgTrace` form newer rustdoc records cfgs in)
+pub fn is_unstable(item: &Value) -> bool {
+    has_attr(item, "unstable")
+        || attr_texts(item).iter().any(|text| {
+            let cfg =
+                text.starts_with("cfg") || text.starts_with("doc(cfg") || text.contains("CfgTrace");
+            cfg && text.contains("feature") && text.contains("\"unstable")
+        })
+}
+
+pub fn is_deprecated(item: &Value) -> bool {
+    item.get("deprecation").is_some_and(|v| !v.is_null())
+}
+
+// Every path each item can be named by from outside the crate: where it's
+// defined and where it's re-exported, walking public modules from the root.
+// A glob re-export of a module brings its items in under the importing module.
+pub fn public_paths(json: &Value) -> HashMap<String, BTreeSet<Vec<String>>> {
+    let mut paths: HashMap<String, BTreeSet<Vec<String>>> = HashMap::new();
+    let Some(root) = json.get("root").and_then(id_key) else {
+        return paths;
+    };
+    let Some(crate_name) = lookup(json, &root).and_then(item_name) else {
+        return paths;
+    };
+
+    let mut visited = HashSet::new();
+    let mut queue = vec![(root, vec![crate_name.to_string()])];
+    while let Some((module_id, prefix)) = queue.pop() {
+        // Glob cycles (`a` re-exporting `b::*` and back) end here
+        if !visited.insert((module_id.clone(), prefix.clone())) {
+            continue;
+        }
+        let children = lookup(json, &module_id)
+            .and_then(item_inner)
+            .and_then(|inner| inner.get("items"))
+            .and_then(|v| v.as_array())
+            .map(|ids| ids.iter().filter_map(id_key).collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        for child_id in children {
+            let Some(child) = lookup(json, &child_id) else {
+                continue;
+            };
+            let (target, name) = match item_kind(child) {
+                Some("use" | "import") => {
+                    let inner = item_inner(child);
+                    let Some(target) = inner.and_then(|inner| inner.get("id")).and_then(id_key)
+                    else {
+                        continue;
+                    };
+                    if inner
+                        .and_then(|inner| inner.get("is_glob"))
+                        .and_then(|v| v.as_bool())
+                        == Some(true)
+                    {
+                        if lookup(json, &target).and_then(item_kind) == Some("module") {
+                            queue.push((target, prefix.clone()));
+                        }
+                        continue;
+                    }
+                    let Some(name) = inner
+                        .and_then(|inner| inner.get("name"))
+                        .and_then(|v| v.as_str())
+                    else {
+                        continue;
+                    };
+                    (target, name.to_string())
+                }
+                _ => {
+                    let Some(name) = item_name(child) else {
+                        continue;
+                    };
+                    (child_id, name.to_string())
+                }
+            };
+
+            let mut path = prefix.clone();
+            path.push(name);
+            if lookup(json, &target).and_then(item_kind) == Some("module") {
+                queue.push((target.clone(), path.clone()));
+            }
+            paths.entry(target).or_default().insert(path);
+        }
+    }
+    paths
+}
+
+// The sealed-trait pattern: a supertrait defined in this crate that
+// downstream code can't name, so nobody else can implement the trait
+pub fn is_sealed_trait(json: &Value, item: &Value, reachable: &HashSet<String>) -> bool {
+    let Some(bounds) = item_inner(item)
+        .and_then(|inner| inner.get("bounds"))
+        .and_then(|v| v.as_array())
+    else {
+        return false;
+    };
+    bounds.iter().any(|bound| {
+        let Some(id) = bound
+            .get("trait_bound")
+            .and_then(|tb| tb.get("trait"))
+            .and_then(|tr| tr.get("id"))
+            .and_then(id_key)
+        else {
+            return false;
+        };
+        let local = json
+            .get("paths")
+            .and_then(|paths| paths.get(&id))
+            .and_then(|summary| summary.get("crate_id"))
+            .and_then(|v| v.as_u64())
+            .is_none_or(|crate_id| crate_id == 0);
+        local && !reachable.contains(&id)
+    })
+}
+
+// Markers that change what downstream code may rely on
+pub fn compat_tags(json: &Value, item: &Value, reachable: &HashSet<String>) -> Vec<String> {
+    let mut tags = Vec::new();
+    if has_attr(item, "non_exhaustive") {
+        tags.push("non_exhaustive".to_string());
+    }
+    // Ignoring the value of a `#[must_use]` function or type warns downstream
+    if has_attr(item, "must_use") {
+        tags.push("must_use".to_string());
+    }
+    if item_kind(item) == Some("trait") && is_sealed_trait(json, item, reachable) {
+        tags.push("sealed".to_string());
+    }
+    tags
+}
+
+// Whether a struct or struct-like variant has fields downstream code can't
+// see, so it can't be built with a literal or destructured exhaustively
+pub fn has_private_fields(item: &Value) -> bool {
+    let Some(kind) = item_inner(item).and_then(|inner| inner.get("kind")) else {
+        return false;
+    };
+    if let Some(fields) = kind.get("plain").or_else(|| kind.get("struct")) {
+        return fields.get("has_stripped_fields").and_then(Value::as_bool) == Some(true);
+    }
+    // Tuple fields that aren't visible are null
+    kind.get("tuple")
+        .and_then(Value::as_array)
+        .is_some_and(|fields| fields.iter().any(Value::is_null))
+}
+
+// Trait items without a default that every implementor has to provide
+pub fn is_required_trait_item(item: &Value) -> bool {
+    let Some(inner) = item_inner(item) else {
+        return false;
+    };
+    match item_kind(item) {
+        Some("function") => inner.get("has_body").and_then(|v| v.as_bool()) == Some(false),
+        Some("assoc_type") => ["type", "default"]
+            .iter()
+            .all(|key| inner.get(key).is_none_or(Value::is_null)),
+        Some("assoc_const") => ["value", "default"]
+            .iter()
+            .all(|key| inner.get(key).is_none_or(Value::is_null)),
+        _ => false,
+    }
+}
@@ -0,0 +1,332 @@
+// Width-aware layout for the search and diff listings. Long signatures wrap
+// at commas, generics and `->` with a hanging indent instead of mid-token,
+// long paths lose their middle (`tokio::…::mpsc::Sender`), and wide terminals
+// get old and new signatures side by side.
+//
+// The width is `--width`, else the terminal's, else `DEFAULT_WIDTH` when
+// stdout isn't a terminal. `-v` keeps paths whole.
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const DEFAULT_WIDTH: usize = 80;
+// Never lay out narrower than this, however small the pane
+const MIN_WIDTH: usize = 40;
+// Continuation lines start this much further in than the first
+const HANG: usize = 4;
+// Side-by-side old/new signatures need at least this many columns
+const TWO_COLUMN_WIDTH: usize = 140;
+
+static WIDTH: OnceLock<usize> = OnceLock::new();
+static FULL_PATHS: AtomicBool = AtomicBool::new(false);
+
+// Lay out for `width` columns this run, whatever the terminal is (`--width`)
+pub fn set_width(width: usize) {
+    let _ = WIDTH.set(width.max(MIN_WIDTH));
+}
+
+// Never shorten paths (`-v`)
+pub fn set_full_paths(full: bool) {
+    FULL_PATHS.store(full, Ordering::Relaxed);
+}
+
+pub fn width() -> usize {
+    *WIDTH.get_or_init(|| {
+        let stdout = std::io::stdout();
+        let detected = if stdout.is_terminal() {
+            terminal_size::terminal_size_of(&stdout).map(|(w, _)| w.0 as usize)
+        } else {
+            None
+        };
+        detected.unwrap_or(DEFAULT_WIDTH).max(MIN_WIDTH)
+    })
+}
+
+// `path` in at most `max` columns: the crate and as many trailing segments
+// as fit, with the ones between elided (`tokio::…::mpsc::Sender`)
+pub fn shorten_path(path: &str, max: usize) -> String {
+    if FULL_PATHS.load(Ordering::Relaxed) || path.chars().count() <= max {
+        return path.to_string();
+    }
+    let segments: Vec<&str> = path.split("::").collect();
+    let Some((first, rest)) = segments.split_first() else {
+        return path.to_string();
+    };
+    let mut kept: Vec<&str> = Vec::new();
+    for segment in rest.iter().rev() {
+        let mut candidate = vec![*first, "…"];
+        candidate.extend(kept.iter().rev());
+        candidate.push(segment);
+        if candidate.join("::").chars().count() > max && !kept.is_empty() {
+            break;
+        }
+        kept.push(segment);
+    }
+    let mut shortened = vec![*first, "…"];
+    shortened.extend(kept.iter().rev());
+    shortened.join("::")
+}
+
+// Split points: after `, ` and a `(`/`<`/`[` opened by a name or generics
+// (arguments and generics, not tuples or slices), and before ` -> `, ` where ` and
+// ` + ` bounds. Each piece keeps its separator, so joining them gives
+// `text` back, and comes with how good a place it is to break, lower being
+// better: shallower brackets first, then `where`, then commas, `->` and
+// brackets, then `+`.
+fn pieces(text: &str) -> Vec<(&str, usize)> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut depth: usize = 0;
+    let bytes = text.as_bytes();
+    for (i, c) in text.char_indices() {
+        // `name(`, `name<`, and `name<T>(` after generic parameters
+        let after_name = i > 0
+            && matches!(bytes[i - 1], b'_' | b'>' | b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9')
+            && !text[..i].ends_with("->");
+        let split_at = match c {
+            '(' | '<' | '[' => {
+                depth += 1;
+                let empty = matches!(bytes.get(i + 1), Some(b')' | b'>' | b']'));
+                (after_name && !empty).then_some((i + 1, 1))
+            }
+            // `->` isn't a closing bracket
+            '>' if i > 0 && bytes[i - 1] == b'-' => None,
+            ')' | '>' | ']' => {
+                depth = depth.saturating_sub(1);
+                None
+            }
+            ',' if bytes.get(i + 1) == Some(&b' ') => Some((i + 2, 1)),
+            ' ' if text[i..].starts_with(" where ") => Some((i + 1, 0)),
+            ' ' if text[i..].starts_with(" -> ") => Some((i + 1, 1)),
+            ' ' if text[i..].starts_with(" + ") => Some((i + 1, 2)),
+            _ => None,
+        };
+        if let Some((end, rank)) = split_at
+            && end > start
+        {
+            pieces.push((&text[start..end], depth * 3 + rank));
+            start = end;
+        }
+    }
+    if start < text.len() {
+        pieces.push((&text[start..], 0));
+    }
+    pieces
+}
+
+// `text` as lines of at most `width` columns, the first starting `indent`
+// columns in and the rest `indent + HANG`. Lines are returned without
+// the indent, except continuation lines carry the `HANG` spaces.
+//
+// Each line ends at the best split point in its second half, so
+// `HashMap<String, Vec<u8>>` stays together when the commas around it
+// will do.
+pub fn wrap_lines(text: &str, indent: usize, width: usize) -> Vec<String> {
+    let first_room = width.saturating_sub(indent).max(MIN_WIDTH / 2);
+    let room = first_room.saturating_sub(HANG).max(MIN_WIDTH / 2);
+    let mut pieces = pieces(text);
+    let mut lines: Vec<String> = Vec::new();
+    let mut next = 0;
+    while next < pieces.len() {
+        let limit = if lines.is_empty() { first_room } else { room };
+        pieces[next].0 = pieces[next].0.trim_start();
+        // (pieces taken, columns, rank) for each place the line could end
+        let mut ends: Vec<(usize, usize, usize)> = Vec::new();
+        let mut len = 0;
+        for (i, (piece, rank)) in pieces.iter().enumerate().skip(next) {
+            if len + piece.trim_end().chars().count() > limit {
+                break;
+            }
+            len += piece.chars().count();
+            ends.push((i + 1, len, *rank));
+        }
+
+        let end = match ends.last() {
+            // A piece too long for a line of its own is cut where the line ends
+            None => {
+                let piece = pieces[next].0;
+                let cut = piece
+                    .char_indices()
+                    .nth(limit)
+                    .map_or(piece.len(), |(i, _)| i);
+                lines.push(piece[..cut].to_string());
+                pieces[next].0 = &piece[cut..];
+                continue;
+            }
+            Some(&(end, _, _)) if end == pieces.len() => end,
+            Some(_) => {
+                let late: Vec<_> = ends
+                    .iter()
+                    .filter(|(_, len, _)| *len >= limit / 2)
+                    .collect();
+                let candidates = if late.is_empty() {
+                    ends.iter().collect()
+                } else {
+                    late
+                };
+                candidates
+                    .into_iter()
+                    .min_by_key(|(_, len, rank)| (*rank, std::cmp::Reverse(*len)))
+                    .map_or(next + 1, |(end, _, _)| *end)
+            }
+        };
+        let line: String = pieces[next..end].iter().map(|(piece, _)| *piece).collect();
+        lines.push(line.trim_end().to_string());
+        next = end;
+    }
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+    let hang = " ".repeat(HANG);
+    for line in lines.iter_mut().skip(1) {
+        line.insert_str(0, &hang);
+    }
+    lines
+}
+
+// `wrap_lines`, joined with the indent of continuation lines, ready for a
+// `println!` that writes `indent` columns before it
+pub fn wrap(text: &str, indent: usize) -> String {
+    let pad = " ".repeat(indent);
+    wrap_lines(text, indent, width()).join(&format!("\n{}", pad))
+}
+
+// `left` and `right` as two columns of rows, each padded to the column
+// width, when the terminal is wide enough for it
+pub fn side_by_side(left: &str, right: &str, indent: usize) -> Option<Vec<(String, String)>> {
+    side_by_side_in(left, right, indent, width())
+}
+
+fn side_by_side_in(
+    left: &str,
+    right: &str,
+    indent: usize,
+    width: usize,
+) -> Option<Vec<(String, String)>> {
+    if width < TWO_COLUMN_WIDTH {
+        return None;
+    }
+    // ` │ ` between the columns
+    let column = (width - indent - 3) / 2;
+    let left = wrap_lines(left, 0, column);
+    let right = wrap_lines(right, 0, column);
+    // No wider than the old side needs, keeping short changes together
+    let column = left
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    let rows = left.len().max(right.len());
+    Some(
+        (0..rows)
+            .map(|i| {
+                let cell = |lines: &[String]| {
+                    let text = lines.get(i).map_or("", String::as_str);
+                    format!("{:<column$}", text)
+                };
+                (cell(&left), cell(&right).trim_end().to_string())
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIGNATURES: &[&str] = &[
+        "pub fn len(&self) -> usize",
+        "pub fn from_reader<R, T>(rdr: R) -> Result<T, Error> where R: Read, T: DeserializeOwned",
+        "pub fn spawn<F>(future: F) -> JoinHandle<F::Output> where F: Future + Send + 'static, \
+         F::Output: Send + 'static",
+        "pub fn insert(&mut self, key: HashMap<String, Vec<u8>>, value: BTreeMap<(u32, u32), \
+         Option<Box<dyn Fn(&str) -> bool + Send + Sync>>>) -> Option<V>",
+        "pub const A_NAME_SO_LONG_THAT_NO_LINE_OF_ANY_WIDTH_BELOW_EIGHTY_HAS_ROOM_FOR_IT: u8",
+    ];
+
+    const PATHS: &[&str] = &[
+        "serde::Deserialize",
+        "tokio::sync::mpsc::bounded::Sender",
+        "windows_sys::Win32::System::Diagnostics::Debug::Extensions::IDebugControl7",
+    ];
+
+    const OLD: &str = "- pub fn connect(addr: SocketAddr, timeout: Duration) -> Result<TcpStream>";
+    const NEW: &str = "+ pub async fn connect<A: ToSocketAddrs>(addr: A, timeout: Option<Duration>, \
+                       retries: u8) -> io::Result<TcpStream>";
+
+    // Signatures, paths and a changed signature laid out as the search and
+    // diff listings do, under a ruler `width` columns long
+    fn render(width: usize) -> String {
+        let mut out = format!("{}\n", "-".repeat(width));
+        for signature in SIGNATURES {
+            let lines = wrap_lines(signature, 4, width);
+            out.push_str(&format!("    {}\n", lines.join("\n    ")));
+        }
+        out.push('\n');
+        for path in PATHS {
+            let max = width - "   Path: ".len();
+            out.push_str(&format!("   Path: {}\n", shorten_path(path, max)));
+        }
+        out.push('\n');
+        match side_by_side_in(OLD, NEW, 4, width) {
+            Some(rows) => {
+                for (left, right) in rows {
+                    out.push_str(&format!("    {} │ {}\n", left, right));
+                }
+            }
+            None => {
+                for line in [OLD, NEW] {
+                    let lines = wrap_lines(line, 4, width);
+                    out.push_str(&format!("    {}\n", lines.join("\n    ")));
+                }
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn layout_at_60_columns() {
+        insta::assert_snapshot!(render(60));
+    }
+
+    #[test]
+    fn layout_at_80_columns() {
+        insta::assert_snapshot!(render(80));
+    }
+
+    #[test]
+    fn layout_at_200_columns() {
+        insta::assert_snapshot!(render(200));
+    }
+
+    #[test]
+    fn lines_fit_the_width() {
+        for width in [60, 80, 200] {
+            for signature in SIGNATURES {
+                for line in wrap_lines(signature, 4, width) {
+                    assert!(
+                        4 + line.chars().count() <= width,
+                        "{:?} at {} columns",
+                        line,
+                        width
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn wrapping_keeps_the_text() {
+        for width in [60, 80, 200] {
+            for signature in SIGNATURES {
+                let joined: String = wrap_lines(signature, 4, width)
+                    .iter()
+                    .map(|line| line.trim_start())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let squeezed = |text: &str| text.split_whitespace().collect::<String>();
+                assert_eq!(squeezed(&joined), squeezed(signature));
+            }
+        }
+    }
+}
@@ -0,0 +1,158 @@
+// docs.rs permalinks for `--links`. Items with a page of their own link to
+// it (`https://docs.rs/{crate}/{version}/{crate}/struct.Name.html`); variants,
+// fields, methods and associated items link to their anchor on the page of
+// the type or trait they belong to, as rustdoc renders them.
+use serde_json::Value;
+use std::cell::OnceCell;
+use std::collections::{BTreeSet, HashMap};
+
+use crate::items;
+
+pub struct Linker<'a> {
+    json: &'a Value,
+    // `https://docs.rs/{crate}/{version}`
+    base: String,
+    // Members -> the type, trait or variant declaring or implementing them
+    parents: HashMap<String, String>,
+    // Where re-exported items can be imported from, found on first use
+    public_paths: OnceCell<HashMap<String, BTreeSet<Vec<String>>>>,
+}
+
+impl<'a> Linker<'a> {
+    // Links into the docs of `crate_name` at the version `json` documents,
+    // or the latest release when it doesn't say
+    pub fn new(json: &'a Value, crate_name: &str) -> Self {
+        let version = json
+            .get("crate_version")
+            .and_then(|v| v.as_str())
+            .unwrap_or("latest");
+        let mut parents = HashMap::new();
+        if let Some(index) = json.get("index").and_then(|v| v.as_object()) {
+            for (id, item) in index {
+                let members = match items::item_kind(item) {
+                    Some("enum" | "struct" | "union" | "trait" | "variant") => {
+                        items::item_inner(item).map(items::own_member_ids)
+                    }
+                    _ => None,
+                };
+                for member in members.unwrap_or_default() {
+                    parents.insert(member, id.clone());
+                }
+                if items::item_kind(item) != Some("impl") {
+                    continue;
+                }
+                // Impl members are documented on the implementing type's page
+                let self_type = items::item_inner(item)
+                    .and_then(|inner| inner.get("for"))
+                    .and_then(|ty| ty.get("resolved_path"))
+                    .and_then(|path| path.get("id"))
+                    .and_then(items::id_key);
+                if let Some(self_type) = self_type {
+                    for member in items::impl_member_ids(item) {
+                        parents.entry(member).or_insert_with(|| self_type.clone());
+                    }
+                }
+            }
+        }
+        Linker {
+            json,
+            base: format!("https://docs.rs/{}/{}", crate_name, version),
+            parents,
+            public_paths: OnceCell::new(),
+        }
+    }
+
+    pub fn url(&self, id: &str) -> Option<String> {
+        let item = items::lookup(self.json, id)?;
+        let kind = items::item_kind(item)?;
+        if matches!(kind, "use" | "import") {
+            return self.reexport_url(item);
+        }
+        let Some(parent) = self.parents.get(id) else {
+            return self.page_url(id);
+        };
+
+        let name = items::item_name(item)?;
+        let parent_item = items::lookup(self.json, parent)?;
+        let parent_kind = items::item_kind(parent_item)?;
+        let anchor = match kind {
+            "variant" => format!("variant.{}", name),
+            // A struct variant's fields are on the enum's page, under the variant
+            "struct_field" if parent_kind == "variant" => {
+                let variant = items::item_name(parent_item)?;
+                let enum_id = self.parents.get(parent)?;
+                return Some(format!(
+                    "{}#variant.{}.field.{}",
+                    self.page_url(enum_id)?,
+                    variant,
+                    name
+                ));
+            }
+            "struct_field" => format!("structfield.{}", name),
+            "function" if parent_kind == "trait" && items::is_required_trait_item(item) => {
+                format!("tymethod.{}", name)
+            }
+            "function" => format!("method.{}", name),
+            "assoc_const" => format!("associatedconstant.{}", name),
+            "assoc_type" => format!("associatedtype.{}", name),
+            _ => return None,
+        };
+        Some(format!("{}#{}", self.page_url(parent)?, anchor))
+    }
+
+    // The page of an item with one, from its path in the "paths" table.
+    // Items of other crates aren't part of these docs.
+    fn page_url(&self, id: &str) -> Option<String> {
+        let summary = self.json.get("paths")?.get(id)?;
+        if summary.get("crate_id").and_then(|v| v.as_u64()) != Some(0) {
+            return None;
+        }
+        let path = items::canonical_path(self.json, id)?;
+        let kind = summary.get("kind").and_then(|v| v.as_str())?;
+        self.path_url(&path, kind)
+    }
+
+    // A re-export of another crate's item is inlined into these docs, under
+    // the path it's re-exported at
+    fn reexport_url(&self, item: &Value) -> Option<String> {
+        let target = items::item_inner(item)?.get("id").and_then(items::id_key)?;
+        if items::lookup(self.json, &target).is_some() {
+            return self.url(&target);
+        }
+        let path = self
+            .public_paths
+            .get_or_init(|| items::public_paths(self.json))
+            .get(&target)?
+            .iter()
+            .next()?;
+        let kind = self
+            .json
+            .get("paths")?
+            .get(&target)?
+            .get("kind")?
+            .as_str()?;
+        self.path_url(path, kind)
+    }
+
+    fn path_url(&self, path: &[String], kind: &str) -> Option<String> {
+        let (name, parents) = path.split_last()?;
+        let prefix = match kind {
+            "module" => return Some(format!("{}/{}/index.html", self.base, path.join("/"))),
+            "function" => "fn",
+            "type_alias" | "typedef" => "type",
+            "trait_alias" => "traitalias",
+            "proc_attribute" => "attr",
+            "proc_derive" => "derive",
+            "struct" | "enum" | "union" | "trait" | "macro" | "constant" | "static"
+            | "primitive" => kind,
+            _ => return None,
+        };
+        Some(format!(
+            "{}/{}/{}.{}.html",
+            self.base,
+            parents.join("/"),
+            prefix,
+            name
+        ))
+    }
+}
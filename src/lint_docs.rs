@@ -0,0 +1,344 @@
+// `zdoc lint-docs`: checks public items' doc comments for broken fences,
+// empty examples, missing examples, unconventional headings and bare URLs
+use anyhow::Result;
+use colored::Colorize;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+
+use crate::LintFormat;
+use crate::items;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Allow,
+    Warn,
+    Deny,
+}
+
+// Every rule with its default level
+pub const RULES: &[(&str, Level)] = &[
+    ("unclosed-fence", Level::Deny),
+    ("empty-example", Level::Warn),
+    ("missing-example", Level::Warn),
+    ("nonstandard-heading", Level::Warn),
+    ("bare-url", Level::Warn),
+];
+
+// The top-level sections rustdoc and the API guidelines use
+const CONVENTIONAL_HEADINGS: &[&str] = &["Examples", "Panics", "Errors", "Safety"];
+
+pub struct Finding {
+    pub rule: &'static str,
+    pub level: Level,
+    pub path: String,
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+// Resolved level for each rule: defaults, then the config file, then flags
+pub struct Levels(BTreeMap<&'static str, Level>);
+
+impl Levels {
+    pub fn new(
+        config: &BTreeMap<String, Level>,
+        allow: &[String],
+        deny: &[String],
+    ) -> Result<Self> {
+        let mut levels: BTreeMap<&'static str, Level> = RULES.iter().copied().collect();
+        let overrides = config
+            .iter()
+            .map(|(rule, level)| (rule.as_str(), *level))
+            .chain(allow.iter().map(|rule| (rule.as_str(), Level::Allow)))
+            .chain(deny.iter().map(|rule| (rule.as_str(), Level::Deny)));
+        for (rule, level) in overrides {
+            let Some((known, _)) = RULES.iter().find(|(name, _)| *name == rule) else {
+                let known: Vec<&str> = RULES.iter().map(|(name, _)| *name).collect();
+                anyhow::bail!(
+                    "Unknown lint-docs rule '{}' (known rules: {})",
+                    rule,
+                    known.join(", ")
+                );
+            };
+            levels.insert(known, level);
+        }
+        Ok(Levels(levels))
+    }
+
+    fn get(&self, rule: &str) -> Level {
+        self.0.get(rule).copied().unwrap_or(Level::Warn)
+    }
+}
+
+// The public items to check with their paths: everything reachable from the
+// crate root, plus the variants, fields, trait items and inherent methods of
+// reachable types. Trait impl members are skipped; their docs are the trait's.
+fn public_items(json: &Value) -> Vec<(String, &Value)> {
+    let reachable = items::reachable_ids(json);
+    let mut out = Vec::new();
+    let mut seen = HashSet::new();
+
+    for id in &reachable {
+        let Some(item) = items::lookup(json, id) else {
+            continue;
+        };
+        if matches!(items::item_kind(item), Some("use" | "import" | "impl")) {
+            continue;
+        }
+        let Some(path) = items::canonical_path(json, id) else {
+            continue;
+        };
+
+        let mut members = items::item_inner(item)
+            .map(items::own_member_ids)
+            .unwrap_or_default();
+        for impl_id in items::impl_ids(item) {
+            if let Some(impl_item) = items::lookup(json, &impl_id)
+                && items::is_inherent_impl(impl_item)
+            {
+                members.extend(items::impl_member_ids(impl_item));
+            }
+        }
+        // A module's children are walked as part of the module tree already
+        if items::item_kind(item) == Some("module") {
+            members.clear();
+        }
+
+        if seen.insert(id.clone()) {
+            out.push((path.join("::"), item));
+        }
+        for member_id in members {
+            let Some(member) = items::lookup(json, &member_id) else {
+                continue;
+            };
+            let Some(name) = items::item_name(member) else {
+                continue;
+            };
+            if seen.insert(member_id.clone()) {
+                out.push((format!("{}::{}", path.join("::"), name), member));
+            }
+        }
+    }
+    out.sort_by(|a, b| a.0.cmp(&b.0));
+    out
+}
+
+// Whether a missing example is worth reporting for this kind of item
+fn wants_example(kind: &str) -> bool {
+    matches!(
+        kind,
+        "function" | "struct" | "enum" | "union" | "trait" | "type_alias"
+    )
+}
+
+// URLs not wrapped in `<...>`, a markdown link or inline code
+fn bare_urls(line: &str) -> Vec<&str> {
+    // Reference definitions (`[name]: https://...`) are links already
+    if line.trim_start().starts_with('[') && line.contains("]:") {
+        return Vec::new();
+    }
+    let mut urls = Vec::new();
+    for scheme in ["https://", "http://"] {
+        for (start, _) in line.match_indices(scheme) {
+            let before = &line[..start];
+            let in_code = before.matches('`').count() % 2 == 1;
+            let wrapped = before.ends_with('<') || before.ends_with('(');
+            if in_code || wrapped {
+                continue;
+            }
+            let end = line[start..]
+                .find(|c: char| c.is_whitespace() || c == '>' || c == ')')
+                .map_or(line.len(), |offset| start + offset);
+            urls.push(line[start..end].trim_end_matches(['.', ',', ';', ':']));
+        }
+    }
+    urls
+}
+
+// Run every rule over one item's docs, returning (rule, message) pairs
+fn check_docs(kind: &str, docs: &str) -> Vec<(&'static str, String)> {
+    let mut problems = Vec::new();
+    let (prose, blocks) = items::parse_docs(docs);
+
+    for block in &blocks {
+        if !block.closed {
+            problems.push((
+                "unclosed-fence",
+                format!(
+                    "code fence opened at doc line {} is never closed",
+                    block.line
+                ),
+            ));
+        }
+        if block.is_rust() && block.code.trim().is_empty() {
+            problems.push((
+                "empty-example",
+                format!("empty code block at doc line {}", block.line),
+            ));
+        }
+    }
+
+    if wants_example(kind) && !blocks.iter().any(|block| block.is_rust()) {
+        problems.push(("missing-example", "no example code block".to_string()));
+    }
+
+    for (number, line) in prose {
+        // Only top-level sections are conventional; subsections are free-form
+        if let Some(heading) = line.trim_start().strip_prefix("# ") {
+            let heading = heading.trim();
+            if !CONVENTIONAL_HEADINGS.contains(&heading) {
+                let suggestion = CONVENTIONAL_HEADINGS
+                    .iter()
+                    .find(|conventional| {
+                        conventional
+                            .to_lowercase()
+                            .starts_with(&heading.to_lowercase())
+                    })
+                    .map(|conventional| format!(" (did you mean `# {}`?)", conventional))
+                    .unwrap_or_default();
+                problems.push((
+                    "nonstandard-heading",
+                    format!(
+                        "heading `# {}` at doc line {} is not one of {}{}",
+                        heading,
+                        number,
+                        CONVENTIONAL_HEADINGS.join("/"),
+                        suggestion
+                    ),
+                ));
+            }
+        }
+        for url in bare_urls(line) {
+            problems.push((
+                "bare-url",
+                format!(
+                    "bare URL at doc line {}; write it as <{}> or a link",
+                    number, url
+                ),
+            ));
+        }
+    }
+
+    problems
+}
+
+pub fn lint_crate(json: &Value, levels: &Levels) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for (path, item) in public_items(json) {
+        let kind = items::item_kind(item).unwrap_or("unknown");
+        let docs = items::item_docs(item).unwrap_or("");
+        let span = item.get("span");
+        let file = span
+            .and_then(|s| s.get("filename"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("<unknown>");
+        let begin = span
+            .and_then(|s| s.get("begin"))
+            .and_then(|v| v.as_array())
+            .map(|pos| {
+                let at = |i: usize| pos.get(i).and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+                (at(0), at(1))
+            })
+            .unwrap_or((0, 0));
+
+        for (rule, message) in check_docs(kind, docs) {
+            let level = levels.get(rule);
+            if level == Level::Allow {
+                continue;
+            }
+            findings.push(Finding {
+                rule,
+                level,
+                path: path.clone(),
+                file: file.to_string(),
+                line: begin.0,
+                column: begin.1,
+                message,
+            });
+        }
+    }
+    findings
+}
+
+// Print findings grouped by source file, in source order
+pub fn display_findings(findings: &[Finding]) {
+    let mut by_file: BTreeMap<&str, Vec<&Finding>> = BTreeMap::new();
+    for finding in findings {
+        by_file.entry(&finding.file).or_default().push(finding);
+    }
+
+    for (file, mut file_findings) in by_file {
+        file_findings.sort_by_key(|f| (f.line, f.column, f.rule));
+        println!("\n{}", file.bold());
+        for finding in file_findings {
+            let label = match finding.level {
+                Level::Deny => format!("error[{}]", finding.rule).red().bold(),
+                _ => format!("warning[{}]", finding.rule).yellow().bold(),
+            };
+            println!(
+                "  {}:{}  {} {}: {}",
+                finding.line, finding.column, label, finding.path, finding.message
+            );
+        }
+    }
+
+    let errors = findings.iter().filter(|f| f.level == Level::Deny).count();
+    let warnings = findings.len() - errors;
+    println!("\n{} warnings, {} errors", warnings, errors);
+}
+
+// Handler for `zdoc lint-docs`. Denied findings always fail; with `check`,
+// any finding does.
+pub fn run(
+    metadata: &cargo_metadata::Metadata,
+    crate_names: &[String],
+    allow: &[String],
+    deny: &[String],
+    check: bool,
+    format: LintFormat,
+) -> Result<()> {
+    let config = crate::config::Config::load(metadata.workspace_root.as_std_path())?;
+    let levels = Levels::new(&config.lint_docs, allow, deny)?;
+
+    crate::generate_json_docs(metadata)?;
+    let mut findings = Vec::new();
+    for crate_name in crate_names {
+        if !crate::doc_source::is_generated(metadata, crate_name) {
+            tracing::warn!("No JSON docs were generated for '{}', skipping", crate_name);
+            continue;
+        }
+        let local = crate::DocSource::Local { metadata };
+        let json = crate::doc_source::read_crate_json(crate_name, local)?.json;
+        findings.extend(lint_crate(&json, &levels));
+    }
+    let ignore = crate::ignore::Ignore::load(metadata.workspace_root.as_std_path())?;
+    let (findings, ignored) = ignore.partition(findings, |finding| finding.path.clone());
+
+    crate::github::write_step_summary(
+        &crate::github::findings_summary(&findings),
+        format == LintFormat::Github,
+    )?;
+    match format {
+        LintFormat::Github => print!("{}", crate::github::render_findings(&findings)),
+        LintFormat::Text if findings.is_empty() => println!("No doc problems found"),
+        LintFormat::Text => display_findings(&findings),
+    }
+    if let Some(note) = crate::ignore::note(ignored.len(), "finding") {
+        eprintln!("{}", note);
+    }
+    if findings.is_empty() {
+        return Ok(());
+    }
+
+    let errors = findings.iter().filter(|f| f.level == Level::Deny).count();
+    if errors > 0 {
+        anyhow::bail!("lint-docs found {} errors", errors);
+    }
+    if check {
+        anyhow::bail!("lint-docs found {} warnings", findings.len());
+    }
+    Ok(())
+}
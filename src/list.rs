@@ -0,0 +1,105 @@
+// `zdoc list`: the public API of a crate, optionally only the items whose
+// docs are missing or too short to be useful
+use crate::ApiItem;
+use crate::ignore::Ignore;
+use crate::links::Linker;
+use crate::table::Table;
+
+// Characters of documentation on an item
+fn doc_len(item: &ApiItem) -> usize {
+    item.docs.as_deref().map_or(0, |docs| docs.chars().count())
+}
+
+// A table with a docs.rs URL column after the others, given a linker
+fn table(headers: &[&str], linker: Option<&Linker>) -> Table {
+    match linker {
+        Some(_) => Table::new(&[headers, &["URL"]].concat()),
+        None => Table::new(headers),
+    }
+}
+
+fn add_row(table: &mut Table, mut row: Vec<String>, item: &ApiItem, linker: Option<&Linker>) {
+    if let Some(linker) = linker {
+        row.push(linker.url(&item.id).unwrap_or_default());
+    }
+    table.add_row(row);
+}
+
+// Handler for `zdoc list`. With `min_docs`, only items with fewer doc
+// characters are listed; trait impl members are skipped as they show the
+// trait's docs. Blanket impl members are never listed.
+pub fn run(
+    crate_name: &str,
+    api: Vec<ApiItem>,
+    min_docs: Option<usize>,
+    linker: Option<&Linker>,
+    ignore: &Ignore,
+) {
+    let api = api
+        .into_iter()
+        .filter(|item| !item.is_pathless_member())
+        .collect();
+    let (mut api, ignored) = ignore.partition(api, ApiItem::full_path);
+    let ignored_note = crate::ignore::note(ignored.len(), "item");
+    api.sort_by_key(|item| item.full_path());
+    let total = api.len();
+
+    let Some(min_docs) = min_docs else {
+        let mut table = table(&["Kind", "Path"], linker);
+        for item in &api {
+            let row = vec![item.item_type.clone(), item.full_path()];
+            add_row(&mut table, row, item, linker);
+        }
+        println!("\nPublic API of {} ({} items):\n", crate_name, total);
+        print!("{}", table.render());
+        if let Some(note) = ignored_note {
+            println!("\n{}", note);
+        }
+        return;
+    };
+
+    let checked: Vec<&ApiItem> = api.iter().filter(|item| !item.inherits_docs).collect();
+    let lacking: Vec<&&ApiItem> = checked
+        .iter()
+        .filter(|item| doc_len(item) < min_docs)
+        .collect();
+
+    if lacking.is_empty() {
+        println!(
+            "All {} public items in {} have at least {} characters of docs",
+            checked.len(),
+            crate_name,
+            min_docs
+        );
+        if let Some(note) = ignored_note {
+            println!("{}", note);
+        }
+        return;
+    }
+
+    let mut table = table(&["Kind", "Path", "Docs"], linker);
+    for item in &lacking {
+        let docs = match doc_len(item) {
+            0 => "none".to_string(),
+            len => format!("{} chars", len),
+        };
+        let row = vec![item.item_type.clone(), item.full_path(), docs];
+        add_row(&mut table, row, item, linker);
+    }
+    println!(
+        "\nItems in {} with fewer than {} characters of docs:\n",
+        crate_name, min_docs
+    );
+    print!("{}", table.render());
+
+    let documented = checked.len() - lacking.len();
+    println!(
+        "\n{} of {} items lack docs ({:.1}% documented)",
+        lacking.len(),
+        checked.len(),
+        100.0 * documented as f64 / checked.len() as f64
+    );
+    if let Some(note) = ignored_note {
+        println!("{}", note);
+    }
+}
@@ -0,0 +1,68 @@
+// `zdoc diff --local`: what the working tree changes about a workspace
+// crate's public API since its last release, e.g. before publishing the
+// next one. The release is the newest one in the crate's registry index
+// (pre-releases only when the working tree's version is one), its docs come
+// from docs.rs, and the working tree is documented as usual. The report ends
+// with the packaging changes (see packaging.rs).
+use anyhow::{Context, Result};
+use cargo_metadata::Metadata;
+use std::collections::BTreeSet;
+use tracing::info;
+
+use crate::packaging::Changes;
+use crate::selection::PackageSelection;
+use crate::{DiffOptions, JsonStyle, git_diff, http, public_deps, registry, sources};
+
+pub async fn run(
+    metadata: &Metadata,
+    crate_name: Option<&str>,
+    options: &DiffOptions,
+    json_style: JsonStyle,
+) -> Result<()> {
+    let package = PackageSelection::default().resolve_one(metadata, crate_name, "diff --local")?;
+    if !metadata.workspace_members.contains(&package.id) {
+        anyhow::bail!(
+            "{} isn't a workspace member; --local compares this project's own crates",
+            package.name
+        );
+    }
+    let crate_name = package.name.to_string();
+
+    let source = sources::for_crate(&crate_name);
+    let releases = registry::releases(&http::client()?, &source, &crate_name)
+        .await
+        .with_context(|| format!("Failed to look up the releases of {}", crate_name))?;
+    let pre = !package.version.pre.is_empty();
+    let release = registry::newest_release(&releases, pre)
+        .with_context(|| format!("{} has no releases to compare with", crate_name))?;
+
+    if options.dry_run {
+        println!(
+            "Would compare {} v{} from {} with the working tree (v{})",
+            crate_name,
+            release.vers,
+            source.docs_host(),
+            package.version
+        );
+        return Ok(());
+    }
+
+    info!("Comparing with {} v{}", crate_name, release.vers);
+    let old = crate::diff_source_docs(&crate_name, &release.vers, None).await?;
+    let new = git_diff::working_tree_docs(metadata, &crate_name)?;
+    let exposed: BTreeSet<String> = public_deps::exposed_packages(metadata, package, &new.json)?
+        .into_keys()
+        .collect();
+    let packaging = Changes::between(release, package, &exposed);
+
+    let report = crate::diff_report(
+        &crate_name,
+        &release.vers,
+        "working tree",
+        &old,
+        &new,
+        options,
+    )?
+    .with_packaging(packaging);
+    crate::print_diff(&report, options, json_style)
+}
@@ -0,0 +1,91 @@
+// `zdoc diff --from-lockfile`: for every registry dependency in the
+// lockfile, whether a newer release is out and what it would break. Locked
+// versions come from `cargo metadata`, the newest releases from the index
+// of the registry each came from (crates.io, or one in `[sources]`),
+// and each dependency behind its newest release is diffed as if a `--batch`
+// file listed `crate locked newest`.
+use anyhow::{Context, Result};
+use cargo_metadata::Metadata;
+use cargo_metadata::semver::Version;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tracing::{info, warn};
+
+use crate::batch::{self, Pair};
+use crate::sources::{self, Source};
+use crate::{DiffFormat, DiffOptions, JsonStyle, http, registry};
+
+// Index lookups in flight at once; they're small, unlike docs downloads
+const MAX_LOOKUPS: usize = 16;
+
+pub async fn run(metadata: &Metadata, options: &DiffOptions, json_style: JsonStyle) -> Result<()> {
+    // Path, git and unconfigured registry dependencies have no releases to
+    // look up
+    let mut locked: BTreeSet<(String, Version)> = BTreeSet::new();
+    let mut sources: BTreeMap<String, Source> = BTreeMap::new();
+    for package in &metadata.packages {
+        if let Some(source) = sources::for_package(package) {
+            locked.insert((package.name.to_string(), package.version.clone()));
+            sources.insert(package.name.to_string(), source);
+        }
+    }
+    if locked.is_empty() {
+        anyhow::bail!("The lockfile has no crates.io or configured registry dependencies");
+    }
+
+    info!(
+        "Looking up the newest releases of {} crates...",
+        sources.len()
+    );
+    let client = http::client()?;
+    let permits = Arc::new(Semaphore::new(MAX_LOOKUPS));
+    let mut tasks = JoinSet::new();
+    for (name, source) in sources {
+        let (client, permits) = (client.clone(), permits.clone());
+        tasks.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let versions = registry::versions(&client, &source, &name).await;
+            (name, versions)
+        });
+    }
+    let mut releases: BTreeMap<String, Vec<Version>> = BTreeMap::new();
+    while let Some(joined) = tasks.join_next().await {
+        let (name, versions) = joined.context("An index lookup panicked")?;
+        match versions {
+            Ok(versions) => {
+                releases.insert(name, versions);
+            }
+            Err(err) => warn!("Skipping {}: {:#}", name, err),
+        }
+    }
+
+    // Pre-releases only count as updates to crates locked at one
+    let mut pairs = Vec::new();
+    let mut up_to_date = 0;
+    for (name, version) in &locked {
+        let Some(versions) = releases.get(name) else {
+            continue;
+        };
+        match registry::newest(versions, !version.pre.is_empty()) {
+            Some(newest) if newest > version => pairs.push(Pair {
+                crate_name: name.clone(),
+                old: version.to_string(),
+                new: newest.to_string(),
+            }),
+            _ => up_to_date += 1,
+        }
+    }
+    info!(
+        "{} of {} locked crates are up to date",
+        up_to_date,
+        locked.len()
+    );
+
+    if pairs.is_empty() && options.format != DiffFormat::Json {
+        println!("Every locked crates.io dependency is at its newest release.");
+        return Ok(());
+    }
+    batch::run_pairs(pairs, options, json_style, "Available updates").await
+}
@@ -0,0 +1,351 @@
+// `zdoc macros`: exported macros with the invocation shapes they accept
+use anyhow::Result;
+use serde_json::Value;
+
+use crate::items;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MacroKind {
+    Declarative,
+    Derive,
+    Attribute,
+    FunctionLike,
+}
+
+impl MacroKind {
+    fn label(self) -> &'static str {
+        match self {
+            MacroKind::Declarative => "macro_rules!",
+            MacroKind::Derive => "derive",
+            MacroKind::Attribute => "attribute",
+            MacroKind::FunctionLike => "function-like",
+        }
+    }
+
+    // Kinds as they appear in the "paths" table for items of other crates
+    fn from_path_kind(kind: &str) -> Option<Self> {
+        match kind {
+            "macro" => Some(MacroKind::Declarative),
+            "proc_derive" => Some(MacroKind::Derive),
+            "proc_attribute" => Some(MacroKind::Attribute),
+            "proc_macro" => Some(MacroKind::FunctionLike),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MacroInfo {
+    pub name: String,
+    pub path: String,
+    pub kind: MacroKind,
+    // Helper attributes a derive makes available (`#[serde(...)]`)
+    pub helpers: Vec<String>,
+    // The `macro_rules!` text, with bodies elided by rustdoc
+    pub definition: Option<String>,
+    pub docs: Option<String>,
+    // The crate a re-exported macro is defined in
+    pub defined_in: Option<String>,
+}
+
+impl MacroInfo {
+    // How the macro is invoked, e.g. `#[derive(Serialize)]` or `vec!(...)`
+    pub fn usage(&self) -> String {
+        match self.kind {
+            MacroKind::Derive => format!("#[derive({})]", self.name),
+            MacroKind::Attribute => format!("#[{}]", self.name),
+            MacroKind::Declarative | MacroKind::FunctionLike => format!("{}!(...)", self.name),
+        }
+    }
+}
+
+// The matcher of each `macro_rules!` arm, without its outer delimiters.
+// Rustdoc renders the arms as `(matcher) => { ... };`.
+pub fn matcher_arms(definition: &str) -> Vec<String> {
+    let Some(start) = definition.find(['{', '(', '[']) else {
+        return Vec::new();
+    };
+    let Some(body) = balanced_group(&definition[start..]) else {
+        return Vec::new();
+    };
+    let mut rest = body[1..body.len() - 1].trim_start();
+
+    let mut arms = Vec::new();
+    while let Some(matcher) = balanced_group(rest) {
+        arms.push(matcher[1..matcher.len() - 1].trim().to_string());
+        rest = rest[matcher.len()..].trim_start();
+        let Some(after_arrow) = rest.strip_prefix("=>") else {
+            break;
+        };
+        rest = after_arrow.trim_start();
+        let Some(transcriber) = balanced_group(rest) else {
+            break;
+        };
+        rest = rest[transcriber.len()..].trim_start();
+        rest = rest.strip_prefix(';').unwrap_or(rest).trim_start();
+    }
+    arms
+}
+
+// The delimited group `text` starts with, including its delimiters
+fn balanced_group(text: &str) -> Option<&str> {
+    if !text.starts_with(['{', '(', '[']) {
+        return None;
+    }
+    let mut depth = 0usize;
+    for (i, c) in text.char_indices() {
+        match c {
+            '{' | '(' | '[' => depth += 1,
+            '}' | ')' | ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&text[..=i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn proc_macro_kind(inner: &Value) -> MacroKind {
+    match inner.get("kind").and_then(|v| v.as_str()) {
+        Some("derive") => MacroKind::Derive,
+        Some("attr") => MacroKind::Attribute,
+        _ => MacroKind::FunctionLike,
+    }
+}
+
+// A short signature for diffs: the arms of a declarative macro, or the
+// shape and helper attributes of a proc macro
+pub fn signature(kind: &str, inner: &Value) -> String {
+    match kind {
+        "macro" => inner
+            .as_str()
+            .map(|definition| {
+                let arms: Vec<String> = matcher_arms(definition)
+                    .iter()
+                    .map(|arm| format!("({})", arm))
+                    .collect();
+                format!("{{ {} }}", arms.join("; "))
+            })
+            .unwrap_or_default(),
+        "proc_macro" => {
+            let helpers: Vec<&str> = inner
+                .get("helpers")
+                .and_then(|v| v.as_array())
+                .map(|h| h.iter().filter_map(|v| v.as_str()).collect())
+                .unwrap_or_default();
+            let shape = proc_macro_kind(inner).label();
+            if helpers.is_empty() {
+                shape.to_string()
+            } else {
+                format!("{} (helpers: {})", shape, helpers.join(", "))
+            }
+        }
+        _ => String::new(),
+    }
+}
+
+// Macros defined in this crate's docs, with their canonical paths
+fn defined_macros(json: &Value) -> Vec<MacroInfo> {
+    let Some(index) = json.get("index").and_then(|v| v.as_object()) else {
+        return Vec::new();
+    };
+    let reachable = items::reachable_ids(json);
+    // Proc macros have no "paths" entry; they live at the crate root
+    let crate_root = json
+        .get("root")
+        .and_then(items::id_key)
+        .and_then(|root| items::lookup(json, &root))
+        .and_then(items::item_name);
+
+    let mut macros = Vec::new();
+    for (id, item) in index {
+        if !reachable.contains(id) {
+            continue;
+        }
+        let Some(name) = items::item_name(item) else {
+            continue;
+        };
+        let inner = items::item_inner(item);
+        let (kind, helpers, definition) = match (items::item_kind(item), inner) {
+            (Some("macro"), Some(inner)) => (
+                MacroKind::Declarative,
+                Vec::new(),
+                inner.as_str().map(String::from),
+            ),
+            (Some("proc_macro"), Some(inner)) => {
+                let helpers = inner
+                    .get("helpers")
+                    .and_then(|v| v.as_array())
+                    .map(|h| {
+                        h.iter()
+                            .filter_map(|v| v.as_str().map(String::from))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                (proc_macro_kind(inner), helpers, None)
+            }
+            _ => continue,
+        };
+        let path = items::canonical_path(json, id)
+            .map(|path| path.join("::"))
+            .or_else(|| crate_root.map(|root| format!("{}::{}", root, name)))
+            .unwrap_or_else(|| name.to_string());
+        macros.push(MacroInfo {
+            name: name.to_string(),
+            path,
+            kind,
+            helpers,
+            definition,
+            docs: items::item_docs(item).map(String::from),
+            defined_in: None,
+        });
+    }
+    macros
+}
+
+// Macros this crate re-exports from other crates: (name, kind, defining crate).
+// Derive crates are the usual case: `serde` re-exports `serde_derive`'s derives.
+fn reexported_macros(json: &Value) -> Vec<(String, MacroKind, String)> {
+    let mut found = Vec::new();
+    for id in items::reachable_ids(json) {
+        let Some(inner) = items::lookup(json, &id)
+            .filter(|item| matches!(items::item_kind(item), Some("use" | "import")))
+            .and_then(items::item_inner)
+        else {
+            continue;
+        };
+        let Some(target) = inner.get("id").and_then(items::id_key) else {
+            continue;
+        };
+        if items::lookup(json, &target).is_some() {
+            continue;
+        }
+        let Some(summary) = json.get("paths").and_then(|paths| paths.get(&target)) else {
+            continue;
+        };
+        let Some(kind) = summary
+            .get("kind")
+            .and_then(|v| v.as_str())
+            .and_then(MacroKind::from_path_kind)
+        else {
+            continue;
+        };
+        let crate_name = summary
+            .get("crate_id")
+            .and_then(|crate_id| {
+                json.get("external_crates")?
+                    .get(crate_id.to_string())?
+                    .get("name")?
+                    .as_str()
+            })
+            .unwrap_or("?");
+        let name = inner
+            .get("name")
+            .and_then(|v| v.as_str())
+            .unwrap_or("?")
+            .to_string();
+        found.push((name, kind, crate_name.to_string()));
+    }
+    found
+}
+
+// Every macro the crate exports, following re-exports into the defining
+// crate's docs when `load_external` can provide them
+pub async fn collect_macros<F, Fut>(json: &Value, load_external: F) -> Result<Vec<MacroInfo>>
+where
+    F: Fn(String) -> Fut,
+    Fut: std::future::Future<Output = Result<Option<Value>>>,
+{
+    let mut macros = defined_macros(json);
+
+    let reexports = reexported_macros(json);
+    let mut external_crates: Vec<&String> = reexports.iter().map(|(_, _, krate)| krate).collect();
+    external_crates.sort();
+    external_crates.dedup();
+    for krate in external_crates {
+        // The standard library's macros aren't in any docs we can load
+        let external = if ["std", "core", "alloc", "proc_macro"].contains(&krate.as_str()) {
+            None
+        } else {
+            load_external(krate.clone()).await?
+        };
+        let defined = external.as_ref().map(defined_macros).unwrap_or_default();
+        for (name, kind, _) in reexports.iter().filter(|(_, _, k)| k == krate) {
+            let mut info = defined
+                .iter()
+                .find(|m| m.name == *name && m.kind == *kind)
+                .cloned()
+                .unwrap_or_else(|| MacroInfo {
+                    name: name.clone(),
+                    path: format!("{}::{}", krate, name),
+                    kind: *kind,
+                    helpers: Vec::new(),
+                    definition: None,
+                    docs: None,
+                    defined_in: None,
+                });
+            info.defined_in = Some(krate.clone());
+            macros.push(info);
+        }
+    }
+
+    macros.sort_by(|a, b| a.name.cmp(&b.name).then(a.path.cmp(&b.path)));
+    Ok(macros)
+}
+
+pub fn display_macros(crate_name: &str, macros: &[MacroInfo]) {
+    if macros.is_empty() {
+        println!("No exported macros found in {}", crate_name);
+        return;
+    }
+
+    println!("\nMacros in {} ({}):\n", crate_name, macros.len());
+    for info in macros {
+        let mut notes = vec![info.kind.label().to_string()];
+        if let Some(krate) = &info.defined_in {
+            notes.push(format!("from {}", krate));
+        }
+        if !info.helpers.is_empty() {
+            notes.push(format!("helpers: {}", info.helpers.join(", ")));
+        }
+        println!("  {}  ({})", info.usage(), notes.join("; "));
+        if let Some(definition) = &info.definition {
+            for arm in matcher_arms(definition) {
+                println!("      {}!({})", info.name, arm);
+            }
+        }
+    }
+}
+
+pub fn display_macro(info: &MacroInfo) {
+    println!("\n{} ({})\n", info.path, info.kind.label());
+    match &info.definition {
+        Some(definition) => println!("{}", definition),
+        None => println!("{}", info.usage()),
+    }
+    if !info.helpers.is_empty() {
+        println!("\nHelper attributes: {}", info.helpers.join(", "));
+    }
+
+    let Some(docs) = &info.docs else {
+        if info.defined_in.is_some() {
+            println!("\n(docs for the defining crate were not available)");
+        }
+        return;
+    };
+    let (prose, blocks) = items::split_docs(docs);
+    if !prose.is_empty() {
+        println!("\n{}", prose);
+    }
+    let examples: Vec<String> = blocks
+        .iter()
+        .filter(|block| block.is_rust())
+        .map(|block| block.visible_code())
+        .collect();
+    for (i, example) in examples.iter().enumerate() {
+        println!("\nExample {}:\n{}", i + 1, example);
+    }
+}
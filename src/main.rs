@@ -1,16 +1,83 @@
 use anyhow::{Context, Result};
 use cargo_metadata::MetadataCommand;
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use colored::Colorize;
-use flate2::read::GzDecoder;
+use doc_source::{DocSource, FetchedDocs};
 use fuzzy_matcher::FuzzyMatcher;
 use fuzzy_matcher::skim::SkimMatcherV2;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::{HashMap, HashSet};
+use std::cell::Cell;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::fs;
-use std::io::Read;
-use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::hash::Hash;
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use style::Role;
+use tracing::{debug, info, warn};
+
+mod aliases;
+mod assoc;
+mod baseline;
+mod batch;
+mod bounds;
+mod cache;
+mod cheatsheet;
+mod clipboard;
+mod compare;
+mod config;
+mod context;
+mod doc_source;
+mod doc_targets;
+mod docs_rs_build;
+mod engine;
+mod feature_docs;
+mod feature_list;
+#[cfg(test)]
+mod fixtures;
+mod freshness;
+mod git_diff;
+mod github;
+mod http;
+mod ignore;
+mod impls;
+mod item_diff;
+mod items;
+mod layout;
+mod links;
+mod lint_docs;
+mod list;
+mod local_diff;
+mod lockfile;
+mod macros;
+mod man;
+mod markdown;
+mod outdated;
+mod packaging;
+mod prelude;
+mod public_deps;
+mod query;
+mod reexports;
+mod registry;
+mod rustdoc_json;
+mod search_index;
+mod selection;
+mod semver_lint;
+mod setup;
+mod show;
+mod signature;
+mod snapshot;
+mod sources;
+mod stability;
+mod stat;
+mod stats;
+mod style;
+mod table;
+mod target_dir;
+mod test_example;
+mod timing;
+mod toolchain;
 
 #[derive(Parser)]
 #[command(
@@ -21,627 +88,5890 @@ use std::process::Command;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    /// Path to the `Cargo.toml` of the project to use, as with cargo
+    #[arg(long, global = true, value_name = "PATH")]
+    manifest_path: Option<PathBuf>,
+    /// Print JSON output on a single line (default when stdout is not a terminal)
+    #[arg(long, global = true, conflicts_with = "pretty")]
+    compact: bool,
+    /// Pretty-print JSON output (default when stdout is a terminal)
+    #[arg(long, global = true)]
+    pretty: bool,
+    /// Refuse to load docs likely to need more memory than this (e.g. `2G`)
+    #[arg(long, global = true, value_name = "SIZE", value_parser = rustdoc_json::parse_size)]
+    max_memory: Option<u64>,
+    /// Proxy for docs.rs downloads, overriding `HTTPS_PROXY`/`HTTP_PROXY`
+    #[arg(long, global = true, value_name = "URL")]
+    proxy: Option<String>,
+    /// Docs server to use instead of docs.rs, with its URL layout (or `ZDOC_DOCS_URL`)
+    #[arg(long, global = true, value_name = "URL")]
+    docs_url: Option<String>,
+    /// Sparse registry index to look up releases in instead of crates.io's (or `ZDOC_REGISTRY_API`)
+    #[arg(long, global = true, value_name = "URL")]
+    registry_api: Option<String>,
+    /// Directory for `cargo doc` output, as with cargo (default: the project's target directory)
+    #[arg(long, global = true, value_name = "DIR")]
+    target_dir: Option<PathBuf>,
+    /// Document for this target triple; docs are read from `<target-dir>/<triple>/doc`
+    #[arg(long, global = true, value_name = "TRIPLE")]
+    target: Option<String>,
+    /// When to color output, as with cargo and git
+    #[arg(long, global = true, value_enum, value_name = "WHEN", default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+    /// Color theme for terminal output (overrides the `[theme]` preset in `zdoc.toml`)
+    #[arg(long, global = true, value_name = "NAME", value_parser = clap::builder::PossibleValuesParser::new(style::PRESETS))]
+    theme: Option<String>,
+    /// Report items under the paths `zdoc.toml` ignores too
+    #[arg(long, global = true)]
+    no_ignore: bool,
+    /// Delete the JSON docs in the target directory and generate them afresh
+    #[arg(long, global = true)]
+    clean: bool,
+    /// Lay out listings for this many columns (default: the terminal's, or 80 when piped)
+    #[arg(long, global = true, value_name = "COLUMNS")]
+    width: Option<usize>,
+    /// Log more on stderr: `-v` for debug details, `-vv` for everything (`RUST_LOG` overrides).
+    /// Also keeps long paths whole in listings
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+    /// Only log errors, and skip the outdated-dependencies footer
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+    /// Never touch the network: docs come from the cache, and releases aren't looked up
+    #[arg(long, global = true)]
+    offline: bool,
+    /// Time the major phases (cargo doc, fetching, parsing, searching, comparing, rendering) and print them on stderr
+    #[arg(long, global = true)]
+    timings: bool,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Fuzzy search query within a crate or globally
     Search {
-        /// The search term
+        /// The search term; separate several with commas (`connect,close`)
         query: String,
-        /// The crate to search within (optional)
+        /// The crate to search within (same as `-p`)
+        #[arg(conflicts_with_all = ["packages", "workspace"])]
         crate_name: Option<String>,
-        /// Limit results
-        #[arg(short, long, default_value_t = 5)]
-        results: usize,
+        #[command(flatten)]
+        selection: selection::PackageSelection,
+        /// Limit results (default 5; every match with `--glob`)
+        #[arg(short, long)]
+        results: Option<usize>,
+        /// Match item names against the query as a shell-style glob (`Async*Stream`) instead of fuzzily
+        #[arg(long, conflicts_with = "no_length_penalty")]
+        glob: bool,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = SearchFormat::Text)]
+        format: SearchFormat,
+        /// Show what would be built and read, then exit
+        #[arg(long)]
+        dry_run: bool,
+        /// With several queries, only show items matching every one
+        #[arg(long)]
+        all_terms: bool,
+        /// Don't rank shorter names above longer ones that match equally well
+        #[arg(long)]
+        no_length_penalty: bool,
+        /// How much a fuzzy match of the item's name counts toward its score
+        #[arg(long, value_name = "WEIGHT", default_value_t = Weights::default().name, value_parser = parse_weight, conflicts_with = "glob")]
+        weight_name: f64,
+        /// How much a fuzzy match of the module path counts; raise it when you remember the module but not the name
+        #[arg(long, value_name = "WEIGHT", default_value_t = Weights::default().path, value_parser = parse_weight, conflicts_with = "glob")]
+        weight_path: f64,
+        /// How much a fuzzy match of the docs counts (they only reorder items whose name or path matched)
+        #[arg(long, value_name = "WEIGHT", default_value_t = Weights::default().docs, value_parser = parse_weight, conflicts_with = "glob")]
+        weight_docs: f64,
+        /// Never run `cargo doc`; search the JSON already generated
+        #[arg(long, conflicts_with = "force_generate")]
+        no_generate: bool,
+        /// Always run `cargo doc`, even when the JSON is newer than the sources
+        #[arg(long)]
+        force_generate: bool,
+        /// Copy the top result's full path to the clipboard
+        #[arg(long, conflicts_with = "format")]
+        copy: bool,
+        /// Print each result's docs.rs URL
+        #[arg(long)]
+        links: bool,
+        /// Print each result's rustdoc JSON index entry as it is, for debugging the JSON
+        #[arg(long, conflicts_with_all = ["format", "copy", "links"])]
+        raw: bool,
+        /// Search private items of libraries too (binaries always include them)
+        #[arg(long)]
+        private: bool,
+        /// Search the packages' examples too
+        #[arg(long)]
+        examples: bool,
+        /// Only show async functions (`async fn`, or returning a future)
+        #[arg(long = "async", conflicts_with = "sync")]
+        async_only: bool,
+        /// Only show functions that aren't async
+        #[arg(long)]
+        sync: bool,
+        /// Leave out results scoring below this
+        #[arg(long, value_name = "SCORE")]
+        min_score: Option<i64>,
+        /// List every match, paged, instead of the best few
+        #[arg(long, conflicts_with = "results")]
+        all: bool,
+        /// Show `#[doc(hidden)]` items too, marked (hidden)
+        #[arg(long)]
+        include_hidden: bool,
     },
     /// Diff public API between versions
     Diff {
+        #[arg(required_unless_present_any = ["batch", "from_lockfile", "git", "local"])]
+        crate_name: Option<String>,
+        #[arg(required_unless_present_any = ["batch", "from_lockfile", "git", "local"])]
+        ver1: Option<String>,
+        #[arg(required_unless_present_any = ["batch", "from_lockfile", "git", "local"])]
+        ver2: Option<String>,
+        /// Diff every `crate old new` line of this file and summarize the verdicts
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["crate_name", "ver1", "ver2", "name_only"])]
+        batch: Option<PathBuf>,
+        /// Diff each crates.io dependency in `Cargo.lock` against its newest release
+        #[arg(long, conflicts_with_all = ["crate_name", "ver1", "ver2", "name_only", "batch"])]
+        from_lockfile: bool,
+        /// Diff a workspace crate's working tree against this git ref (`main`, a tag, a commit)
+        #[arg(long, value_name = "REF", conflicts_with_all = ["ver1", "ver2", "batch", "from_lockfile", "features", "no_default_features"])]
+        git: Option<String>,
+        /// Diff a workspace crate's working tree against its last published release, packaging included
+        #[arg(long, conflicts_with_all = ["ver1", "ver2", "batch", "from_lockfile", "git", "features", "no_default_features"])]
+        local: bool,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = DiffFormat::Text)]
+        format: DiffFormat,
+        /// Show what would be fetched, then exit
+        #[arg(long)]
+        dry_run: bool,
+        /// Compare the API with these features enabled (documents both versions locally)
+        #[arg(long, value_delimiter = ',')]
+        features: Vec<String>,
+        /// Compare the API without the default features (documents both versions locally)
+        #[arg(long)]
+        no_default_features: bool,
+        /// Show up to N unchanged items from the same module around each change (text output)
+        #[arg(long, value_name = "N", default_value_t = 0)]
+        context: usize,
+        /// Only print the changed paths, each prefixed with A, D or M
+        #[arg(long, conflicts_with_all = ["format", "context"])]
+        name_only: bool,
+        /// Print a per-module histogram of the changes instead of listing them
+        #[arg(long, conflicts_with_all = ["format", "context", "name_only", "batch", "from_lockfile"])]
+        stat: bool,
+        /// Write type aliases in signatures as the types they stand for
+        #[arg(long)]
+        resolve_aliases: bool,
+        /// Compare `#[doc(hidden)]` items too, instead of leaving them out of the API
+        #[arg(long)]
+        include_hidden: bool,
+        /// Compare the auto trait impls rustdoc works out (`Send`, `Sync`, `Unpin`, ...) too
+        #[arg(long)]
+        auto_impls: bool,
+        /// Compare only this item and its members (`Client`, `http::Client::send`)
+        #[arg(long, value_name = "PATH", conflicts_with_all = ["batch", "from_lockfile", "stat", "name_only", "context"])]
+        item: Option<String>,
+        /// With --item, print a line diff of the item's docs
+        #[arg(long, requires = "item")]
+        docs: bool,
+        /// Let `latest` and requirements like `1.0` resolve to pre-releases
+        #[arg(long)]
+        pre: bool,
+    },
+    /// Print a workspace crate's public API as JSON, to save as a baseline for `verify`
+    Baseline {
+        /// The crate (same as `-p`)
+        #[arg(conflicts_with_all = ["packages", "workspace"])]
+        crate_name: Option<String>,
+        #[command(flatten)]
+        selection: selection::PackageSelection,
+    },
+    /// Compare a workspace crate's public API to a saved baseline; fails on breaking changes
+    Verify {
+        /// The crate (defaults to the one the baseline was made from)
+        #[arg(conflicts_with_all = ["packages", "workspace"])]
+        crate_name: Option<String>,
+        #[command(flatten)]
+        selection: selection::PackageSelection,
+        /// The file written by `zdoc baseline`
+        #[arg(long)]
+        baseline: PathBuf,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = DiffFormat::Text)]
+        format: DiffFormat,
+    },
+    /// Print a crate's public API as sorted lines of text, to commit and review with `git diff`
+    Snapshot {
+        crate_name: String,
+        /// Use this version's docs from docs.rs instead of local docs (`latest`, `1.2`, `1.2.3`)
+        #[arg(long)]
+        version: Option<String>,
+    },
+    /// A one-or-two-screen summary of a crate's most important API
+    Cheatsheet {
         crate_name: String,
-        ver1: String,
-        ver2: String,
+        /// Summarize this module instead of the crate root (`net::http`)
+        #[arg(long)]
+        module: Option<String>,
+        /// Use this version's docs from docs.rs instead of local docs (`latest`, `1.2`, `1.2.3`)
+        #[arg(long)]
+        version: Option<String>,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = CheatsheetFormat::Text)]
+        format: CheatsheetFormat,
+    },
+    /// Install and check a toolchain that writes rustdoc JSON, and save it in zdoc.toml
+    Setup {
+        /// The rustup toolchain to use (default: the configured one, else `nightly`)
+        #[arg(long)]
+        toolchain: Option<String>,
+        /// Install what's missing without asking
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Inspect or clear the cache of docs downloaded from docs.rs
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+    /// Look inside the files zdoc reads, for troubleshooting
+    Debug {
+        #[command(subcommand)]
+        action: DebugAction,
     },
     /// List available features
-    Features { crate_name: String },
+    Features {
+        /// The crate (same as `-p`)
+        #[arg(conflicts_with_all = ["packages", "workspace"])]
+        crate_name: Option<String>,
+        #[command(flatten)]
+        selection: selection::PackageSelection,
+    },
+    /// Assemble a markdown context bundle for an item (docs, signature types, siblings)
+    Context {
+        /// Path to the item, e.g. `Client::connect` or `mycrate::Config`
+        item_path: String,
+        /// How many levels of signature types to include
+        #[arg(long, default_value_t = 1)]
+        depth: usize,
+        /// Size budget for the bundle in characters
+        #[arg(long, default_value_t = 12000)]
+        max_chars: usize,
+        /// Copy the bundle to the clipboard instead of printing it
+        #[arg(long)]
+        copy: bool,
+    },
+    /// Show associated types and consts of a trait, or those a type's impls assign
+    Assoc {
+        /// Path to the trait or type, e.g. `tower::Service` or `MyService`
+        item_path: String,
+    },
+    /// List the types implementing a trait
+    Impls {
+        /// The crate whose docs to search for impls
+        crate_name: String,
+        /// The trait, e.g. `Serialize` or `serde::Serialize`
+        trait_path: String,
+        /// Use this version's docs from docs.rs instead of local docs
+        #[arg(long)]
+        version: Option<String>,
+        /// Also list blanket impls (`impl<T: Bound> Trait for T`)
+        #[arg(long)]
+        include_blanket: bool,
+        /// Also list auto-trait impls the compiler derives (`Send`, `Sync`, ...)
+        #[arg(long)]
+        include_auto: bool,
+    },
+    /// Answer JSON requests (search, show, signature) read from stdin, one per line, keeping docs loaded between them
+    Batch,
+    /// Print the parts of a crate's rustdoc JSON a JSONPath or JSON Pointer expression picks out
+    Query {
+        /// The crate whose docs to query
+        crate_name: String,
+        /// `$.index['0:42'].inner`, `$..is_async`, `index.*.name` or `/index/0:42`
+        expression: String,
+        /// Use this version's docs from docs.rs instead of local docs
+        #[arg(long)]
+        version: Option<String>,
+        /// Print where each match is instead of its value
+        #[arg(long)]
+        paths: bool,
+    },
+    /// Check public items' docs for broken fences, missing examples and other problems
+    LintDocs {
+        /// The crate to check (same as `-p`)
+        #[arg(conflicts_with_all = ["packages", "workspace"])]
+        crate_name: Option<String>,
+        #[command(flatten)]
+        selection: selection::PackageSelection,
+        /// Don't report this rule (repeatable)
+        #[arg(long, value_name = "RULE")]
+        allow: Vec<String>,
+        /// Report this rule as an error (repeatable)
+        #[arg(long, value_name = "RULE")]
+        deny: Vec<String>,
+        /// Exit with an error if anything is reported, not only errors
+        #[arg(long)]
+        check: bool,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = LintFormat::Text)]
+        format: LintFormat,
+    },
+    /// List a crate's public API, or only the items lacking docs
+    List {
+        /// The crate (same as `-p`; required with `--version`)
+        #[arg(conflicts_with_all = ["packages", "workspace"])]
+        crate_name: Option<String>,
+        #[command(flatten)]
+        selection: selection::PackageSelection,
+        /// Only list items without docs (same as `--min-docs 1`)
+        #[arg(long, conflicts_with = "min_docs")]
+        undocumented: bool,
+        /// Only list items with fewer than N characters of docs
+        #[arg(long, value_name = "N")]
+        min_docs: Option<usize>,
+        /// Use this version's docs from docs.rs instead of local docs
+        #[arg(long)]
+        version: Option<String>,
+        /// Add a column with each item's docs.rs URL
+        #[arg(long)]
+        links: bool,
+    },
+    /// Show an item's docs as a man page
+    Man {
+        /// Path to the item, e.g. `Client::connect`
+        item_path: String,
+        /// Write troff source instead (for `man -l` or installing under `man/`)
+        #[arg(long)]
+        roff: bool,
+    },
+    /// Show a crate's or module's docs with a listing of its public items
+    Show {
+        /// A crate name, or a path such as `mycrate::net`
+        item_path: String,
+        /// Print the item's source definition instead (its declaration if the docs have no source span)
+        #[arg(long)]
+        peek: bool,
+        /// How much source to print with `--peek`, in characters (rounded up to a whole line)
+        #[arg(long, default_value_t = 2000, requires = "peek")]
+        max_chars: usize,
+    },
+    /// Print an item's declaration as Rust, for copy-paste
+    Signature {
+        /// Path to the item, e.g. `Client::connect`
+        item_path: String,
+        /// Copy the declaration to the clipboard instead of printing it
+        #[arg(long)]
+        copy: bool,
+    },
+    /// Compile and run one of an item's doc examples against the resolved crate version
+    TestExample {
+        /// Path to the item, e.g. `Client::connect`
+        item_path: String,
+        /// Which of the item's examples to test, counting from 1
+        #[arg(long, default_value_t = 1)]
+        nth: usize,
+    },
+    /// List exported macros with the invocation shapes they accept, or show one macro
+    Macros {
+        crate_name: String,
+        /// Show this macro's full definition and examples
+        name: Option<String>,
+        /// Use this version's docs from docs.rs instead of local docs
+        #[arg(long)]
+        version: Option<String>,
+    },
+    /// Compare two crates' newest releases side by side: API size, features, MSRV, dependencies
+    Compare {
+        crate_a: String,
+        crate_b: String,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = SearchFormat::Text)]
+        format: SearchFormat,
+    },
+    /// Count a crate's public items and how many are documented, in its newest release
+    Stats {
+        crate_name: String,
+        /// Show how the API grew and churned over its last releases
+        #[arg(long)]
+        trend: bool,
+        /// Releases `--trend` covers
+        #[arg(long, default_value_t = 10, requires = "trend", value_parser = clap::value_parser!(u16).range(2..))]
+        releases: u16,
+        /// Count pre-releases as releases
+        #[arg(long)]
+        pre: bool,
+        /// Output format; JSON gives the series for plotting
+        #[arg(long, value_enum, default_value_t = SearchFormat::Text)]
+        format: SearchFormat,
+    },
+    /// List the dependencies a crate's public API exposes, and the items exposing them
+    PublicDeps {
+        /// The crate (same as `-p`)
+        #[arg(conflicts_with_all = ["packages", "workspace"])]
+        crate_name: Option<String>,
+        #[command(flatten)]
+        selection: selection::PackageSelection,
+        /// Exit with an error if a dependency on the `[public-deps] deny` list is exposed
+        #[arg(long)]
+        check: bool,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = SearchFormat::Text)]
+        format: SearchFormat,
+    },
+    /// List direct dependencies with newer releases, and whether upgrading would break their API
+    Outdated {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = SearchFormat::Text)]
+        format: SearchFormat,
+    },
+    /// List the names a glob import of a crate's prelude brings into scope
+    Prelude {
+        crate_name: String,
+        /// Flatten this module instead of the ones named `prelude`
+        #[arg(long)]
+        module: Option<String>,
+    },
 }
 
-fn search_docs(
-    metadata: &cargo_metadata::Metadata,
-    crate_name: Option<&str>,
-    query: &str,
-    limit: usize,
-) -> Result<()> {
-    // Step 1: Run cargo doc with JSON output format (requires nightly or RUSTC_BOOTSTRAP)
-    println!("Generating JSON documentation...");
+#[derive(Subcommand)]
+enum CacheAction {
+    /// List cached entries with their size and age
+    Ls,
+    /// Show the total size and entry count
+    Stats,
+    /// Remove cached entries
+    Clear {
+        /// Only this crate's entries
+        #[arg(long = "crate", value_name = "NAME")]
+        crate_name: Option<String>,
+        /// Only entries older than this, e.g. `7d` or `12h`
+        #[arg(long, value_parser = humantime::parse_duration)]
+        older_than: Option<std::time::Duration>,
+    },
+    /// Print the cache directory
+    Path,
+}
 
-    // Try to generate docs for dependencies and this crate
-    let status = Command::new("cargo")
-        .arg("doc")
-        .env("RUSTDOCFLAGS", "-Z unstable-options --output-format json")
-        .env("RUSTC_BOOTSTRAP", "1") // Enable unstable features on stable
-        .status()
-        .context("Failed to run `cargo doc`. Make sure you have Rust installed.")?;
+#[derive(Subcommand)]
+enum DebugAction {
+    /// Summarize the search index rustdoc wrote with the HTML docs
+    SearchIndex {
+        /// A doc directory or index file (default: this project's target/doc)
+        path: Option<PathBuf>,
+        /// Print the summary as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
 
-    if !status.success() {
-        println!("Warning: cargo doc returned non-zero status, but continuing...");
+// The manifest cargo metadata was loaded from: the package whose directory
+// zdoc runs in (or that `--manifest-path` names), else the workspace root
+fn manifest_path(metadata: &cargo_metadata::Metadata) -> PathBuf {
+    match metadata.root_package() {
+        Some(package) => package.manifest_path.clone().into(),
+        None => metadata.workspace_root.join("Cargo.toml").into(),
     }
+}
 
-    // Step 2: Find the generated JSON file(s)
-    let target_dir = &metadata.target_directory;
-    let doc_dir = PathBuf::from(target_dir).join("doc");
-
-    // Get the crate(s) to search
-    let crates_to_search: Vec<String> = if let Some(name) = crate_name {
-        vec![name.to_string()]
-    } else {
-        // Search all workspace crates
-        metadata
-            .workspace_packages()
-            .iter()
-            .map(|p| p.name.to_string())
-            .collect()
-    };
-
-    // Step 3 & 4: Load JSON files and fuzzy match
-    let mut all_results = Vec::new();
-
-    for crate_name in &crates_to_search {
-        let json_path = doc_dir.join(format!("{}.json", crate_name));
+// Run `cargo doc` with JSON output format, on the configured toolchain
+fn generate_json_docs(metadata: &cargo_metadata::Metadata) -> Result<()> {
+    freshness::clean_if_asked(&target_dir::doc_dir(metadata))?;
+    info!("Generating JSON documentation...");
 
-        if !json_path.exists() {
-            continue; // Skip if JSON doesn't exist for this crate
+    // Try to generate docs for dependencies and this crate. Asking cargo for
+    // examples leaves the binaries out, so they get a run of their own.
+    let mut success = true;
+    for args in cargo_doc_runs(metadata) {
+        debug!(
+            "Running {} {}",
+            toolchain::describe(),
+            args.iter()
+                .map(|arg| arg.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join(" ")
+        );
+        let phase = timing::phase("cargo doc");
+        let status = toolchain::cargo_doc()
+            .args(&args)
+            .status()
+            .context("Failed to run `cargo doc`. Make sure you have Rust installed.")?;
+        debug!("cargo doc finished in {:.2?} ({})", phase.elapsed(), status);
+        if !status.success() {
+            success = false;
+            break;
         }
-
-        let json_content = fs::read_to_string(&json_path)
-            .with_context(|| format!("Failed to read {}", json_path.display()))?;
-
-        let json_data: Value = serde_json::from_str(&json_content)
-            .with_context(|| format!("Failed to parse JSON from {}", json_path.display()))?;
-
-        let matches = fuzzy_search_json(&json_data, crate_name, query)?;
-        all_results.extend(matches);
     }
 
-    // Sort by score and limit
-    all_results.sort_by(|a, b| b.score.cmp(&a.score));
-    all_results.truncate(limit);
-
-    // Display results
-    if all_results.is_empty() {
-        println!("No matches found for '{}'", query);
-    } else {
-        println!("\nSearch results for '{}':\n", query);
-        for (i, result) in all_results.iter().enumerate() {
-            println!("{}. {} ({})", i + 1, result.name, result.item_type);
-            println!("   Crate: {}", result.crate_name);
-            if let Some(path) = &result.path {
-                println!("   Path: {}", path);
-            }
-            if let Some(desc) = &result.description {
-                let desc_preview: String = desc.chars().take(100).collect();
-                println!(
-                    "   {}{}",
-                    desc_preview,
-                    if desc.len() > 100 { "..." } else { "" }
-                );
-            }
-            println!();
-        }
+    if !success {
+        warn!("cargo doc returned non-zero status, but continuing...");
+        let members = doc_targets::of_packages(metadata, &metadata.workspace_packages());
+        freshness::warn_stale(metadata, &members, "cargo doc failed");
     }
 
     Ok(())
 }
 
-#[derive(Debug)]
-struct SearchResult {
-    name: String,
-    crate_name: String,
-    item_type: String,
-    path: Option<String>,
-    description: Option<String>,
-    score: i64,
+// The arguments of each `cargo doc` run `generate_json_docs` makes
+fn cargo_doc_runs(metadata: &cargo_metadata::Metadata) -> Vec<Vec<std::ffi::OsString>> {
+    let mut args: Vec<std::ffi::OsString> =
+        vec!["--manifest-path".into(), manifest_path(metadata).into()];
+    args.extend(target_dir::cargo_args(metadata));
+    args.extend(doc_targets::cargo_args().into_iter().map(Into::into));
+    // Examples first: their run documents the library as a dependency,
+    // without `--document-private-items`, and the usual run then redoes it
+    let mut runs = Vec::new();
+    if doc_targets::examples() {
+        let mut examples = args.clone();
+        examples.push("--examples".into());
+        runs.push(examples);
+    }
+    runs.push(args);
+    runs
 }
 
-// Data structures for diff functionality
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-struct ApiItem {
-    name: String,
-    item_type: String,
-    path: Vec<String>,
-    signature: String, // Serialized representation of the signature
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ColorChoice {
+    /// Color even when piped, for tools that read ANSI escapes
+    Always,
+    /// Color on a terminal, unless `NO_COLOR` is set
+    Auto,
+    /// Never color
+    Never,
 }
 
-impl ApiItem {
-    fn full_path(&self) -> String {
-        if self.path.is_empty() {
-            self.name.clone()
-        } else {
-            format!("{}::{}", self.path.join("::"), self.name)
+impl ColorChoice {
+    // `auto` leaves it to `colored`, which checks for a terminal and reads
+    // `NO_COLOR`, `CLICOLOR` and `CLICOLOR_FORCE`
+    fn apply(self) {
+        match self {
+            ColorChoice::Always => colored::control::set_override(true),
+            ColorChoice::Never => colored::control::set_override(false),
+            ColorChoice::Auto => {}
         }
     }
 
-    fn display_string(&self) -> String {
-        format!("{} {}", self.item_type, self.full_path())
+    // Whether log lines on stderr are colored
+    fn stderr(self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+            }
+        }
     }
 }
 
-fn fuzzy_search_json(
-    json_data: &Value,
-    crate_name: &str,
-    query: &str,
-) -> Result<Vec<SearchResult>> {
-    let matcher = SkimMatcherV2::default();
-    let mut results = Vec::new();
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum DiffFormat {
+    /// Colored terminal output
+    Text,
+    /// A self-contained HTML fragment for wikis and dashboards
+    Html,
+    /// Machine-readable JSON
+    Json,
+    /// Breaking changes as named lints, like cargo-semver-checks
+    SemverLint,
+    /// GitHub Actions annotations, with a collapsible summary
+    Github,
+}
 
-    // Get the index object from the JSON
-    let index = json_data
-        .get("index")
-        .and_then(|v| v.as_object())
-        .context("Missing or invalid 'index' field in JSON")?;
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum CheatsheetFormat {
+    /// Tight columns for the terminal
+    Text,
+    /// Nested lists for wikis and READMEs
+    Markdown,
+}
 
-    // Search through all items in the index
-    for (_id, item) in index {
-        // Get the item name
-        let name = match item.get("name").and_then(|v| v.as_str()) {
-            Some(n) => n,
-            None => continue, // Skip unnamed items
-        };
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum LintFormat {
+    /// Findings grouped by file
+    Text,
+    /// GitHub Actions annotations on the offending lines
+    Github,
+}
 
-        // Fuzzy match against the query
-        if let Some(score) = matcher.fuzzy_match(name, query) {
-            // Get the item type from the "inner" field
-            let item_type = item
-                .get("inner")
-                .and_then(|inner| inner.as_object())
-                .and_then(|obj| obj.keys().next().map(|s| s.to_string()))
-                .unwrap_or_else(|| "unknown".to_string());
-
-            // Extract documentation if available
-            let description = item
-                .get("docs")
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string());
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum SearchFormat {
+    /// Human-readable listing
+    Text,
+    /// Machine-readable JSON
+    Json,
+}
 
-            results.push(SearchResult {
-                name: name.to_string(),
-                crate_name: crate_name.to_string(),
-                item_type,
-                path: None, // We'll skip path building for simplicity
-                description,
-                score,
-            });
+// How JSON output is laid out: pretty for people, compact for pipes
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum JsonStyle {
+    Pretty,
+    Compact,
+}
+
+impl JsonStyle {
+    // Explicit flags win; otherwise pretty-print only when a person is watching
+    fn from_flags(compact: bool, pretty: bool) -> Self {
+        if compact {
+            JsonStyle::Compact
+        } else if pretty || std::io::stdout().is_terminal() {
+            JsonStyle::Pretty
+        } else {
+            JsonStyle::Compact
         }
     }
-
-    Ok(results)
 }
 
-// Fetch rustdoc JSON from docs.rs
-async fn fetch_docs_json(crate_name: &str, version: &str) -> Result<Value> {
-    // docs.rs serves JSON files compressed with gzip
-    let url = format!("https://docs.rs/crate/{}/{}/json.gz", crate_name, version);
-
-    println!("Fetching documentation for {} v{}...", crate_name, version);
-
-    let response = reqwest::get(&url)
-        .await
-        .context(format!("Failed to fetch docs from {}", url))?;
+// Diagnostics go to stderr through `tracing`, keeping stdout for results.
+// `RUST_LOG` takes precedence over `-v`.
+fn init_logging(verbose: u8, quiet: bool, color: ColorChoice) {
+    let filter = tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| {
+        let level = match verbose {
+            _ if quiet => "error",
+            0 => "info",
+            1 => "debug",
+            _ => "trace",
+        };
+        tracing_subscriber::EnvFilter::new(format!("zdoc={}", level))
+    });
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .with_ansi(color.stderr())
+        .with_target(false)
+        .without_time()
+        .init();
+}
 
-    if !response.status().is_success() {
-        anyhow::bail!(
-            "Failed to fetch docs for {} v{}: HTTP {}. Make sure the version exists on docs.rs and has JSON docs available (added May 2025).",
-            crate_name,
-            version,
-            response.status()
-        );
+fn print_json(value: &Value, style: JsonStyle) -> Result<()> {
+    let text = match style {
+        JsonStyle::Pretty => serde_json::to_string_pretty(value),
+        JsonStyle::Compact => serde_json::to_string(value),
     }
-
-    let compressed_bytes = response
-        .bytes()
-        .await
-        .context("Failed to read response body")?;
-
-    // Explicitly decompress the gzip data
-    let mut decoder = GzDecoder::new(&compressed_bytes[..]);
-    let mut json_text = String::new();
-    decoder
-        .read_to_string(&mut json_text)
-        .context("Failed to decompress gzip data")?;
-
-    let json_data: Value =
-        serde_json::from_str(&json_text).context("Failed to parse JSON response")?;
-
-    Ok(json_data)
+    .context("Failed to serialize JSON output")?;
+    println!("{}", text);
+    Ok(())
 }
 
-// Extract API items from rustdoc JSON with signature details
-fn extract_api_items(json_data: &Value) -> Result<Vec<ApiItem>> {
-    let mut items = Vec::new();
-
-    let index = json_data
-        .get("index")
-        .and_then(|v| v.as_object())
-        .context("Missing or invalid 'index' field in JSON")?;
-
-    // Build a map of item IDs to their paths
-    let mut id_to_path: HashMap<String, Vec<String>> = HashMap::new();
-
-    // First pass: collect all items and build path information
-    for (id, item) in index {
-        if item.get("name").and_then(|v| v.as_str()).is_some() {
-            // Try to get the path from "path" field
-            let path = item
-                .get("path")
-                .and_then(|v| v.as_array())
-                .map(|arr| {
-                    arr.iter()
-                        .filter_map(|v| v.as_str().map(String::from))
-                        .collect::<Vec<_>>()
-                })
-                .unwrap_or_default();
+// When `zdoc search` runs `cargo doc`
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Generate {
+    // Only when the sources are newer than the JSON
+    Auto,
+    Always,
+    Never,
+}
 
-            id_to_path.insert(id.clone(), path);
+// Split a search on commas, except those inside a glob's `{a,b}` alternation
+fn split_queries(query: &str) -> Vec<&str> {
+    let mut queries = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in query.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                queries.push(&query[start..i]);
+                start = i + 1;
+            }
+            _ => {}
         }
     }
+    queries.push(&query[start..]);
+    queries
+}
 
-    // Second pass: extract items with their signatures
-    for (id, item) in index {
-        let name = match item.get("name").and_then(|v| v.as_str()) {
-            Some(n) => n.to_string(),
-            None => continue,
-        };
-
-        let inner = match item.get("inner").and_then(|v| v.as_object()) {
-            Some(i) => i,
-            None => continue,
-        };
-
-        let item_type = inner.keys().next().map(String::from).unwrap_or_default();
+// Fuzzy searches show this many results unless `--results` says otherwise
+const DEFAULT_SEARCH_RESULTS: usize = 5;
 
-        // Skip certain internal items
-        if item_type == "Import" || item_type == "ProcMacro" {
-            continue;
-        }
+// How `zdoc search` should run and present its results
+struct SearchOptions {
+    limit: Option<usize>,
+    glob: bool,
+    all_terms: bool,
+    format: SearchFormat,
+    dry_run: bool,
+    length_penalty: bool,
+    // How much the name, path and docs count toward a fuzzy score
+    weights: Weights,
+    generate: Generate,
+    // Copy the top result's path
+    copy: bool,
+    // Look up each result's docs.rs URL
+    links: bool,
+    // Print the index entries behind the results instead
+    raw: bool,
+    // `--async` (Some(true)) or `--sync` (Some(false)): only functions that
+    // are, or aren't, async
+    only_async: Option<bool>,
+    // Drop results scoring below this
+    min_score: Option<i64>,
+    // List every match (paged) instead of the best `limit`
+    all: bool,
+    // Keep `#[doc(hidden)]` items, which aren't part of the supported API
+    include_hidden: bool,
+}
 
-        let path = id_to_path.get(id).cloned().unwrap_or_default();
+// What limiting the results left out, from the full sorted list
+struct Truncation {
+    omitted: usize,
+    best_omitted: i64,
+    shown_min: i64,
+    // A `--results` showing four times as many, and the `--min-score`
+    // that shows the same ones
+    more_results: usize,
+    more_min_score: i64,
+}
 
-        // Extract signature based on item type
-        let signature = extract_signature(&item_type, inner.get(&item_type));
+impl Truncation {
+    fn of(results: &[SearchResult], limit: usize) -> Option<Self> {
+        let shown_min = results.get(limit.checked_sub(1)?)?.score;
+        let best_omitted = results.get(limit)?.score;
+        let more_results = limit.saturating_mul(4).min(results.len());
+        Some(Truncation {
+            omitted: results.len() - limit,
+            best_omitted,
+            shown_min,
+            more_results,
+            more_min_score: results[more_results - 1].score,
+        })
+    }
 
-        items.push(ApiItem {
-            name,
-            item_type,
-            path,
-            signature,
-        });
+    fn footer(&self) -> String {
+        format!(
+            "… {} more {} (best omitted score: {} vs shown minimum {}); rerun with --results {} or --min-score {}, or --all for every match",
+            self.omitted,
+            if self.omitted == 1 {
+                "match"
+            } else {
+                "matches"
+            },
+            self.best_omitted,
+            self.shown_min,
+            self.more_results,
+            self.more_min_score
+        )
     }
 
-    Ok(items)
+    fn to_json(&self) -> Value {
+        serde_json::json!({
+            "count": self.omitted,
+            "best_score": self.best_omitted,
+            "shown_min_score": self.shown_min,
+        })
+    }
 }
 
-// Extract signature details for different item types
-fn extract_signature(item_type: &str, inner_data: Option<&Value>) -> String {
-    let inner = match inner_data {
-        Some(d) => d,
-        None => return String::new(),
+fn search_docs(
+    metadata: &cargo_metadata::Metadata,
+    targets: &[doc_targets::DocTarget],
+    queries: &[String],
+    options: &SearchOptions,
+    json_style: JsonStyle,
+) -> Result<()> {
+    let doc_dir = target_dir::doc_dir(metadata);
+    let regenerate = match options.generate {
+        Generate::Always => true,
+        Generate::Never => false,
+        // Docs written without hidden items are as fresh as ones with them;
+        // cargo tells them apart by their flags and reruns rustdoc if needed
+        Generate::Auto => options.include_hidden || !freshness::all_fresh(metadata, targets),
     };
 
-    match item_type {
-        "Function" | "Method" => {
-            // Extract function signature: parameters and return type
-            let mut sig_parts = Vec::new();
+    if options.dry_run {
+        if freshness::clean_pending() {
+            println!("Would delete the JSON files in {}", doc_dir.display());
+        }
+        if regenerate {
+            for args in cargo_doc_runs(metadata) {
+                let args: Vec<String> = args
+                    .iter()
+                    .map(|arg| arg.to_string_lossy().into_owned())
+                    .collect();
+                println!("Would run: {} {}", toolchain::describe(), args.join(" "));
+            }
+        } else {
+            println!("Would reuse the existing JSON docs");
+        }
+        println!("Would read:");
+        for target in targets {
+            let json_path = target.json_path(&doc_dir);
+            let state = match fs::metadata(&json_path) {
+                Ok(file) => format!("exists, {} KB", file.len() / 1024),
+                Err(_) => "not generated yet".to_string(),
+            };
+            println!("  {} ({})", json_path.display(), state);
+        }
+        return Ok(());
+    }
 
-            // Get parameters
-            if let Some(decl) = inner.get("decl") {
-                if let Some(inputs) = decl.get("inputs").and_then(|v| v.as_array()) {
-                    let params: Vec<String> = inputs
-                        .iter()
-                        .filter_map(|input| {
-                            let name = input.get(0).and_then(|v| v.as_str())?;
-                            let type_str = format_type(input.get(1)?);
-                            Some(format!("{}: {}", name, type_str))
-                        })
-                        .collect();
-                    sig_parts.push(format!("({})", params.join(", ")));
-                }
+    // Step 1: Run cargo doc with JSON output format, unless the JSON is current
+    match options.generate {
+        _ if regenerate => generate_json_docs(metadata)?,
+        Generate::Never => {
+            freshness::warn_stale(metadata, targets, "--no-generate skipped cargo doc")
+        }
+        _ => debug!("JSON docs are newer than the sources; not running cargo doc"),
+    }
 
-                // Get return type
-                if let Some(output) = decl.get("output") {
-                    if !output.is_null() {
-                        let ret_type = format_type(output);
-                        if ret_type != "()" {
-                            sig_parts.push(format!("-> {}", ret_type));
-                        }
-                    }
-                }
-            }
+    // Step 3 & 4: Load JSON files and fuzzy match
+    let mut all_results = Vec::new();
+    let ignore = ignore::Ignore::load(metadata.workspace_root.as_std_path())?;
+    let mut resolver = reexports::Resolver::new(&doc_dir);
 
-            sig_parts.join(" ")
+    for target in targets {
+        // Skip if JSON doesn't exist for this target
+        let json_path = target.json_path(&doc_dir);
+        if !json_path.exists() {
+            debug!("Skipping {}: no JSON docs were generated", target.name);
+            continue;
         }
+        let crate_name = &target.package;
+        // `--raw` shows entries whole, so it can't use the slimmed tree
+        let mut phase = timing::phase(format!("parse {}", target.name));
+        let json_data = if options.raw {
+            doc_source::read_crate_json(crate_name, DocSource::File { path: &json_path })?.json
+        } else {
+            rustdoc_json::read_file_for_search(&json_path)?
+        };
+        let index_len = json_data
+            .get("index")
+            .and_then(|v| v.as_object())
+            .map_or(0, |index| index.len());
+        phase.items(index_len);
+        drop(phase);
+        debug!("{}: {} items in the index", target.name, index_len);
 
-        "Struct" => {
-            // Extract struct fields
-            if let Some(kind) = inner.get("kind") {
-                if let Some(kind_str) = kind.as_str() {
-                    match kind_str {
-                        "plain" => {
-                            if let Some(fields) = inner.get("fields").and_then(|v| v.as_array()) {
-                                let field_sigs: Vec<String> = fields
-                                    .iter()
-                                    .filter_map(|field_id| {
-                                        // This is a simplified version; proper implementation would
-                                        // look up field details from index
-                                        field_id.as_str().map(String::from)
-                                    })
-                                    .collect();
-                                return format!("{{ {} fields }}", field_sigs.len());
-                            }
-                        }
-                        "tuple" => {
-                            if let Some(fields) = inner.get("fields").and_then(|v| v.as_array()) {
-                                return format!("({} fields)", fields.len());
-                            }
-                        }
-                        "unit" => return "".to_string(),
-                        _ => {}
+        // Each query runs separately; an item matched by several is merged
+        // into one result listing every query it matched
+        let mut phase = timing::phase(format!("search {}", target.name));
+        let mut merged: HashMap<String, SearchResult> = HashMap::new();
+        for query in queries {
+            let matcher = if options.glob {
+                NameMatcher::glob(query)?
+            } else {
+                NameMatcher::fuzzy(options.length_penalty, options.weights)
+            };
+            for result in search_json(&json_data, crate_name, query, &matcher, &mut resolver)? {
+                match merged.get_mut(&result.id) {
+                    Some(existing) => {
+                        existing.score += result.score;
+                        existing.matched.extend(result.matched);
+                    }
+                    None => {
+                        merged.insert(result.id.clone(), result);
                     }
                 }
             }
-            String::new()
         }
-
-        "Enum" => {
-            // Extract enum variants
-            if let Some(variants) = inner.get("variants").and_then(|v| v.as_array()) {
-                return format!("{{ {} variants }}", variants.len());
+        phase.items(merged.len());
+        drop(phase);
+        // Binaries and examples aren't published, so have no docs.rs pages
+        if options.links && target.kind == doc_targets::Kind::Lib {
+            let linker = links::Linker::new(&json_data, crate_name);
+            for result in merged.values_mut() {
+                result.url = linker.url(&result.id);
             }
-            String::new()
         }
-
-        "Trait" => {
-            // Extract trait items (methods, associated types)
-            if let Some(items) = inner.get("items").and_then(|v| v.as_array()) {
-                return format!("{{ {} items }}", items.len());
+        if let Some(label) = target.label() {
+            for result in merged.values_mut() {
+                result.target = Some(label.clone());
             }
-            String::new()
         }
-
-        _ => String::new(),
-    }
-}
-
-// Helper to format type information from JSON
-fn format_type(type_data: &Value) -> String {
-    // This is a simplified type formatter
-    // Real rustdoc JSON has complex nested type structures
-    if let Some(resolved_path) = type_data.get("resolved_path") {
-        if let Some(name) = resolved_path.get("name").and_then(|v| v.as_str()) {
-            return name.to_string();
+        if options.raw {
+            for result in merged.values_mut() {
+                result.raw = json_data["index"].get(&result.id).cloned();
+            }
         }
+        all_results.extend(
+            merged
+                .into_values()
+                .filter(|result| !options.all_terms || result.matched.len() == queries.len())
+                .filter(|result| options.include_hidden || !result.hidden)
+                .filter(|result| options.min_score.is_none_or(|min| result.score >= min))
+                .filter(|result| match options.only_async {
+                    Some(only_async) => {
+                        result.is_function() && result.asyncness.is_some() == only_async
+                    }
+                    None => true,
+                }),
+        );
     }
 
-    if let Some(primitive) = type_data.get("primitive").and_then(|v| v.as_str()) {
-        return primitive.to_string();
-    }
+    let (mut all_results, ignored) =
+        ignore.partition(all_results, |result| result.full_path.clone());
 
-    if let Some(borrowed_ref) = type_data.get("borrowed_ref") {
-        let mutable = borrowed_ref
-            .get("mutable")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false);
-        let inner_type = borrowed_ref
-            .get("type")
-            .map(format_type)
-            .unwrap_or_else(|| "?".to_string());
-        return if mutable {
-            format!("&mut {}", inner_type)
-        } else {
-            format!("&{}", inner_type)
-        };
+    // Sort by score (then name, so equal scores list predictably) and limit.
+    // Glob searches list every match unless a limit was given.
+    all_results.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.name.cmp(&b.name)));
+    let limit = match (options.limit, options.glob) {
+        _ if options.all => usize::MAX,
+        (Some(limit), _) => limit,
+        (None, true) => usize::MAX,
+        (None, false) => DEFAULT_SEARCH_RESULTS,
+    };
+    let total_matches = all_results.len();
+    let truncation = Truncation::of(&all_results, limit);
+    all_results.truncate(limit);
+    let _render = timing::phase("render");
+
+    let quoted: Vec<String> = queries.iter().map(|q| format!("'{}'", q)).collect();
+    let described = if options.all_terms && queries.len() > 1 {
+        format!("all of {}", quoted.join(", "))
+    } else {
+        quoted.join(", ")
+    };
+
+    if options.raw {
+        let entries: Vec<Value> = all_results
+            .iter()
+            .filter_map(|result| result.raw.clone())
+            .collect();
+        return print_json(&Value::Array(entries), json_style);
     }
 
-    // Fallback for complex types
-    "...".to_string()
-}
+    if options.format == SearchFormat::Json {
+        let results: Vec<Value> = all_results
+            .iter()
+            .map(|result| {
+                let mut entry = result.to_json();
+                if options.links {
+                    entry["url"] = result.url.clone().into();
+                }
+                entry
+            })
+            .collect();
+        let mut json = serde_json::json!({
+            "queries": queries,
+            "all_terms": options.all_terms,
+            "results": results,
+            "total_matches": total_matches,
+            "omitted": truncation.as_ref().map(Truncation::to_json),
+            "ignored": ignored.len(),
+        });
+        if let Some(timings) = timing::to_json() {
+            json["timings"] = timings;
+        }
+        return print_json(&json, json_style);
+    }
 
-// Compare two sets of API items and categorize changes
-fn compare_api_items(
-    old_items: Vec<ApiItem>,
-    new_items: Vec<ApiItem>,
-) -> (Vec<ApiItem>, Vec<ApiItem>, Vec<(ApiItem, ApiItem)>) {
-    let old_set: HashMap<String, ApiItem> = old_items
-        .into_iter()
-        .map(|item| (format!("{}::{}", item.full_path(), item.item_type), item))
-        .collect();
+    // Display results, paged when `--all` may make them long
+    let mut out = String::new();
+    if all_results.is_empty() {
+        out.push_str(&format!("No matches found for {}\n", described));
+    } else {
+        out.push_str(&format!("\nSearch results for {}:\n\n", described));
+        for (i, result) in all_results.iter().enumerate() {
+            let kind = match result.asyncness {
+                Some(asyncness @ signature::Asyncness::Async) => {
+                    format!("{} {}", asyncness.label(), result.item_type)
+                }
+                Some(asyncness) => format!("{}, {}", result.item_type, asyncness.label()),
+                None => result.item_type.clone(),
+            };
+            let kind = match style::kind_role(&result.item_type) {
+                Some(role) => style::paint(role, &kind),
+                None => kind.normal(),
+            };
+            let mut heading = format!("{}. {} ({})", i + 1, result.name, kind);
+            if !result.tags.is_empty() {
+                heading.push_str(&format!(" [{}]", result.tags.join(", ")));
+            }
+            if result.deprecated {
+                heading.push_str(&format!(
+                    " {}",
+                    style::paint(Role::Deprecated, "deprecated")
+                ));
+            }
+            if result.hidden {
+                heading.push_str(&format!(" {}", style::paint(Role::Muted, "(hidden)")));
+            }
+            out.push_str(&format!("{}\n", heading));
+            match &result.target {
+                Some(target) => {
+                    out.push_str(&format!("   Crate: {} ({})\n", result.crate_name, target))
+                }
+                None => out.push_str(&format!("   Crate: {}\n", result.crate_name)),
+            }
+            if let Some(alias) = &result.alias {
+                out.push_str(&format!("   Alias: {}\n", alias));
+            }
+            if queries.len() > 1 {
+                out.push_str(&format!("   Matched: {}\n", result.matched.join(", ")));
+            }
+            if let Some(path) = &result.path {
+                let path = layout::shorten_path(path, layout::width() - "   Path: ".len());
+                out.push_str(&format!("   Path: {}\n", style::paint(Role::Path, &path)));
+            }
+            if let Some(target) = &result.reexport_of {
+                let target =
+                    layout::shorten_path(target, layout::width() - "   Re-exports: ".len());
+                out.push_str(&format!("   Re-exports: {}\n", target));
+            }
+            if let Some(url) = &result.url {
+                out.push_str(&format!("   Docs: {}\n", url));
+            }
+            if let Some(desc) = &result.description {
+                // One line of summary, as much as the terminal fits
+                let summary = items::summary(desc);
+                let room = layout::width() - "   ".len() - "...".len();
+                let mut desc_preview: String = summary.chars().take(room).collect();
+                if summary.chars().count() > room {
+                    desc_preview.push_str("...");
+                }
+                out.push_str(&format!(
+                    "   {}\n",
+                    style::paint(Role::DocText, &desc_preview)
+                ));
+            }
+            out.push('\n');
+        }
+    }
 
-    let new_set: HashMap<String, ApiItem> = new_items
-        .into_iter()
-        .map(|item| (format!("{}::{}", item.full_path(), item.item_type), item))
-        .collect();
+    if let Some(truncation) = &truncation {
+        out.push_str(&format!(
+            "{}\n",
+            style::paint(Role::Muted, &truncation.footer())
+        ));
+    }
+    if let Some(note) = ignore::note(ignored.len(), "result") {
+        out.push_str(&format!("{}\n", note));
+    }
+    if options.all {
+        man::page_output(&out)?;
+    } else {
+        print!("{}", out);
+    }
 
-    let old_keys: HashSet<_> = old_set.keys().cloned().collect();
-    let new_keys: HashSet<_> = new_set.keys().cloned().collect();
+    if options.copy
+        && let Some(top) = all_results.first()
+    {
+        clipboard::copy(&top.full_path, &format!("`{}`", top.full_path));
+    }
 
-    // Items only in new version (added)
-    let added: Vec<ApiItem> = new_keys
-        .difference(&old_keys)
-        .filter_map(|key| new_set.get(key).cloned())
-        .collect();
+    Ok(())
+}
 
-    // Items only in old version (removed)
-    let removed: Vec<ApiItem> = old_keys
-        .difference(&new_keys)
-        .filter_map(|key| old_set.get(key).cloned())
-        .collect();
+#[derive(Debug)]
+struct SearchResult {
+    // Index id, unique within the crate
+    id: String,
+    name: String,
+    crate_name: String,
+    item_type: String,
+    path: Option<String>,
+    // The path to import or name the item by, for `--copy`
+    full_path: String,
+    description: Option<String>,
+    // Compatibility markers such as `non_exhaustive` and `sealed`
+    tags: Vec<String>,
+    deprecated: bool,
+    // `#[doc(hidden)]`, or inside or a member of something that is
+    hidden: bool,
+    // Where a re-export from another crate is defined
+    reexport_of: Option<String>,
+    // The item's docs.rs page, with `--links`
+    url: Option<String>,
+    // The item's index entry, with `--raw`
+    raw: Option<Value>,
+    // The binary or example the item is in (`bin: server`), not the library
+    target: Option<String>,
+    // The `#[doc(alias)]` the query matched, when it matched better than the name
+    alias: Option<String>,
+    // The queries this item matched
+    matched: Vec<String>,
+    // For functions, whether and how they're async
+    asyncness: Option<signature::Asyncness>,
+    score: i64,
+}
 
-    // Items in both but with different signatures (modified)
-    let modified: Vec<(ApiItem, ApiItem)> = old_keys
-        .intersection(&new_keys)
-        .filter_map(|key| {
-            let old_item = old_set.get(key)?;
-            let new_item = new_set.get(key)?;
-            if old_item.signature != new_item.signature {
-                Some((old_item.clone(), new_item.clone()))
-            } else {
-                None
-            }
+impl SearchResult {
+    fn to_json(&self) -> Value {
+        serde_json::json!({
+            "name": self.name,
+            "crate": self.crate_name,
+            "target": self.target,
+            "kind": self.item_type,
+            "path": self.path,
+            "description": self.description,
+            "tags": self.tags,
+            "deprecated": self.deprecated,
+            "hidden": self.hidden,
+            "reexport_of": self.reexport_of,
+            "alias": self.alias,
+            "async": self.asyncness.map(signature::Asyncness::to_json),
+            "matched": self.matched,
+            "score": self.score,
         })
-        .collect();
+    }
 
-    (added, removed, modified)
+    fn is_function(&self) -> bool {
+        matches!(
+            self.item_type.as_str(),
+            "function" | "method" | "trait method" | "extern fn"
+        )
+    }
 }
 
-// Display diff results with git-style colored output
-fn display_diff(
-    crate_name: &str,
-    ver1: &str,
-    ver2: &str,
-    mut added: Vec<ApiItem>,
-    mut removed: Vec<ApiItem>,
-    mut modified: Vec<(ApiItem, ApiItem)>,
-) {
-    println!(
-        "\nAPI diff for {} ({}...{}):\n",
-        crate_name.bold(),
-        ver1,
-        ver2
-    );
+// Data structures for diff functionality
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+struct ApiItem {
+    // Index id, for looking the item up again; ids aren't stable across
+    // versions, so baselines leave it out
+    #[serde(skip)]
+    id: String,
+    name: String,
+    item_type: String,
+    path: Vec<String>,
+    signature: String, // Serialized representation of the signature
+    // Compatibility markers: `non_exhaustive`, `sealed`, `required` (trait items without a default)
+    tags: Vec<String>,
+    // Markers of the enclosing enum/struct/trait, for members
+    parent_tags: Vec<String>,
+    // What a member belongs to: `struct`, `enum`, `variant`, `union`,
+    // `trait`, `impl` (inherent) or `trait_impl`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    parent_kind: Option<String>,
+    // Other public paths the item can be imported by (re-exports), sorted
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    reexports: Vec<String>,
+    // Trimmed doc comment, None when the item has none. Docs aren't part of
+    // the API contract, so baselines leave them out.
+    #[serde(skip)]
+    docs: Option<String>,
+    // Trait impl members show the trait's docs rather than their own
+    #[serde(skip)]
+    inherits_docs: bool,
+    // Where the item is defined (file, first line), when the docs were read
+    // with source spans. Baselines leave it out.
+    #[serde(skip)]
+    location: Option<(String, usize)>,
+    // Which of `deprecated`, `doc(hidden)` and `unstable` apply. They feed
+    // the diff's stability summary rather than being compared item by item.
+    #[serde(skip)]
+    stability: Vec<&'static str>,
+    // For members, whether the enclosing type is new too (set when diffing)
+    #[serde(skip)]
+    parent_added: bool,
+}
 
-    let added_count = added.len();
-    let removed_count = removed.len();
-    let modified_count = modified.len();
+// The identity of an item across versions. Kept as separate fields rather
+// than one joined string, since impl segments such as `<A as From<B>>`
+// contain `::` themselves and a joined path can read the same for two items.
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct DiffKey<'a> {
+    path: &'a [String],
+    name: &'a str,
+    kind: &'a str,
+    disambiguator: &'a str,
+}
 
-    let total_changes = added_count + removed_count + modified_count;
-    if total_changes == 0 {
-        println!("{}", "No API changes detected.".dimmed());
-        return;
+// Semver impact of a single change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Severity {
+    Minor,
+    Breaking,
+}
+
+impl Severity {
+    fn label(self) -> &'static str {
+        match self {
+            Severity::Minor => "minor",
+            Severity::Breaking => "breaking",
+        }
     }
+}
 
-    // Display removed items (red with -)
-    if !removed.is_empty() {
-        println!("{}", format!("Removed ({}):", removed_count).red().bold());
-        removed.sort_by(|a, b| a.full_path().cmp(&b.full_path()));
-        for item in removed {
-            let display = format!("- {} {}", item.display_string(), item.signature);
-            println!("  {}", display.red());
+impl ApiItem {
+    fn signature_with_tags(&self) -> String {
+        if self.tags.is_empty() {
+            self.shown_signature().to_string()
+        } else {
+            format!("{} [{}]", self.shown_signature(), self.tags.join(", "))
         }
-        println!();
     }
 
-    // Display added items (green with +)
-    if !added.is_empty() {
-        println!("{}", format!("Added ({}):", added_count).green().bold());
-        added.sort_by(|a, b| a.full_path().cmp(&b.full_path()));
-        for item in added {
-            let display = format!("+ {} {}", item.display_string(), item.signature);
-            println!("  {}", display.green());
+    fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    fn parent_has_tag(&self, tag: &str) -> bool {
+        self.parent_tags.iter().any(|t| t == tag)
+    }
+
+    // Methods are functions too; old baselines list them as `function`
+    fn kind_family(&self) -> &str {
+        match self.item_type.as_str() {
+            "method" | "trait method" => "function",
+            kind => kind,
         }
-        println!();
     }
 
-    // Display modified items (yellow with ~)
-    if !modified.is_empty() {
-        println!(
-            "{}",
-            format!("Modified ({}):", modified_count).yellow().bold()
-        );
-        modified.sort_by(|a, b| a.0.full_path().cmp(&b.0.full_path()));
-        for (old_item, new_item) in modified {
-            println!("  {}", format!("~ {}", old_item.display_string()).yellow());
-            println!("    {} {}", "-".red(), old_item.signature.red());
-            println!("    {} {}", "+".green(), new_item.signature.green());
+    // What an item is matched on across versions
+    fn diff_key(&self) -> DiffKey<'_> {
+        DiffKey {
+            path: &self.path,
+            name: &self.name,
+            kind: self.kind_family(),
+            // Members of blanket impls have no path, so the signature is all
+            // that tells one `from` from another
+            disambiguator: if self.path.is_empty() {
+                &self.signature
+            } else {
+                ""
+            },
         }
-        println!();
     }
 
-    println!(
-        "{}",
-        format!(
-            "Summary: +{} / -{} / ~{}",
-            added_count, removed_count, modified_count
-        )
-        .bold()
-    );
-}
+    // Every path downstream code can name the item by
+    fn public_paths(&self) -> Vec<String> {
+        let mut paths = vec![self.full_path()];
+        paths.extend(self.reexports.iter().cloned());
+        paths
+    }
 
-// Main diff command handler
-async fn diff_docs(crate_name: &str, ver1: &str, ver2: &str) -> Result<()> {
-    // Fetch both versions
-    let json1 = fetch_docs_json(crate_name, ver1).await?;
-    let json2 = fetch_docs_json(crate_name, ver2).await?;
+    // Members no path leads to: those of blanket impls (`from`, `borrow`,
+    // `try_into`, ...), which rustdoc lists once for every type they cover,
+    // and of impls for types without a path of their own (`&Foo`). They're
+    // the implemented trait's API more than the crate's, so listings and
+    // saved APIs leave them out. The crate root is the one item placed at
+    // no path.
+    fn is_pathless_member(&self) -> bool {
+        self.path.is_empty() && self.item_type != "module"
+    }
 
-    println!("Parsing API items...");
+    fn full_path(&self) -> String {
+        if self.path.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}::{}", self.path.join("::"), self.name)
+        }
+    }
 
-    // Extract API items from both versions
-    let items1 = extract_api_items(&json1)?;
-    let items2 = extract_api_items(&json2)?;
+    // An impl as Rust declares it, `impl<T> Send for foo::Foo<T>`, taking the
+    // parameters from its signature and the rest from its name
+    fn impl_header(&self) -> Option<String> {
+        if self.item_type != "impl" {
+            return None;
+        }
+        let segment = self.name.strip_prefix('<')?.strip_suffix('>')?;
+        let split = bounds::find_top_level(segment, " as ")?;
+        let (self_type, trait_name) = (&segment[..split], &segment[split + " as ".len()..]);
+        let params = match bounds::group_end(&self.signature) {
+            Some(end) if self.signature.starts_with('<') => &self.signature[..=end],
+            _ => "",
+        };
+        let module: Vec<&str> = self.path.iter().map(String::as_str).collect();
+        let self_path = [module.as_slice(), &[self_type]].concat().join("::");
+        Some(format!("impl{} {} for {}", params, trait_name, self_path))
+    }
 
-    println!("Comparing {} items...", items1.len() + items2.len());
+    // The signature as printed after `display_string`: an impl's parameters
+    // are in its header already, leaving its where clause
+    fn shown_signature(&self) -> &str {
+        match bounds::group_end(&self.signature) {
+            Some(end) if self.item_type == "impl" && self.signature.starts_with('<') => {
+                self.signature[end + 1..].trim_start()
+            }
+            _ => &self.signature,
+        }
+    }
 
-    // Compare and categorize changes
-    let (added, removed, modified) = compare_api_items(items1, items2);
+    fn display_string(&self) -> String {
+        let head = self
+            .impl_header()
+            .unwrap_or_else(|| format!("{} {}", self.item_type, self.full_path()));
+        if self.tags.is_empty() {
+            head
+        } else {
+            format!("{} [{}]", head, self.tags.join(", "))
+        }
+    }
 
-    // Display results
-    display_diff(crate_name, ver1, ver2, added, removed, modified);
+    // `display_string` for a line starting `indent` columns in, with the
+    // path shortened if it wouldn't fit on the line by itself
+    fn display_fitted(&self, indent: usize) -> String {
+        if self.item_type == "impl" {
+            return self.display_string();
+        }
+        let mut other = indent + self.item_type.chars().count() + 1;
+        if !self.tags.is_empty() {
+            other += self.tags.join(", ").chars().count() + 3;
+        }
+        let path = layout::shorten_path(
+            &self.full_path(),
+            layout::width().saturating_sub(other).max(20),
+        );
+        if self.tags.is_empty() {
+            format!("{} {}", self.item_type, path)
+        } else {
+            format!("{} {} [{}]", self.item_type, path, self.tags.join(", "))
+        }
+    }
+}
 
-    Ok(())
+// Adding an item is minor unless it forces downstream changes: a new variant
+// or field breaks exhaustive matches (and struct literals), and a new
+// required trait item breaks implementors. Parents opt out of those
+// guarantees with `#[non_exhaustive]` or sealing.
+fn added_severity(item: &ApiItem) -> Severity {
+    let new_member =
+        member_addition(item).is_some_and(|(severity, _)| severity == Severity::Breaking);
+    let new_requirement = item.has_tag("required") && !item.parent_has_tag("sealed");
+    if new_member || new_requirement {
+        Severity::Breaking
+    } else {
+        Severity::Minor
+    }
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    // 1. Immediate constraint check
-    if !Path::new("Cargo.toml").exists() {
-        anyhow::bail!("Error: No `Cargo.toml` found. `zdoc` must be run within a Rust project.");
+// Whether adding a variant, or a field to a struct or struct-like variant,
+// breaks downstream code, and why: it does unless the type is
+// `#[non_exhaustive]` or, for a struct, already has private fields. None
+// for other items and for members of types that are new themselves.
+fn member_addition(item: &ApiItem) -> Option<(Severity, String)> {
+    let member = item.item_type == "variant"
+        || (item.item_type == "struct_field"
+            && matches!(item.parent_kind.as_deref(), Some("struct" | "variant")));
+    if !member || item.parent_added {
+        return None;
     }
+    let parent = item.parent_kind.as_deref().unwrap_or("enum");
+    Some(if item.parent_has_tag("non_exhaustive") {
+        (
+            Severity::Minor,
+            format!("the {} is #[non_exhaustive]", parent),
+        )
+    } else if item.parent_has_tag("private_fields") {
+        (
+            Severity::Minor,
+            format!("the {} has private fields", parent),
+        )
+    } else {
+        (
+            Severity::Breaking,
+            format!("the {} isn't #[non_exhaustive]", parent),
+        )
+    })
+}
 
-    let cli = Cli::parse();
+// Paths `old` could be imported by that `new` no longer can
+fn lost_paths(old: &ApiItem, new: &ApiItem) -> Vec<String> {
+    let remaining = new.public_paths();
+    old.public_paths()
+        .into_iter()
+        .filter(|path| !remaining.contains(path))
+        .collect()
+}
 
-    // 2. Fetch project metadata (this is fast after the first run)
-    let metadata = MetadataCommand::new()
-        .exec()
-        .context("Failed to parse cargo metadata")?;
+// Whether a signature change only drops bounds, which lets callers and
+// implementors do more. Not for unsealed trait items, whose implementors
+// now have to accept anything the looser bounds allow, nor for a trait
+// losing a `Self` bound that generic code may rely on.
+fn bounds_loosened(old: &ApiItem, new: &ApiItem) -> bool {
+    let (old_sig, new_sig) = (
+        member_count_generics(&old.signature).unwrap_or_else(|| old.signature.clone()),
+        member_count_generics(&new.signature).unwrap_or_else(|| new.signature.clone()),
+    );
+    let implemented_downstream =
+        new.parent_kind.as_deref() == Some("trait") && !new.parent_has_tag("sealed");
+    bounds::only_loosened(&old_sig, &new_sig)
+        && !implemented_downstream
+        && !bounds::lost_self_bounds(&old_sig, &new_sig)
+}
 
-    match &cli.command {
-        Commands::Search {
-            query,
-            crate_name,
-            results,
-        } => {
-            search_docs(&metadata, crate_name.as_deref(), query, *results)?;
+// Whether a function only became `const` or stopped being `unsafe`, which
+// every caller still compiles with. Implementors of an unsealed trait
+// method declared `unsafe fn` no longer match it.
+fn qualifiers_relaxed(old: &ApiItem, new: &ApiItem) -> bool {
+    let (old_qualifiers, old_rest) = signature::split_qualifiers(&old.signature);
+    let (new_qualifiers, new_rest) = signature::split_qualifiers(&new.signature);
+    let implemented_downstream =
+        new.parent_kind.as_deref() == Some("trait") && !new.parent_has_tag("sealed");
+    let relaxed = |qualifier: &str| match qualifier {
+        "const" => new_qualifiers.contains(&qualifier),
+        "unsafe" => !implemented_downstream && old_qualifiers.contains(&qualifier),
+        _ => false,
+    };
+    old_rest == new_rest
+        && old_qualifiers != new_qualifiers
+        && old_qualifiers
+            .iter()
+            .filter(|q| !new_qualifiers.contains(q))
+            .all(|q| relaxed(q))
+        && new_qualifiers
+            .iter()
+            .filter(|q| !old_qualifiers.contains(q))
+            .all(|q| relaxed(q))
+}
+
+// Whether the signature changed in a way that can break downstream code
+fn signature_breaking(old: &ApiItem, new: &ApiItem) -> bool {
+    // Member counts (`{ 3 variants }`) change whenever members are added or
+    // removed; those members are reported and classified on their own
+    let counts_only = member_count_generics(&old.signature)
+        .is_some_and(|generics| member_count_generics(&new.signature) == Some(generics));
+    // Giving an associated type a default only changes what implementors
+    // may leave out; losing one makes it required, which the tags catch
+    let default_added = new.item_type == "assoc_type"
+        && without_assoc_default(&old.signature).is_none()
+        && without_assoc_default(&new.signature) == Some(old.signature.as_str());
+    // The same bounds in another order (`Send + Base` for `Base + Send`)
+    let reordered = bounds::split(&old.signature) == bounds::split(&new.signature);
+    old.signature != new.signature
+        && !counts_only
+        && !default_added
+        && !reordered
+        && !bounds_loosened(old, new)
+        && !qualifiers_relaxed(old, new)
+}
+
+// A function's return type gaining, losing or swapping an `Option` or
+// `Result` wrapper (`-> T` becoming `-> Option<T>`): breaking, and easy to
+// miss in a long signature, so it's called out on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReturnWrapping {
+    Wrapped(&'static str),
+    Unwrapped(&'static str),
+    Swapped(&'static str, &'static str),
+}
+
+impl ReturnWrapping {
+    fn describe(self) -> String {
+        match self {
+            ReturnWrapping::Wrapped(wrapper) => format!("return type now wrapped in {}", wrapper),
+            ReturnWrapping::Unwrapped(wrapper) => {
+                format!("return type no longer wrapped in {}", wrapper)
+            }
+            ReturnWrapping::Swapped(old, new) => {
+                format!("return type changed from {} to {}", old, new)
+            }
         }
+    }
 
-        Commands::Diff {
-            crate_name,
-            ver1,
-            ver2,
-        } => {
-            diff_docs(crate_name, ver1, ver2).await?;
+    fn to_json(self) -> Value {
+        match self {
+            ReturnWrapping::Wrapped(wrapper) => serde_json::json!({ "gained": wrapper }),
+            ReturnWrapping::Unwrapped(wrapper) => serde_json::json!({ "lost": wrapper }),
+            ReturnWrapping::Swapped(old, new) => {
+                serde_json::json!({ "lost": old, "gained": new })
+            }
         }
+    }
+}
 
-        Commands::Features { crate_name } => {
-            // Find the package in the metadata
-            let package = metadata
-                .packages
-                .iter()
-                .find(|p| p.name == *crate_name)
-                .with_context(|| format!("Crate '{}' not found in dependencies", crate_name))?;
+// A function switching between sync and async (`async fn` or returning a
+// future, see `signature::asyncness`): callers have to start or stop
+// awaiting it, so it's breaking and called out like a return wrapper
+fn async_change(old: &ApiItem, new: &ApiItem) -> Option<&'static str> {
+    if new.kind_family() != "function" {
+        return None;
+    }
+    let was_async = signature::asyncness(&old.signature).is_some();
+    match (was_async, signature::asyncness(&new.signature).is_some()) {
+        (false, true) => Some("now async"),
+        (true, false) => Some("no longer async"),
+        _ => None,
+    }
+}
+
+// What's worth calling out about a modification besides its severity
+fn modification_notes(old: &ApiItem, new: &ApiItem) -> Vec<String> {
+    let mut notes = Vec::new();
+    if let Some(change) = async_change(old, new) {
+        notes.push(change.to_string());
+    }
+    if let Some(wrapping) = return_wrapping(old, new) {
+        notes.push(wrapping.describe());
+    }
+    notes.extend(impl_condition_changes(old, new));
+    notes.extend(supertrait_changes(old, new));
+    notes
+}
+
+// Supertraits a trait gained (implementors must now implement them too) and
+// lost (generic code over the trait may have used them)
+fn supertrait_changes(old: &ApiItem, new: &ApiItem) -> Vec<String> {
+    if new.item_type != "trait" || old.signature == new.signature {
+        return Vec::new();
+    }
+    let supertraits = |signature: &str| -> BTreeSet<String> {
+        bounds::split(signature)
+            .1
+            .into_iter()
+            .filter(|(subject, _)| subject == "Self")
+            .map(|(_, bound)| bound)
+            .collect()
+    };
+    let (old_supertraits, new_supertraits) =
+        (supertraits(&old.signature), supertraits(&new.signature));
+    let listed = |bounds: Vec<&String>| {
+        let bounds: Vec<&str> = bounds.into_iter().map(String::as_str).collect();
+        bounds.join(", ")
+    };
+    let gained: Vec<_> = new_supertraits.difference(&old_supertraits).collect();
+    let lost: Vec<_> = old_supertraits.difference(&new_supertraits).collect();
+    let mut changes = Vec::new();
+    if !gained.is_empty() {
+        changes.push(format!("new supertrait {}", listed(gained)));
+    }
+    if !lost.is_empty() {
+        changes.push(format!("no longer a supertrait: {}", listed(lost)));
+    }
+    changes
+}
 
-            println!("Features for {} (v{}):", package.name, package.version);
+// What an impl's where clause gained and lost (`now requires T: Sync`),
+// since a long clause hides which bound it was
+fn impl_condition_changes(old: &ApiItem, new: &ApiItem) -> Vec<String> {
+    if new.item_type != "impl" || old.signature == new.signature {
+        return Vec::new();
+    }
+    let (_, old_bounds) = bounds::split(&old.signature);
+    let (_, new_bounds) = bounds::split(&new.signature);
+    let listed = |bounds: Vec<&(String, String)>| {
+        let bounds: Vec<String> = bounds
+            .into_iter()
+            .map(|(subject, bound)| format!("{}: {}", subject, bound))
+            .collect();
+        bounds.join(", ")
+    };
+    let gained: Vec<_> = new_bounds.difference(&old_bounds).collect();
+    let lost: Vec<_> = old_bounds.difference(&new_bounds).collect();
+    let mut changes = Vec::new();
+    if !gained.is_empty() {
+        changes.push(format!("now requires {}", listed(gained)));
+    }
+    if !lost.is_empty() {
+        changes.push(format!("no longer requires {}", listed(lost)));
+    }
+    changes
+}
 
-            if package.features.is_empty() {
-                println!("  (No features defined)");
+fn return_wrapping(old: &ApiItem, new: &ApiItem) -> Option<ReturnWrapping> {
+    if new.kind_family() != "function" || old.signature == new.signature {
+        return None;
+    }
+    let old_type = signature::return_type(&old.signature);
+    let new_type = signature::return_type(&new.signature);
+    match (signature::wrapper(old_type), signature::wrapper(new_type)) {
+        (None, Some((wrapper, inner))) if inner == old_type => {
+            Some(ReturnWrapping::Wrapped(wrapper))
+        }
+        (Some((wrapper, inner)), None) if inner == new_type => {
+            Some(ReturnWrapping::Unwrapped(wrapper))
+        }
+        (Some((old_wrapper, old_inner)), Some((new_wrapper, new_inner))) => {
+            if old_wrapper != new_wrapper && old_inner == new_inner {
+                Some(ReturnWrapping::Swapped(old_wrapper, new_wrapper))
+            } else if new_inner == old_type {
+                // `Option<T>` becoming `Result<Option<T>, E>`
+                Some(ReturnWrapping::Wrapped(new_wrapper))
+            } else if old_inner == new_type {
+                Some(ReturnWrapping::Unwrapped(old_wrapper))
             } else {
-                for (feature, deps) in &package.features {
-                    let dep_list = if deps.is_empty() {
-                        "".to_string()
-                    } else {
-                        format!(" -> {}", deps.join(", "))
-                    };
-                    println!("  [ ] {} {}", feature, dep_list);
-                }
+                None
             }
         }
+        _ => None,
     }
+}
 
-    Ok(())
+// Signature changes and lost import paths are breaking, unless the change
+// only loosens bounds. Otherwise only markers changed: gaining
+// `non_exhaustive`/`sealed` or becoming required restricts downstream code
+// (breaking), losing them relaxes it (minor). Requirements added to a sealed
+// trait only affect the crate itself. `must_use` only adds or removes a lint,
+// so either direction is minor.
+fn modified_severity(old: &ApiItem, new: &ApiItem) -> Severity {
+    if signature_breaking(old, new) {
+        return Severity::Breaking;
+    }
+    // `use` statements naming a path that went away stop compiling
+    if !lost_paths(old, new).is_empty() {
+        return Severity::Breaking;
+    }
+    let restricted = ["non_exhaustive", "sealed", "required"]
+        .iter()
+        .filter(|tag| !(**tag == "required" && new.parent_has_tag("sealed")))
+        .any(|tag| !old.has_tag(tag) && new.has_tag(tag));
+    if restricted {
+        Severity::Breaking
+    } else {
+        Severity::Minor
+    }
+}
+
+// Each character a name has beyond the query costs a point, up to
+// MAX_LENGTH_PENALTY. Skim scores differ by more than that between good and
+// poor matches, so the penalty mostly reorders near-ties.
+const MAX_LENGTH_PENALTY: i64 = 5;
+
+fn length_penalty(name: &str, query: &str) -> i64 {
+    let extra = name.chars().count().saturating_sub(query.chars().count());
+    (extra as i64).min(MAX_LENGTH_PENALTY)
+}
+
+// How much a fuzzy match of each field counts toward an item's score. The
+// name's score is used as is by default; the module path and docs add a
+// fraction of theirs, enough to order items whose names match about equally
+// well but not to outrank a better name.
+#[derive(Clone, Copy)]
+struct Weights {
+    name: f64,
+    path: f64,
+    docs: f64,
+}
+
+impl Default for Weights {
+    fn default() -> Self {
+        Weights {
+            name: 1.0,
+            path: 0.2,
+            docs: 0.1,
+        }
+    }
+}
+
+// Parse a field weight: a number from 0 up
+fn parse_weight(text: &str) -> Result<f64, String> {
+    match text.trim().parse::<f64>() {
+        Ok(weight) if weight.is_finite() && weight >= 0.0 => Ok(weight),
+        _ => Err(format!("'{}' is not a weight (a number from 0 up)", text)),
+    }
+}
+
+// How a query is matched against item names
+enum NameMatcher {
+    Fuzzy {
+        matcher: Box<SkimMatcherV2>,
+        length_penalty: bool,
+        weights: Weights,
+    },
+    // A shell-style pattern (`Async*Stream`); every match scores the same
+    Glob(globset::GlobMatcher),
+}
+
+impl NameMatcher {
+    fn fuzzy(length_penalty: bool, weights: Weights) -> Self {
+        NameMatcher::Fuzzy {
+            matcher: Box::default(),
+            length_penalty,
+            weights,
+        }
+    }
+
+    fn glob(pattern: &str) -> Result<Self> {
+        let glob = globset::Glob::new(pattern)
+            .with_context(|| format!("Invalid glob pattern '{}'", pattern))?;
+        Ok(NameMatcher::Glob(glob.compile_matcher()))
+    }
+
+    fn score(&self, name: &str, query: &str) -> Option<i64> {
+        match self {
+            NameMatcher::Fuzzy {
+                matcher,
+                length_penalty,
+                ..
+            } => {
+                let score = matcher.fuzzy_match(name, query)?;
+                if *length_penalty {
+                    Some(score - self::length_penalty(name, query))
+                } else {
+                    Some(score)
+                }
+            }
+            NameMatcher::Glob(glob) => glob.is_match(name).then_some(0),
+        }
+    }
+
+    // Whether matching the module path can find an item its name doesn't
+    fn matches_paths(&self) -> bool {
+        matches!(self, NameMatcher::Fuzzy { weights, .. } if weights.path > 0.0)
+    }
+
+    // An item's weighted score from its name, the path it's under and its
+    // docs. The item matches when its name or its path does; the docs only
+    // add to the score, since a query matches some part of most long texts.
+    // Globs match the name alone.
+    fn score_item(
+        &self,
+        name: &str,
+        path: Option<&str>,
+        docs: Option<&str>,
+        query: &str,
+    ) -> Option<i64> {
+        let NameMatcher::Fuzzy {
+            matcher, weights, ..
+        } = self
+        else {
+            return self.score(name, query);
+        };
+        let field = |weight: f64, text: Option<&str>| {
+            let text = text.filter(|_| weight > 0.0)?;
+            Some(weight * matcher.fuzzy_match(text, query)? as f64)
+        };
+        let name_score = self
+            .score(name, query)
+            .map(|score| weights.name * score as f64);
+        let path_score = field(weights.path, path);
+        if name_score.is_none() && path_score.is_none() {
+            return None;
+        }
+        let docs_score = field(weights.docs, docs);
+        let total = [name_score, path_score, docs_score]
+            .into_iter()
+            .flatten()
+            .sum::<f64>();
+        Some(total.round() as i64)
+    }
+}
+
+fn search_json(
+    json_data: &Value,
+    crate_name: &str,
+    query: &str,
+    matcher: &NameMatcher,
+    resolver: &mut reexports::Resolver,
+) -> Result<Vec<SearchResult>> {
+    let mut results = Vec::new();
+
+    // Get the index object from the JSON
+    let index = json_data
+        .get("index")
+        .and_then(|v| v.as_object())
+        .context("Missing or invalid 'index' field in JSON")?;
+
+    let reachable = items::reachable_ids(json_data);
+    let hidden = items::hidden_items(json_data);
+    let impl_owners = impl_member_owners(json_data);
+    let method_labels = method_labels(json_data);
+    // Where re-exported items can be imported from, found on the first match
+    let mut public_paths = None;
+
+    // Search through all items in the index
+    for (id, item) in index {
+        if items::item_kind(item) == Some("use") {
+            if let Some(mut result) = search_reexport(
+                json_data,
+                crate_name,
+                query,
+                matcher,
+                (id, item),
+                &mut public_paths,
+                resolver,
+            ) {
+                result.hidden = hidden.contains(id);
+                results.push(result);
+            }
+            continue;
+        }
+
+        // Get the item name
+        let name = match item.get("name").and_then(|v| v.as_str()) {
+            Some(n) => n,
+            None => continue, // Skip unnamed items
+        };
+        // The name, or a `#[doc(alias)]` that matches better; the name wins ties
+        let aliases = items::doc_aliases(item);
+        let best = aliases
+            .iter()
+            .map(String::as_str)
+            .chain([name])
+            .filter_map(|candidate| Some((matcher.score(candidate, query)?, candidate)))
+            .max_by_key(|(score, _)| *score);
+        // Only items whose name matches need their path, unless the path
+        // can match by itself
+        if best.is_none() && !matcher.matches_paths() {
+            continue;
+        }
+        let matched_name = best.map_or(name, |(_, candidate)| candidate);
+
+        // Impl members show which impl they come from
+        let owner = impl_owners.get(id).map(|owner| owner.join("::"));
+        let full_path = match (&owner, items::canonical_path(json_data, id)) {
+            (Some(owner), _) => format!("{}::{}", owner, name),
+            (None, Some(path)) => path.join("::"),
+            (None, None) => name.to_string(),
+        };
+        // What the item is under, its name left off
+        let parent = full_path
+            .strip_suffix(name)
+            .and_then(|parent| parent.strip_suffix("::"));
+        let docs = item.get("docs").and_then(|v| v.as_str());
+
+        if let Some(score) = matcher.score_item(matched_name, parent, docs, query) {
+            // Get the item type from the "inner" field
+            let item_type = match method_labels.get(id) {
+                Some(label) => label.to_string(),
+                None => api_kind(item).unwrap_or_else(|| "unknown".to_string()),
+            };
+            let description = docs.map(|s| s.to_string());
+
+            results.push(SearchResult {
+                id: id.clone(),
+                name: name.to_string(),
+                crate_name: crate_name.to_string(),
+                item_type,
+                path: owner,
+                full_path,
+                description,
+                tags: items::compat_tags(json_data, item, &reachable),
+                deprecated: items::is_deprecated(item),
+                hidden: hidden.contains(id),
+                reexport_of: None,
+                url: None,
+                raw: None,
+                target: None,
+                alias: (matched_name != name).then(|| matched_name.to_string()),
+                matched: vec![query.to_string()],
+                asyncness: items::item_inner(item)
+                    .filter(|_| items::item_kind(item) == Some("function"))
+                    .and_then(signature::function_asyncness),
+                score,
+            });
+        }
+    }
+
+    Ok(results)
+}
+
+// A `use` of an item from another crate, matched by the name it's imported
+// under and described with the target's docs. Re-exports within the crate
+// are skipped: the item they point at is matched itself.
+fn search_reexport(
+    json_data: &Value,
+    crate_name: &str,
+    query: &str,
+    matcher: &NameMatcher,
+    (id, item): (&String, &Value),
+    public_paths: &mut Option<HashMap<String, BTreeSet<Vec<String>>>>,
+    resolver: &mut reexports::Resolver,
+) -> Option<SearchResult> {
+    let inner = items::item_inner(item)?;
+    if inner.get("is_glob").and_then(|v| v.as_bool()) == Some(true) {
+        return None;
+    }
+    let name = inner.get("name").and_then(|v| v.as_str())?;
+    let score = matcher.score_item(name, None, None, query)?;
+    let target_id = inner.get("id").and_then(items::id_key)?;
+    let target = resolver.resolve(json_data, &target_id)?;
+
+    // Shown by the path it's imported by here, not where it's defined
+    let local_path = public_paths
+        .get_or_insert_with(|| items::public_paths(json_data))
+        .get(&target_id)
+        .and_then(|paths| paths.iter().next())
+        .map(|path| path.join("::"));
+    let description = match &target.missing {
+        Some(reason) => Some(format!("Re-export of {} ({})", target.path, reason)),
+        None => target.docs,
+    };
+
+    Some(SearchResult {
+        id: id.clone(),
+        name: name.to_string(),
+        crate_name: crate_name.to_string(),
+        item_type: target.kind.unwrap_or_else(|| "use".to_string()),
+        full_path: local_path.clone().unwrap_or_else(|| name.to_string()),
+        path: local_path,
+        description,
+        tags: Vec::new(),
+        deprecated: false,
+        hidden: false,
+        reexport_of: Some(target.path),
+        url: None,
+        raw: None,
+        target: None,
+        alias: None,
+        matched: vec![query.to_string()],
+        asyncness: None,
+        score,
+    })
+}
+
+// The path segment an impl's members live under: the self type with its
+// generic arguments (`Wrapper<T>`), and for trait impls the trait too
+// (`<Wrapper<u8> as From<u8>>`), so members of different impls of the same
+// type never share a path
+fn impl_segment(impl_inner: &Value) -> Option<String> {
+    let self_type = impl_inner.get("for")?;
+    let resolved = self_type.get("resolved_path")?;
+    let last_segment = |path: &Value| -> Option<String> {
+        let name = path
+            .get("path")
+            .or_else(|| path.get("name"))
+            .and_then(|v| v.as_str())?;
+        let name = name.rsplit("::").next().unwrap_or(name);
+        Some(format!("{}{}", name, format_generic_args(path.get("args"))))
+    };
+    let owner = last_segment(resolved)?;
+    // `impl !Send for Foo`
+    let negative = impl_inner.get("is_negative").and_then(|v| v.as_bool()) == Some(true);
+    match impl_inner.get("trait").filter(|t| !t.is_null()) {
+        Some(trait_path) => Some(format!(
+            "<{} as {}{}>",
+            owner,
+            if negative { "!" } else { "" },
+            last_segment(trait_path)?
+        )),
+        None => Some(owner),
+    }
+}
+
+// Whether synthesized auto trait impls (`impl<T> Send for Foo<T> where T:
+// Send`) are extracted. Rustdoc works out their bounds itself, and how it
+// writes them differs between releases, so they're left out unless `diff
+// --auto-impls` asks for them.
+static AUTO_IMPLS: AtomicBool = AtomicBool::new(false);
+
+// `auto` for a synthesized impl's bound: just the trait's name, since
+// rustdoc writes `Send` in one release and `core::marker::Send` in another
+fn auto_bound(bound: &str) -> &str {
+    let end = bound.find('<').unwrap_or(bound.len());
+    match bound[..end].rfind("::") {
+        Some(i) => &bound[i + 2..],
+        None => bound,
+    }
+}
+
+// An impl's conditions: its parameters bare, then every bound in one where
+// clause, grouped by what they bound and sorted (`<T, U> where T: Clone +
+// Send, U: Sync`). Bounds on a parameter and in the where clause mean the
+// same thing on an impl, so rewriting one as the other isn't a change.
+fn impl_signature(inner: &Value, auto: bool) -> String {
+    let params = format_generic_params(inner.get("generics"));
+    let declared = format!("{}{}", params, format_where_clause(inner.get("generics")));
+    let (_, bounds) = bounds::split(&declared);
+    let mut grouped: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for (subject, bound) in &bounds {
+        let bound = if auto { auto_bound(bound) } else { bound };
+        grouped.entry(subject).or_default().insert(bound);
+    }
+    let bare: Vec<&str> = match params.strip_prefix('<').and_then(|p| p.strip_suffix('>')) {
+        Some(params) => bounds::split_top_level(params)
+            .into_iter()
+            .map(|param| {
+                // Const parameters keep their type
+                match bounds::find_top_level(param, ": ") {
+                    Some(colon) if !param.starts_with("const ") => &param[..colon],
+                    _ => param,
+                }
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+    let mut signature = if bare.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", bare.join(", "))
+    };
+    if !grouped.is_empty() {
+        let predicates: Vec<String> = grouped
+            .iter()
+            .map(|(subject, bounds)| {
+                let bounds: Vec<&str> = bounds.iter().copied().collect();
+                format!("{}: {}", subject, bounds.join(" + "))
+            })
+            .collect();
+        signature.push_str(&format!(" where {}", predicates.join(", ")));
+    }
+    signature.trim_start().to_string()
+}
+
+// A trait impl as an item of its own, named like its members' owner
+// (`<Foo<T> as Send>`) under the self type's module, so that changing its
+// conditions shows up as a modification. Blanket impls belong to the trait
+// rather than the type, and inherent impls have no conditions apart from
+// their members', so neither is one.
+fn impl_api_item(
+    json_data: &Value,
+    id: &str,
+    item: &Value,
+    hidden_ids: &HashSet<String>,
+) -> Option<ApiItem> {
+    let inner = items::item_inner(item)?;
+    inner.get("trait").filter(|t| !t.is_null())?;
+    if inner.get("blanket_impl").is_some_and(|v| !v.is_null()) {
+        return None;
+    }
+    let auto = inner.get("is_synthetic").and_then(|v| v.as_bool()) == Some(true);
+    if auto && !AUTO_IMPLS.load(Ordering::Relaxed) {
+        return None;
+    }
+    let self_id = inner
+        .get("for")?
+        .get("resolved_path")?
+        .get("id")
+        .and_then(items::id_key)?;
+    let mut path = items::canonical_path(json_data, &self_id)?;
+    path.pop();
+    let hidden = hidden_ids.contains(id) || hidden_ids.contains(&self_id);
+    let stability = [
+        ("deprecated", items::is_deprecated(item)),
+        ("doc(hidden)", hidden),
+        ("unstable", items::is_unstable(item)),
+    ]
+    .into_iter()
+    .filter_map(|(marker, applies)| applies.then_some(marker))
+    .collect();
+    Some(ApiItem {
+        id: id.to_string(),
+        name: impl_segment(inner)?,
+        item_type: "impl".to_string(),
+        path,
+        signature: impl_signature(inner, auto),
+        tags: if auto {
+            vec!["auto".to_string()]
+        } else {
+            Vec::new()
+        },
+        parent_tags: Vec::new(),
+        parent_kind: None,
+        reexports: Vec::new(),
+        docs: items::item_docs(item).map(|docs| docs.trim().to_string()),
+        // Rustdoc doesn't ask impls for docs any more than their members
+        inherits_docs: true,
+        location: items::item_location(item),
+        stability,
+        parent_added: false,
+    })
+}
+
+// Full paths of the types impl members belong to, keyed by member id:
+// the self type's module path followed by its impl segment. Blanket and
+// synthetic impls are left out, since their members belong to no one type.
+fn impl_member_owners(json_data: &Value) -> HashMap<String, Vec<String>> {
+    let mut owners = HashMap::new();
+    let Some(index) = json_data.get("index").and_then(|v| v.as_object()) else {
+        return owners;
+    };
+    for item in index.values() {
+        if items::item_kind(item) != Some("impl") {
+            continue;
+        }
+        let Some(inner) = items::item_inner(item) else {
+            continue;
+        };
+        let shared = inner.get("blanket_impl").is_some_and(|v| !v.is_null())
+            || inner.get("is_synthetic").and_then(|v| v.as_bool()) == Some(true);
+        if shared {
+            continue;
+        }
+        let Some(mut path) = inner
+            .get("for")
+            .and_then(|ty| ty.get("resolved_path"))
+            .and_then(|path| path.get("id"))
+            .and_then(items::id_key)
+            .and_then(|id| items::canonical_path(json_data, &id))
+        else {
+            continue;
+        };
+        let Some(segment) = impl_segment(inner) else {
+            continue;
+        };
+        path.pop();
+        path.push(segment);
+        for member in items::impl_member_ids(item) {
+            owners.insert(member, path.clone());
+        }
+    }
+    owners
+}
+
+// Extract API items from rustdoc JSON with signature details
+fn extract_api_items(json_data: &Value) -> Result<Vec<ApiItem>> {
+    let mut phase = timing::phase("extract items");
+    let mut items = Vec::new();
+
+    let index = json_data
+        .get("index")
+        .and_then(|v| v.as_object())
+        .context("Missing or invalid 'index' field in JSON")?;
+
+    let reachable = items::reachable_ids(json_data);
+    let impl_owners = impl_member_owners(json_data);
+    let method_labels = method_labels(json_data);
+    let public_paths = items::public_paths(json_data);
+    let hidden_ids = items::hidden_items(json_data);
+
+    // Build a map of item IDs to their parent paths. Items with their own page
+    // have a canonical path in the "paths" table; members (variants, fields,
+    // trait items, impl items) live under their parent instead.
+    let mut id_to_path: HashMap<String, Vec<String>> = HashMap::new();
+    let mut parent_of: HashMap<String, String> = HashMap::new();
+    let mut parent_kind: HashMap<String, &str> = HashMap::new();
+    let mut trait_impl_members: HashSet<String> = HashSet::new();
+
+    // First pass: canonical paths and member -> parent links
+    for (id, item) in index {
+        if let Some(mut path) = items::canonical_path(json_data, id) {
+            path.pop();
+            id_to_path.insert(id.clone(), path);
+        }
+
+        let Some(inner) = items::item_inner(item) else {
+            continue;
+        };
+        let (parent, kind, members) = match items::item_kind(item) {
+            Some(kind @ ("enum" | "struct" | "union" | "trait" | "variant")) => {
+                (id.clone(), kind, items::own_member_ids(inner))
+            }
+            Some("impl") => {
+                // Blanket and synthetic impls share their members across every
+                // type they apply to, so they can't attribute them to one type
+                let shared = inner.get("blanket_impl").is_some_and(|v| !v.is_null())
+                    || inner.get("is_synthetic").and_then(|v| v.as_bool()) == Some(true);
+                if shared {
+                    trait_impl_members.extend(items::impl_member_ids(item));
+                    continue;
+                }
+
+                // Impl members belong to the implementing type
+                let Some(self_type) = inner
+                    .get("for")
+                    .and_then(|ty| ty.get("resolved_path"))
+                    .and_then(|path| path.get("id"))
+                    .and_then(items::id_key)
+                else {
+                    continue;
+                };
+                let members = items::impl_member_ids(item);
+                let kind = if items::is_inherent_impl(item) {
+                    "impl"
+                } else {
+                    trait_impl_members.extend(members.iter().cloned());
+                    "trait_impl"
+                };
+                (self_type, kind, members)
+            }
+            _ => continue,
+        };
+        for member in members {
+            parent_kind.insert(member.clone(), kind);
+            parent_of.insert(member, parent.clone());
+        }
+    }
+
+    // Members take their parent's full path, following nested members
+    // (a struct variant's fields) up to an item with a canonical path
+    fn full_path_of(
+        json_data: &Value,
+        id: &str,
+        id_to_path: &HashMap<String, Vec<String>>,
+        parent_of: &HashMap<String, String>,
+    ) -> Option<Vec<String>> {
+        let name = items::lookup(json_data, id).and_then(items::item_name)?;
+        let mut path = match id_to_path.get(id) {
+            Some(path) => path.clone(),
+            None => full_path_of(json_data, parent_of.get(id)?, id_to_path, parent_of)?,
+        };
+        path.push(name.to_string());
+        Some(path)
+    }
+
+    // Second pass: extract items with their signatures
+    for (id, item) in index {
+        if items::item_kind(item) == Some("impl") {
+            items.extend(impl_api_item(json_data, id, item, &hidden_ids));
+            continue;
+        }
+        let name = match item.get("name").and_then(|v| v.as_str()) {
+            Some(n) => n.to_string(),
+            None => continue,
+        };
+
+        let Some(inner_key) = items::item_kind(item) else {
+            continue;
+        };
+        let item_type = match method_labels.get(id) {
+            Some(label) => label.to_string(),
+            None => api_kind(item).unwrap_or_default(),
+        };
+
+        // Re-exports aren't items of their own
+        if matches!(inner_key.to_ascii_lowercase().as_str(), "import" | "use") {
+            continue;
+        }
+
+        let parent = parent_of.get(id).and_then(|parent_id| {
+            items::lookup(json_data, parent_id).map(|parent| (parent_id, parent))
+        });
+
+        let path = match (id_to_path.get(id), parent) {
+            (Some(path), _) => path.clone(),
+            (None, _) if impl_owners.contains_key(id) => impl_owners[id].clone(),
+            (None, Some((parent_id, _))) => {
+                full_path_of(json_data, parent_id, &id_to_path, &parent_of).unwrap_or_default()
+            }
+            (None, None) => Vec::new(),
+        };
+
+        // Extract signature based on item type
+        let signature = extract_signature(inner_key, items::item_inner(item));
+
+        let mut tags = items::compat_tags(json_data, item, &reachable);
+        let mut parent_tags = parent
+            .map(|(_, parent)| items::compat_tags(json_data, parent, &reachable))
+            .unwrap_or_default();
+        // Downstream code can't build or destructure a struct with private
+        // fields, so adding fields to one is safe
+        if parent.is_some_and(|(_, parent)| items::has_private_fields(parent)) {
+            parent_tags.push("private_fields".to_string());
+        }
+        if parent.is_some_and(|(_, parent)| items::item_kind(parent) == Some("trait"))
+            && items::is_required_trait_item(item)
+        {
+            tags.push("required".to_string());
+        }
+
+        // Re-exports only name items with their own page
+        let canonical = [path.as_slice(), std::slice::from_ref(&name)].concat();
+        let mut reexports: Vec<String> = public_paths
+            .get(id)
+            .into_iter()
+            .flatten()
+            .filter(|public| id_to_path.contains_key(id) && **public != canonical)
+            .map(|public| public.join("::"))
+            .collect();
+        reexports.sort();
+
+        let stability = [
+            ("deprecated", items::is_deprecated(item)),
+            ("doc(hidden)", hidden_ids.contains(id)),
+            ("unstable", items::is_unstable(item)),
+        ]
+        .into_iter()
+        .filter_map(|(marker, applies)| applies.then_some(marker))
+        .collect();
+
+        items.push(ApiItem {
+            id: id.clone(),
+            name,
+            item_type,
+            path,
+            signature,
+            tags,
+            parent_tags,
+            parent_kind: parent_kind.get(id).map(|kind| kind.to_string()),
+            reexports,
+            docs: items::item_docs(item).map(|docs| docs.trim().to_string()),
+            inherits_docs: trait_impl_members.contains(id),
+            location: items::item_location(item),
+            stability,
+            parent_added: false,
+        });
+    }
+
+    phase.items(items.len());
+    Ok(items)
+}
+
+// `method` for functions in impls and `trait method` for those a trait
+// declares, keyed by id; rustdoc calls them all functions
+fn method_labels(json_data: &Value) -> HashMap<String, &'static str> {
+    let mut labels = HashMap::new();
+    let Some(index) = json_data.get("index").and_then(|v| v.as_object()) else {
+        return labels;
+    };
+    for item in index.values() {
+        let (label, members) = match items::item_kind(item) {
+            Some("impl") => ("method", items::impl_member_ids(item)),
+            Some("trait") => (
+                "trait method",
+                items::item_inner(item)
+                    .map(items::own_member_ids)
+                    .unwrap_or_default(),
+            ),
+            _ => continue,
+        };
+        for member in members {
+            let is_function = items::lookup(json_data, &member)
+                .and_then(items::item_kind)
+                .is_some_and(|kind| kind == "function");
+            if is_function {
+                labels.insert(member, label);
+            }
+        }
+    }
+    labels
+}
+
+// Item kinds in rustdoc JSON (`opaque_ty` is from older formats). Others
+// are still listed, under their raw kind.
+const KNOWN_KINDS: &[&str] = &[
+    "module",
+    "extern_crate",
+    "use",
+    "import",
+    "union",
+    "struct",
+    "struct_field",
+    "enum",
+    "variant",
+    "function",
+    "trait",
+    "trait_alias",
+    "impl",
+    "type_alias",
+    "opaque_ty",
+    "constant",
+    "static",
+    "extern_type",
+    "macro",
+    "proc_macro",
+    "primitive",
+    "assoc_const",
+    "assoc_type",
+];
+
+// Log each kind zdoc doesn't know once, so new rustdoc additions get
+// noticed. True the first time a kind is seen.
+fn report_unknown_kind(kind: &str) -> bool {
+    static REPORTED: std::sync::Mutex<BTreeSet<String>> = std::sync::Mutex::new(BTreeSet::new());
+    let mut reported = REPORTED.lock().unwrap_or_else(|err| err.into_inner());
+    let first = reported.insert(kind.to_string());
+    if first {
+        debug!(
+            "Unrecognized rustdoc item kind `{}`; listing it as is",
+            kind
+        );
+    }
+    first
+}
+
+// The kind an item is listed as: its rustdoc kind, except that functions
+// with a foreign ABI and statics from `extern` blocks are `extern fn` and
+// `extern static`
+fn api_kind(item: &Value) -> Option<String> {
+    let kind = items::item_kind(item)?;
+    if !KNOWN_KINDS.contains(&kind) {
+        report_unknown_kind(kind);
+    }
+    let inner = items::item_inner(item);
+    let foreign = match kind {
+        "function" => inner
+            .and_then(|inner| inner.get("header"))
+            .and_then(signature::abi)
+            .is_some(),
+        "static" => {
+            inner
+                .and_then(|inner| inner.get("is_unsafe"))
+                .and_then(|v| v.as_bool())
+                == Some(true)
+        }
+        "extern_type" => return Some("extern type".to_string()),
+        _ => false,
+    };
+    Some(if foreign {
+        format!("extern {}", items::kind_keyword(kind))
+    } else {
+        kind.to_string()
+    })
+}
+
+// Extract signature details for different item types
+fn extract_signature(item_type: &str, inner_data: Option<&Value>) -> String {
+    let inner = match inner_data {
+        Some(d) => d,
+        None => return String::new(),
+    };
+
+    // Generic parameters lead the signature and the where clause follows
+    // the parameters, so changing a bound or a default shows up as a
+    // modification
+    let generics = format_generic_params(inner.get("generics"));
+    let where_clause = format_where_clause(inner.get("generics"));
+
+    // Kinds are lowercase in current rustdoc JSON, capitalized in older formats
+    let body = match item_type.to_ascii_lowercase().as_str() {
+        "function" | "method" => {
+            // Extract function signature: parameters and return type
+            let mut sig_parts = Vec::new();
+
+            // Get parameters (`sig` in current formats, `decl` in older ones)
+            if let Some(decl) = inner.get("sig").or_else(|| inner.get("decl")) {
+                if let Some(inputs) = decl.get("inputs").and_then(|v| v.as_array()) {
+                    let mut params: Vec<String> = inputs
+                        .iter()
+                        .filter_map(|input| {
+                            let name = input.get(0).and_then(|v| v.as_str())?;
+                            let ty = input.get(1)?;
+                            // `&self` rather than `self: &Self`
+                            Some(if name == "self" {
+                                signature::receiver(ty)
+                            } else {
+                                format!("{}: {}", name, format_type(ty))
+                            })
+                        })
+                        .collect();
+                    // `printf(format: *const c_char, ...)`
+                    if decl.get("is_c_variadic").and_then(|v| v.as_bool()) == Some(true) {
+                        params.push("...".to_string());
+                    }
+                    sig_parts.push(format!("({})", params.join(", ")));
+                }
+
+                // Get return type
+                if let Some(output) = decl.get("output")
+                    && !output.is_null()
+                {
+                    let ret_type = format_type(output);
+                    if ret_type != "()" {
+                        sig_parts.push(format!("-> {}", ret_type));
+                    }
+                }
+            }
+
+            // Qualifiers come first, as in Rust:
+            // `const unsafe extern "C" <T>(x: T) -> i32`
+            return format!(
+                "{}{}{}{}",
+                signature::qualifiers(inner.get("header")),
+                generics,
+                sig_parts.join(" "),
+                where_clause
+            );
+        }
+
+        // `= Clone + Send`
+        "trait_alias" => inner
+            .get("params")
+            .and_then(|v| v.as_array())
+            .map(|bounds| format!("= {}", format_bounds(bounds)))
+            .unwrap_or_default(),
+
+        // `= Vec<u8>`, or `= impl Iterator<Item = u8>` for an opaque alias
+        "type_alias" => inner
+            .get("type")
+            .map(|ty| format!("= {}", format_type(ty)))
+            .unwrap_or_default(),
+
+        // Older formats give `type Foo = impl Trait` its own kind
+        "opaque_ty" => inner
+            .get("bounds")
+            .and_then(|v| v.as_array())
+            .map(|bounds| format!("= impl {}", format_bounds(bounds)))
+            .unwrap_or_default(),
+
+        // Unions always have named fields
+        "union" => match items::field_ids(inner) {
+            Some(fields) => format!("{{ {} fields }}", fields.len()),
+            None => String::new(),
+        },
+
+        // A field's signature is its type
+        "struct_field" => format_type(inner),
+
+        // `mut u32` for a `static mut`
+        "static" => {
+            let ty = inner.get("type").map(format_type).unwrap_or_default();
+            if inner.get("is_mutable").and_then(|v| v.as_bool()) == Some(true) {
+                format!("mut {}", ty)
+            } else {
+                ty
+            }
+        }
+
+        "struct" => {
+            // Extract struct fields. Older formats use a string `kind` with a
+            // sibling `fields` list; current ones nest the fields in the kind.
+            let kind = inner.get("kind");
+            let kind_str = kind.and_then(|v| v.as_str()).or_else(|| {
+                kind.and_then(|v| v.as_object())
+                    .and_then(|obj| obj.keys().next())
+                    .map(String::as_str)
+            });
+            let fields = items::field_ids(inner).map(|ids| ids.len());
+            match (kind_str, fields) {
+                (Some("plain"), Some(count)) => format!("{{ {} fields }}", count),
+                (Some("tuple"), Some(count)) => format!("({} fields)", count),
+                _ => String::new(),
+            }
+        }
+
+        // Enum variants
+        "enum" => inner
+            .get("variants")
+            .and_then(|v| v.as_array())
+            .map(|variants| format!("{{ {} variants }}", variants.len()))
+            .unwrap_or_default(),
+
+        // Supertraits, then the trait's items (methods, associated types and
+        // consts): `<T>: Display + Send where T: Clone { 3 items }`. `Sized`
+        // isn't implied on a trait's `Self`, so it stays.
+        "trait" => {
+            let supertraits = inner
+                .get("bounds")
+                .and_then(|v| v.as_array())
+                .filter(|bounds| !bounds.is_empty())
+                .map(|bounds| format!(": {}", format_bounds(bounds)))
+                .unwrap_or_default();
+            let items = inner
+                .get("items")
+                .and_then(|v| v.as_array())
+                .map(|items| format!(" {{ {} items }}", items.len()))
+                .unwrap_or_default();
+            return format!("{}{}{}{}", generics, supertraits, where_clause, items)
+                .trim_start()
+                .to_string();
+        }
+
+        "macro" | "proc_macro" => return macros::signature(item_type, inner),
+
+        // Written in declaration order, which puts the where clause of a
+        // generic associated type before its default:
+        // `<'a>: Iterator<Item = &'a T> where Self: 'a = Iter<'a, T>`. In an
+        // impl the default slot holds the assignment.
+        "assoc_type" => {
+            let bounds = inner
+                .get("bounds")
+                .and_then(|v| v.as_array())
+                .filter(|bounds| !bounds.is_empty())
+                .map(|bounds| format!(": {}", format_bounds(bounds)))
+                .unwrap_or_default();
+            // Current formats call the default `type`, older ones `default`
+            let default = inner
+                .get("type")
+                .or_else(|| inner.get("default"))
+                .filter(|v| !v.is_null())
+                .map(|ty| format!(" = {}", format_type(ty)))
+                .unwrap_or_default();
+            return format!("{}{}{}{}", generics, bounds, where_clause, default)
+                .trim_start()
+                .to_string();
+        }
+
+        _ => String::new(),
+    };
+    // Where Rust puts the where clause: `<T>(x: T) where T: Send` and
+    // `<T>(2 fields) where T: Send`, but `<T> where T: Send { 2 fields }`
+    // and `<T> where T: Send = Vec<T>`
+    if body.starts_with(['{', '=']) {
+        format!("{}{} {}", generics, where_clause, body)
+            .trim_start()
+            .to_string()
+    } else {
+        format!("{}{}{}", generics, body, where_clause)
+    }
+}
+
+// A lifetime as signatures show it, or `None` for the elided `'_`. Some
+// rustdoc releases write elided lifetimes out (`&'_ str`, `Token<'_>`) and
+// others leave them off, so dropping them keeps the same declaration
+// comparing equal across releases; named lifetimes and `'static` are kept.
+fn shown_lifetime(lifetime: Option<&Value>) -> Option<&str> {
+    lifetime
+        .and_then(|v| v.as_str())
+        .filter(|lifetime| *lifetime != "'_")
+}
+
+thread_local! {
+    static KEEP_ELIDED_ARGS: Cell<bool> = const { Cell::new(false) };
+}
+
+// Run `render` with `'_` kept among generic arguments, for declarations
+// meant to be pasted: a GAT's `Self::Loan<'_>` doesn't compile as
+// `Self::Loan`. `&'_ T` and `&T` are the same, so references still drop it.
+fn keeping_elided_args<T>(render: impl FnOnce() -> T) -> T {
+    let was = KEEP_ELIDED_ARGS.with(|keep| keep.replace(true));
+    let result = render();
+    KEEP_ELIDED_ARGS.with(|keep| keep.set(was));
+    result
+}
+
+// Render a rustdoc JSON type node as Rust source, the way rustdoc shows it
+fn format_type(type_data: &Value) -> String {
+    if let Some(target) = type_data.get("resolved_path").and_then(aliases::resolve) {
+        return format_type(&target);
+    }
+    if let Some(resolved_path) = type_data.get("resolved_path")
+        && let Some(name) = resolved_path
+            .get("path")
+            .or_else(|| resolved_path.get("name"))
+            .and_then(|v| v.as_str())
+    {
+        return format!("{}{}", name, format_generic_args(resolved_path.get("args")));
+    }
+
+    if let Some(generic) = type_data.get("generic").and_then(|v| v.as_str()) {
+        return generic.to_string();
+    }
+
+    if let Some(primitive) = type_data.get("primitive").and_then(|v| v.as_str()) {
+        return match primitive {
+            "never" => "!".to_string(),
+            _ => primitive.to_string(),
+        };
+    }
+
+    // A `_` placeholder
+    if type_data.as_str() == Some("infer") {
+        return "_".to_string();
+    }
+
+    // `<T as Iterator>::Item`, or `Self::Output` where that's unambiguous
+    if let Some(qualified) = type_data.get("qualified_path") {
+        let name = qualified
+            .get("name")
+            .and_then(|v| v.as_str())
+            .unwrap_or("?");
+        let name = format!("{}{}", name, format_generic_args(qualified.get("args")));
+        let self_type = qualified
+            .get("self_type")
+            .map(format_type)
+            .unwrap_or_else(|| "?".to_string());
+        // Written as the `F::Output` shorthand, the trait's path is empty
+        let trait_path = qualified
+            .get("trait")
+            .filter(|t| !t.is_null() && t.get("path").and_then(|v| v.as_str()) != Some(""))
+            .map(|path| format_type(&serde_json::json!({ "resolved_path": path })));
+        return match trait_path {
+            Some(trait_path) if self_type != "Self" => {
+                format!("<{} as {}>::{}", self_type, trait_path, name)
+            }
+            _ => format!("{}::{}", self_type, name),
+        };
+    }
+
+    // `impl Trait` in argument or return position
+    if let Some(bounds) = type_data.get("impl_trait").and_then(|v| v.as_array()) {
+        return format!("impl {}", format_bounds(bounds));
+    }
+
+    if let Some(borrowed_ref) = type_data.get("borrowed_ref") {
+        let mutable = borrowed_ref
+            .get("is_mutable")
+            .or_else(|| borrowed_ref.get("mutable"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let inner_type = borrowed_ref
+            .get("type")
+            .map(format_pointee)
+            .unwrap_or_else(|| "?".to_string());
+        // `&'a str` when the lifetime is named, so changing which lifetime
+        // a reference borrows for shows in diffs
+        let lifetime = shown_lifetime(borrowed_ref.get("lifetime"))
+            .map(|lifetime| format!("{} ", lifetime))
+            .unwrap_or_default();
+        return if mutable {
+            format!("&{}mut {}", lifetime, inner_type)
+        } else {
+            format!("&{}{}", lifetime, inner_type)
+        };
+    }
+
+    // `()`, `(A,)` and `(A, B)`
+    if let Some(elements) = type_data.get("tuple").and_then(|v| v.as_array()) {
+        let elements: Vec<String> = elements.iter().map(format_type).collect();
+        return match elements.as_slice() {
+            [single] => format!("({},)", single),
+            _ => format!("({})", elements.join(", ")),
+        };
+    }
+
+    if let Some(element) = type_data.get("slice") {
+        return format!("[{}]", format_type(element));
+    }
+
+    if let Some(array) = type_data.get("array") {
+        let element = array
+            .get("type")
+            .map(format_type)
+            .unwrap_or_else(|| "?".to_string());
+        let len = array.get("len").and_then(|v| v.as_str()).unwrap_or("_");
+        return format!("[{}; {}]", element, len);
+    }
+
+    if let Some(pointer) = type_data.get("raw_pointer") {
+        let mutable = pointer
+            .get("is_mutable")
+            .or_else(|| pointer.get("mutable"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let pointee = pointer
+            .get("type")
+            .map(format_pointee)
+            .unwrap_or_else(|| "?".to_string());
+        return format!("*{} {}", if mutable { "mut" } else { "const" }, pointee);
+    }
+
+    // `dyn Trait + Send + 'a` trait objects
+    if let Some(dyn_trait) = type_data.get("dyn_trait") {
+        let mut bounds: Vec<String> = dyn_trait
+            .get("traits")
+            .and_then(|v| v.as_array())
+            .map(|traits| {
+                traits
+                    .iter()
+                    .map(|poly_trait| {
+                        let trait_path = poly_trait
+                            .get("trait")
+                            .map(|path| format_type(&serde_json::json!({ "resolved_path": path })))
+                            .unwrap_or_else(|| "?".to_string());
+                        format!("{}{}", format_binder(poly_trait), trait_path)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        if let Some(lifetime) = dyn_trait.get("lifetime").and_then(|v| v.as_str()) {
+            bounds.push(lifetime.to_string());
+        }
+        return format!("dyn {}", bounds.join(" + "));
+    }
+
+    // `unsafe extern "C" fn(*const c_void) -> c_int`, with parameter names
+    // only where the pointer type gives them
+    if let Some(pointer) = type_data.get("function_pointer") {
+        let sig = pointer.get("sig").or_else(|| pointer.get("decl"));
+        let mut params: Vec<String> = sig
+            .and_then(|sig| sig.get("inputs"))
+            .and_then(|v| v.as_array())
+            .map(|inputs| {
+                inputs
+                    .iter()
+                    .filter_map(|input| {
+                        let name = input.get(0)?.as_str()?;
+                        let ty = format_type(input.get(1)?);
+                        Some(if name == "_" {
+                            ty
+                        } else {
+                            format!("{}: {}", name, ty)
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        if sig
+            .and_then(|sig| sig.get("is_c_variadic"))
+            .and_then(|v| v.as_bool())
+            == Some(true)
+        {
+            params.push("...".to_string());
+        }
+        let output = sig
+            .and_then(|sig| sig.get("output"))
+            .filter(|output| !output.is_null())
+            .map(|output| format!(" -> {}", format_type(output)))
+            .unwrap_or_default();
+        return format!(
+            "{}{}fn({}){}",
+            format_binder(pointer),
+            signature::qualifiers(pointer.get("header")),
+            params.join(", "),
+            output
+        );
+    }
+
+    // Fallback for complex types
+    "...".to_string()
+}
+
+// The type behind `&` or `*const`: a trait object with several bounds needs
+// parentheses there (`&(dyn Error + Send)`)
+fn format_pointee(type_data: &Value) -> String {
+    let rendered = format_type(type_data);
+    let bound_count = type_data.get("dyn_trait").map_or(0, |dyn_trait| {
+        let traits = dyn_trait
+            .get("traits")
+            .and_then(|v| v.as_array())
+            .map_or(0, |traits| traits.len());
+        let lifetime = dyn_trait.get("lifetime").is_some_and(|v| v.is_string());
+        traits + usize::from(lifetime)
+    });
+    if bound_count > 1 {
+        format!("({})", rendered)
+    } else {
+        rendered
+    }
+}
+
+// Format a list of generic bounds (`Clone + Send + 'a`)
+fn format_bounds(bounds: &[Value]) -> String {
+    bounds
+        .iter()
+        .map(|bound| {
+            if let Some(trait_bound) = bound.get("trait_bound") {
+                let trait_path = trait_bound
+                    .get("trait")
+                    .map(|path| format_type(&serde_json::json!({ "resolved_path": path })))
+                    .unwrap_or_else(|| "?".to_string());
+                // `?Sized` and friends
+                let trait_path = match trait_bound.get("modifier").and_then(|v| v.as_str()) {
+                    Some("maybe") => format!("?{}", trait_path),
+                    _ => trait_path,
+                };
+                format!("{}{}", format_binder(trait_bound), trait_path)
+            } else if let Some(lifetime) = bound.get("outlives").and_then(|v| v.as_str()) {
+                lifetime.to_string()
+            } else {
+                "...".to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+// A higher-ranked `for<'a> ` binder, or nothing when there is none
+fn format_binder(node: &Value) -> String {
+    let lifetimes: Vec<&str> = node
+        .get("generic_params")
+        .and_then(|v| v.as_array())
+        .map(|params| {
+            params
+                .iter()
+                .filter_map(|param| param.get("name").and_then(|v| v.as_str()))
+                .collect()
+        })
+        .unwrap_or_default();
+    if lifetimes.is_empty() {
+        String::new()
+    } else {
+        format!("for<{}> ", lifetimes.join(", "))
+    }
+}
+
+// Format a path segment's generic arguments (`<'a, T, Item = u8>` or
+// `(A) -> B`), or nothing when there are none
+fn format_generic_args(args: Option<&Value>) -> String {
+    let Some(args) = args.filter(|args| !args.is_null()) else {
+        return String::new();
+    };
+
+    if let Some(angle) = args.get("angle_bracketed") {
+        let mut rendered: Vec<String> = angle
+            .get("args")
+            .and_then(|v| v.as_array())
+            .map(|args| {
+                args.iter()
+                    .filter_map(|arg| {
+                        Some(if let Some(ty) = arg.get("type") {
+                            format_type(ty)
+                        } else if let Some(lifetime) = arg.get("lifetime") {
+                            match shown_lifetime(Some(lifetime)) {
+                                Some(lifetime) => lifetime.to_string(),
+                                None if KEEP_ELIDED_ARGS.with(Cell::get) => "'_".to_string(),
+                                None => return None,
+                            }
+                        } else if let Some(expr) = arg
+                            .get("const")
+                            .and_then(|c| c.get("expr"))
+                            .and_then(|v| v.as_str())
+                        {
+                            expr.to_string()
+                        } else {
+                            "_".to_string()
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        // Associated item constraints (`Item = u8`, `Item: Clone`)
+        for constraint in angle
+            .get("constraints")
+            .and_then(|v| v.as_array())
+            .map(|c| c.as_slice())
+            .unwrap_or_default()
+        {
+            let Some(name) = constraint.get("name").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            let binding = constraint.get("binding");
+            if let Some(ty) = binding
+                .and_then(|b| b.get("equality"))
+                .and_then(|e| e.get("type"))
+            {
+                rendered.push(format!("{} = {}", name, format_type(ty)));
+            } else if let Some(bounds) = binding
+                .and_then(|b| b.get("constraint"))
+                .and_then(|v| v.as_array())
+            {
+                rendered.push(format!("{}: {}", name, format_bounds(bounds)));
+            }
+        }
+        return if rendered.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", rendered.join(", "))
+        };
+    }
+
+    if let Some(paren) = args.get("parenthesized") {
+        let inputs: Vec<String> = paren
+            .get("inputs")
+            .and_then(|v| v.as_array())
+            .map(|inputs| inputs.iter().map(format_type).collect())
+            .unwrap_or_default();
+        let output = paren
+            .get("output")
+            .filter(|output| !output.is_null())
+            .map(|output| format!(" -> {}", format_type(output)))
+            .unwrap_or_default();
+        return format!("({}){}", inputs.join(", "), output);
+    }
+
+    String::new()
+}
+
+// Format generic parameters (`<'a, T: Clone>`), or nothing when there are none
+fn format_generic_params(generics: Option<&Value>) -> String {
+    let params = generics
+        .and_then(|g| g.get("params"))
+        .and_then(|v| v.as_array())
+        .map(|params| params.as_slice())
+        .unwrap_or_default();
+
+    let rendered: Vec<String> = params
+        .iter()
+        .filter_map(|param| {
+            let name = param.get("name").and_then(|v| v.as_str())?;
+            let kind = param.get("kind")?;
+            if let Some(ty) = kind.get("type") {
+                // `impl Trait` arguments show up as synthetic params
+                if ty.get("is_synthetic").and_then(|v| v.as_bool()) == Some(true) {
+                    return None;
+                }
+                let bounds = ty
+                    .get("bounds")
+                    .and_then(|v| v.as_array())
+                    .map(|b| format_bounds(&without_implied_sized(b)))
+                    .unwrap_or_default();
+                let mut rendered = if bounds.is_empty() {
+                    name.to_string()
+                } else {
+                    format!("{}: {}", name, bounds)
+                };
+                // `S = RandomState`
+                if let Some(default) = ty.get("default").filter(|d| !d.is_null()) {
+                    rendered.push_str(&format!(" = {}", format_type(default)));
+                }
+                Some(rendered)
+            } else if let Some(konst) = kind.get("const") {
+                let ty = konst
+                    .get("type")
+                    .map(format_type)
+                    .unwrap_or_else(|| "?".to_string());
+                let default = konst
+                    .get("default")
+                    .and_then(|v| v.as_str())
+                    .map(|default| format!(" = {}", default))
+                    .unwrap_or_default();
+                Some(format!("const {}: {}{}", name, ty, default))
+            } else {
+                // Elided lifetimes aren't part of the declaration
+                if name == "'_" {
+                    return None;
+                }
+                let outlives: Vec<&str> = kind
+                    .get("lifetime")
+                    .and_then(|l| l.get("outlives"))
+                    .and_then(|v| v.as_array())
+                    .map(|o| o.iter().filter_map(|v| v.as_str()).collect())
+                    .unwrap_or_default();
+                Some(if outlives.is_empty() {
+                    name.to_string()
+                } else {
+                    format!("{}: {}", name, outlives.join(" + "))
+                })
+            }
+        })
+        .collect();
+
+    if rendered.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", rendered.join(", "))
+    }
+}
+
+// Bounds minus a plain `Sized`, which every type parameter has unless it
+// says `?Sized`
+fn without_implied_sized(bounds: &[Value]) -> Vec<Value> {
+    bounds
+        .iter()
+        .filter(|bound| {
+            let Some(trait_bound) = bound.get("trait_bound") else {
+                return true;
+            };
+            let path = trait_bound
+                .get("trait")
+                .and_then(|t| t.get("path"))
+                .and_then(|v| v.as_str())
+                .unwrap_or_default();
+            let modifier = trait_bound.get("modifier").and_then(|v| v.as_str());
+            !(path.rsplit("::").next() == Some("Sized") && modifier.is_none_or(|m| m == "none"))
+        })
+        .cloned()
+        .collect()
+}
+
+// Format a where clause (` where T: Clone, Self: 'a`), or nothing when empty
+fn format_where_clause(generics: Option<&Value>) -> String {
+    let predicates = generics
+        .and_then(|g| g.get("where_predicates"))
+        .and_then(|v| v.as_array())
+        .map(|p| p.as_slice())
+        .unwrap_or_default();
+
+    let rendered: Vec<String> = predicates
+        .iter()
+        .filter_map(|predicate| {
+            if let Some(bound) = predicate.get("bound_predicate") {
+                let ty = format_type(bound.get("type")?);
+                let bounds = bound.get("bounds")?.as_array()?;
+                // `Self: Sized` opts a trait method out of `dyn`; on a generic
+                // parameter it's implied
+                let bounds = if ty == "Self" {
+                    format_bounds(bounds)
+                } else {
+                    format_bounds(&without_implied_sized(bounds))
+                };
+                if bounds.is_empty() {
+                    return None;
+                }
+                Some(format!("{}{}: {}", format_binder(bound), ty, bounds))
+            } else if let Some(lifetime) = predicate.get("lifetime_predicate") {
+                let outlives: Vec<&str> = lifetime
+                    .get("outlives")?
+                    .as_array()?
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .collect();
+                Some(format!(
+                    "{}: {}",
+                    lifetime.get("lifetime")?.as_str()?,
+                    outlives.join(" + ")
+                ))
+            } else if let Some(eq) = predicate.get("eq_predicate") {
+                let rhs = eq.get("rhs")?;
+                let rhs = rhs.get("type").unwrap_or(rhs);
+                Some(format!(
+                    "{} = {}",
+                    format_type(eq.get("lhs")?),
+                    format_type(rhs)
+                ))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    if rendered.is_empty() {
+        String::new()
+    } else {
+        format!(" where {}", rendered.join(", "))
+    }
+}
+
+// Compare two sets of API items and categorize changes
+fn compare_api_items(
+    old_items: Vec<ApiItem>,
+    new_items: Vec<ApiItem>,
+) -> (Vec<ApiItem>, Vec<ApiItem>, Vec<(ApiItem, ApiItem)>) {
+    let mut phase = timing::phase("compare items");
+    phase.items(old_items.len() + new_items.len());
+    // Pair items by index first, so nothing is cloned. Items sharing a key
+    // within one version are paired in order, and any left over are added or
+    // removed rather than matched with something else.
+    let mut old_by_key: HashMap<DiffKey, VecDeque<usize>> = HashMap::new();
+    for (i, item) in old_items.iter().enumerate() {
+        old_by_key.entry(item.diff_key()).or_default().push_back(i);
+    }
+    let mut pairs = Vec::new();
+    let mut added_indices = Vec::new();
+    for (j, item) in new_items.iter().enumerate() {
+        match old_by_key
+            .get_mut(&item.diff_key())
+            .and_then(VecDeque::pop_front)
+        {
+            Some(i) => pairs.push((i, j)),
+            None => added_indices.push(j),
+        }
+    }
+    let mut removed_indices: Vec<usize> = old_by_key.into_values().flatten().collect();
+    removed_indices.sort_unstable();
+
+    let mut old_items: Vec<Option<ApiItem>> = old_items.into_iter().map(Some).collect();
+    let mut new_items: Vec<Option<ApiItem>> = new_items.into_iter().map(Some).collect();
+
+    // Items only in new version (added)
+    let mut added: Vec<ApiItem> = added_indices
+        .into_iter()
+        .filter_map(|j| new_items[j].take())
+        .collect();
+    // Members of new types come with them rather than being added to
+    // something downstream code already uses
+    let added_paths: HashSet<String> = added.iter().map(ApiItem::full_path).collect();
+    for item in &mut added {
+        item.parent_added = added_paths.contains(&item.path.join("::"));
+    }
+
+    // Items only in old version (removed)
+    let removed: Vec<ApiItem> = removed_indices
+        .into_iter()
+        .filter_map(|i| old_items[i].take())
+        .collect();
+
+    // Items in both but with different signatures (modified)
+    let mut modified: Vec<(ApiItem, ApiItem)> = pairs
+        .into_iter()
+        .filter_map(|(i, j)| {
+            let old_item = old_items[i].take()?;
+            let new_item = new_items[j].take()?;
+            (old_item.signature != new_item.signature
+                || old_item.tags != new_item.tags
+                || old_item.reexports != new_item.reexports)
+                .then_some((old_item, new_item))
+        })
+        .collect();
+
+    // An item that moved but is still re-exported at its old path wasn't
+    // removed; pair it with its new definition instead
+    let mut still_removed = Vec::new();
+    for old_item in removed {
+        let old_path = old_item.full_path();
+        let moved_to = added.iter().position(|new_item| {
+            new_item.kind_family() == old_item.kind_family()
+                && new_item.reexports.contains(&old_path)
+        });
+        match moved_to {
+            Some(i) => modified.push((old_item, added.remove(i))),
+            None => still_removed.push(old_item),
+        }
+    }
+
+    (added, still_removed, modified)
+}
+
+// The categorized changes between two versions, sorted by path for display
+struct DiffReport {
+    crate_name: String,
+    ver1: String,
+    ver2: String,
+    added: Vec<ApiItem>,
+    removed: Vec<ApiItem>,
+    modified: Vec<(ApiItem, ApiItem)>,
+    // Unchanged items shown around changes, sorted by path (`--context`)
+    unchanged: Vec<ApiItem>,
+    context: usize,
+    // Deprecated, hidden and unstable items in each version, when extracted
+    stability: Option<(stability::Summary, stability::Summary)>,
+    // Changes to items under ignored paths, left out of the report
+    ignored_changes: usize,
+    // Paths of removed items the new version still has, `#[doc(hidden)]`
+    now_hidden: HashSet<String>,
+    // The one item compared, with `--item`
+    focus: Option<item_diff::Focus>,
+    // Feature, dependency and MSRV changes, with `--local`
+    packaging: Option<packaging::Changes>,
+    // How docs.rs built the two versions differently, when it did
+    docs_rs_build: Option<docs_rs_build::Changes>,
+}
+
+impl DiffReport {
+    fn new(
+        crate_name: &str,
+        ver1: &str,
+        ver2: &str,
+        (mut added, mut removed, mut modified): (
+            Vec<ApiItem>,
+            Vec<ApiItem>,
+            Vec<(ApiItem, ApiItem)>,
+        ),
+    ) -> Self {
+        added.sort_by_key(|item| item.full_path());
+        removed.sort_by_key(|item| item.full_path());
+        modified.sort_by_key(|(old_item, _)| old_item.full_path());
+        DiffReport {
+            crate_name: crate_name.to_string(),
+            ver1: ver1.to_string(),
+            ver2: ver2.to_string(),
+            added,
+            removed,
+            modified,
+            unchanged: Vec::new(),
+            context: 0,
+            stability: None,
+            ignored_changes: 0,
+            now_hidden: HashSet::new(),
+            focus: None,
+            packaging: None,
+            docs_rs_build: None,
+        }
+    }
+
+    // Count the changes between items under ignored paths
+    fn with_ignored(mut self, old: Vec<ApiItem>, new: Vec<ApiItem>) -> Self {
+        let (added, removed, modified) = compare_api_items(old, new);
+        self.ignored_changes = added.len() + removed.len() + modified.len();
+        self
+    }
+
+    fn with_stability(mut self, stability: (stability::Summary, stability::Summary)) -> Self {
+        self.stability = Some(stability);
+        self
+    }
+
+    fn with_now_hidden(mut self, now_hidden: HashSet<String>) -> Self {
+        self.now_hidden = now_hidden;
+        self
+    }
+
+    // Why a removed item is gone, when it wasn't deleted
+    fn removal_reason(&self, item: &ApiItem) -> Option<&'static str> {
+        self.now_hidden
+            .contains(&item.full_path())
+            .then_some("now #[doc(hidden)]")
+    }
+
+    fn with_focus(mut self, focus: Option<item_diff::Focus>) -> Self {
+        self.focus = focus;
+        self
+    }
+
+    fn with_packaging(mut self, packaging: packaging::Changes) -> Self {
+        self.packaging = Some(packaging);
+        self
+    }
+
+    fn with_docs_rs_build(mut self, docs_rs_build: Option<docs_rs_build::Changes>) -> Self {
+        self.docs_rs_build = docs_rs_build;
+        self
+    }
+
+    // Show up to `context` of the `unchanged` items around each change
+    fn with_context(mut self, context: usize, mut unchanged: Vec<ApiItem>) -> Self {
+        unchanged.sort_by_key(|item| item.full_path());
+        self.unchanged = unchanged;
+        self.context = context;
+        self
+    }
+
+    // The unchanged siblings just before and just after `item` in its module
+    fn context_around(&self, item: &ApiItem) -> (Vec<&ApiItem>, Vec<&ApiItem>) {
+        if self.context == 0 {
+            return (Vec::new(), Vec::new());
+        }
+        let full_path = item.full_path();
+        let (before, after): (Vec<&ApiItem>, Vec<&ApiItem>) = self
+            .unchanged
+            .iter()
+            .filter(|sibling| sibling.path == item.path)
+            .partition(|sibling| sibling.full_path() < full_path);
+        let skip = before.len().saturating_sub(self.context);
+        (
+            before.into_iter().skip(skip).collect(),
+            after.into_iter().take(self.context).collect(),
+        )
+    }
+
+    // Print a change's context lines around `print_change`
+    fn with_context_lines(&self, item: &ApiItem, print_change: impl FnOnce()) {
+        let (before, after) = self.context_around(item);
+        let print_context = |sibling: &&ApiItem| {
+            let display = format!(
+                "{} {}",
+                sibling.display_fitted(4),
+                sibling.shown_signature()
+            );
+            println!(
+                "    {}",
+                style::paint(Role::Muted, &layout::wrap(&display, 4))
+            );
+        };
+        before.iter().for_each(print_context);
+        print_change();
+        after.iter().for_each(print_context);
+    }
+
+    fn breaking_count(&self) -> usize {
+        breaking_changes(&self.added, &self.removed, &self.modified)
+    }
+
+    fn change_count(&self) -> usize {
+        self.added.len() + self.removed.len() + self.modified.len()
+    }
+}
+
+// Display diff results with git-style colored output
+fn display_diff(report: &DiffReport) {
+    let DiffReport {
+        crate_name,
+        ver1,
+        ver2,
+        added,
+        removed,
+        modified,
+        ..
+    } = report;
+
+    println!(
+        "\nAPI diff for {} ({}...{}):\n",
+        crate_name.bold(),
+        ver1,
+        ver2
+    );
+    if let Some(build) = &report.docs_rs_build {
+        build.print(added.len(), removed.len());
+    }
+    if let Some(table) = report
+        .stability
+        .as_ref()
+        .and_then(|(old, new)| stability::render_table(old, new))
+    {
+        println!("{}", table);
+    }
+
+    let added_count = added.len();
+    let removed_count = removed.len();
+    let modified_count = modified.len();
+    let breaking_count = report.breaking_count();
+
+    let total_changes = added_count + removed_count + modified_count;
+    let ignored_note = ignore::note(report.ignored_changes, "change");
+    if total_changes == 0 {
+        println!("{}", style::paint(Role::Muted, "No API changes detected."));
+        if let Some(note) = ignored_note {
+            println!("{}", style::paint(Role::Muted, &note));
+        }
+        return;
+    }
+
+    // Display removed items (red with -)
+    if !removed.is_empty() {
+        let heading = format!("Removed ({}):", removed_count);
+        println!("{}", style::paint(Role::Removed, &heading).bold());
+        for item in removed {
+            report.with_context_lines(item, || {
+                let display = format!("- {} {}", item.display_fitted(4), item.shown_signature());
+                let display = style::paint(Role::Removed, &layout::wrap(&display, 2));
+                match report.removal_reason(item) {
+                    Some(reason) => println!(
+                        "  {} {}",
+                        display,
+                        style::paint(Role::Muted, &format!("({})", reason))
+                    ),
+                    None => println!("  {}", display),
+                }
+            });
+        }
+        println!();
+    }
+
+    // Display added items (green with +)
+    if !added.is_empty() {
+        let heading = format!("Added ({}):", added_count);
+        println!("{}", style::paint(Role::Added, &heading).bold());
+        for item in added {
+            report.with_context_lines(item, || {
+                let display = format!("+ {} {}", item.display_fitted(4), item.shown_signature());
+                let display = style::paint(Role::Added, &layout::wrap(&display, 2));
+                match (added_severity(item), member_addition(item)) {
+                    (Severity::Breaking, Some((_, reason))) => {
+                        let note = format!("(breaking: {})", reason);
+                        println!("  {} {}", display, style::paint(Role::Removed, &note));
+                    }
+                    (Severity::Breaking, None) => println!(
+                        "  {} {}",
+                        display,
+                        style::paint(Role::Removed, "(breaking)")
+                    ),
+                    (_, Some((_, reason))) => {
+                        let note = format!("(non-breaking: {})", reason);
+                        println!("  {} {}", display, style::paint(Role::Muted, &note));
+                    }
+                    (_, None) => println!("  {}", display),
+                }
+            });
+        }
+        println!();
+    }
+
+    // Display modified items (yellow with ~)
+    if !modified.is_empty() {
+        let heading = format!("Modified ({}):", modified_count);
+        println!("{}", style::paint(Role::Modified, &heading).bold());
+        for (old_item, new_item) in modified {
+            report.with_context_lines(new_item, || {
+                let mut label = modified_severity(old_item, new_item).label().to_string();
+                let notes = modification_notes(old_item, new_item);
+                if !notes.is_empty() {
+                    label = format!("{}: {}", label, notes.join("; "));
+                }
+                println!(
+                    "  {} ({})",
+                    style::paint(Role::Modified, &format!("~ {}", old_item.display_fitted(4))),
+                    label
+                );
+                // Side by side when the terminal is wide enough, else stacked
+                let old_new = |old: &str, new: &str| {
+                    let (old, new) = (format!("- {}", old), format!("+ {}", new));
+                    match layout::side_by_side(&old, &new, 4) {
+                        Some(rows) => {
+                            for (left, right) in rows {
+                                println!(
+                                    "    {} │ {}",
+                                    style::paint(Role::Removed, &left),
+                                    style::paint(Role::Added, &right)
+                                );
+                            }
+                        }
+                        None => {
+                            let wrapped = |line: &str| layout::wrap(line, 4);
+                            println!("    {}", style::paint(Role::Removed, &wrapped(&old)));
+                            println!("    {}", style::paint(Role::Added, &wrapped(&new)));
+                        }
+                    }
+                };
+                if old_item.signature != new_item.signature {
+                    old_new(old_item.shown_signature(), new_item.shown_signature());
+                }
+                if old_item.tags != new_item.tags {
+                    old_new(
+                        &format!("[{}]", old_item.tags.join(", ")),
+                        &format!("[{}]", new_item.tags.join(", ")),
+                    );
+                }
+                for line in path_changes(old_item, new_item) {
+                    println!("    {}", line);
+                }
+            });
+        }
+        println!();
+    }
+
+    println!(
+        "{}",
+        format!(
+            "Summary: +{} / -{} / ~{} ({} breaking)",
+            added_count, removed_count, modified_count, breaking_count
+        )
+        .bold()
+    );
+    if let Some(note) = ignored_note {
+        println!("{}", style::paint(Role::Muted, &note));
+    }
+}
+
+// The items of `new_items` that are neither added nor modified
+fn unchanged_items(
+    new_items: Vec<ApiItem>,
+    added: &[ApiItem],
+    modified: &[(ApiItem, ApiItem)],
+) -> Vec<ApiItem> {
+    let changed: HashSet<DiffKey> = added
+        .iter()
+        .chain(modified.iter().map(|(_, new_item)| new_item))
+        .map(ApiItem::diff_key)
+        .collect();
+    new_items
+        .into_iter()
+        .filter(|item| !changed.contains(&item.diff_key()))
+        .collect()
+}
+
+// How the paths an item is imported by changed, one line each
+fn path_changes(old: &ApiItem, new: &ApiItem) -> Vec<String> {
+    let mut lines = Vec::new();
+    if old.full_path() != new.full_path() {
+        lines.push(format!(
+            "canonical path moved: `{}` -> `{}`",
+            old.full_path(),
+            new.full_path()
+        ));
+    }
+    for path in lost_paths(old, new) {
+        lines.push(format!("re-export path removed: `{}`", path));
+    }
+    let previous = old.public_paths();
+    for path in new.reexports.iter().filter(|path| !previous.contains(path)) {
+        lines.push(format!("re-export path added: `{}`", path));
+    }
+    lines
+}
+
+// An associated type's signature minus its default (` = u8`), if it has one
+fn without_assoc_default(signature: &str) -> Option<&str> {
+    if signature.starts_with("= ") {
+        return Some("");
+    }
+    bounds::find_top_level(signature, " = ").map(|start| &signature[..start])
+}
+
+// Container signatures summarize members as `{ N variants }` or `(N fields)`,
+// around any generic parameters and where clause. Returns the signature
+// minus that summary for such signatures.
+fn member_count_generics(signature: &str) -> Option<String> {
+    let is_count = |body: &str| {
+        body.split_once(' ').is_some_and(|(count, noun)| {
+            count.parse::<usize>().is_ok() && matches!(noun, "fields" | "variants" | "items")
+        })
+    };
+    if let Some(rest) = signature.strip_suffix(" }") {
+        let start = rest.rfind("{ ")?;
+        return is_count(&rest[start + 2..]).then(|| rest[..start].trim_end().to_string());
+    }
+    // A tuple struct's where clause follows its fields
+    signature.match_indices('(').find_map(|(start, _)| {
+        let end = start + signature[start..].find(')')?;
+        is_count(&signature[start + 1..end])
+            .then(|| format!("{}{}", &signature[..start], &signature[end + 1..]))
+    })
+}
+
+// Removals are always breaking; additions and modifications depend on the rules above
+fn breaking_changes(
+    added: &[ApiItem],
+    removed: &[ApiItem],
+    modified: &[(ApiItem, ApiItem)],
+) -> usize {
+    removed.len()
+        + added
+            .iter()
+            .filter(|item| added_severity(item) == Severity::Breaking)
+            .count()
+        + modified
+            .iter()
+            .filter(|(old, new)| modified_severity(old, new) == Severity::Breaking)
+            .count()
+}
+
+// `git diff --name-status` style: `A`, `D` or `M`, a tab and the path, sorted
+// by path, without colors
+fn print_name_only(report: &DiffReport) {
+    let mut lines: Vec<(String, char)> = report
+        .added
+        .iter()
+        .map(|item| (item.full_path(), 'A'))
+        .chain(report.removed.iter().map(|item| (item.full_path(), 'D')))
+        .chain(
+            report
+                .modified
+                .iter()
+                .map(|(_, new_item)| (new_item.full_path(), 'M')),
+        )
+        .collect();
+    lines.sort();
+    for (path, status) in lines {
+        println!("{}\t{}", status, path);
+    }
+}
+
+// Render the diff in the requested output format
+fn emit_diff(format: DiffFormat, json_style: JsonStyle, report: &DiffReport) -> Result<()> {
+    match format {
+        DiffFormat::Text => {
+            display_diff(report);
+            if let Some(packaging) = &report.packaging {
+                println!();
+                packaging.print();
+            }
+        }
+        DiffFormat::Html => print!("{}", render_diff_html(report)),
+        DiffFormat::Json => print_json(&render_diff_json(report), json_style)?,
+        DiffFormat::SemverLint => print!("{}", semver_lint::render(report)),
+        DiffFormat::Github => print!("{}", github::render_diff(report)),
+    }
+    github::write_step_summary(&github::diff_summary(report), format == DiffFormat::Github)
+}
+
+// The JSON shape of a diff: each change carries its full path and severity
+fn render_diff_json(report: &DiffReport) -> Value {
+    let change = |item: &ApiItem, severity: Severity| {
+        serde_json::json!({
+            "path": item.full_path(),
+            "kind": item.item_type,
+            "signature": item.signature,
+            "tags": item.tags,
+            "severity": severity.label(),
+        })
+    };
+
+    let stability = report
+        .stability
+        .as_ref()
+        .map(|(old, new)| stability::to_json(old, new));
+    let mut json = serde_json::json!({
+        "crate": report.crate_name,
+        "from": report.ver1,
+        "to": report.ver2,
+        "stability": stability,
+        "added": report.added.iter().map(|item| {
+            let mut added = change(item, added_severity(item));
+            if let Some((_, reason)) = member_addition(item) {
+                added["reason"] = Value::from(reason);
+            }
+            added
+        }).collect::<Vec<_>>(),
+        "removed": report.removed.iter().map(|item| {
+            let mut removed = change(item, Severity::Breaking);
+            if let Some(reason) = report.removal_reason(item) {
+                removed["reason"] = Value::from(reason);
+            }
+            removed
+        }).collect::<Vec<_>>(),
+        "modified": report.modified.iter().map(|(old_item, new_item)| serde_json::json!({
+            "path": old_item.full_path(),
+            "kind": old_item.item_type,
+            "old": { "path": old_item.full_path(), "signature": old_item.signature, "tags": old_item.tags, "reexports": old_item.reexports },
+            "new": { "path": new_item.full_path(), "signature": new_item.signature, "tags": new_item.tags, "reexports": new_item.reexports },
+            "paths_removed": lost_paths(old_item, new_item),
+            "return_wrapping": return_wrapping(old_item, new_item).map(ReturnWrapping::to_json),
+            "async_change": async_change(old_item, new_item),
+            "condition_changes": impl_condition_changes(old_item, new_item),
+            "supertrait_changes": supertrait_changes(old_item, new_item),
+            "severity": modified_severity(old_item, new_item).label(),
+        })).collect::<Vec<_>>(),
+        "summary": {
+            "added": report.added.len(),
+            "removed": report.removed.len(),
+            "modified": report.modified.len(),
+            "breaking": report.breaking_count(),
+            "ignored": report.ignored_changes,
+        },
+    });
+    if let Some(packaging) = &report.packaging {
+        json["packaging"] = packaging.to_json();
+    }
+    if let Some(build) = &report.docs_rs_build {
+        json["docs_rs_build"] = build.to_json();
+    }
+    if let Some(timings) = timing::to_json() {
+        json["timings"] = timings;
+    }
+    json
+}
+
+// Minimal default styling; the classes are the stable interface for restyling
+const DIFF_HTML_STYLE: &str = "<style>
+.zdoc-diff ul { list-style: none; padding-left: 1em; }
+.zdoc-diff code { font-family: monospace; }
+.zdoc-diff .zdoc-added { color: #1a7f37; }
+.zdoc-diff .zdoc-removed { color: #cf222e; }
+.zdoc-diff .zdoc-modified { color: #9a6700; }
+.zdoc-diff .zdoc-old { color: #cf222e; text-decoration: line-through; }
+.zdoc-diff .zdoc-new { color: #1a7f37; }
+</style>
+";
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Render the diff as a self-contained HTML fragment with a class per change kind
+fn render_diff_html(report: &DiffReport) -> String {
+    let DiffReport {
+        crate_name,
+        ver1,
+        ver2,
+        added,
+        removed,
+        modified,
+        ..
+    } = report;
+
+    let mut html = String::from(DIFF_HTML_STYLE);
+    html.push_str("<div class=\"zdoc-diff\">\n");
+    html.push_str(&format!(
+        "<h2>API diff for <code>{}</code> ({}...{})</h2>\n",
+        escape_html(crate_name),
+        escape_html(ver1),
+        escape_html(ver2)
+    ));
+
+    if added.is_empty() && removed.is_empty() && modified.is_empty() {
+        html.push_str("<p class=\"zdoc-empty\">No API changes detected.</p>\n</div>\n");
+        return html;
+    }
+
+    let item_line = |class: &str, severity: Severity, item: &ApiItem| {
+        let note = member_addition(item)
+            .filter(|_| class == "zdoc-added")
+            .map(|(_, reason)| {
+                format!(
+                    " <span class=\"zdoc-note\">({})</span>",
+                    escape_html(&reason)
+                )
+            })
+            .unwrap_or_default();
+        format!(
+            "<li class=\"{} zdoc-{}\"><span class=\"zdoc-item\">{}</span> <code>{}</code>{}</li>\n",
+            class,
+            severity.label(),
+            escape_html(&item.display_string()),
+            escape_html(item.shown_signature()),
+            note
+        )
+    };
+
+    if !removed.is_empty() {
+        html.push_str(&format!(
+            "<h3 class=\"zdoc-removed\">Removed ({})</h3>\n<ul class=\"zdoc-removed\">\n",
+            removed.len()
+        ));
+        for item in removed {
+            html.push_str(&item_line("zdoc-removed", Severity::Breaking, item));
+        }
+        html.push_str("</ul>\n");
+    }
+
+    if !added.is_empty() {
+        html.push_str(&format!(
+            "<h3 class=\"zdoc-added\">Added ({})</h3>\n<ul class=\"zdoc-added\">\n",
+            added.len()
+        ));
+        for item in added {
+            html.push_str(&item_line("zdoc-added", added_severity(item), item));
+        }
+        html.push_str("</ul>\n");
+    }
+
+    if !modified.is_empty() {
+        html.push_str(&format!(
+            "<h3 class=\"zdoc-modified\">Modified ({})</h3>\n<ul class=\"zdoc-modified\">\n",
+            modified.len()
+        ));
+        for (old_item, new_item) in modified {
+            let mut paths: String = path_changes(old_item, new_item)
+                .iter()
+                .map(|line| {
+                    format!(
+                        "<br><span class=\"zdoc-paths\">{}</span>",
+                        escape_html(line)
+                    )
+                })
+                .collect();
+            for note in modification_notes(old_item, new_item) {
+                paths.push_str(&format!(
+                    "<br><span class=\"zdoc-note\">{}</span>",
+                    escape_html(&note)
+                ));
+            }
+            html.push_str(&format!(
+                "<li class=\"zdoc-modified zdoc-{}\"><span class=\"zdoc-item\">{}</span><br>\
+                 <code class=\"zdoc-old\">{}</code><br>\
+                 <code class=\"zdoc-new\">{}</code>{}</li>\n",
+                modified_severity(old_item, new_item).label(),
+                escape_html(&old_item.display_string()),
+                escape_html(&old_item.signature_with_tags()),
+                escape_html(&new_item.signature_with_tags()),
+                paths,
+            ));
+        }
+        html.push_str("</ul>\n");
+    }
+
+    html.push_str(&format!(
+        "<p class=\"zdoc-summary\">Summary: +{} / -{} / ~{} ({} breaking)</p>\n</div>\n",
+        added.len(),
+        removed.len(),
+        modified.len(),
+        report.breaking_count()
+    ));
+    html
+}
+
+// Options of the diff command
+#[derive(Clone)]
+struct DiffOptions {
+    format: DiffFormat,
+    dry_run: bool,
+    // Let `latest` and version requirements resolve to pre-releases
+    pre: bool,
+    // Document both versions locally with these features instead of using docs.rs
+    features: Option<feature_docs::Features>,
+    // Unchanged items to show around each change
+    context: usize,
+    // Print only the changed paths
+    name_only: bool,
+    // Print per-module counts only
+    stat: bool,
+    // Compare signatures with type aliases written out
+    resolve_aliases: bool,
+    // Keep `#[doc(hidden)]` items in the comparison
+    include_hidden: bool,
+    // Paths left out of the comparison
+    ignore: ignore::Ignore,
+    // Compare only this item and its members
+    item: Option<String>,
+    // With `item`, print a line diff of its docs
+    docs: bool,
+}
+
+// One version's docs: from docs.rs, or built locally for a feature set
+async fn diff_source_docs(
+    crate_name: &str,
+    version: &str,
+    features: Option<&feature_docs::Features>,
+) -> Result<FetchedDocs> {
+    match features {
+        Some(features) => {
+            let path = feature_docs::build(crate_name, version, features)?;
+            doc_source::read_crate_json(crate_name, DocSource::File { path: &path })
+        }
+        None => doc_source::load_crate_json(crate_name, DocSource::DocsRs { version }).await,
+    }
+}
+
+// A crate's docs: a release's from docs.rs with `--version`, else the
+// project's own, generated first
+async fn crate_or_release_json(
+    crate_name: &str,
+    version: Option<&str>,
+    load_metadata: &dyn Fn() -> Result<cargo_metadata::Metadata>,
+) -> Result<Value> {
+    match version {
+        Some(version) => Ok(
+            doc_source::load_crate_json(crate_name, DocSource::DocsRs { version })
+                .await?
+                .json,
+        ),
+        None => {
+            let metadata = load_metadata()?;
+            generate_json_docs(&metadata)?;
+            let local = DocSource::Local {
+                metadata: &metadata,
+            };
+            Ok(doc_source::read_crate_json(crate_name, local)?.json)
+        }
+    }
+}
+
+// Main diff command handler
+async fn diff_docs(
+    crate_name: &str,
+    ver1: &str,
+    ver2: &str,
+    options: &DiffOptions,
+    json_style: JsonStyle,
+) -> Result<()> {
+    let ver1 = &registry::resolve_version(crate_name, ver1, options.pre).await?;
+    let ver2 = &registry::resolve_version(crate_name, ver2, options.pre).await?;
+    if options.dry_run {
+        match &options.features {
+            Some(features) => {
+                println!("Would document locally with {}:", features.describe());
+                for version in [ver1, ver2] {
+                    println!("  {} v{}", crate_name, version);
+                }
+            }
+            None => {
+                for version in [ver1, ver2] {
+                    if cache::is_cached(crate_name, version) {
+                        println!("Cached: {} v{}", crate_name, version);
+                    } else {
+                        let url = sources::for_crate(crate_name).json_url(crate_name, version);
+                        println!("Would fetch: {}", url);
+                    }
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    // Fetch both versions
+    let docs1 = diff_source_docs(crate_name, ver1, options.features.as_ref()).await?;
+    let docs2 = diff_source_docs(crate_name, ver2, options.features.as_ref()).await?;
+
+    warn_same_release(ver1, ver2, &docs1, &docs2);
+    let mut report = diff_report(crate_name, ver1, ver2, &docs1, &docs2, options)?;
+    // Built locally, both versions already share `--features`
+    if options.features.is_none() {
+        report = report.with_docs_rs_build(docs_rs_build::compare(crate_name, ver1, ver2).await);
+    }
+    print_diff(&report, options, json_style)
+}
+
+// Print a report as the options ask: a histogram, the changed paths, or in
+// the chosen format
+fn print_diff(report: &DiffReport, options: &DiffOptions, json_style: JsonStyle) -> Result<()> {
+    let _render = timing::phase("render");
+    if let Some(focus) = &report.focus {
+        item_diff::print(report, focus, options.docs, options.format, json_style)
+    } else if options.stat {
+        stat::print(report);
+        Ok(())
+    } else if options.name_only {
+        print_name_only(report);
+        Ok(())
+    } else {
+        emit_diff(options.format, json_style, report)
+    }
+}
+
+// Warn when two requested versions resolve to the same release (e.g. `1.0`
+// and `1.0.5`)
+fn warn_same_release(ver1: &str, ver2: &str, docs1: &FetchedDocs, docs2: &FetchedDocs) {
+    if let (Some(resolved1), Some(resolved2)) = (docs1.crate_version(), docs2.crate_version())
+        && resolved1 == resolved2
+    {
+        warn!(
+            "'{}' and '{}' both resolve to v{}; you are diffing a version against itself.",
+            ver1, ver2, resolved1
+        );
+    }
+}
+
+// Compare two fetched versions of a crate
+fn diff_report(
+    crate_name: &str,
+    ver1: &str,
+    ver2: &str,
+    docs1: &FetchedDocs,
+    docs2: &FetchedDocs,
+    options: &DiffOptions,
+) -> Result<DiffReport> {
+    // Identical payloads can't contain API changes, so skip extraction
+    // entirely, unless an item's report was asked for
+    if docs1.content_hash == docs2.content_hash && options.item.is_none() {
+        return Ok(DiffReport::new(crate_name, ver1, ver2, Default::default()));
+    }
+
+    info!("Parsing API items...");
+
+    // Extract API items from both versions
+    let extract = |json: &Value| {
+        if options.resolve_aliases {
+            aliases::resolving(json, || extract_api_items(json))
+        } else {
+            extract_api_items(json)
+        }
+    };
+    let mut items1 = extract(&docs1.json)?;
+    let mut items2 = extract(&docs2.json)?;
+    let stability = (
+        stability::Summary::of(&items1),
+        stability::Summary::of(&items2),
+    );
+    // Items that became hidden are gone from the supported API, so they
+    // count as removed; the report says why
+    let mut now_hidden = HashSet::new();
+    if !options.include_hidden {
+        now_hidden.extend(
+            items2
+                .iter()
+                .filter(|item| item.stability.contains(&"doc(hidden)"))
+                .map(ApiItem::full_path),
+        );
+        for items in [&mut items1, &mut items2] {
+            items.retain(|item| !item.stability.contains(&"doc(hidden)"));
+        }
+    }
+    let (items1, ignored1) = options.ignore.partition(items1, ApiItem::full_path);
+    let (items2, ignored2) = options.ignore.partition(items2, ApiItem::full_path);
+    debug!("{} v{}: {} items", crate_name, ver1, items1.len());
+    debug!("{} v{}: {} items", crate_name, ver2, items2.len());
+    let (focus, items1, items2) = match &options.item {
+        Some(query) => {
+            let (focus, items1, items2) = item_diff::focus(query, items1, items2)?;
+            (Some(focus), items1, items2)
+        }
+        None => (None, items1, items2),
+    };
+
+    info!("Comparing {} items...", items1.len() + items2.len());
+
+    // Keep the new version's items around when context lines were asked for
+    let new_items = if options.context > 0 {
+        items2.clone()
+    } else {
+        Vec::new()
+    };
+
+    // Compare and categorize changes
+    let (added, removed, modified) = compare_api_items(items1, items2);
+    let unchanged = unchanged_items(new_items, &added, &modified);
+
+    Ok(
+        DiffReport::new(crate_name, ver1, ver2, (added, removed, modified))
+            .with_context(options.context, unchanged)
+            .with_stability(stability)
+            .with_ignored(ignored1, ignored2)
+            .with_now_hidden(now_hidden)
+            .with_focus(focus),
+    )
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    cli.color.apply();
+    init_logging(cli.verbose, cli.quiet, cli.color);
+    timing::init(cli.timings);
+    if let Some(limit) = cli.max_memory {
+        rustdoc_json::set_memory_limit(limit);
+    }
+    if let Some(proxy) = cli.proxy.clone() {
+        http::set_proxy(proxy);
+    }
+    http::set_offline(cli.offline);
+    target_dir::set_overrides(cli.target_dir.clone(), cli.target.clone());
+    ignore::set_disabled(cli.no_ignore);
+    freshness::set_clean(cli.clean);
+    style::init(cli.theme.as_deref());
+    toolchain::init();
+    sources::init(cli.docs_url.clone(), cli.registry_api.clone());
+    if let Some(width) = cli.width {
+        layout::set_width(width);
+    }
+    layout::set_full_paths(cli.verbose > 0);
+    // Commands that only compare APIs never look at source spans or link
+    // tables, which are much of a big crate's JSON. GitHub annotations of a
+    // local crate's changes point at its source, so they keep the spans.
+    let annotates_source = match &cli.command {
+        Commands::Diff {
+            format, git, local, ..
+        } => *format == DiffFormat::Github && (git.is_some() || *local),
+        Commands::Verify { format, .. } => *format == DiffFormat::Github,
+        _ => false,
+    };
+    if !annotates_source
+        && matches!(
+            cli.command,
+            Commands::Diff { .. }
+                | Commands::Baseline { .. }
+                | Commands::Verify { .. }
+                | Commands::Snapshot { .. }
+                | Commands::Cheatsheet { .. }
+                | Commands::Compare { .. }
+                | Commands::Stats { .. }
+                | Commands::Outdated { .. }
+        )
+    {
+        rustdoc_json::set_detail(rustdoc_json::Detail::Api);
+    }
+    let json_style = JsonStyle::from_flags(cli.compact, cli.pretty);
+
+    // Project metadata, loaded only by the commands that need a project, so
+    // those working from docs.rs alone run anywhere. Cargo finds the nearest
+    // `Cargo.toml` upwards from the current directory.
+    let load_metadata = || -> Result<cargo_metadata::Metadata> {
+        let mut command = MetadataCommand::new();
+        if let Some(manifest_path) = &cli.manifest_path {
+            command.manifest_path(manifest_path);
+        }
+        let mut phase = timing::phase("cargo metadata");
+        let metadata = command.exec().map_err(|err| match err {
+            cargo_metadata::Error::CargoMetadata { stderr }
+                if stderr.contains("could not find") =>
+            {
+                anyhow::anyhow!(
+                    "No `Cargo.toml` found in this directory or any parent; this command \
+                     must be run within a Rust project (or given `--manifest-path`)"
+                )
+            }
+            err => anyhow::Error::new(err).context("Failed to parse cargo metadata"),
+        })?;
+        phase.items(metadata.packages.len());
+        debug!(
+            "Loaded cargo metadata ({} packages) in {:.2?}",
+            metadata.packages.len(),
+            phase.elapsed()
+        );
+        sources::register(&metadata);
+        Ok(metadata)
+    };
+
+    match &cli.command {
+        Commands::Search {
+            query,
+            crate_name,
+            selection,
+            results,
+            format,
+            dry_run,
+            all_terms,
+            glob,
+            no_length_penalty,
+            weight_name,
+            weight_path,
+            weight_docs,
+            no_generate,
+            force_generate,
+            copy,
+            links,
+            raw,
+            private,
+            examples,
+            async_only,
+            sync,
+            min_score,
+            all,
+            include_hidden,
+        } => {
+            if cli.clean && *no_generate {
+                anyhow::bail!(
+                    "--clean regenerates the docs, so it can't be used with --no-generate"
+                );
+            }
+            let metadata = load_metadata()?;
+            let queries: Vec<String> = split_queries(query)
+                .into_iter()
+                .map(str::trim)
+                .filter(|q| !q.is_empty())
+                .map(String::from)
+                .collect();
+            if queries.is_empty() {
+                anyhow::bail!("Search query is empty");
+            }
+            let options = SearchOptions {
+                limit: *results,
+                glob: *glob,
+                all_terms: *all_terms,
+                format: *format,
+                dry_run: *dry_run,
+                length_penalty: !*no_length_penalty,
+                weights: Weights {
+                    name: *weight_name,
+                    path: *weight_path,
+                    docs: *weight_docs,
+                },
+                generate: if *force_generate || cli.clean {
+                    Generate::Always
+                } else if *no_generate {
+                    Generate::Never
+                } else {
+                    Generate::Auto
+                },
+                copy: *copy,
+                links: *links,
+                raw: *raw,
+                only_async: (*async_only || *sync).then_some(*async_only),
+                min_score: *min_score,
+                all: *all,
+                include_hidden: *include_hidden,
+            };
+            doc_targets::set_private(*private);
+            doc_targets::set_examples(*examples);
+            toolchain::set_document_hidden(*include_hidden);
+            let packages = selection.resolve(&metadata, crate_name.as_deref())?;
+            let targets = doc_targets::of_packages(&metadata, &packages);
+            search_docs(&metadata, &targets, &queries, &options, json_style)?;
+        }
+
+        Commands::Diff {
+            crate_name,
+            ver1,
+            ver2,
+            batch,
+            from_lockfile,
+            git,
+            local,
+            format,
+            dry_run,
+            features,
+            no_default_features,
+            context,
+            name_only,
+            stat,
+            resolve_aliases,
+            include_hidden,
+            auto_impls,
+            item,
+            docs,
+            pre,
+        } => {
+            AUTO_IMPLS.store(*auto_impls, Ordering::Relaxed);
+            // docs.rs only has each release's default docs build
+            let features =
+                (!features.is_empty() || *no_default_features).then(|| feature_docs::Features {
+                    features: features.clone(),
+                    no_default_features: *no_default_features,
+                });
+            let options = DiffOptions {
+                format: *format,
+                dry_run: *dry_run,
+                pre: *pre,
+                features,
+                context: *context,
+                name_only: *name_only,
+                stat: *stat,
+                resolve_aliases: *resolve_aliases,
+                include_hidden: *include_hidden,
+                ignore: ignore::Ignore::load_nearest()?,
+                item: item.clone(),
+                docs: *docs,
+            };
+            match (batch, crate_name, ver1, ver2) {
+                (Some(batch), _, _, _) => batch::run(batch, &options, json_style).await?,
+                _ if *from_lockfile => {
+                    lockfile::run(&load_metadata()?, &options, json_style).await?
+                }
+                (None, crate_name, None, None) if *local => {
+                    local_diff::run(
+                        &load_metadata()?,
+                        crate_name.as_deref(),
+                        &options,
+                        json_style,
+                    )
+                    .await?
+                }
+                (None, crate_name, None, None) if git.is_some() => {
+                    let git_ref = git.as_deref().unwrap_or_default();
+                    git_diff::run(
+                        &load_metadata()?,
+                        crate_name.as_deref(),
+                        git_ref,
+                        &options,
+                        json_style,
+                    )
+                    .await?
+                }
+                (None, Some(crate_name), Some(ver1), Some(ver2)) => {
+                    diff_docs(crate_name, ver1, ver2, &options, json_style).await?
+                }
+                // clap requires all three without `--batch`, `--from-lockfile` or `--git`
+                _ => anyhow::bail!(
+                    "diff needs a crate and two versions, --batch, --from-lockfile or --git"
+                ),
+            }
+        }
+
+        Commands::Baseline {
+            crate_name,
+            selection,
+        } => {
+            let metadata = load_metadata()?;
+            let package = selection.resolve_one(&metadata, crate_name.as_deref(), "baseline")?;
+            baseline::run_baseline(&metadata, &package.name)?;
+        }
+
+        Commands::Verify {
+            crate_name,
+            selection,
+            baseline,
+            format,
+        } => {
+            let metadata = load_metadata()?;
+            // Without a selection, the crate the baseline was made from
+            let crate_name = if crate_name.is_none() && selection.is_empty() {
+                None
+            } else {
+                let package = selection.resolve_one(&metadata, crate_name.as_deref(), "verify")?;
+                Some(package.name.as_str())
+            };
+            baseline::run_verify(&metadata, crate_name, baseline, *format, json_style)?;
+        }
+
+        Commands::Snapshot {
+            crate_name,
+            version,
+        } => {
+            let json = match version {
+                Some(version) => {
+                    let version = registry::resolve_version(crate_name, version, false).await?;
+                    let source = DocSource::DocsRs { version: &version };
+                    doc_source::load_crate_json(crate_name, source).await?.json
+                }
+                None => {
+                    let metadata = load_metadata()?;
+                    generate_json_docs(&metadata)?;
+                    let local = DocSource::Local {
+                        metadata: &metadata,
+                    };
+                    doc_source::read_crate_json(crate_name, local)?.json
+                }
+            };
+            print!("{}", snapshot::render(crate_name, &json)?);
+        }
+
+        Commands::Cheatsheet {
+            crate_name,
+            module,
+            version,
+            format,
+        } => {
+            let json = match version {
+                Some(version) => {
+                    let version = registry::resolve_version(crate_name, version, false).await?;
+                    let source = DocSource::DocsRs { version: &version };
+                    doc_source::load_crate_json(crate_name, source).await?.json
+                }
+                None => {
+                    let metadata = load_metadata()?;
+                    generate_json_docs(&metadata)?;
+                    let local = DocSource::Local {
+                        metadata: &metadata,
+                    };
+                    doc_source::read_crate_json(crate_name, local)?.json
+                }
+            };
+            let sheet = cheatsheet::Cheatsheet::build(crate_name, &json, module.as_deref())?;
+            match format {
+                CheatsheetFormat::Text => print!("{}", sheet.render_text()),
+                CheatsheetFormat::Markdown => print!("{}", sheet.render_markdown()),
+            }
+        }
+
+        Commands::Setup { toolchain, yes } => {
+            // Next to the project's other settings, if there's a project
+            let cwd = std::env::current_dir().context("Failed to read the current directory")?;
+            let config_dir = config::Config::find(&cwd)
+                .or_else(|| {
+                    load_metadata()
+                        .ok()
+                        .map(|metadata| metadata.workspace_root.into_std_path_buf())
+                })
+                .unwrap_or(cwd);
+            setup::run(&setup::System, toolchain.as_deref(), *yes, &config_dir)?;
+        }
+        Commands::Cache { action } => match action {
+            CacheAction::Ls => cache::list()?,
+            CacheAction::Stats => cache::stats()?,
+            CacheAction::Clear {
+                crate_name,
+                older_than,
+            } => cache::clear(crate_name.as_deref(), *older_than)?,
+            CacheAction::Path => println!("{}", cache::root().display()),
+        },
+
+        Commands::Debug { action } => match action {
+            DebugAction::SearchIndex { path, json } => {
+                let path = match path {
+                    Some(path) => path.clone(),
+                    None => target_dir::doc_dir(&load_metadata()?),
+                };
+                let index = search_index::load(&path)?;
+                if *json {
+                    print_json(&search_index::summary_json(&index), json_style)?;
+                } else {
+                    search_index::print_summary(&index);
+                }
+            }
+        },
+
+        Commands::Features {
+            crate_name,
+            selection,
+        } => {
+            let metadata = load_metadata()?;
+            let package = selection.resolve_one(&metadata, crate_name.as_deref(), "features")?;
+            feature_list::print(&metadata, package);
+        }
+
+        Commands::Context {
+            item_path,
+            depth,
+            max_chars,
+            copy,
+        } => {
+            let metadata = load_metadata()?;
+            context::run(&metadata, item_path, *depth, *max_chars, *copy)?;
+        }
+
+        Commands::Assoc { item_path } => {
+            let metadata = load_metadata()?;
+            assoc::run(&metadata, item_path)?;
+        }
+
+        Commands::Impls {
+            crate_name,
+            trait_path,
+            version,
+            include_blanket,
+            include_auto,
+        } => {
+            let json =
+                crate_or_release_json(crate_name, version.as_deref(), &load_metadata).await?;
+            let implementors = impls::find_implementors(&json, trait_path)?;
+            let included = impls::Included {
+                blanket: *include_blanket,
+                auto: *include_auto,
+            };
+            impls::display_implementors(crate_name, trait_path, &implementors, included);
+        }
+
+        Commands::Batch => {
+            // For `show` requests; `search` leaves hidden items out itself
+            toolchain::set_document_hidden(true);
+            engine::run_stdio(load_metadata()).await?;
+        }
+
+        Commands::Query {
+            crate_name,
+            expression,
+            version,
+            paths,
+        } => {
+            let json =
+                crate_or_release_json(crate_name, version.as_deref(), &load_metadata).await?;
+            query::run(&json, expression, *paths, json_style)?;
+        }
+
+        Commands::LintDocs {
+            crate_name,
+            selection,
+            allow,
+            deny,
+            check,
+            format,
+        } => {
+            let metadata = load_metadata()?;
+            let crates = selection.resolve(&metadata, crate_name.as_deref())?;
+            let crates: Vec<String> = crates.iter().map(|p| p.name.to_string()).collect();
+            lint_docs::run(&metadata, &crates, allow, deny, *check, *format)?;
+        }
+
+        Commands::List {
+            crate_name,
+            selection,
+            undocumented,
+            min_docs,
+            version,
+            links,
+        } => {
+            let (crate_name, json, ignore) = match version {
+                // A published release needn't be in the dependency graph
+                Some(version) => {
+                    let crate_name = crate_name
+                        .clone()
+                        .context("`--version` needs the crate name as an argument")?;
+                    let source = DocSource::DocsRs { version };
+                    let json = doc_source::load_crate_json(&crate_name, source).await?.json;
+                    (crate_name, json, ignore::Ignore::load_nearest()?)
+                }
+                None => {
+                    let metadata = load_metadata()?;
+                    let package =
+                        selection.resolve_one(&metadata, crate_name.as_deref(), "list")?;
+                    generate_json_docs(&metadata)?;
+                    let local = DocSource::Local {
+                        metadata: &metadata,
+                    };
+                    let json = doc_source::read_crate_json(&package.name, local)?.json;
+                    let ignore = ignore::Ignore::load(metadata.workspace_root.as_std_path())?;
+                    (package.name.to_string(), json, ignore)
+                }
+            };
+            let min_docs = if *undocumented { Some(1) } else { *min_docs };
+            let linker = links.then(|| links::Linker::new(&json, &crate_name));
+            list::run(
+                &crate_name,
+                extract_api_items(&json)?,
+                min_docs,
+                linker.as_ref(),
+                &ignore,
+            );
+        }
+
+        Commands::Man { item_path, roff } => {
+            let metadata = load_metadata()?;
+            man::run(&metadata, item_path, *roff)?;
+        }
+
+        Commands::Show {
+            item_path,
+            peek,
+            max_chars,
+        } => {
+            let metadata = load_metadata()?;
+            // Hidden items can be shown, under a warning
+            toolchain::set_document_hidden(true);
+            show::run(&metadata, item_path, *peek, *max_chars)?;
+        }
+
+        Commands::Signature { item_path, copy } => {
+            let metadata = load_metadata()?;
+            signature::run(&metadata, item_path, *copy)?;
+        }
+
+        Commands::TestExample { item_path, nth } => {
+            let metadata = load_metadata()?;
+            test_example::run(&metadata, item_path, *nth)?;
+        }
+
+        Commands::Macros {
+            crate_name,
+            name,
+            version,
+        } => {
+            let macros = match version {
+                Some(version) => {
+                    let source = DocSource::DocsRs { version };
+                    let json = doc_source::load_crate_json(crate_name, source).await?.json;
+                    // Derive crates are versioned separately from the crates
+                    // re-exporting them; docs.rs may not have every one
+                    macros::collect_macros(&json, |krate| async move {
+                        let latest = DocSource::DocsRs { version: "latest" };
+                        Ok(doc_source::load_crate_json(&krate, latest)
+                            .await
+                            .ok()
+                            .map(|docs| docs.json))
+                    })
+                    .await?
+                }
+                None => {
+                    let metadata = load_metadata()?;
+                    generate_json_docs(&metadata)?;
+                    let local = DocSource::Local {
+                        metadata: &metadata,
+                    };
+                    let json = doc_source::read_crate_json(crate_name, local)?.json;
+                    // Dependencies without generated docs are skipped
+                    let metadata = &metadata;
+                    macros::collect_macros(&json, |krate| async move {
+                        if !doc_source::is_generated(metadata, &krate) {
+                            return Ok(None);
+                        }
+                        Ok(Some(doc_source::read_crate_json(&krate, local)?.json))
+                    })
+                    .await?
+                }
+            };
+
+            match name {
+                Some(name) => {
+                    let info = macros
+                        .iter()
+                        .find(|info| info.name == *name)
+                        .with_context(|| format!("No macro '{}' in {}", name, crate_name))?;
+                    macros::display_macro(info);
+                }
+                None => macros::display_macros(crate_name, &macros),
+            }
+        }
+
+        Commands::Compare {
+            crate_a,
+            crate_b,
+            format,
+        } => {
+            compare::run(crate_a, crate_b, *format, json_style).await?;
+        }
+
+        Commands::Stats {
+            crate_name,
+            trend,
+            releases,
+            pre,
+            format,
+        } => {
+            let releases = if *trend { *releases as usize } else { 1 };
+            stats::run(crate_name, releases, *pre, *format, json_style).await?;
+        }
+
+        Commands::PublicDeps {
+            crate_name,
+            selection,
+            check,
+            format,
+        } => {
+            let metadata = load_metadata()?;
+            let package = selection.resolve_one(&metadata, crate_name.as_deref(), "public-deps")?;
+            generate_json_docs(&metadata)?;
+            let local = DocSource::Local {
+                metadata: &metadata,
+            };
+            let json = doc_source::read_crate_json(&package.name, local)?.json;
+            public_deps::run(&metadata, package, &json, *check, *format, json_style)?;
+        }
+
+        Commands::Outdated { format } => {
+            outdated::run(&load_metadata()?, *format, json_style).await?;
+        }
+
+        Commands::Prelude { crate_name, module } => {
+            let metadata = load_metadata()?;
+            generate_json_docs(&metadata)?;
+            let local = DocSource::Local {
+                metadata: &metadata,
+            };
+            let json = doc_source::read_crate_json(crate_name, local)?.json;
+            prelude::run(&json, crate_name, module.as_deref())?;
+        }
+    }
+
+    // The opt-in footer about direct dependencies with newer releases
+    let footer = !matches!(
+        cli.command,
+        Commands::Outdated { .. }
+            | Commands::Cache { .. }
+            | Commands::Debug { .. }
+            | Commands::Setup { .. }
+    );
+    if footer
+        && !cli.quiet
+        && !cli.offline
+        && outdated::notify_enabled()
+        && let Ok(metadata) = load_metadata()
+    {
+        outdated::notice(&metadata).await;
+    }
+
+    timing::finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An item at `full_path` (`demo::Mode::Fast`), with no markers
+    fn api_item(kind: &str, full_path: &str, signature: &str) -> ApiItem {
+        let mut path: Vec<String> = full_path.split("::").map(String::from).collect();
+        let name = path.pop().unwrap_or_default();
+        ApiItem {
+            id: String::new(),
+            name,
+            item_type: kind.to_string(),
+            path,
+            signature: signature.to_string(),
+            tags: Vec::new(),
+            parent_tags: Vec::new(),
+            parent_kind: None,
+            reexports: Vec::new(),
+            docs: None,
+            inherits_docs: false,
+            location: None,
+            stability: Vec::new(),
+            parent_added: false,
+        }
+    }
+
+    fn tagged(mut item: ApiItem, tags: &[&str]) -> ApiItem {
+        item.tags = tags.iter().map(|tag| tag.to_string()).collect();
+        item
+    }
+
+    // `item` as a member of a `parent_kind` marked with `parent_tags`
+    fn member_of(mut item: ApiItem, parent_kind: &str, parent_tags: &[&str]) -> ApiItem {
+        item.parent_kind = Some(parent_kind.to_string());
+        item.parent_tags = parent_tags.iter().map(|tag| tag.to_string()).collect();
+        item
+    }
+
+    use Severity::{Breaking, Minor};
+
+    // Markers, for tables of cases
+    type Tags = &'static [&'static str];
+
+    #[test]
+    fn added_member_severity() {
+        let cases: &[(&str, &str, Tags, Severity)] = &[
+            ("variant", "enum", &[], Breaking),
+            ("variant", "enum", &["non_exhaustive"], Minor),
+            ("struct_field", "struct", &[], Breaking),
+            ("struct_field", "struct", &["non_exhaustive"], Minor),
+            ("struct_field", "struct", &["private_fields"], Minor),
+            ("struct_field", "variant", &[], Breaking),
+            ("struct_field", "variant", &["non_exhaustive"], Minor),
+            // Unions are built by naming one field, so new ones break nothing
+            ("struct_field", "union", &[], Minor),
+        ];
+        for &(kind, parent_kind, parent_tags, expected) in cases {
+            let item = member_of(
+                api_item(kind, "demo::Parent::member", "u8"),
+                parent_kind,
+                parent_tags,
+            );
+            assert_eq!(
+                added_severity(&item),
+                expected,
+                "{} added to a {} {:?}",
+                kind,
+                parent_kind,
+                parent_tags
+            );
+        }
+    }
+
+    #[test]
+    fn added_member_of_new_parent_is_minor() {
+        for (kind, parent_kind) in [("variant", "enum"), ("struct_field", "struct")] {
+            let mut item = member_of(
+                api_item(kind, "demo::Parent::member", "u8"),
+                parent_kind,
+                &[],
+            );
+            item.parent_added = true;
+            assert_eq!(added_severity(&item), Minor);
+            assert_eq!(member_addition(&item), None);
+        }
+    }
+
+    #[test]
+    fn member_addition_explains_itself() {
+        let field = api_item("struct_field", "demo::Config::retries", "u8");
+        let reason = |tags: &[&str]| member_addition(&member_of(field.clone(), "struct", tags));
+        assert_eq!(
+            reason(&[]),
+            Some((Breaking, "the struct isn't #[non_exhaustive]".to_string()))
+        );
+        assert_eq!(
+            reason(&["non_exhaustive"]),
+            Some((Minor, "the struct is #[non_exhaustive]".to_string()))
+        );
+        assert_eq!(
+            reason(&["private_fields"]),
+            Some((Minor, "the struct has private fields".to_string()))
+        );
+        assert_eq!(
+            member_addition(&api_item("function", "demo::f", "()")),
+            None
+        );
+    }
+
+    #[test]
+    fn added_trait_item_severity() {
+        let cases: &[(&str, Tags, Tags, Severity)] = &[
+            ("trait method", &["required"], &[], Breaking),
+            ("trait method", &["required"], &["sealed"], Minor),
+            ("trait method", &[], &[], Minor),
+            ("assoc_type", &["required"], &[], Breaking),
+            ("assoc_type", &["required"], &["sealed"], Minor),
+            ("assoc_const", &["required"], &[], Breaking),
+            ("assoc_const", &[], &[], Minor),
+        ];
+        for &(kind, tags, parent_tags, expected) in cases {
+            let item = member_of(
+                tagged(api_item(kind, "demo::Codec::member", "()"), tags),
+                "trait",
+                parent_tags,
+            );
+            assert_eq!(
+                added_severity(&item),
+                expected,
+                "{} {:?} added to a trait {:?}",
+                kind,
+                tags,
+                parent_tags
+            );
+        }
+    }
+
+    #[test]
+    fn added_free_items_are_minor() {
+        for kind in ["function", "struct", "enum", "trait", "macro"] {
+            assert_eq!(added_severity(&api_item(kind, "demo::thing", "()")), Minor);
+        }
+    }
+
+    #[test]
+    fn marker_changes() {
+        let cases: &[(&str, Tags, Tags, Tags, Severity)] = &[
+            ("enum", &[], &["non_exhaustive"], &[], Breaking),
+            ("enum", &["non_exhaustive"], &[], &[], Minor),
+            ("struct", &[], &["non_exhaustive"], &[], Breaking),
+            ("struct", &["non_exhaustive"], &[], &[], Minor),
+            ("trait", &[], &["sealed"], &[], Breaking),
+            ("trait", &["sealed"], &[], &[], Minor),
+            ("trait method", &[], &["required"], &[], Breaking),
+            ("trait method", &["required"], &[], &[], Minor),
+            ("trait method", &[], &["required"], &["sealed"], Minor),
+            ("function", &[], &["must_use"], &[], Minor),
+            ("function", &["must_use"], &[], &[], Minor),
+        ];
+        for &(kind, old_tags, new_tags, parent_tags, expected) in cases {
+            let item = api_item(kind, "demo::Thing", "{ 2 variants }");
+            let old = member_of(tagged(item.clone(), old_tags), "trait", parent_tags);
+            let new = member_of(tagged(item, new_tags), "trait", parent_tags);
+            assert_eq!(
+                modified_severity(&old, &new),
+                expected,
+                "{} going from {:?} to {:?}",
+                kind,
+                old_tags,
+                new_tags
+            );
+        }
+    }
+
+    #[test]
+    fn non_exhaustive_added_alongside_a_new_variant() {
+        let old = api_item("enum", "demo::Mode", "{ 2 variants }");
+        let new = tagged(
+            api_item("enum", "demo::Mode", "{ 3 variants }"),
+            &["non_exhaustive"],
+        );
+        assert_eq!(modified_severity(&old, &new), Breaking);
+        assert_eq!(modified_severity(&new, &old), Minor);
+    }
+
+    #[test]
+    fn signature_changes() {
+        let cases: &[(&str, &str, Severity)] = &[
+            ("(x: u8) -> u8", "(x: u16) -> u8", Breaking),
+            ("<T: Clone + Debug>(x: T)", "<T: Clone>(x: T)", Minor),
+            ("<T: Clone>(x: T)", "<T: Clone + Debug>(x: T)", Breaking),
+            ("<T>(x: &T)", "<T: ?Sized>(x: &T)", Minor),
+            ("(x: u8) -> u8", "const (x: u8) -> u8", Minor),
+            ("const (x: u8) -> u8", "(x: u8) -> u8", Breaking),
+            ("unsafe (x: u8) -> u8", "(x: u8) -> u8", Minor),
+            ("(x: u8) -> u8", "unsafe (x: u8) -> u8", Breaking),
+            ("<T: Send + Sync>(x: T)", "<T: Sync + Send>(x: T)", Minor),
+        ];
+        for &(old_sig, new_sig, expected) in cases {
+            let old = api_item("function", "demo::f", old_sig);
+            let new = api_item("function", "demo::f", new_sig);
+            assert_eq!(
+                modified_severity(&old, &new),
+                expected,
+                "{} -> {}",
+                old_sig,
+                new_sig
+            );
+        }
+    }
+
+    #[test]
+    fn trait_method_relaxations_depend_on_sealing() {
+        let cases: &[(&str, &str)] = &[
+            ("<T: Clone>(&self, x: T)", "<T>(&self, x: T)"),
+            ("unsafe (&self)", "(&self)"),
+        ];
+        for &(old_sig, new_sig) in cases {
+            for (parent_tags, expected) in [(&[][..], Breaking), (&["sealed"][..], Minor)] {
+                let method = |sig| {
+                    member_of(
+                        api_item("trait method", "demo::Codec::encode", sig),
+                        "trait",
+                        parent_tags,
+                    )
+                };
+                assert_eq!(
+                    modified_severity(&method(old_sig), &method(new_sig)),
+                    expected,
+                    "{} -> {} in a trait {:?}",
+                    old_sig,
+                    new_sig,
+                    parent_tags
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn lost_supertrait_is_breaking() {
+        let old = api_item("trait", "demo::Codec", "where Self: Send + Clone");
+        let new = api_item("trait", "demo::Codec", "where Self: Send");
+        assert_eq!(modified_severity(&old, &new), Breaking);
+    }
+
+    // Notes quote bounds, whose generics mustn't turn into markup
+    #[test]
+    fn html_notes_are_escaped() {
+        let old = api_item(
+            "impl",
+            "demo::<Wrapper<T> as From<T>>",
+            "<T> where T: Clone",
+        );
+        let new = api_item(
+            "impl",
+            "demo::<Wrapper<T> as From<T>>",
+            "<T> where T: Clone + Into<String>",
+        );
+        let old_trait = api_item("trait", "demo::Codec", "where Self: Send");
+        let new_trait = api_item("trait", "demo::Codec", "where Self: Send + AsRef<[u8]>");
+        let report = DiffReport::new(
+            "demo",
+            "1.0.0",
+            "2.0.0",
+            (vec![], vec![], vec![(old, new), (old_trait, new_trait)]),
+        );
+        let html = render_diff_html(&report);
+        for note in [
+            "now requires T: Into&lt;String&gt;",
+            "new supertrait AsRef&lt;[u8]&gt;",
+        ] {
+            let span = format!("<span class=\"zdoc-note\">{}</span>", note);
+            assert!(html.contains(&span), "{}", html);
+        }
+        assert!(!html.contains("Into<String>"), "{}", html);
+        assert!(!html.contains("AsRef<"), "{}", html);
+    }
+
+    #[test]
+    fn pathless_members_are_blanket_impl_members() {
+        let json = fixtures::load("prelude");
+        let mut pathless: Vec<String> = extract_api_items(&json)
+            .unwrap()
+            .iter()
+            .filter(|item| item.is_pathless_member())
+            .map(|item| format!("{} {}", item.item_type, item.name))
+            .collect();
+        pathless.sort();
+        // From core's `impl<T> Borrow<T> for T`, `impl<T, U> Into<U> for T`, ...
+        assert_eq!(
+            pathless,
+            [
+                "assoc_type Error",
+                "assoc_type Error",
+                "method borrow",
+                "method borrow_mut",
+                "method from",
+                "method into",
+                "method try_from",
+                "method try_into",
+                "method type_id",
+            ]
+        );
+    }
+
+    #[test]
+    fn lost_reexport_is_breaking() {
+        let mut old = api_item("struct", "demo::inner::Widget", "{ 1 fields }");
+        old.reexports = vec!["demo::Widget".to_string()];
+        let new = api_item("struct", "demo::inner::Widget", "{ 1 fields }");
+        assert_eq!(lost_paths(&old, &new), vec!["demo::Widget".to_string()]);
+        assert_eq!(modified_severity(&old, &new), Breaking);
+        assert_eq!(modified_severity(&new, &old), Minor);
+    }
+
+    // Two releases of a crate with futures in every kind of position, where
+    // one function changed and one was added: nothing else may show up as
+    // modified, however its future is spelled
+    #[test]
+    fn futures_compare_equal_across_releases() {
+        let items = |name: &str| extract_api_items(&fixtures::load(name)).unwrap();
+        let (added, removed, modified) = compare_api_items(items("tasks-old"), items("tasks-new"));
+        assert!(removed.is_empty(), "removed {:?}", removed);
+        let added: Vec<String> = added.iter().map(ApiItem::full_path).collect();
+        assert_eq!(added, ["tasks::timeout"]);
+        let modified: Vec<(&str, &str)> = modified
+            .iter()
+            .map(|(old, new)| (old.signature.as_str(), new.signature.as_str()))
+            .collect();
+        assert_eq!(
+            modified,
+            [("async (ms: u64)", "async (duration: std::time::Duration)")]
+        );
+    }
+
+    // Two releases differing only in where clauses: each changed bound is a
+    // modification rated by what it does to callers and implementors, and a
+    // bound moved into the where clause is restated, not changed
+    #[test]
+    fn where_clause_changes_across_releases() {
+        let items = |name: &str| extract_api_items(&fixtures::load(name)).unwrap();
+        let (added, removed, modified) =
+            compare_api_items(items("bounds-old"), items("bounds-new"));
+        assert!(added.is_empty(), "added {:?}", added);
+        assert!(removed.is_empty(), "removed {:?}", removed);
+        let mut found: Vec<(String, Severity, &str, &str)> = modified
+            .iter()
+            .map(|(old, new)| {
+                (
+                    old.full_path(),
+                    modified_severity(old, new),
+                    old.signature.as_str(),
+                    new.signature.as_str(),
+                )
+            })
+            .collect();
+        found.sort_by(|a, b| a.0.cmp(&b.0));
+        let expected = [
+            (
+                "bounds::Store",
+                Breaking,
+                "where Self: Sized { 1 items }",
+                "where Self: Sized + Send { 1 items }",
+            ),
+            // Implementors of an unsealed trait must now accept any `T`
+            (
+                "bounds::Store::keep",
+                Breaking,
+                "<T>(&self, value: T) where T: Debug",
+                "<T>(&self, value: T)",
+            ),
+            (
+                "bounds::Wrapper",
+                Breaking,
+                "<T>(1 fields) where T: Send",
+                "<T>(1 fields) where T: Send + Sync",
+            ),
+            (
+                "bounds::convert",
+                Minor,
+                "<T: Into<String>>(value: T) -> String",
+                "<T>(value: T) -> String where T: Into<String>",
+            ),
+            (
+                "bounds::process",
+                Minor,
+                "<T>(value: T) -> T where T: Clone + Debug",
+                "<T>(value: T) -> T where T: Clone",
+            ),
+        ];
+        let expected: Vec<(String, Severity, &str, &str)> = expected
+            .into_iter()
+            .map(|(path, severity, old, new)| (path.to_string(), severity, old, new))
+            .collect();
+        assert_eq!(found, expected);
+    }
+
+    // A slice of libc's own docs: the union and extern items C bindings are
+    // made of
+    #[test]
+    fn libc_union_and_extern_items() {
+        let mut found: Vec<(String, String, String)> = extract_api_items(&fixtures::load("libc"))
+            .unwrap()
+            .into_iter()
+            .filter(|item| item.item_type != "module")
+            .map(|item| (item.full_path(), item.item_type, item.signature))
+            .collect();
+        found.sort();
+        let expected = [
+            (
+                "libc::new::linux_uapi::linux::if_packet::tpacket_req_u",
+                "union",
+                "{ 2 fields }",
+            ),
+            (
+                "libc::new::linux_uapi::linux::if_packet::tpacket_req_u::req",
+                "struct_field",
+                "tpacket_req",
+            ),
+            (
+                "libc::new::linux_uapi::linux::if_packet::tpacket_req_u::req3",
+                "struct_field",
+                "tpacket_req3",
+            ),
+            ("libc::unix::in6addr_any", "extern static", "in6_addr"),
+            (
+                "libc::unix::linux_like::linux::gnu::environ",
+                "extern static",
+                "mut *mut *mut c_char",
+            ),
+            (
+                "libc::unix::malloc",
+                "extern fn",
+                "unsafe extern \"C\" (size: size_t) -> *mut c_void",
+            ),
+            (
+                "libc::unix::printf",
+                "extern fn",
+                "unsafe extern \"C\" (format: *const c_char, ...) -> c_int",
+            ),
+            (
+                "libc::unix::qsort",
+                "extern fn",
+                "unsafe extern \"C\" (base: *mut c_void, num: size_t, size: size_t, \
+                 compar: Option<unsafe extern \"C\" fn(*const c_void, *const c_void) -> c_int>)",
+            ),
+            (
+                "libc::unix::strlen",
+                "extern fn",
+                "unsafe extern \"C\" (cs: *const c_char) -> size_t",
+            ),
+        ];
+        let expected: Vec<(String, String, String)> = expected
+            .iter()
+            .map(|(path, kind, signature)| {
+                (path.to_string(), kind.to_string(), signature.to_string())
+            })
+            .collect();
+        assert_eq!(found, expected);
+    }
+
+    // Rustdoc calls all of these functions; listings tell them apart
+    #[test]
+    fn method_labels_tell_functions_apart() {
+        let label = |fixture: &str, full_path: &str| {
+            let items = extract_api_items(&fixtures::load(fixture)).unwrap();
+            let item = items
+                .into_iter()
+                .find(|item| item.full_path() == full_path)
+                .unwrap_or_else(|| panic!("{} has no {}", fixture, full_path));
+            (item.item_type, item.tags.contains(&"required".to_string()))
+        };
+        let cases = [
+            ("widgets", "widgets::draw", "function", false),
+            ("widgets", "widgets::Draw::draw", "trait method", true),
+            ("widgets", "widgets::Draw::name", "trait method", false),
+            ("widgets", "widgets::Widget::area", "method", false),
+            ("widgets", "widgets::Widget::new", "method", false),
+            (
+                "tasks-old",
+                "tasks::<JoinHandle<T> as Future>::poll",
+                "method",
+                false,
+            ),
+        ];
+        for (fixture, full_path, kind, required) in cases {
+            assert_eq!(
+                label(fixture, full_path),
+                (kind.to_string(), required),
+                "{}",
+                full_path
+            );
+        }
+
+        // Keyed by id, so the free `draw` and `Draw::draw` stay apart
+        let json = fixtures::load("widgets");
+        let labels = method_labels(&json);
+        let ids = |name: &str| -> Vec<&String> {
+            json["index"]
+                .as_object()
+                .unwrap()
+                .iter()
+                .filter(|(_, item)| item["name"] == name)
+                .map(|(id, _)| id)
+                .collect()
+        };
+        let mut draw_labels: Vec<Option<&str>> = ids("draw")
+            .into_iter()
+            .map(|id| labels.get(id).copied())
+            .collect();
+        draw_labels.sort();
+        assert_eq!(draw_labels, [None, Some("trait method")]);
+    }
+
+    // Kinds only nightly crates declare are listed like any other
+    #[test]
+    fn nightly_only_kinds() {
+        let mut found: Vec<(String, String, String)> =
+            extract_api_items(&fixtures::load("nightly"))
+                .unwrap()
+                .into_iter()
+                .map(|item| (item.full_path(), item.item_type, item.signature))
+                .collect();
+        found.sort();
+        let expected = [
+            ("nightly", "module", ""),
+            (
+                "nightly::Counter",
+                "type_alias",
+                "= impl Iterator<Item = u32>",
+            ),
+            ("nightly::Handle", "extern type", ""),
+            ("nightly::Shareable", "trait_alias", "= Clone + Send + Sync"),
+            ("nightly::counter", "function", "() -> Counter"),
+            ("nightly::halt", "function", "() -> !"),
+        ];
+        let expected: Vec<(String, String, String)> = expected
+            .iter()
+            .map(|(path, kind, signature)| {
+                (path.to_string(), kind.to_string(), signature.to_string())
+            })
+            .collect();
+        assert_eq!(found, expected);
+    }
+
+    // A kind from a newer rustdoc than zdoc knows is listed under its own
+    // name with no signature, and reported once
+    #[test]
+    fn unknown_kinds_are_listed_as_is() {
+        let mut json = fixtures::load("nightly");
+        let alias = json["index"]
+            .as_object_mut()
+            .unwrap()
+            .values_mut()
+            .find(|item| item["name"] == "Shareable")
+            .unwrap();
+        alias["inner"] =
+            serde_json::json!({ "zdoc_test_kind": alias["inner"]["trait_alias"].take() });
+
+        let items = extract_api_items(&json).unwrap();
+        let item = items.iter().find(|item| item.name == "Shareable").unwrap();
+        assert_eq!(item.item_type, "zdoc_test_kind");
+        assert_eq!(item.signature, "");
+        assert_eq!(items.len(), 6);
+        // Extracting already reported it
+        assert!(!report_unknown_kind("zdoc_test_kind"));
+        assert!(report_unknown_kind("zdoc_other_test_kind"));
+        assert!(!report_unknown_kind("zdoc_other_test_kind"));
+    }
+
+    // Hiding a module hides everything inside it, down to the members of its
+    // types and traits, but not what the crate re-exports from it
+    #[test]
+    fn hidden_modules_hide_their_contents() {
+        let json = fixtures::load("hidden");
+        let mut found: Vec<(String, bool)> = extract_api_items(&json)
+            .unwrap()
+            .into_iter()
+            .filter(|item| !item.is_pathless_member() && item.item_type != "impl")
+            .map(|item| {
+                let hidden = item.stability.contains(&"doc(hidden)");
+                (item.full_path(), hidden)
+            })
+            .collect();
+        found.sort();
+        let expected = [
+            ("hidden", false),
+            ("hidden::Handle", false),
+            ("hidden::Handle::__raw", true),
+            ("hidden::Handle::id", false),
+            ("hidden::Handle::new", false),
+            ("hidden::__private", true),
+            // Defined in the hidden module, but re-exported at the root
+            ("hidden::__private::Exposed", false),
+            ("hidden::__private::Helper", true),
+            ("hidden::__private::Helper::help", true),
+            ("hidden::__private::Shim", true),
+            ("hidden::__private::Shim::get", true),
+            ("hidden::__private::Shim::value", true),
+            ("hidden::__private::nested", true),
+            ("hidden::__private::nested::deep", true),
+            ("hidden::__private::support", true),
+        ];
+        let expected: Vec<(String, bool)> = expected
+            .iter()
+            .map(|(path, hidden)| (path.to_string(), *hidden))
+            .collect();
+        assert_eq!(found, expected);
+
+        let matcher = NameMatcher::fuzzy(true, Weights::default());
+        let mut resolver = reexports::Resolver::new(std::path::Path::new(""));
+        let mut search = |query: &str| -> Vec<(String, bool)> {
+            let mut results: Vec<(String, bool)> =
+                search_json(&json, "hidden", query, &matcher, &mut resolver)
+                    .unwrap()
+                    .into_iter()
+                    .filter(|result| result.name == query)
+                    .map(|result| (result.full_path, result.hidden))
+                    .collect();
+            results.sort();
+            results
+        };
+        assert_eq!(
+            search("deep"),
+            [("hidden::__private::nested::deep".to_string(), true)]
+        );
+        assert_eq!(
+            search("get"),
+            [("hidden::__private::Shim::get".to_string(), true)]
+        );
+        assert_eq!(
+            search("Exposed"),
+            [("hidden::__private::Exposed".to_string(), false)]
+        );
+    }
+
+    // Pairing items across versions, on generated item sets drawn from a
+    // handful of names so that paths, names and kinds collide all the time
+    mod pairing {
+        use super::*;
+        use proptest::prelude::*;
+
+        // `io::Read` as one segment reads the same as `io` then `Read` once
+        // joined, as impl segments like `<A as From<B>>` can
+        const SEGMENTS: &[&str] = &["demo", "io", "Read", "io::Read", "<A as From<B>>"];
+        const NAMES: &[&str] = &["io", "Read", "from", "new"];
+        const KINDS: &[&str] = &["module", "function", "method", "trait method", "struct"];
+        const SIGNATURES: &[&str] = &["(value: T) -> Self", "(value: u8) -> Self", "()"];
+
+        // An item `depth` segments deep; pathless ones are keyed by their
+        // signature too
+        fn item(depth: std::ops::Range<usize>) -> impl Strategy<Value = ApiItem> {
+            (
+                prop::collection::vec(prop::sample::select(SEGMENTS), depth),
+                prop::sample::select(NAMES),
+                prop::sample::select(KINDS),
+                prop::sample::select(SIGNATURES),
+            )
+                .prop_map(|(path, name, kind, signature)| {
+                    let mut item = api_item(kind, name, signature);
+                    item.path = path.iter().map(|segment| segment.to_string()).collect();
+                    item
+                })
+        }
+
+        fn items(depth: std::ops::Range<usize>) -> impl Strategy<Value = Vec<ApiItem>> {
+            prop::collection::vec(item(depth), 0..16)
+        }
+
+        // How many items of each version have each key
+        fn counts<'a>(
+            old: &'a [ApiItem],
+            new: &'a [ApiItem],
+        ) -> HashMap<DiffKey<'a>, (usize, usize)> {
+            let mut counts: HashMap<DiffKey, (usize, usize)> = HashMap::new();
+            for item in old {
+                counts.entry(item.diff_key()).or_default().0 += 1;
+            }
+            for item in new {
+                counts.entry(item.diff_key()).or_default().1 += 1;
+            }
+            counts
+        }
+
+        proptest! {
+            // Items are only paired with an item of the same key, as many
+            // times as both versions have one, and the rest are added or
+            // removed under their own key
+            #[test]
+            fn pairs_only_items_with_the_same_key(old in items(0..3), new in items(0..3)) {
+                let (added, removed, modified) = compare_api_items(old.clone(), new.clone());
+                for (old_item, new_item) in &modified {
+                    prop_assert_eq!(old_item.diff_key(), new_item.diff_key());
+                    prop_assert_ne!(&old_item.signature, &new_item.signature);
+                }
+                let expected = counts(&old, &new);
+                let actual = counts(&removed, &added);
+                for (key, (old_count, new_count)) in &expected {
+                    let (removed_count, added_count) = actual.get(key).copied().unwrap_or_default();
+                    prop_assert_eq!(removed_count, old_count.saturating_sub(*new_count), "{:?}", key);
+                    prop_assert_eq!(added_count, new_count.saturating_sub(*old_count), "{:?}", key);
+                }
+                prop_assert!(actual.keys().all(|key| expected.contains_key(key)));
+            }
+
+            // The same items in another order, some with a new signature:
+            // nothing is added or removed, and only the changed ones are
+            // modified. Changing a pathless item's signature changes its
+            // key, so every item here has a path.
+            #[test]
+            fn reordered_items_pair_with_themselves(
+                old in items(1..3),
+                changed in prop::collection::vec(any::<bool>(), 16),
+                ranks in prop::collection::vec(any::<u8>(), 16),
+            ) {
+                let new: Vec<ApiItem> = old
+                    .iter()
+                    .zip(&changed)
+                    .map(|(item, &changed)| {
+                        let mut item = item.clone();
+                        if changed {
+                            item.signature.push_str(" + changed");
+                        }
+                        item
+                    })
+                    .collect();
+                // Shuffle keys, keeping the order of items sharing a key,
+                // since those are paired in order
+                let first: Vec<usize> = new
+                    .iter()
+                    .map(|item| new.iter().position(|other| other.diff_key() == item.diff_key()).unwrap())
+                    .collect();
+                let mut order: Vec<usize> = (0..new.len()).collect();
+                order.sort_by_key(|&i| (ranks[first[i]], first[i]));
+                let new = order.into_iter().map(|i| new[i].clone()).collect();
+
+                let (added, removed, modified) = compare_api_items(old.clone(), new);
+                prop_assert!(added.is_empty(), "added {:?}", added);
+                prop_assert!(removed.is_empty(), "removed {:?}", removed);
+                let changed_count = old.iter().zip(&changed).filter(|&(_, &changed)| changed).count();
+                prop_assert_eq!(modified.len(), changed_count);
+                for (old_item, new_item) in &modified {
+                    prop_assert_eq!(old_item.diff_key(), new_item.diff_key());
+                    prop_assert_eq!(format!("{} + changed", old_item.signature), new_item.signature.clone());
+                }
+            }
+        }
+    }
+
+    // Rendering type nodes, from hand-written JSON in the current format
+    mod rendering {
+        use super::*;
+        use serde_json::json;
+
+        fn path(name: &str, args: &[Value]) -> Value {
+            let args = if args.is_empty() {
+                Value::Null
+            } else {
+                json!({ "angle_bracketed": { "args": args, "constraints": [] } })
+            };
+            json!({ "resolved_path": { "path": name, "id": 0, "args": args } })
+        }
+
+        fn ty(type_data: Value) -> Value {
+            json!({ "type": type_data })
+        }
+
+        fn primitive(name: &str) -> Value {
+            json!({ "primitive": name })
+        }
+
+        fn generic(name: &str) -> Value {
+            json!({ "generic": name })
+        }
+
+        fn reference(lifetime: Option<&str>, is_mutable: bool, pointee: Value) -> Value {
+            json!({ "borrowed_ref": { "lifetime": lifetime, "is_mutable": is_mutable, "type": pointee } })
+        }
+
+        fn check(cases: &[(Value, &str)], render: impl Fn(&Value) -> String) {
+            for (node, expected) in cases {
+                assert_eq!(render(node), *expected, "rendering {}", node);
+            }
+        }
+
+        // The same declaration from rustdoc releases that write elided
+        // lifetimes out and ones that leave them off compares equal, but a
+        // named lifetime becoming `'static` is a change
+        #[test]
+        fn elided_lifetimes_compare_equal() {
+            let elided = || json!({ "lifetime": "'_" });
+            assert_eq!(shown_lifetime(Some(&json!("'_"))), None);
+            assert_eq!(shown_lifetime(Some(&Value::Null)), None);
+            assert_eq!(shown_lifetime(None), None);
+            assert_eq!(shown_lifetime(Some(&json!("'a"))), Some("'a"));
+            assert_eq!(shown_lifetime(Some(&json!("'static"))), Some("'static"));
+            check(
+                &[
+                    (reference(Some("'_"), false, primitive("str")), "&str"),
+                    (reference(None, false, primitive("str")), "&str"),
+                    (reference(Some("'a"), true, primitive("str")), "&'a mut str"),
+                    (path("Token", &[elided()]), "Token"),
+                    (path("Token", &[]), "Token"),
+                    (path("Token", &[json!({ "lifetime": "'a" })]), "Token<'a>"),
+                    (path("Cow", &[elided(), ty(primitive("str"))]), "Cow<str>"),
+                ],
+                format_type,
+            );
+
+            let parse = |input: Value, output: Value| {
+                let signature = format!(
+                    "(&self, input: {}) -> {}",
+                    format_type(&input),
+                    format_type(&output)
+                );
+                api_item("method", "demo::Parser::parse", &signature)
+            };
+            let written_out = parse(
+                reference(Some("'_"), false, primitive("str")),
+                path("Token", &[elided()]),
+            );
+            let left_off = parse(reference(None, false, primitive("str")), path("Token", &[]));
+            let (added, removed, modified) =
+                compare_api_items(vec![written_out.clone()], vec![left_off]);
+            assert!(added.is_empty() && removed.is_empty() && modified.is_empty());
+
+            let to_static = parse(
+                reference(None, false, primitive("str")),
+                path("Token", &[json!({ "lifetime": "'static" })]),
+            );
+            let (_, _, modified) = compare_api_items(vec![written_out], vec![to_static]);
+            assert_eq!(modified.len(), 1);
+            assert_eq!(
+                modified[0].1.signature,
+                "(&self, input: &str) -> Token<'static>"
+            );
+
+            let borrowed = |lifetime| {
+                let output = reference(Some(lifetime), false, primitive("str"));
+                api_item(
+                    "method",
+                    "demo::Parser::rest",
+                    &format!("(&'a self) -> {}", format_type(&output)),
+                )
+            };
+            let (old, new) = (borrowed("'a"), borrowed("'static"));
+            assert_eq!(
+                compare_api_items(vec![old.clone()], vec![new.clone()])
+                    .2
+                    .len(),
+                1
+            );
+            assert_eq!(modified_severity(&old, &new), Breaking);
+        }
+
+        // Declarations keep `'_` among generic arguments, where it can't
+        // always be left out
+        #[test]
+        fn declarations_keep_elided_arguments() {
+            let elided = || json!({ "lifetime": "'_" });
+            let token = path("Token", &[elided()]);
+            assert_eq!(keeping_elided_args(|| format_type(&token)), "Token<'_>");
+            let str_ref = reference(Some("'_"), false, primitive("str"));
+            assert_eq!(keeping_elided_args(|| format_type(&str_ref)), "&str");
+            // Only while rendering them
+            assert_eq!(format_type(&token), "Token");
+        }
+
+        #[test]
+        fn format_type_cases() {
+            check(
+                &[
+                    (primitive("u8"), "u8"),
+                    (generic("T"), "T"),
+                    (path("String", &[]), "String"),
+                    (path("Vec", &[ty(generic("T"))]), "Vec<T>"),
+                    (
+                        path(
+                            "HashMap",
+                            &[
+                                ty(path("String", &[])),
+                                ty(path("Vec", &[ty(primitive("u8"))])),
+                            ],
+                        ),
+                        "HashMap<String, Vec<u8>>",
+                    ),
+                    (
+                        path("Cow", &[json!({ "lifetime": "'a" }), ty(primitive("str"))]),
+                        "Cow<'a, str>",
+                    ),
+                    (
+                        path(
+                            "Buffer",
+                            &[
+                                json!({ "const": { "expr": "16", "value": null, "is_literal": true } }),
+                            ],
+                        ),
+                        "Buffer<16>",
+                    ),
+                    (json!({ "tuple": [] }), "()"),
+                    (json!({ "tuple": [primitive("u8")] }), "(u8,)"),
+                    (
+                        json!({ "tuple": [primitive("u8"), generic("T")] }),
+                        "(u8, T)",
+                    ),
+                    (json!({ "slice": primitive("u8") }), "[u8]"),
+                    (
+                        json!({ "array": { "type": primitive("u8"), "len": "32" } }),
+                        "[u8; 32]",
+                    ),
+                    (
+                        json!({ "array": { "type": generic("T"), "len": "N" } }),
+                        "[T; N]",
+                    ),
+                    (reference(None, false, primitive("str")), "&str"),
+                    (reference(Some("'a"), true, generic("T")), "&'a mut T"),
+                    (
+                        reference(
+                            None,
+                            false,
+                            json!({ "slice": path("Vec", &[ty(generic("T"))]) }),
+                        ),
+                        "&[Vec<T>]",
+                    ),
+                    (
+                        json!({ "raw_pointer": { "is_mutable": false, "type": primitive("u8") } }),
+                        "*const u8",
+                    ),
+                    (
+                        json!({ "raw_pointer": { "is_mutable": true, "type": json!({ "raw_pointer": { "is_mutable": false, "type": primitive("i8") } }) } }),
+                        "*mut *const i8",
+                    ),
+                    // Older formats' `mutable`
+                    (
+                        json!({ "borrowed_ref": { "lifetime": null, "mutable": true, "type": generic("T") } }),
+                        "&mut T",
+                    ),
+                    (
+                        json!({ "function_pointer": {
+                            "sig": {
+                                "inputs": [["_", generic("T")], ["len", primitive("usize")]],
+                                "output": primitive("bool"),
+                                "is_c_variadic": false,
+                            },
+                            "generic_params": [],
+                            "header": { "is_const": false, "is_unsafe": false, "is_async": false, "abi": "Rust" },
+                        } }),
+                        "fn(T, len: usize) -> bool",
+                    ),
+                    (
+                        json!({ "function_pointer": {
+                            "sig": {
+                                "inputs": [["_", reference(Some("'a"), false, primitive("str"))]],
+                                "output": null,
+                                "is_c_variadic": true,
+                            },
+                            "generic_params": [{ "name": "'a", "kind": { "lifetime": { "outlives": [] } } }],
+                            "header": { "is_const": false, "is_unsafe": true, "is_async": false, "abi": { "C": { "unwind": false } } },
+                        } }),
+                        "for<'a> unsafe extern \"C\" fn(&'a str, ...)",
+                    ),
+                    // Anything else is elided
+                    (
+                        json!({ "pat": { "type": primitive("u8"), "__pat_unstable_do_not_use": "1..=9" } }),
+                        "...",
+                    ),
+                ],
+                format_type,
+            );
+        }
+
+        fn trait_bound(trait_path: Value, lifetimes: &[&str], modifier: &str) -> Value {
+            let generic_params: Vec<Value> = lifetimes
+                .iter()
+                .map(|name| json!({ "name": name, "kind": { "lifetime": { "outlives": [] } } }))
+                .collect();
+            json!({ "trait_bound": {
+                "trait": trait_path["resolved_path"],
+                "generic_params": generic_params,
+                "modifier": modifier,
+            } })
+        }
+
+        // `Fn(inputs) -> output`
+        fn fn_trait(inputs: &[Value], output: Option<Value>) -> Value {
+            json!({ "resolved_path": { "path": "Fn", "id": 0, "args": {
+                "parenthesized": { "inputs": inputs, "output": output }
+            } } })
+        }
+
+        fn future(output: Value) -> Value {
+            json!({ "resolved_path": { "path": "Future", "id": 0, "args": {
+                "angle_bracketed": {
+                    "args": [],
+                    "constraints": [{
+                        "name": "Output",
+                        "args": null,
+                        "binding": { "equality": { "type": output } },
+                    }],
+                }
+            } } })
+        }
+
+        #[test]
+        fn format_bounds_cases() {
+            let cases = [
+                (json!([]), ""),
+                (
+                    json!([trait_bound(path("Clone", &[]), &[], "none")]),
+                    "Clone",
+                ),
+                (
+                    json!([
+                        trait_bound(path("Clone", &[]), &[], "none"),
+                        trait_bound(path("Send", &[]), &[], "none"),
+                        { "outlives": "'a" },
+                    ]),
+                    "Clone + Send + 'a",
+                ),
+                (
+                    json!([trait_bound(path("Sized", &[]), &[], "maybe")]),
+                    "?Sized",
+                ),
+                (
+                    json!([trait_bound(
+                        fn_trait(
+                            &[reference(Some("'a"), false, primitive("str"))],
+                            Some(primitive("bool"))
+                        ),
+                        &["'a"],
+                        "none",
+                    )]),
+                    "for<'a> Fn(&'a str) -> bool",
+                ),
+                (
+                    json!([trait_bound(fn_trait(&[generic("T")], None), &[], "none")]),
+                    "Fn(T)",
+                ),
+                (
+                    json!([trait_bound(future(generic("T")), &[], "none")]),
+                    "Future<Output = T>",
+                ),
+                (
+                    json!([trait_bound(
+                        json!({ "resolved_path": { "path": "Iterator", "id": 0, "args": {
+                            "angle_bracketed": { "args": [], "constraints": [{
+                                "name": "Item",
+                                "args": null,
+                                "binding": { "constraint": [trait_bound(path("Clone", &[]), &[], "none")] },
+                            }] }
+                        } } }),
+                        &[],
+                        "none",
+                    )]),
+                    "Iterator<Item: Clone>",
+                ),
+            ];
+            for (bounds, expected) in cases {
+                assert_eq!(
+                    format_bounds(bounds.as_array().unwrap()),
+                    expected,
+                    "rendering {}",
+                    bounds
+                );
+            }
+        }
+
+        #[test]
+        fn format_binder_cases() {
+            let binder = |lifetimes: &[&str]| {
+                trait_bound(path("Fn", &[]), lifetimes, "none")["trait_bound"].clone()
+            };
+            check(
+                &[
+                    (binder(&[]), ""),
+                    (binder(&["'a"]), "for<'a> "),
+                    (binder(&["'a", "'b"]), "for<'a, 'b> "),
+                    (json!({}), ""),
+                ],
+                format_binder,
+            );
+        }
+
+        #[test]
+        fn trait_object_and_impl_trait_cases() {
+            let dyn_trait = |traits: &[&Value], lifetime: Option<&str>| {
+                let traits: Vec<Value> = traits
+                    .iter()
+                    .map(|bound| bound["trait_bound"].clone())
+                    .collect();
+                json!({ "dyn_trait": { "traits": traits, "lifetime": lifetime } })
+            };
+            let send = trait_bound(path("Send", &[]), &[], "none");
+            let future = trait_bound(future(json!({ "tuple": [] })), &[], "none");
+            let error = trait_bound(path("Error", &[]), &[], "none");
+            check(
+                &[
+                    (dyn_trait(&[&error], None), "dyn Error"),
+                    (
+                        dyn_trait(&[&future, &send], Some("'a")),
+                        "dyn Future<Output = ()> + Send + 'a",
+                    ),
+                    (
+                        path(
+                            "Pin",
+                            &[ty(path("Box", &[ty(dyn_trait(&[&future, &send], None))]))],
+                        ),
+                        "Pin<Box<dyn Future<Output = ()> + Send>>",
+                    ),
+                    // Behind a reference, several bounds need parentheses
+                    (
+                        reference(None, false, dyn_trait(&[&error], None)),
+                        "&dyn Error",
+                    ),
+                    (
+                        reference(None, true, dyn_trait(&[&error, &send], None)),
+                        "&mut (dyn Error + Send)",
+                    ),
+                    (
+                        reference(None, false, dyn_trait(&[&error], Some("'static"))),
+                        "&(dyn Error + 'static)",
+                    ),
+                    (
+                        json!({ "impl_trait": [future, send, { "outlives": "'a" }] }),
+                        "impl Future<Output = ()> + Send + 'a",
+                    ),
+                    (
+                        json!({ "impl_trait": [trait_bound(
+                            fn_trait(&[reference(Some("'a"), false, primitive("str"))], None),
+                            &["'a"],
+                            "none",
+                        )] }),
+                        "impl for<'a> Fn(&'a str)",
+                    ),
+                ],
+                format_type,
+            );
+        }
+
+        fn qualified(self_type: Value, trait_path: Option<Value>, name: &str) -> Value {
+            let trait_path = trait_path.map(|path| path["resolved_path"].clone());
+            json!({ "qualified_path": {
+                "name": name,
+                "args": null,
+                "self_type": self_type,
+                "trait": trait_path,
+            } })
+        }
+
+        #[test]
+        fn qualified_path_never_and_infer_cases() {
+            check(
+                &[
+                    (
+                        qualified(generic("T"), Some(path("Iterator", &[])), "Item"),
+                        "<T as Iterator>::Item",
+                    ),
+                    (
+                        qualified(generic("Self"), Some(path("Iterator", &[])), "Item"),
+                        "Self::Item",
+                    ),
+                    // The `F::Output` shorthand names no trait
+                    (
+                        qualified(generic("F"), Some(path("", &[])), "Output"),
+                        "F::Output",
+                    ),
+                    (qualified(generic("Self"), None, "Output"), "Self::Output"),
+                    (
+                        qualified(
+                            path("Vec", &[ty(generic("T"))]),
+                            Some(path("IntoIterator", &[])),
+                            "IntoIter",
+                        ),
+                        "<Vec<T> as IntoIterator>::IntoIter",
+                    ),
+                    (
+                        qualified(
+                            generic("T"),
+                            Some(path("Add", &[ty(generic("Rhs"))])),
+                            "Output",
+                        ),
+                        "<T as Add<Rhs>>::Output",
+                    ),
+                    (
+                        path(
+                            "Option",
+                            &[ty(qualified(generic("F"), Some(path("", &[])), "Output"))],
+                        ),
+                        "Option<F::Output>",
+                    ),
+                    (primitive("never"), "!"),
+                    (
+                        path("Result", &[ty(generic("T")), ty(primitive("never"))]),
+                        "Result<T, !>",
+                    ),
+                    (json!("infer"), "_"),
+                    (path("Vec", &[ty(json!("infer"))]), "Vec<_>"),
+                ],
+                format_type,
+            );
+        }
+
+        fn type_param(name: &str, bounds: &[&Value], default: Option<Value>) -> Value {
+            json!({ "name": name, "kind": { "type": {
+                "bounds": bounds,
+                "default": default,
+                "is_synthetic": false,
+            } } })
+        }
+
+        #[test]
+        fn format_generic_params_cases() {
+            let sized = trait_bound(path("Sized", &[]), &[], "none");
+            let maybe_sized = trait_bound(path("Sized", &[]), &[], "maybe");
+            let clone = trait_bound(path("Clone", &[]), &[], "none");
+            let lifetime = |name: &str, outlives: &[&str]| json!({ "name": name, "kind": { "lifetime": { "outlives": outlives } } });
+            let generics = |params: Vec<Value>| json!({ "params": params, "where_predicates": [] });
+            check(
+                &[
+                    (generics(vec![]), ""),
+                    (
+                        generics(vec![type_param("T", &[&clone], None)]),
+                        "<T: Clone>",
+                    ),
+                    // `Sized` goes without saying, `?Sized` doesn't
+                    (generics(vec![type_param("T", &[&sized], None)]), "<T>"),
+                    (
+                        generics(vec![type_param("T", &[&maybe_sized], None)]),
+                        "<T: ?Sized>",
+                    ),
+                    (
+                        generics(vec![
+                            type_param("K", &[], None),
+                            type_param("S", &[], Some(path("RandomState", &[]))),
+                        ]),
+                        "<K, S = RandomState>",
+                    ),
+                    (
+                        generics(vec![type_param("Rhs", &[&clone], Some(generic("Self")))]),
+                        "<Rhs: Clone = Self>",
+                    ),
+                    (
+                        generics(vec![json!({ "name": "N", "kind": { "const": {
+                            "type": primitive("usize"),
+                            "default": "16",
+                        } } })]),
+                        "<const N: usize = 16>",
+                    ),
+                    (
+                        generics(vec![lifetime("'a", &[]), lifetime("'b", &["'a"])]),
+                        "<'a, 'b: 'a>",
+                    ),
+                    // Elided lifetimes and `impl Trait` arguments aren't declared
+                    (
+                        generics(vec![
+                            lifetime("'_", &[]),
+                            json!({ "name": "impl Read", "kind": { "type": {
+                                "bounds": [], "default": null, "is_synthetic": true,
+                            } } }),
+                            type_param("T", &[], None),
+                        ]),
+                        "<T>",
+                    ),
+                ],
+                |generics| format_generic_params(Some(generics)),
+            );
+        }
+    }
 }
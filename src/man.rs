@@ -0,0 +1,306 @@
+// `zdoc man`: an item's docs laid out like a man page (NAME, SYNOPSIS,
+// DESCRIPTION, EXAMPLES, SEE ALSO), paged in the terminal or written as roff
+use anyhow::{Context, Result};
+use colored::Colorize;
+use serde_json::Value;
+use std::io::{IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+use crate::items::{self, CodeBlock, ResolvedItem};
+use crate::{markdown, signature};
+
+// Man pages indent section bodies by this much
+const INDENT: &str = "       ";
+
+// The page's content, shared by both backends
+struct Page {
+    title: String,
+    crate_name: String,
+    version: Option<String>,
+    summary: String,
+    synopsis: String,
+    // The docs' prose, still markdown
+    description: String,
+    examples: Vec<CodeBlock>,
+    see_also: Vec<String>,
+}
+
+impl Page {
+    fn new(json: &Value, crate_name: &str, target: &ResolvedItem) -> Self {
+        let docs = items::item_docs(target.item).unwrap_or("");
+        let (description, blocks) = items::split_docs(docs);
+        Page {
+            title: target.full_path(),
+            crate_name: crate_name.to_string(),
+            version: json
+                .get("crate_version")
+                .and_then(|v| v.as_str())
+                .map(String::from),
+            summary: items::summary(docs),
+            synopsis: signature::render(json, target.item),
+            description,
+            examples: blocks.into_iter().filter(CodeBlock::is_rust).collect(),
+            see_also: see_also(json, target.item),
+        }
+    }
+}
+
+// The full paths of the item's intra-doc links. Links to members have no
+// entry in the "paths" table, so they're resolved through their owner; links
+// that can't be resolved keep the path they were written with.
+fn see_also(json: &Value, item: &Value) -> Vec<String> {
+    let Some(links) = item.get("links").and_then(|v| v.as_object()) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<String> = links
+        .iter()
+        .map(|(text, id)| {
+            // `fn@parse`, `parse()` and `Vec!` disambiguate the same path
+            let written = text.trim_matches('`');
+            let written = written.split_once('@').map_or(written, |(_, path)| path);
+            let written = written.trim_end_matches("()").trim_end_matches('!');
+            let Some(id) = items::id_key(id) else {
+                return written.to_string();
+            };
+            items::canonical_path(json, &id)
+                .map(|path| path.join("::"))
+                .or_else(|| {
+                    let segments: Vec<&str> = written.split("::").collect();
+                    items::resolve_path(json, &segments)
+                        .into_iter()
+                        .find(|resolved| resolved.id == id)
+                        .map(|resolved| resolved.full_path())
+                })
+                .unwrap_or_else(|| written.to_string())
+        })
+        .collect();
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+fn render_text(page: &Page) -> String {
+    let indent = |text: &str| -> String {
+        text.lines()
+            .map(|line| {
+                if line.is_empty() {
+                    String::new()
+                } else {
+                    format!("{}{}", INDENT, line)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let mut sections = vec![(
+        "NAME",
+        if page.summary.is_empty() {
+            page.title.clone()
+        } else {
+            format!("{} - {}", page.title, page.summary)
+        },
+    )];
+    sections.push(("SYNOPSIS", page.synopsis.clone()));
+    if !page.description.is_empty() {
+        sections.push(("DESCRIPTION", markdown::render(&page.description)));
+    }
+    if !page.examples.is_empty() {
+        let examples: Vec<String> = page
+            .examples
+            .iter()
+            .map(|block| block.visible_code())
+            .collect();
+        sections.push(("EXAMPLES", examples.join("\n\n")));
+    }
+    if !page.see_also.is_empty() {
+        sections.push(("SEE ALSO", page.see_also.join(", ")));
+    }
+
+    let version = page.version.as_deref().unwrap_or("");
+    let mut out = format!("{}  ({} {})\n", page.title.bold(), page.crate_name, version);
+    for (heading, body) in sections {
+        out.push_str(&format!("\n{}\n{}\n", heading.bold(), indent(&body)));
+    }
+    out
+}
+
+// Escape text for roff: backslashes and hyphens, and lines that would
+// otherwise be read as requests
+fn roff_escape(line: &str) -> String {
+    let escaped = line.replace('\\', "\\e").replace('-', "\\-");
+    if escaped.starts_with(['.', '\'']) {
+        format!("\\&{}", escaped)
+    } else {
+        escaped
+    }
+}
+
+// Doc prose as roff: paragraphs become `.PP`, headings `.SS`. A heading
+// is only written once prose follows it, since `# Examples` loses its code
+// blocks to the EXAMPLES section.
+fn roff_prose(markdown: &str, out: &mut Vec<String>) {
+    let mut in_paragraph = false;
+    let mut heading = None;
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            in_paragraph = false;
+            continue;
+        }
+        if let Some(text) = markdown::heading_text(trimmed) {
+            heading = Some(text);
+            in_paragraph = false;
+            continue;
+        }
+        if let Some(text) = heading.take() {
+            out.push(format!(".SS {}", roff_escape(text)));
+        }
+        if !in_paragraph {
+            out.push(".PP".to_string());
+            in_paragraph = true;
+        }
+        out.push(roff_escape(&items::strip_links(trimmed)));
+    }
+}
+
+// Preformatted lines, indented
+fn roff_code(code: &str, out: &mut Vec<String>) {
+    out.push(".RS 4".to_string());
+    out.push(".nf".to_string());
+    out.extend(code.lines().map(roff_escape));
+    out.push(".fi".to_string());
+    out.push(".RE".to_string());
+}
+
+fn render_roff(page: &Page) -> String {
+    let mut out = vec![format!(
+        ".TH \"{}\" 3 \"\" \"{} {}\" \"{}\"",
+        page.title.replace('"', ""),
+        page.crate_name,
+        page.version.as_deref().unwrap_or(""),
+        page.crate_name
+    )];
+
+    out.push(".SH NAME".to_string());
+    if page.summary.is_empty() {
+        out.push(roff_escape(&page.title));
+    } else {
+        out.push(format!(
+            "{} \\- {}",
+            roff_escape(&page.title),
+            roff_escape(&page.summary)
+        ));
+    }
+
+    out.push(".SH SYNOPSIS".to_string());
+    out.push(".nf".to_string());
+    out.extend(page.synopsis.lines().map(roff_escape));
+    out.push(".fi".to_string());
+
+    if !page.description.is_empty() {
+        out.push(".SH DESCRIPTION".to_string());
+        roff_prose(&page.description, &mut out);
+    }
+
+    if !page.examples.is_empty() {
+        out.push(".SH EXAMPLES".to_string());
+        for block in &page.examples {
+            roff_code(&block.visible_code(), &mut out);
+        }
+    }
+
+    if !page.see_also.is_empty() {
+        out.push(".SH SEE ALSO".to_string());
+        let last = page.see_also.len() - 1;
+        for (i, path) in page.see_also.iter().enumerate() {
+            let separator = if i == last { "" } else { "," };
+            out.push(format!(".BR {} (3){}", roff_escape(path), separator));
+        }
+    }
+
+    out.push(String::new());
+    out.join("\n")
+}
+
+// Show text through `$PAGER` (or `less -R`) when stdout is a terminal,
+// falling back to printing it when no pager can be started
+pub fn page_output(text: &str) -> Result<()> {
+    if !std::io::stdout().is_terminal() {
+        print!("{}", text);
+        return Ok(());
+    }
+
+    let pager = std::env::var("PAGER").unwrap_or_else(|_| "less -R".to_string());
+    let mut parts = pager.split_whitespace();
+    let Some(program) = parts.next() else {
+        print!("{}", text);
+        return Ok(());
+    };
+    let Ok(mut child) = Command::new(program)
+        .args(parts)
+        .stdin(Stdio::piped())
+        .spawn()
+    else {
+        print!("{}", text);
+        return Ok(());
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // The pager closing early (the user quit) isn't an error
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    child.wait().context("Failed to wait for the pager")?;
+    Ok(())
+}
+
+// Handler for `zdoc man`
+pub fn run(metadata: &cargo_metadata::Metadata, item_path: &str, roff: bool) -> Result<()> {
+    let located = items::locate(metadata, item_path)?;
+    let target = located.resolved();
+    let page = Page::new(&located.json, &located.crate_name, &target);
+
+    if roff {
+        print!("{}", render_roff(&page));
+        Ok(())
+    } else {
+        page_output(&render_text(&page))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    fn page(path: &str) -> Page {
+        let json = fixtures::load("widgets");
+        let segments: Vec<&str> = path.split("::").collect();
+        let resolved = items::resolve_path(&json, &segments);
+        assert_eq!(resolved.len(), 1, "one item at {}", path);
+        Page::new(&json, "widgets", &resolved[0])
+    }
+
+    #[test]
+    fn roff_page_with_every_section() {
+        insta::assert_snapshot!(render_roff(&page("widgets::Widget")));
+    }
+
+    #[test]
+    fn roff_page_for_a_method() {
+        insta::assert_snapshot!(render_roff(&page("widgets::Widget::new")));
+    }
+
+    #[test]
+    fn roff_page_without_docs() {
+        insta::assert_snapshot!(render_roff(&page("widgets::Shape::Square")));
+    }
+
+    #[test]
+    fn escapes_roff() {
+        assert_eq!(roff_escape("a-b \\ c"), "a\\-b \\e c");
+        assert_eq!(roff_escape(".TH not a request"), "\\&.TH not a request");
+        assert_eq!(roff_escape("'quoted"), "\\&'quoted");
+        assert_eq!(roff_escape("in the .middle"), "in the .middle");
+    }
+}
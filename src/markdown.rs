@@ -0,0 +1,65 @@
+// Minimal terminal rendering of doc comment markdown: bold headings,
+// indented code blocks without rustdoc's hidden lines, and links reduced
+// to their text
+use colored::Colorize;
+
+use crate::items::{self, CodeBlock};
+
+pub fn render(docs: &str) -> String {
+    let mut out = Vec::new();
+    let mut fence: Option<(String, CodeBlock)> = None;
+
+    for line in docs.lines() {
+        let trimmed = line.trim_start();
+        if let Some((marker, block)) = fence.as_mut() {
+            if items::closes_fence(trimmed, marker) {
+                let (_, block) = fence.take().expect("open fence");
+                for code_line in block.visible_code().lines() {
+                    out.push(format!("    {}", code_line).dimmed().to_string());
+                }
+            } else {
+                if !block.code.is_empty() {
+                    block.code.push('\n');
+                }
+                block.code.push_str(line);
+            }
+            continue;
+        }
+
+        if let Some(marker) = items::opening_fence(trimmed) {
+            let info = trimmed[marker.len()..].trim().to_string();
+            fence = Some((
+                marker,
+                CodeBlock {
+                    info,
+                    code: String::new(),
+                    line: 0,
+                    closed: false,
+                },
+            ));
+        } else if let Some(heading) = heading_text(trimmed) {
+            out.push(heading.bold().to_string());
+        } else {
+            out.push(items::strip_links(line));
+        }
+    }
+
+    // An unclosed fence runs to the end, as in CommonMark
+    if let Some((_, block)) = fence {
+        for code_line in block.visible_code().lines() {
+            out.push(format!("    {}", code_line).dimmed().to_string());
+        }
+    }
+
+    out.join("\n")
+}
+
+// The text of an ATX heading (`# Title`, `## Title`, ...)
+pub fn heading_text(line: &str) -> Option<&str> {
+    let hashes = line.chars().take_while(|c| *c == '#').count();
+    if (1..=6).contains(&hashes) {
+        line[hashes..].strip_prefix(' ').map(str::trim)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,393 @@
+// `zdoc outdated`: the project's direct registry dependencies that have a
+// newer release out, each with a verdict on what upgrading would do to its
+// API (`breaking`, `additive`, or `unknown` when either release lacks JSON
+// docs). With `[outdated] notify = true` in `zdoc.toml`, other commands end
+// with a one-line footer when any dependency is behind.
+//
+// Everything is cached in `<cache>/outdated.json`: each crate's published
+// versions for a day, and verdicts for good, since two releases' docs never
+// change (an `unknown` is retried after a day, in case docs.rs caught up).
+// With `--offline` only the cache is used, however old; `--offline` and
+// `--quiet` also silence the footer.
+use anyhow::{Context, Result};
+use cargo_metadata::Metadata;
+use cargo_metadata::semver::Version;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tracing::{debug, info, warn};
+
+use crate::config::Config;
+use crate::ignore::Ignore;
+use crate::sources::{self, Source};
+use crate::style::{self, Role};
+use crate::table::Table;
+use crate::{JsonStyle, SearchFormat, http, registry};
+
+const STATE_FILE: &str = "outdated.json";
+// How long looked-up releases (and `unknown` verdicts) are trusted
+const TTL: Duration = Duration::from_secs(24 * 60 * 60);
+// Index lookups in flight at once, as with `diff --from-lockfile`
+const MAX_LOOKUPS: usize = 16;
+// Releases whose docs are compared at once, as with `diff --batch`
+const MAX_DIFFS: usize = 4;
+
+#[derive(Serialize, Deserialize)]
+struct Releases {
+    // Unix seconds
+    checked: u64,
+    // Every published version, yanked ones aside
+    #[serde(default)]
+    versions: Vec<Version>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Verdict {
+    verdict: String,
+    checked: u64,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct State {
+    releases: BTreeMap<String, Releases>,
+    // `crate old new` -> verdict
+    verdicts: BTreeMap<String, Verdict>,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs())
+}
+
+fn is_fresh(checked: u64) -> bool {
+    now().saturating_sub(checked) < TTL.as_secs()
+}
+
+impl State {
+    fn path() -> PathBuf {
+        crate::cache::root().join(STATE_FILE)
+    }
+
+    // A missing or unreadable state is an empty one; it's only a cache
+    fn load() -> Self {
+        let path = Self::path();
+        let Ok(text) = fs::read_to_string(&path) else {
+            return State::default();
+        };
+        serde_json::from_str(&text).unwrap_or_else(|e| {
+            debug!("Ignoring {}: {}", path.display(), e);
+            State::default()
+        })
+    }
+
+    // Written to a temporary file renamed into place, so concurrent runs
+    // never read half of it
+    fn save(&self) -> Result<()> {
+        let path = Self::path();
+        let dir = crate::cache::root();
+        fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        let mut staged = tempfile::NamedTempFile::new_in(&dir)
+            .with_context(|| format!("Failed to create a temporary file in {}", dir.display()))?;
+        serde_json::to_writer(&mut staged, self).context("Failed to serialize the state")?;
+        staged
+            .persist(&path)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(())
+    }
+}
+
+// A direct dependency with a newer release
+struct Outdated {
+    crate_name: String,
+    locked: Version,
+    newest: Version,
+}
+
+impl Outdated {
+    fn key(&self) -> String {
+        format!("{} {} {}", self.crate_name, self.locked, self.newest)
+    }
+}
+
+// The registry packages the workspace members depend on directly (normal,
+// dev and build dependencies), at their locked versions
+fn direct_deps(metadata: &Metadata) -> BTreeMap<(String, Version), Source> {
+    let mut deps = BTreeMap::new();
+    let Some(resolve) = &metadata.resolve else {
+        return deps;
+    };
+    let members: BTreeSet<_> = metadata.workspace_members.iter().collect();
+    for node in resolve
+        .nodes
+        .iter()
+        .filter(|node| members.contains(&node.id))
+    {
+        for dep in &node.deps {
+            if members.contains(&dep.pkg) {
+                continue;
+            }
+            let Some(package) = metadata.packages.iter().find(|p| p.id == dep.pkg) else {
+                continue;
+            };
+            // Path, git and unconfigured registry dependencies have no
+            // releases to look up
+            if let Some(source) = sources::for_package(package) {
+                deps.insert((package.name.to_string(), package.version.clone()), source);
+            }
+        }
+    }
+    deps
+}
+
+// Look up the releases of the crates whose entry is older than a day, or
+// has no versions (written before they were kept)
+async fn refresh(state: &mut State, crates: BTreeMap<&str, &Source>) -> Result<()> {
+    let stale: Vec<(String, Source)> = crates
+        .into_iter()
+        .filter(|(name, _)| {
+            !state
+                .releases
+                .get(*name)
+                .is_some_and(|r| is_fresh(r.checked) && !r.versions.is_empty())
+        })
+        .map(|(name, source)| (name.to_string(), source.clone()))
+        .collect();
+    if stale.is_empty() || http::is_offline() {
+        return Ok(());
+    }
+    debug!("Looking up the newest releases of {} crates", stale.len());
+    let client = http::client()?;
+    let permits = Arc::new(Semaphore::new(MAX_LOOKUPS));
+    let mut tasks = JoinSet::new();
+    for (name, source) in stale {
+        let (client, permits) = (client.clone(), permits.clone());
+        tasks.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let versions = registry::versions(&client, &source, &name).await;
+            (name, versions)
+        });
+    }
+    while let Some(joined) = tasks.join_next().await {
+        let (name, versions) = joined.context("An index lookup panicked")?;
+        match versions {
+            Ok(versions) => {
+                state.releases.insert(
+                    name,
+                    Releases {
+                        checked: now(),
+                        versions,
+                    },
+                );
+            }
+            Err(e) => debug!("Couldn't look up {}: {:#}", name, e),
+        }
+    }
+    Ok(())
+}
+
+// The direct dependencies with newer releases, by name
+async fn check(metadata: &Metadata, state: &mut State) -> Result<(Vec<Outdated>, usize)> {
+    let deps = direct_deps(metadata);
+    let crates = deps
+        .iter()
+        .map(|((name, _), source)| (name.as_str(), source))
+        .collect();
+    refresh(state, crates).await?;
+
+    let mut outdated = Vec::new();
+    for (name, locked) in deps.keys() {
+        let Some(releases) = state.releases.get(name) else {
+            continue;
+        };
+        // Pre-releases only count as updates to crates locked at one
+        match registry::newest(&releases.versions, !locked.pre.is_empty()) {
+            Some(newest) if newest > locked => outdated.push(Outdated {
+                crate_name: name.clone(),
+                locked: locked.clone(),
+                newest: newest.clone(),
+            }),
+            _ => {}
+        }
+    }
+    Ok((outdated, deps.len()))
+}
+
+// Whether `zdoc.toml` asks for the footer
+pub fn notify_enabled() -> bool {
+    let Some(root) = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| Config::find(&cwd))
+    else {
+        return false;
+    };
+    match Config::load(&root) {
+        Ok(config) => config.outdated.notify,
+        Err(e) => {
+            debug!("Not checking for outdated dependencies: {:#}", e);
+            false
+        }
+    }
+}
+
+// The footer other commands end with: how many direct dependencies are
+// behind, if any. Failures are logged at debug level and otherwise ignored.
+pub async fn notice(metadata: &Metadata) {
+    let mut state = State::load();
+    let outdated = match check(metadata, &mut state).await {
+        Ok((outdated, _)) => outdated,
+        Err(e) => {
+            debug!("Couldn't check for outdated dependencies: {:#}", e);
+            return;
+        }
+    };
+    if let Err(e) = state.save() {
+        debug!("{:#}", e);
+    }
+    let line = match outdated.len() {
+        0 => return,
+        1 => "1 direct dep has a newer version".to_string(),
+        n => format!("{} direct deps have newer versions", n),
+    };
+    eprintln!(
+        "\n{}",
+        style::paint(
+            Role::Muted,
+            &format!("{}; run `zdoc outdated` for API impact", line)
+        )
+    );
+}
+
+// What upgrading from `old` to `new` does to the API
+async fn verdict(crate_name: &str, old: &str, new: &str, ignore: &Ignore) -> Result<&'static str> {
+    let old = crate::stats::public_items(crate_name, old, ignore).await?;
+    let new = crate::stats::public_items(crate_name, new, ignore).await?;
+    let (added, removed, modified) = crate::compare_api_items(old, new);
+    Ok(
+        if crate::breaking_changes(&added, &removed, &modified) > 0 {
+            "breaking"
+        } else {
+            "additive"
+        },
+    )
+}
+
+// Fill in the verdicts not cached yet (or `unknown` for more than a day)
+async fn diff_outdated(state: &mut State, outdated: &[Outdated]) -> Result<()> {
+    let pending: Vec<&Outdated> = outdated
+        .iter()
+        .filter(|dep| match state.verdicts.get(&dep.key()) {
+            Some(verdict) => verdict.verdict == "unknown" && !is_fresh(verdict.checked),
+            None => true,
+        })
+        .collect();
+    if pending.is_empty() || http::is_offline() {
+        return Ok(());
+    }
+    info!(
+        "Comparing the APIs of {} dependencies with their newest releases...",
+        pending.len()
+    );
+    let ignore = Arc::new(Ignore::load_nearest()?);
+    let permits = Arc::new(Semaphore::new(MAX_DIFFS));
+    let mut tasks = JoinSet::new();
+    for dep in pending {
+        let (ignore, permits) = (ignore.clone(), permits.clone());
+        let (key, crate_name) = (dep.key(), dep.crate_name.clone());
+        let (old, new) = (dep.locked.to_string(), dep.newest.to_string());
+        tasks.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let verdict = verdict(&crate_name, &old, &new, &ignore).await;
+            (key, verdict)
+        });
+    }
+    while let Some(joined) = tasks.join_next().await {
+        let (key, verdict) = joined.context("An outdated diff task panicked")?;
+        let verdict = verdict.unwrap_or_else(|e| {
+            debug!("No verdict for {}: {:#}", key, e);
+            "unknown"
+        });
+        state.verdicts.insert(
+            key,
+            Verdict {
+                verdict: verdict.to_string(),
+                checked: now(),
+            },
+        );
+    }
+    Ok(())
+}
+
+pub async fn run(metadata: &Metadata, format: SearchFormat, json_style: JsonStyle) -> Result<()> {
+    let mut state = State::load();
+    let (outdated, total) = check(metadata, &mut state).await?;
+    diff_outdated(&mut state, &outdated).await?;
+    if let Err(e) = state.save() {
+        warn!("{:#}", e);
+    }
+    if http::is_offline() && total > 0 && state.releases.is_empty() {
+        warn!("Nothing is cached yet; run `zdoc outdated` once without --offline");
+    }
+
+    let verdict = |dep: &Outdated| {
+        state
+            .verdicts
+            .get(&dep.key())
+            .map_or("unknown", |verdict| verdict.verdict.as_str())
+    };
+    if format == SearchFormat::Json {
+        let deps: Vec<Value> = outdated
+            .iter()
+            .map(|dep| {
+                serde_json::json!({
+                    "crate": dep.crate_name,
+                    "locked": dep.locked.to_string(),
+                    "latest": dep.newest.to_string(),
+                    "verdict": verdict(dep),
+                })
+            })
+            .collect();
+        return crate::print_json(
+            &serde_json::json!({ "outdated": deps, "direct_dependencies": total }),
+            json_style,
+        );
+    }
+
+    if outdated.is_empty() {
+        println!(
+            "All {} direct registry dependencies are at their newest release.",
+            total
+        );
+        return Ok(());
+    }
+    let mut table = Table::new(&["Crate", "Locked", "Latest", "Verdict"]);
+    for dep in &outdated {
+        table.add_row(vec![
+            dep.crate_name.clone(),
+            dep.locked.to_string(),
+            dep.newest.to_string(),
+            verdict(dep).to_string(),
+        ]);
+    }
+    println!(
+        "\n{} of {} direct dependencies have newer versions:\n",
+        outdated.len(),
+        total
+    );
+    print!("{}", table.render());
+    println!(
+        "\n{}",
+        style::paint(
+            Role::Muted,
+            "`zdoc diff <crate> <locked> <latest>` lists the changes"
+        )
+    );
+    Ok(())
+}
@@ -0,0 +1,210 @@
+// What changed about how a workspace crate is packaged since its last
+// release, for `diff --local`: features added, removed or switched on or off
+// by default, dependencies the public API exposes moving to an incompatible
+// version (breaking for the crate's users too), and the MSRV. The release's
+// side comes from its registry index entry, the working tree's from
+// `cargo metadata`.
+use cargo_metadata::semver::VersionReq;
+use cargo_metadata::{DependencyKind, Package};
+use colored::Colorize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::registry::Release;
+use crate::style::{self, Role};
+
+pub struct Changes {
+    since: String,
+    features_added: Vec<String>,
+    features_removed: Vec<String>,
+    // Features that are now on by default, or no longer are
+    default_added: Vec<String>,
+    default_removed: Vec<String>,
+    // Exposed dependencies now required at an incompatible version:
+    // name, the release's requirement and the working tree's
+    public_bumps: Vec<(String, String, String)>,
+    msrv: Option<(Option<String>, Option<String>)>,
+}
+
+// A release's features as its index entry lists them, with the implicit
+// ones of optional dependencies
+pub fn release_features(release: &Release) -> BTreeMap<String, Vec<String>> {
+    let mut features = release.features.clone();
+    features.extend(release.features2.clone());
+    let optional = release.deps.iter().filter(|dep| dep.optional);
+    with_implicit_features(features, optional.map(|dep| dep.name.as_str()))
+}
+
+// Cargo gives each optional dependency a feature of its own unless some
+// feature enables it with `dep:`
+fn with_implicit_features<'a>(
+    mut features: BTreeMap<String, Vec<String>>,
+    optional: impl Iterator<Item = &'a str>,
+) -> BTreeMap<String, Vec<String>> {
+    for name in optional {
+        let explicit = format!("dep:{}", name);
+        let referenced = features.values().flatten().any(|value| *value == explicit);
+        if !referenced && !features.contains_key(name) {
+            features.insert(name.to_string(), vec![explicit]);
+        }
+    }
+    features
+}
+
+// The part of a requirement semver says stays compatible: `1` for `1.2`,
+// `0.3` for `0.3.1`, `0.0.4` for `0.0.4`
+fn compatible_range(req: &str) -> Option<String> {
+    let req = VersionReq::parse(req).ok()?;
+    let first = req.comparators.first()?;
+    Some(match (first.major, first.minor, first.patch) {
+        (0, Some(0), Some(patch)) => format!("0.0.{}", patch),
+        (0, Some(minor), _) => format!("0.{}", minor),
+        (major, _, _) => major.to_string(),
+    })
+}
+
+// `1.70` and `1.70.0` are the same MSRV
+fn normalize_msrv(version: &str) -> String {
+    let version = version.trim();
+    version
+        .strip_suffix(".0")
+        .filter(|short| short.matches('.').count() == 1)
+        .unwrap_or(version)
+        .to_string()
+}
+
+fn difference(left: &BTreeSet<String>, right: &BTreeSet<String>) -> Vec<String> {
+    left.difference(right).cloned().collect()
+}
+
+impl Changes {
+    // Compare the `published` release with the working tree's `package`,
+    // whose API exposes the packages named in `exposed`
+    pub fn between(published: &Release, package: &Package, exposed: &BTreeSet<String>) -> Self {
+        let old_features = release_features(published);
+        let new_optional = package.dependencies.iter().filter(|dep| dep.optional);
+        let new_features = with_implicit_features(
+            package.features.clone(),
+            new_optional.map(|dep| dep.rename.as_deref().unwrap_or(&dep.name)),
+        );
+
+        let names = |features: &BTreeMap<String, Vec<String>>| -> BTreeSet<String> {
+            features
+                .keys()
+                .filter(|name| *name != "default")
+                .cloned()
+                .collect()
+        };
+        let (old_names, new_names) = (names(&old_features), names(&new_features));
+        let defaults = |features: &BTreeMap<String, Vec<String>>| -> BTreeSet<String> {
+            features
+                .get("default")
+                .into_iter()
+                .flatten()
+                .cloned()
+                .collect()
+        };
+        let (old_default, new_default) = (defaults(&old_features), defaults(&new_features));
+
+        let mut public_bumps = Vec::new();
+        for name in exposed {
+            let old = published.deps.iter().find(|dep| {
+                dep.package_name() == name
+                    && dep.kind.as_deref().is_none_or(|kind| kind == "normal")
+            });
+            let new = package
+                .dependencies
+                .iter()
+                .find(|dep| dep.name == *name && dep.kind == DependencyKind::Normal);
+            let (Some(old), Some(new)) = (old, new) else {
+                continue;
+            };
+            let new_req = new.req.to_string();
+            if compatible_range(&old.req) != compatible_range(&new_req) {
+                public_bumps.push((name.clone(), old.req.clone(), new_req));
+            }
+        }
+
+        let old_msrv = published.rust_version.as_deref().map(normalize_msrv);
+        let new_msrv = package
+            .rust_version
+            .as_ref()
+            .map(|version| normalize_msrv(&version.to_string()));
+        Changes {
+            since: published.vers.clone(),
+            features_added: difference(&new_names, &old_names),
+            features_removed: difference(&old_names, &new_names),
+            default_added: difference(&new_default, &old_default),
+            default_removed: difference(&old_default, &new_default),
+            public_bumps,
+            msrv: (old_msrv != new_msrv).then_some((old_msrv, new_msrv)),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.features_added.is_empty()
+            && self.features_removed.is_empty()
+            && self.default_added.is_empty()
+            && self.default_removed.is_empty()
+            && self.public_bumps.is_empty()
+            && self.msrv.is_none()
+    }
+
+    pub fn print(&self) {
+        if self.is_empty() {
+            let line = format!("No packaging changes since {}.", self.since);
+            println!("{}", style::paint(Role::Muted, &line));
+            return;
+        }
+        println!(
+            "{}",
+            format!("Packaging changes since {}:", self.since).bold()
+        );
+        for name in &self.features_removed {
+            let line = format!("- feature {}", name);
+            println!("  {}", style::paint(Role::Removed, &line));
+        }
+        for name in &self.features_added {
+            let line = format!("+ feature {}", name);
+            println!("  {}", style::paint(Role::Added, &line));
+        }
+        for name in &self.default_removed {
+            let line = format!("~ {} is no longer a default feature", name);
+            println!("  {}", style::paint(Role::Modified, &line));
+        }
+        for name in &self.default_added {
+            let line = format!("~ {} is now a default feature", name);
+            println!("  {}", style::paint(Role::Modified, &line));
+        }
+        for (name, old, new) in &self.public_bumps {
+            let line = format!("~ public dependency {}: {} -> {}", name, old, new);
+            println!(
+                "  {} ({})",
+                style::paint(Role::Modified, &line),
+                style::paint(Role::Removed, "breaking")
+            );
+        }
+        if let Some((old, new)) = &self.msrv {
+            let show = |msrv: &Option<String>| msrv.clone().unwrap_or_else(|| "none".to_string());
+            let line = format!("~ MSRV: {} -> {}", show(old), show(new));
+            println!("  {}", style::paint(Role::Modified, &line));
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        let bumps: Vec<Value> = self
+            .public_bumps
+            .iter()
+            .map(|(name, old, new)| serde_json::json!({ "crate": name, "from": old, "to": new }))
+            .collect();
+        serde_json::json!({
+            "since": self.since,
+            "features_added": self.features_added,
+            "features_removed": self.features_removed,
+            "default_added": self.default_added,
+            "default_removed": self.default_removed,
+            "public_dependency_bumps": bumps,
+            "msrv": self.msrv.as_ref().map(|(old, new)| serde_json::json!({ "from": old, "to": new })),
+        })
+    }
+}
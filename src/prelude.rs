@@ -0,0 +1,390 @@
+// `zdoc prelude`: the names a `use module::*` brings into scope, with glob
+// re-exports and nested preludes flattened
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+
+use crate::items;
+use crate::table::Table;
+
+// One name a glob import of the module brings into scope
+pub struct PreludeEntry {
+    pub name: String,
+    pub kind: String,
+    // Where the item is defined, not where it was re-exported from
+    pub path: String,
+    // The id of the item, or its path when the id isn't known
+    target: String,
+}
+
+impl PreludeEntry {
+    // Names only clash within a namespace: `Debug` the trait and `Debug`
+    // the derive macro can both be imported
+    fn namespace(&self) -> &'static str {
+        match self.kind.as_str() {
+            "function" | "constant" | "static" => "value",
+            "macro" | "proc_macro" | "proc_attribute" | "proc_derive" => "macro",
+            _ => "type",
+        }
+    }
+}
+
+// The flattened contents of one prelude module
+pub struct Prelude {
+    pub path: String,
+    pub entries: Vec<PreludeEntry>,
+    // Several different items reachable under the same name
+    pub collisions: Vec<(String, Vec<String>)>,
+    // Glob re-exports of modules from other crates, whose contents aren't in these docs
+    pub unexpanded: Vec<String>,
+}
+
+// Describe the item a `use` (or a module member) points at
+fn entry_for(json: &Value, name: &str, id: Option<String>, source: &str) -> PreludeEntry {
+    let item = id.as_deref().and_then(|id| items::lookup(json, id));
+    let summary = id
+        .as_deref()
+        .and_then(|id| json.get("paths").and_then(|paths| paths.get(id)));
+    let kind = item
+        .and_then(items::item_kind)
+        .or_else(|| summary.and_then(|s| s.get("kind")).and_then(|v| v.as_str()))
+        .unwrap_or("item");
+    let path = id
+        .as_deref()
+        .and_then(|id| items::canonical_path(json, id))
+        .map(|path| path.join("::"))
+        .unwrap_or_else(|| source.to_string());
+
+    PreludeEntry {
+        name: name.to_string(),
+        kind: kind.to_string(),
+        target: id.unwrap_or_else(|| path.clone()),
+        path,
+    }
+}
+
+// Collect what `use module::*` imports from `module_id`. Names the module
+// defines or imports explicitly shadow names that arrive through its globs.
+fn expand(
+    json: &Value,
+    module_id: &str,
+    visited: &mut HashSet<String>,
+    unexpanded: &mut Vec<String>,
+) -> Vec<PreludeEntry> {
+    if !visited.insert(module_id.to_string()) {
+        return Vec::new();
+    }
+    let Some(module) = items::lookup(json, module_id) else {
+        return Vec::new();
+    };
+    let member_ids: Vec<String> = items::item_inner(module)
+        .and_then(|inner| inner.get("items"))
+        .and_then(|v| v.as_array())
+        .map(|ids| ids.iter().filter_map(items::id_key).collect())
+        .unwrap_or_default();
+
+    let mut explicit = Vec::new();
+    let mut globbed = Vec::new();
+    for member_id in member_ids {
+        let Some(member) = items::lookup(json, &member_id) else {
+            continue;
+        };
+        let kind = items::item_kind(member);
+        if !matches!(kind, Some("use" | "import")) {
+            if let Some(name) = items::item_name(member) {
+                explicit.push(entry_for(json, name, Some(member_id), name));
+            }
+            continue;
+        }
+
+        let Some(inner) = items::item_inner(member) else {
+            continue;
+        };
+        let source = inner.get("source").and_then(|v| v.as_str()).unwrap_or("?");
+        let target_id = inner.get("id").and_then(items::id_key);
+        let is_glob = inner
+            .get("is_glob")
+            .or_else(|| inner.get("glob"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        if !is_glob {
+            let name = inner.get("name").and_then(|v| v.as_str()).unwrap_or(source);
+            explicit.push(entry_for(json, name, target_id, source));
+            continue;
+        }
+
+        let target = target_id
+            .as_deref()
+            .and_then(|id| items::lookup(json, id).map(|item| (id, item)));
+        match target {
+            Some((id, item)) if items::item_kind(item) == Some("module") => {
+                globbed.extend(expand(json, id, visited, unexpanded));
+            }
+            // `use Enum::*` brings in the variants
+            Some((id, item)) if items::item_kind(item) == Some("enum") => {
+                let enum_path = items::canonical_path(json, id)
+                    .map(|path| path.join("::"))
+                    .unwrap_or_else(|| source.to_string());
+                for variant_id in items::own_member_ids(items::item_inner(item).unwrap_or(item)) {
+                    let Some(name) = items::lookup(json, &variant_id).and_then(items::item_name)
+                    else {
+                        continue;
+                    };
+                    let mut entry = entry_for(json, name, Some(variant_id), name);
+                    entry.path = format!("{}::{}", enum_path, name);
+                    globbed.push(entry);
+                }
+            }
+            _ => unexpanded.push(source.to_string()),
+        }
+    }
+
+    let shadowed: HashSet<(String, &str)> = explicit
+        .iter()
+        .map(|entry| (entry.name.clone(), entry.namespace()))
+        .collect();
+    globbed.retain(|entry| !shadowed.contains(&(entry.name.clone(), entry.namespace())));
+    explicit.extend(globbed);
+    explicit
+}
+
+// Flatten the module with id `module_id`
+pub fn flatten(json: &Value, module_id: &str) -> Prelude {
+    let mut unexpanded = Vec::new();
+    let mut entries = expand(json, module_id, &mut HashSet::new(), &mut unexpanded);
+
+    entries.sort_by(|a, b| {
+        (a.name.as_str(), a.namespace(), a.path.as_str()).cmp(&(
+            b.name.as_str(),
+            b.namespace(),
+            b.path.as_str(),
+        ))
+    });
+    // The same item reached through two globs is one name, not a clash
+    entries.dedup_by(|a, b| a.name == b.name && a.target == b.target);
+
+    let mut by_name: BTreeMap<(String, &str), Vec<String>> = BTreeMap::new();
+    for entry in &entries {
+        by_name
+            .entry((entry.name.clone(), entry.namespace()))
+            .or_default()
+            .push(entry.path.clone());
+    }
+    let collisions = by_name
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|((name, _), paths)| (name, paths))
+        .collect();
+
+    unexpanded.sort();
+    unexpanded.dedup();
+
+    Prelude {
+        path: items::canonical_path(json, module_id)
+            .map(|path| path.join("::"))
+            .unwrap_or_else(|| module_id.to_string()),
+        entries,
+        collisions,
+        unexpanded,
+    }
+}
+
+// Ids of the modules to flatten: those matching `module_path`, or every
+// module named `prelude` (not the crate root of a crate by that name)
+pub fn find_modules(json: &Value, module_path: Option<&str>) -> Vec<String> {
+    if let Some(module_path) = module_path {
+        let segments: Vec<&str> = module_path.split("::").collect();
+        return items::resolve_path(json, &segments)
+            .into_iter()
+            .filter(|resolved| items::item_kind(resolved.item) == Some("module"))
+            .map(|resolved| resolved.id)
+            .collect();
+    }
+
+    let Some(index) = json.get("index").and_then(|v| v.as_object()) else {
+        return Vec::new();
+    };
+    let root = json.get("root").and_then(items::id_key);
+    let mut ids: Vec<(String, String)> = index
+        .iter()
+        .filter(|(id, item)| {
+            items::item_kind(item) == Some("module")
+                && items::item_name(item) == Some("prelude")
+                && root.as_deref() != Some(id.as_str())
+        })
+        .map(|(id, _)| {
+            let path = items::canonical_path(json, id)
+                .map(|path| path.join("::"))
+                .unwrap_or_default();
+            (path, id.clone())
+        })
+        .collect();
+    ids.sort();
+    ids.into_iter().map(|(_, id)| id).collect()
+}
+
+pub fn display_prelude(prelude: &Prelude) {
+    println!(
+        "\n{} brings {} names into scope:\n",
+        prelude.path,
+        prelude.entries.len()
+    );
+
+    let clashing: HashSet<&str> = prelude
+        .collisions
+        .iter()
+        .flat_map(|(_, paths)| paths.iter().map(String::as_str))
+        .collect();
+    let mut table = Table::new(&["Name", "Kind", "Defined at", "Note"]);
+    for entry in &prelude.entries {
+        let note = if clashing.contains(entry.path.as_str()) {
+            "collision"
+        } else {
+            ""
+        };
+        table.add_row(vec![
+            entry.name.clone(),
+            entry.kind.clone(),
+            entry.path.clone(),
+            note.to_string(),
+        ]);
+    }
+    print!("{}", table.render());
+
+    if !prelude.collisions.is_empty() {
+        println!("\nCollisions (a glob import makes these names ambiguous):");
+        for (name, paths) in &prelude.collisions {
+            println!("  {}: {}", name, paths.join(", "));
+        }
+    }
+
+    if !prelude.unexpanded.is_empty() {
+        println!("\nGlob re-exports from other crates (not expanded):");
+        for source in &prelude.unexpanded {
+            println!("  {}::*", source);
+        }
+    }
+}
+
+// Handler for `zdoc prelude`
+pub fn run(json: &Value, crate_name: &str, module_path: Option<&str>) -> Result<()> {
+    let module_ids = find_modules(json, module_path);
+    if module_ids.is_empty() {
+        match module_path {
+            Some(path) => anyhow::bail!("No module matching '{}' in {}", path, crate_name),
+            None => anyhow::bail!(
+                "{} has no module named `prelude`; pass one with --module",
+                crate_name
+            ),
+        }
+    }
+
+    for module_id in module_ids {
+        display_prelude(&flatten(json, &module_id));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    fn prelude(json: &Value, path: &str) -> Prelude {
+        let ids = find_modules(json, Some(path));
+        assert_eq!(ids.len(), 1, "one module at {}", path);
+        flatten(json, &ids[0])
+    }
+
+    fn names(prelude: &Prelude) -> Vec<(&str, &str, &str)> {
+        prelude
+            .entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.name.as_str(),
+                    entry.kind.as_str(),
+                    entry.path.as_str(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn finds_every_prelude_module() {
+        let json = fixtures::load("prelude");
+        let paths: Vec<String> = find_modules(&json, None)
+            .iter()
+            .map(|id| flatten(&json, id).path)
+            .collect();
+        assert_eq!(
+            paths,
+            ["prelude::nested::prelude", "prelude::prelude"].map(String::from)
+        );
+    }
+
+    #[test]
+    fn flattens_nested_and_glob_reexports() {
+        let json = fixtures::load("prelude");
+        let flattened = prelude(&json, "prelude::prelude");
+        assert_eq!(
+            names(&flattened),
+            [
+                ("Blue", "struct", "prelude::colors::Blue"),
+                ("Blue", "function", "prelude::colors::Blue"),
+                ("Circle", "struct", "prelude::shapes::Circle"),
+                ("Dark", "variant", "prelude::colors::Tone::Dark"),
+                ("Draw", "trait", "prelude::nested::prelude::Draw"),
+                ("Light", "variant", "prelude::colors::Tone::Light"),
+                ("Red", "struct", "prelude::colors::Red"),
+                ("Square", "struct", "prelude::colors::Square"),
+                ("Square", "struct", "prelude::shapes::Square"),
+                ("Tone", "enum", "prelude::colors::Tone"),
+                // The prelude's own `area` shadows the one from `shapes::*`
+                ("area", "function", "prelude::prelude::area"),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_collisions_within_a_namespace() {
+        let json = fixtures::load("prelude");
+        let flattened = prelude(&json, "prelude::prelude");
+        // `Circle` comes through two globs but is one item; `Blue` is a type
+        // and a function
+        assert_eq!(
+            flattened.collisions,
+            [(
+                "Square".to_string(),
+                vec![
+                    "prelude::colors::Square".to_string(),
+                    "prelude::shapes::Square".to_string()
+                ]
+            )]
+        );
+    }
+
+    #[test]
+    fn leaves_other_crates_globs_unexpanded() {
+        let json = fixtures::load("prelude");
+        assert_eq!(prelude(&json, "prelude::prelude").unexpanded, ["core::cmp"]);
+        assert!(
+            prelude(&json, "prelude::nested::prelude")
+                .unexpanded
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn explicit_reexports_keep_their_definition_path() {
+        let json = fixtures::load("prelude");
+        assert_eq!(
+            names(&prelude(&json, "prelude::nested::prelude")),
+            [
+                ("Circle", "struct", "prelude::shapes::Circle"),
+                ("Draw", "trait", "prelude::nested::prelude::Draw"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,367 @@
+// `zdoc public-deps`: the dependencies whose types and traits a crate's
+// public API exposes, in parameters, return types, public fields, bounds
+// and trait impls. Bumping one of those to a new major version is a breaking
+// change for the crate too, since callers name its types, so each is listed
+// with the items that expose it and the version requirement on it, and
+// pre-1.0 or wildcard requirements are flagged.
+//
+// Every public item's declaration is walked for paths to other items; a
+// path's id leads to the crate it's defined in (`paths` and
+// `external_crates` in the rustdoc JSON), and that crate's name is matched
+// with the package's dependencies by their library's name. Rustdoc uses
+// that name even for a dependency the package renames in `Cargo.toml`.
+use anyhow::Result;
+use cargo_metadata::semver::{Op, VersionReq};
+use cargo_metadata::{DependencyKind, Metadata, Package};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::style::{self, Role};
+use crate::{JsonStyle, SearchFormat, items};
+
+// Crates that ship with Rust, never dependencies
+const SYSROOT_CRATES: &[&str] = &["std", "core", "alloc", "proc_macro", "test"];
+
+// Ids of the items `value`'s types and bounds name
+fn referenced_ids(value: &Value, ids: &mut BTreeSet<String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                // Type paths (`resolved_path`) and trait paths (`trait` in
+                // bounds and impls) carry the id of what they name
+                if matches!(key.as_str(), "resolved_path" | "trait")
+                    && let Some(id) = value.get("id").and_then(items::id_key)
+                {
+                    ids.insert(id);
+                }
+                referenced_ids(value, ids);
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                referenced_ids(value, ids);
+            }
+        }
+        _ => {}
+    }
+}
+
+// The name rustdoc knows an item's crate by, for items from other crates
+fn external_crate(json: &Value, id: &str) -> Option<String> {
+    let crate_id = json.get("paths")?.get(id)?.get("crate_id")?;
+    if crate_id.as_u64() == Some(0) {
+        return None;
+    }
+    let key = crate_id.to_string();
+    let name = json
+        .get("external_crates")?
+        .get(&key)?
+        .get("name")?
+        .as_str()?;
+    Some(name.to_string())
+}
+
+// Extern crate name -> the public items whose declarations name something
+// from it. Hidden and ignored items aren't part of the API, so don't count.
+pub fn exposed(json: &Value) -> Result<BTreeMap<String, BTreeSet<String>>> {
+    let mut api = crate::extract_api_items(json)?;
+    api.retain(|item| !item.stability.contains(&"doc(hidden)"));
+    let api = crate::ignore::Ignore::load_nearest()?
+        .partition(api, crate::ApiItem::full_path)
+        .0;
+    let mut exposed: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for item in &api {
+        let Some(inner) = items::lookup(json, &item.id).and_then(items::item_inner) else {
+            continue;
+        };
+        let mut ids = BTreeSet::new();
+        referenced_ids(inner, &mut ids);
+        for id in ids {
+            let Some(krate) = external_crate(json, &id) else {
+                continue;
+            };
+            if SYSROOT_CRATES.contains(&krate.as_str()) {
+                continue;
+            }
+            exposed.entry(krate).or_default().insert(item.full_path());
+        }
+    }
+    Ok(exposed)
+}
+
+// Crate name, as rustdoc knows it -> the package it is, for `package`'s
+// dependencies
+pub fn dependency_packages<'a>(
+    metadata: &'a Metadata,
+    package: &Package,
+) -> BTreeMap<String, &'a Package> {
+    let Some(node) = metadata
+        .resolve
+        .as_ref()
+        .and_then(|resolve| resolve.nodes.iter().find(|node| node.id == package.id))
+    else {
+        return BTreeMap::new();
+    };
+    node.deps
+        .iter()
+        .filter_map(|dep| {
+            let dependency = metadata.packages.iter().find(|p| p.id == dep.pkg)?;
+            let library = dependency
+                .targets
+                .iter()
+                .find(|target| crate::doc_targets::is_library(target))?;
+            Some((library.name.clone(), dependency))
+        })
+        .collect()
+}
+
+// The packages among `package`'s dependencies that its API exposes, by name
+pub fn exposed_packages(
+    metadata: &Metadata,
+    package: &Package,
+    json: &Value,
+) -> Result<BTreeMap<String, BTreeSet<String>>> {
+    let packages = dependency_packages(metadata, package);
+    Ok(exposed(json)?
+        .into_iter()
+        .filter_map(|(krate, items)| Some((packages.get(&krate)?.name.to_string(), items)))
+        .collect())
+}
+
+// Why exposing a dependency at `req` is a hazard: a pre-1.0 version may
+// break in any minor release, a wildcard in any release at all
+fn hazard(req: &VersionReq) -> Option<&'static str> {
+    let wildcard =
+        req.comparators.is_empty() || req.comparators.iter().any(|c| c.op == Op::Wildcard);
+    if wildcard {
+        Some("wildcard")
+    } else if req.comparators.iter().any(|c| c.major == 0) {
+        Some("pre-1.0")
+    } else {
+        None
+    }
+}
+
+// Each exposed dependency with `package`'s requirement on it, if it's a
+// direct one
+struct Exposed<'a> {
+    name: String,
+    req: Option<&'a VersionReq>,
+    items: BTreeSet<String>,
+    denied: bool,
+}
+
+// Handler for `zdoc public-deps`. With `check`, exposing a dependency on
+// the `[public-deps] deny` list in zdoc.toml is an error.
+pub fn run(
+    metadata: &Metadata,
+    package: &Package,
+    json: &Value,
+    check: bool,
+    format: SearchFormat,
+    json_style: JsonStyle,
+) -> Result<()> {
+    let config = crate::config::Config::load(metadata.workspace_root.as_std_path())?;
+    let deny = &config.public_deps.deny;
+    let exposed: Vec<Exposed> = exposed_packages(metadata, package, json)?
+        .into_iter()
+        .map(|(name, items)| Exposed {
+            req: package
+                .dependencies
+                .iter()
+                .find(|dep| dep.name == name && dep.kind == DependencyKind::Normal)
+                .map(|dep| &dep.req),
+            denied: deny.contains(&name),
+            name,
+            items,
+        })
+        .collect();
+
+    if format == SearchFormat::Json {
+        let deps: Vec<Value> = exposed
+            .iter()
+            .map(|dep| {
+                serde_json::json!({
+                    "crate": dep.name,
+                    "req": dep.req.map(VersionReq::to_string),
+                    "hazard": dep.req.and_then(hazard),
+                    "denied": dep.denied,
+                    "items": dep.items,
+                })
+            })
+            .collect();
+        crate::print_json(
+            &serde_json::json!({ "crate": package.name, "exposed": deps }),
+            json_style,
+        )?;
+    } else if exposed.is_empty() {
+        println!(
+            "{}'s public API doesn't expose any of its dependencies.",
+            package.name
+        );
+    } else {
+        println!(
+            "\n{} dependencies exposed by {}'s public API:\n",
+            exposed.len(),
+            package.name
+        );
+        for dep in &exposed {
+            let noun = if dep.items.len() == 1 {
+                "item"
+            } else {
+                "items"
+            };
+            let mut line = dep.name.clone();
+            if let Some(req) = dep.req {
+                line.push_str(&format!(" {}", req));
+            }
+            line.push_str(&format!(" ({} {})", dep.items.len(), noun));
+            let mut flags: Vec<&str> = dep.req.and_then(hazard).into_iter().collect();
+            if dep.denied {
+                flags.push("denied");
+            }
+            if flags.is_empty() {
+                println!("  {}", line);
+            } else {
+                let flags = format!("[{}]", flags.join(", "));
+                println!("  {} {}", line, style::paint(Role::Removed, &flags));
+            }
+            for item in &dep.items {
+                println!("    {}", style::paint(Role::Path, item));
+            }
+        }
+    }
+
+    let denied: Vec<&str> = exposed
+        .iter()
+        .filter(|dep| dep.denied)
+        .map(|dep| dep.name.as_str())
+        .collect();
+    if check && !denied.is_empty() {
+        anyhow::bail!(
+            "{}'s public API exposes denied dependencies: {}",
+            package.name,
+            denied.join(", ")
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use std::path::Path;
+
+    // The `leaky` fixture exposes `clock` (0.2) in return types, a bound
+    // and a trait impl, `units` (1.4, renamed `metric`) in a parameter and
+    // a field, and `anything` (`*`) in a return type. `quiet` is only used
+    // in a body, a private field and a hidden function.
+    fn leaky() -> Value {
+        fixtures::load("leaky")
+    }
+
+    fn copy_dir(from: &Path, to: &Path) {
+        std::fs::create_dir_all(to).unwrap();
+        for entry in std::fs::read_dir(from).unwrap() {
+            let entry = entry.unwrap();
+            let target = to.join(entry.file_name());
+            if entry.file_type().unwrap().is_dir() {
+                copy_dir(&entry.path(), &target);
+            } else {
+                std::fs::copy(entry.path(), target).unwrap();
+            }
+        }
+    }
+
+    // Cargo metadata for a copy of the fixture crate and its path
+    // dependencies, so the lockfile cargo writes stays out of the tree
+    fn leaky_metadata() -> (tempfile::TempDir, Metadata) {
+        let dir = tempfile::tempdir().unwrap();
+        copy_dir(&fixtures::path("leaky").with_extension(""), dir.path());
+        let metadata = cargo_metadata::MetadataCommand::new()
+            .manifest_path(dir.path().join("Cargo.toml"))
+            .other_options(["--offline".to_string()])
+            .exec()
+            .unwrap();
+        (dir, metadata)
+    }
+
+    fn names(items: &BTreeSet<String>) -> Vec<&str> {
+        items.iter().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn finds_each_kind_of_leak() {
+        let exposed = exposed(&leaky()).unwrap();
+        let crates: Vec<&str> = exposed.keys().map(String::as_str).collect();
+        assert_eq!(crates, ["anything", "clock", "units"]);
+        assert_eq!(names(&exposed["anything"]), ["leaky::whatever"]);
+        assert_eq!(
+            names(&exposed["clock"]),
+            [
+                "leaky::<Timer as Tick>",
+                "leaky::Trip::started",
+                "leaky::now",
+                "leaky::schedule",
+            ]
+        );
+        assert_eq!(
+            names(&exposed["units"]),
+            ["leaky::Trip::length", "leaky::walk"]
+        );
+    }
+
+    #[test]
+    fn matches_renamed_dependencies() {
+        let (_dir, metadata) = leaky_metadata();
+        let package = metadata.root_package().unwrap();
+        let packages = exposed_packages(&metadata, package, &leaky()).unwrap();
+        let names: Vec<&str> = packages.keys().map(String::as_str).collect();
+        assert_eq!(names, ["anything", "clock", "units"]);
+    }
+
+    #[test]
+    fn flags_hazardous_requirements() {
+        let cases = [
+            ("*", Some("wildcard")),
+            ("1.*", Some("wildcard")),
+            ("0.2", Some("pre-1.0")),
+            ("~0.2.3", Some("pre-1.0")),
+            ("=0.0.1", Some("pre-1.0")),
+            ("1.4", None),
+            (">=1, <3", None),
+        ];
+        for (req, expected) in cases {
+            assert_eq!(
+                hazard(&VersionReq::parse(req).unwrap()),
+                expected,
+                "{}",
+                req
+            );
+        }
+    }
+
+    // `zdoc.toml` beside the fixture denies `anything`
+    #[test]
+    fn check_fails_on_denied_dependencies() {
+        let (_dir, metadata) = leaky_metadata();
+        let package = metadata.root_package().unwrap();
+        let run = |check| {
+            run(
+                &metadata,
+                package,
+                &leaky(),
+                check,
+                SearchFormat::Json,
+                JsonStyle::Compact,
+            )
+        };
+        let error = run(true).expect_err("anything is denied");
+        assert_eq!(
+            error.to_string(),
+            "leaky's public API exposes denied dependencies: anything"
+        );
+        run(false).unwrap();
+    }
+}
@@ -0,0 +1,202 @@
+// `zdoc query`: pick values out of a crate's rustdoc JSON, for looking at
+// what the typed extractors don't handle yet. Expressions are a JSONPath
+// subset or a JSON Pointer:
+//
+//     $.index['0:42'].inner      a member, quoted when it isn't an identifier
+//     $.paths.*.path             every member
+//     $.index[*].name            every element or member
+//     $.external_crates[0]       an array element (or a member named `0`)
+//     $..is_async                every `is_async` anywhere below
+//     /index/0:42/inner          a JSON Pointer (RFC 6901)
+//
+// The leading `$` is optional, so `index.0:42.name` works too.
+use anyhow::{Result, bail};
+use serde_json::Value;
+
+use crate::JsonStyle;
+
+enum Step {
+    // A member by name, or an array element by index
+    Member(String),
+    // Every member or element
+    All,
+    // A member by name at any depth, including here
+    Descend(String),
+    // Every value at any depth
+    DescendAll,
+}
+
+// Where a value sits, for `--paths`: `$.index['0:42'].name`
+fn push_location(location: &str, key: &str) -> String {
+    let simple = !key.is_empty()
+        && !key.starts_with(|c: char| c.is_ascii_digit())
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if simple {
+        format!("{}.{}", location, key)
+    } else {
+        format!("{}['{}']", location, key.replace('\'', "\\'"))
+    }
+}
+
+fn parse(expression: &str) -> Result<Vec<Step>> {
+    let invalid = |reason: &str| anyhow::anyhow!("Invalid query '{}': {}", expression, reason);
+    let mut rest = expression.trim();
+    rest = rest.strip_prefix('$').unwrap_or(rest);
+    let mut steps = Vec::new();
+    // A bare name may start the expression without a dot
+    let mut dotted = !rest.is_empty() && !rest.starts_with(['.', '[']);
+    while !rest.is_empty() || dotted {
+        let descend = rest.starts_with("..");
+        if descend || rest.starts_with('.') || dotted {
+            if !dotted {
+                rest = &rest[if descend { 2 } else { 1 }..];
+            }
+            dotted = false;
+            if rest.starts_with('[') {
+                if descend {
+                    return Err(invalid("`..` must be followed by a name or `*`"));
+                }
+                continue;
+            }
+            let end = rest.find(['.', '[']).unwrap_or(rest.len());
+            let name = &rest[..end];
+            rest = &rest[end..];
+            steps.push(match (name, descend) {
+                ("", _) => return Err(invalid("expected a name after `.`")),
+                ("*", false) => Step::All,
+                ("*", true) => Step::DescendAll,
+                (name, false) => Step::Member(name.to_string()),
+                (name, true) => Step::Descend(name.to_string()),
+            });
+        } else if let Some(bracketed) = rest.strip_prefix('[') {
+            let (key, after) = match bracketed.chars().next() {
+                Some(quote @ ('\'' | '"')) => {
+                    let close = bracketed[1..]
+                        .find(quote)
+                        .ok_or_else(|| invalid("unterminated quote"))?;
+                    let key = &bracketed[1..close + 1];
+                    let after = bracketed[close + 2..]
+                        .strip_prefix(']')
+                        .ok_or_else(|| invalid("expected `]` after the quoted name"))?;
+                    (Some(key), after)
+                }
+                _ => {
+                    let close = bracketed
+                        .find(']')
+                        .ok_or_else(|| invalid("unterminated `[`"))?;
+                    let inside = bracketed[..close].trim();
+                    let after = &bracketed[close + 1..];
+                    if inside == "*" {
+                        (None, after)
+                    } else if inside.parse::<usize>().is_ok() {
+                        (Some(inside), after)
+                    } else {
+                        return Err(invalid(
+                            "brackets take an index, `*` or a quoted name (filters aren't supported)",
+                        ));
+                    }
+                }
+            };
+            steps.push(match key {
+                Some(key) => Step::Member(key.to_string()),
+                None => Step::All,
+            });
+            rest = after;
+        } else {
+            return Err(invalid(&format!("unexpected '{}'", rest)));
+        }
+    }
+    Ok(steps)
+}
+
+// The values directly below `value`, with their keys
+fn children(value: &Value) -> Vec<(String, &Value)> {
+    match value {
+        Value::Object(map) => map.iter().map(|(key, v)| (key.clone(), v)).collect(),
+        Value::Array(values) => values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (i.to_string(), v))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn member<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    match value {
+        Value::Object(map) => map.get(key),
+        Value::Array(values) => values.get(key.parse::<usize>().ok()?),
+        _ => None,
+    }
+}
+
+// `value` and everything below it, depth first
+fn descendants<'a>(location: String, value: &'a Value, out: &mut Vec<(String, &'a Value)>) {
+    out.push((location.clone(), value));
+    for (key, child) in children(value) {
+        descendants(push_location(&location, &key), child, out);
+    }
+}
+
+fn evaluate<'a>(root: &'a Value, steps: &[Step]) -> Vec<(String, &'a Value)> {
+    let mut current = vec![("$".to_string(), root)];
+    for step in steps {
+        let mut next = Vec::new();
+        for (location, value) in current {
+            let below = match step {
+                Step::Member(_) | Step::All => vec![(location, value)],
+                Step::Descend(_) | Step::DescendAll => {
+                    let mut all = Vec::new();
+                    descendants(location, value, &mut all);
+                    all
+                }
+            };
+            for (location, value) in below {
+                match step {
+                    Step::Member(key) | Step::Descend(key) => {
+                        if let Some(found) = member(value, key) {
+                            next.push((push_location(&location, key), found));
+                        }
+                    }
+                    Step::All | Step::DescendAll => {
+                        for (key, child) in children(value) {
+                            next.push((push_location(&location, &key), child));
+                        }
+                    }
+                }
+            }
+        }
+        current = next;
+    }
+    current
+}
+
+// The values `expression` picks out of `json`, each with where it is
+fn select<'a>(json: &'a Value, expression: &str) -> Result<Vec<(String, &'a Value)>> {
+    if expression.starts_with('/') {
+        return Ok(json
+            .pointer(expression)
+            .map(|value| (expression.to_string(), value))
+            .into_iter()
+            .collect());
+    }
+    Ok(evaluate(json, &parse(expression)?))
+}
+
+// Handler for `zdoc query`: each match printed as JSON, one after another
+// (one per line with `--compact`, so the output pipes into line tools), or
+// with `--paths` just where each one is
+pub fn run(json: &Value, expression: &str, paths: bool, json_style: JsonStyle) -> Result<()> {
+    let matches = select(json, expression)?;
+    if matches.is_empty() {
+        bail!("Nothing matches '{}'", expression);
+    }
+    for (location, value) in matches {
+        if paths {
+            println!("{}", location);
+        } else {
+            crate::print_json(value, json_style)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,172 @@
+// Re-exports of items from other crates, for search. `pub use
+// tokio::sync::Mutex` is a `use` item with no docs of its own: the docs live
+// in tokio's JSON, which `cargo doc` wrote next to the searched crate's when
+// tokio is a dependency. Targets are followed from crate to crate until an
+// item with an index entry turns up, so a facade re-exporting a re-export
+// still finds the definition.
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use tracing::debug;
+
+use crate::items;
+
+// What a re-export points at
+pub struct Target {
+    // The path the target crate's docs name it by
+    pub path: String,
+    pub kind: Option<String>,
+    pub docs: Option<String>,
+    // Why the docs couldn't be read, when they couldn't
+    pub missing: Option<String>,
+}
+
+// One loaded crate, with its public paths inverted for lookups by path
+struct LoadedCrate {
+    json: Value,
+    by_path: HashMap<Vec<String>, String>,
+}
+
+// Loads the JSON of re-exported crates on first use, for the rest of a search
+pub struct Resolver {
+    doc_dir: PathBuf,
+    // None for crates whose JSON is missing or unreadable
+    loaded: HashMap<String, Option<Rc<LoadedCrate>>>,
+}
+
+impl Resolver {
+    pub fn new(doc_dir: &Path) -> Self {
+        Resolver {
+            doc_dir: doc_dir.to_path_buf(),
+            loaded: HashMap::new(),
+        }
+    }
+
+    fn load(&mut self, crate_name: &str) -> Option<Rc<LoadedCrate>> {
+        if let Some(loaded) = self.loaded.get(crate_name) {
+            return loaded.clone();
+        }
+        let json_path = crate::doc_source::crate_json_path(&self.doc_dir, crate_name);
+        let loaded = if json_path.exists() {
+            match crate::rustdoc_json::read_file_for_search(&json_path) {
+                Ok(json) => {
+                    let mut by_path = HashMap::new();
+                    for (id, paths) in items::public_paths(&json) {
+                        for path in paths {
+                            by_path.insert(path, id.clone());
+                        }
+                    }
+                    Some(Rc::new(LoadedCrate { json, by_path }))
+                }
+                Err(e) => {
+                    debug!("Not following re-exports into {}: {:#}", crate_name, e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        self.loaded.insert(crate_name.to_string(), loaded.clone());
+        loaded
+    }
+
+    // Follow the target `id` of a `use` in `json` to its definition, when
+    // the target is in another crate. None for targets in `json` itself.
+    pub fn resolve(&mut self, json: &Value, id: &str) -> Option<Target> {
+        if items::lookup(json, id).is_some() {
+            return None;
+        }
+        let (mut crate_name, mut path, mut kind) = external_target(json, id)?;
+        let mut visited = HashSet::new();
+        loop {
+            let degraded = |kind: Option<String>, path: &[String], missing: String| Target {
+                path: path.join("::"),
+                kind,
+                docs: None,
+                missing: Some(missing),
+            };
+            if !visited.insert(path.clone()) {
+                return Some(degraded(
+                    kind,
+                    &path,
+                    "the re-exports form a cycle".to_string(),
+                ));
+            }
+            let Some(loaded) = self.load(&crate_name) else {
+                return Some(degraded(
+                    kind,
+                    &path,
+                    format!("no local docs for {}", crate_name),
+                ));
+            };
+            let Some(target_id) = find(&loaded, &path) else {
+                return Some(degraded(
+                    kind,
+                    &path,
+                    format!("not found in the docs of {}", crate_name),
+                ));
+            };
+            if let Some(item) = items::lookup(&loaded.json, &target_id) {
+                return Some(Target {
+                    path: path.join("::"),
+                    kind: crate::api_kind(item).or(kind),
+                    docs: items::item_docs(item).map(String::from),
+                    missing: None,
+                });
+            }
+            // Re-exported again, from a third crate
+            match external_target(&loaded.json, &target_id) {
+                Some(next) => (crate_name, path, kind) = next,
+                None => {
+                    return Some(degraded(
+                        kind,
+                        &path,
+                        format!("not found in the docs of {}", crate_name),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+// The crate, path and kind the "paths" table records for an external id
+fn external_target(json: &Value, id: &str) -> Option<(String, Vec<String>, Option<String>)> {
+    let summary = json.get("paths")?.get(id)?;
+    let crate_id = summary.get("crate_id").and_then(items::id_key)?;
+    let crate_name = json
+        .get("external_crates")?
+        .get(&crate_id)?
+        .get("name")?
+        .as_str()?;
+    let kind = summary
+        .get("kind")
+        .and_then(|v| v.as_str())
+        .map(String::from);
+    Some((
+        crate_name.to_string(),
+        items::canonical_path(json, id)?,
+        kind,
+    ))
+}
+
+// The id of `path` in a loaded crate: a public path, or where it's defined
+fn find(loaded: &LoadedCrate, path: &[String]) -> Option<String> {
+    if let Some(id) = loaded.by_path.get(path) {
+        return Some(id.clone());
+    }
+    let paths = loaded.json.get("paths")?.as_object()?;
+    paths.iter().find_map(|(id, summary)| {
+        let matches = summary
+            .get("path")
+            .and_then(|v| v.as_array())
+            .is_some_and(|segments| {
+                segments.len() == path.len()
+                    && segments
+                        .iter()
+                        .zip(path)
+                        .all(|(segment, name)| segment.as_str() == Some(name))
+            });
+        (matches && items::lookup(&loaded.json, id).is_some()).then(|| id.clone())
+    })
+}
@@ -0,0 +1,243 @@
+// Published versions from a sparse registry index (crates.io's, or a private
+// registry's), the files cargo reads to resolve dependencies: one JSON line
+// per release of a crate, at a path derived from its name (`se/rd/serde`).
+// Each line also has the release's features, dependencies and MSRV, as its
+// `Cargo.toml` declared them.
+use anyhow::{Context, Result};
+use cargo_metadata::semver::{Version, VersionReq};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use tracing::debug;
+
+use crate::sources::Source;
+
+#[derive(Deserialize)]
+pub struct Release {
+    pub vers: String,
+    #[serde(default)]
+    pub yanked: bool,
+    #[serde(default)]
+    pub deps: Vec<Dependency>,
+    #[serde(default)]
+    pub features: BTreeMap<String, Vec<String>>,
+    // Features using `dep:` or `?` syntax, kept apart so older cargo
+    // versions can skip them
+    #[serde(default)]
+    pub features2: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    pub rust_version: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct Dependency {
+    // The name it's used under, which is the package name unless renamed
+    pub name: String,
+    pub req: String,
+    #[serde(default)]
+    pub optional: bool,
+    // `normal`, `dev` or `build`; missing means normal
+    #[serde(default)]
+    pub kind: Option<String>,
+    // The package name, when `name` is a rename
+    #[serde(default)]
+    pub package: Option<String>,
+}
+
+impl Dependency {
+    pub fn package_name(&self) -> &str {
+        self.package.as_deref().unwrap_or(&self.name)
+    }
+}
+
+// Where the index keeps a crate's file: by name length for short names,
+// else under its first four characters. Names come from the command line
+// and lockfiles, so anything that can't be a crate's is turned away first.
+fn index_path(crate_name: &str) -> Result<String> {
+    let valid = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+    if crate_name.is_empty() || !crate_name.chars().all(valid) {
+        anyhow::bail!(
+            "'{}' isn't a valid crate name (only ASCII letters, digits, `-` and `_`)",
+            crate_name
+        );
+    }
+    let name = crate_name.to_ascii_lowercase();
+    Ok(match name.len() {
+        1 => format!("1/{}", name),
+        2 => format!("2/{}", name),
+        3 => format!("3/{}/{}", &name[..1], name),
+        _ => format!("{}/{}/{}", &name[..2], &name[2..4], name),
+    })
+}
+
+// Every release of `crate_name` in `source`'s index, yanked ones included
+pub async fn releases(
+    client: &reqwest::Client,
+    source: &Source,
+    crate_name: &str,
+) -> Result<Vec<Release>> {
+    let Some(index) = source.index_url() else {
+        anyhow::bail!("No sparse index is configured for {}", crate_name);
+    };
+    let url = format!("{}/{}", index, index_path(crate_name)?);
+    let response = source
+        .get(client, &url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to fetch {}", url))?;
+    debug!("GET {} -> {}", url, response.status());
+    if !response.status().is_success() {
+        anyhow::bail!(
+            "No index entry for {} in {}: HTTP {}{}",
+            crate_name,
+            index,
+            response.status(),
+            crate::selection::did_you_mean_remote(crate_name)
+        );
+    }
+    let text = response
+        .text()
+        .await
+        .context("Failed to read the index entry")?;
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line)
+                .with_context(|| format!("Unexpected index entry for {}", crate_name))
+        })
+        .collect()
+}
+
+// The releases of `crate_name` in `source` that haven't been yanked
+pub async fn versions(
+    client: &reqwest::Client,
+    source: &Source,
+    crate_name: &str,
+) -> Result<Vec<Version>> {
+    let releases = releases(client, source, crate_name).await?;
+    Ok(releases
+        .iter()
+        .filter(|release| !release.yanked)
+        // Versions cargo couldn't publish today are skipped, not fatal
+        .filter_map(|release| Version::parse(&release.vers).ok())
+        .collect())
+}
+
+// The newest release that isn't yanked, skipping pre-releases unless `pre`
+// asks for them
+pub fn newest_release(releases: &[Release], pre: bool) -> Option<&Release> {
+    releases
+        .iter()
+        .filter(|release| !release.yanked)
+        .filter_map(|release| Some((Version::parse(&release.vers).ok()?, release)))
+        .filter(|(version, _)| pre || version.pre.is_empty())
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, release)| release)
+}
+
+// The release a diff's `spec` names: itself when it's a full version, so
+// pre-releases like `1.0.0-beta.2` are fetched as written, else the newest
+// release matching it (`latest` matches any). Semver order puts
+// `2.0.0-rc.1` above `1.9.0`, so pre-releases only count with `pre`. When
+// the index can't be read (`--offline`, or no index configured), `spec` is
+// left for the docs server to resolve.
+pub async fn resolve_version(crate_name: &str, spec: &str, pre: bool) -> Result<String> {
+    if Version::parse(spec).is_ok() || crate::http::is_offline() {
+        return Ok(spec.to_string());
+    }
+    let requirement = match spec {
+        "latest" => None,
+        spec => match VersionReq::parse(spec) {
+            Ok(requirement) => Some(requirement),
+            Err(_) => return Ok(spec.to_string()),
+        },
+    };
+    let source = crate::sources::for_crate(crate_name);
+    let found = match crate::http::client() {
+        Ok(client) => releases(&client, &source, crate_name).await,
+        Err(err) => Err(err),
+    };
+    let found = match found {
+        Ok(found) => found,
+        Err(err) => {
+            debug!(
+                "Leaving {} {} to the docs server: {:#}",
+                crate_name, spec, err
+            );
+            return Ok(spec.to_string());
+        }
+    };
+    // A requirement only matches pre-releases of its own version, so with
+    // `pre` they're matched by the release they lead up to
+    let matches = |version: &Version| match &requirement {
+        None => true,
+        Some(requirement) => {
+            requirement.matches(version)
+                || (pre
+                    && requirement.matches(&Version::new(
+                        version.major,
+                        version.minor,
+                        version.patch,
+                    )))
+        }
+    };
+    let newest = found
+        .iter()
+        .filter(|release| !release.yanked)
+        .filter_map(|release| Version::parse(&release.vers).ok())
+        .filter(|version| pre || version.pre.is_empty())
+        .filter(|version| matches(version))
+        .max();
+    match newest {
+        Some(version) => {
+            debug!("{} {} resolves to {}", crate_name, spec, version);
+            Ok(version.to_string())
+        }
+        None => anyhow::bail!(
+            "No release of {} matches '{}'{}",
+            crate_name,
+            spec,
+            if pre {
+                ""
+            } else {
+                " (pass --pre to consider pre-releases)"
+            }
+        ),
+    }
+}
+
+// The newest of `versions`, skipping pre-releases unless `pre` asks for them
+pub fn newest(versions: &[Version], pre: bool) -> Option<&Version> {
+    versions
+        .iter()
+        .filter(|version| pre || version.pre.is_empty())
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_paths() {
+        for (name, path) in [
+            ("a", "1/a"),
+            ("cc", "2/cc"),
+            ("syn", "3/s/syn"),
+            ("Serde_JSON", "se/rd/serde_json"),
+        ] {
+            assert_eq!(index_path(name).unwrap(), path);
+        }
+    }
+
+    #[test]
+    fn non_ascii_names_are_errors() {
+        for name in ["sérde", "é", "日本語", "", "serde json"] {
+            let error = index_path(name).expect_err(name);
+            assert!(
+                error.to_string().contains("isn't a valid crate name"),
+                "{}",
+                error
+            );
+        }
+    }
+}
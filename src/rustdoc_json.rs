@@ -0,0 +1,286 @@
+// Reading rustdoc JSON without holding its text in memory. Files are parsed
+// straight from a buffered reader, so only the parsed tree is ever resident,
+// and commands that need less than every field get a slimmer tree still:
+// source spans and link tables (and for search, function signatures), the
+// bulk of big crates like `windows-sys`, are dropped item by item as the
+// index streams past, so they're never built up as a whole.
+//
+// `--max-memory` refuses to load a file whose parsed tree would likely
+// exceed the limit, instead of letting the OS kill zdoc halfway through.
+use anyhow::{Context, Result};
+use serde::de::{DeserializeSeed, Deserializer, MapAccess, Visitor};
+use serde_json::{Map, Value};
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::fs::File;
+use std::hash::Hasher;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::Instant;
+use tracing::debug;
+
+// A parsed `serde_json::Value` tree takes about this many times the size of
+// its JSON text: rustdoc output is mostly small objects and short strings,
+// each with its own allocation (regex-syntax's 2 MB of JSON parses to ~30 MB)
+const PARSED_SIZE_FACTOR: u64 = 15;
+
+static MEMORY_LIMIT: OnceLock<u64> = OnceLock::new();
+static DETAIL: OnceLock<Detail> = OnceLock::new();
+
+// How much of each index item parsing keeps
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Detail {
+    // Everything
+    Full,
+    // What comparing APIs reads: no source spans or intra-doc link tables
+    Api,
+    // Less still, for search (see `slim_item`)
+    Search,
+}
+
+// Set how much of the docs read from now on keep, for commands that never
+// look at the rest
+pub fn set_detail(detail: Detail) {
+    let _ = DETAIL.set(detail);
+}
+
+fn detail() -> Detail {
+    DETAIL.get().copied().unwrap_or(Detail::Full)
+}
+
+// Set the `--max-memory` limit for the rest of the run
+pub fn set_memory_limit(bytes: u64) {
+    let _ = MEMORY_LIMIT.set(bytes);
+}
+
+// Parse sizes like `512M`, `2G` or `1.5GiB` (powers of 1024; a bare number is bytes)
+pub fn parse_size(text: &str) -> Result<u64, String> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("'{}' is not a size like 512M or 2G", text))?;
+    let unit = unit.trim().to_ascii_uppercase();
+    let unit = unit.trim_end_matches("IB").trim_end_matches('B');
+    let multiplier: u64 = match unit {
+        "" => 1,
+        "K" => 1 << 10,
+        "M" => 1 << 20,
+        "G" => 1 << 30,
+        "T" => 1 << 40,
+        _ => {
+            return Err(format!(
+                "unknown size unit in '{}' (use K, M, G or T)",
+                text
+            ));
+        }
+    };
+    Ok((number * multiplier as f64) as u64)
+}
+
+fn format_size(bytes: u64) -> String {
+    if bytes >= 1 << 30 {
+        format!("{:.1} GiB", bytes as f64 / (1u64 << 30) as f64)
+    } else {
+        format!("{:.1} MiB", bytes as f64 / (1u64 << 20) as f64)
+    }
+}
+
+// Fail early when parsing `json_size` bytes of JSON would likely need more
+// memory than `--max-memory` allows
+pub fn check_memory(json_size: u64, what: &str) -> Result<()> {
+    let Some(&limit) = MEMORY_LIMIT.get() else {
+        return Ok(());
+    };
+    let needed = json_size.saturating_mul(PARSED_SIZE_FACTOR);
+    if needed > limit {
+        anyhow::bail!(
+            "{} is {} of JSON, which needs about {} to load, over the --max-memory limit of {}. \
+             Raise the limit, or narrow the command to a smaller crate.",
+            what,
+            format_size(json_size),
+            format_size(needed),
+            format_size(limit)
+        );
+    }
+    Ok(())
+}
+
+// Hashes everything read through it
+struct HashingReader<R> {
+    inner: R,
+    hasher: DefaultHasher,
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.hasher.write(&buf[..read]);
+        Ok(read)
+    }
+}
+
+// Parse JSON from a reader, returning it with a hash of its bytes, keeping
+// as much as `set_detail` asked for
+pub fn read_hashed(reader: impl Read) -> Result<(Value, u64)> {
+    read_hashed_as(reader, detail())
+}
+
+// `read_hashed` at a given detail. The hash is always of the whole text.
+pub fn read_hashed_as(reader: impl Read, detail: Detail) -> Result<(Value, u64)> {
+    let mut hashing = HashingReader {
+        inner: BufReader::new(reader),
+        hasher: DefaultHasher::new(),
+    };
+    let json = match detail {
+        Detail::Full => serde_json::from_reader(&mut hashing)?,
+        detail => {
+            let mut deserializer = serde_json::Deserializer::from_reader(&mut hashing);
+            let json = SlimDocument(detail).deserialize(&mut deserializer)?;
+            deserializer.end()?;
+            json
+        }
+    };
+    Ok((json, hashing.hasher.finish()))
+}
+
+fn open_checked(path: &Path) -> Result<File> {
+    let file = File::open(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+    check_memory(size, &path.display().to_string())?;
+    Ok(file)
+}
+
+// Whether a JSON file was documented with private items, read from its
+// `includes_private` field, which rustdoc writes before the index, so the
+// rest of the file needn't be parsed. None when it can't be told.
+pub fn includes_private(path: &Path) -> Option<bool> {
+    const FIELD: &str = "\"includes_private\":";
+    let mut head = Vec::new();
+    File::open(path)
+        .ok()?
+        .take(512)
+        .read_to_end(&mut head)
+        .ok()?;
+    let head = String::from_utf8_lossy(&head);
+    let value = head[head.find(FIELD)? + FIELD.len()..].trim_start();
+    Some(value.starts_with("true"))
+}
+
+// Parse a JSON file for searching: the same tree as `read_hashed`, minus what
+// search never looks at (see `slim_item`)
+pub fn read_file_for_search(path: &Path) -> Result<Value> {
+    let file = open_checked(path)?;
+    let started = Instant::now();
+    let mut deserializer = serde_json::Deserializer::from_reader(BufReader::new(file));
+    let json = SlimDocument(Detail::Search)
+        .deserialize(&mut deserializer)
+        .and_then(|json| deserializer.end().map(|()| json))
+        .with_context(|| format!("Failed to parse JSON from {}", path.display()))?;
+    debug!(
+        "Parsed {} for search in {:.2?}",
+        path.display(),
+        started.elapsed()
+    );
+    Ok(json)
+}
+
+// Drop the parts of an index item `detail` leaves out: source spans and link
+// tables, and for search function parameters too (search only needs to know
+// it's a function, its header for the ABI and its return type for whether
+// it's async)
+fn slim_item(item: &mut Value, detail: Detail) {
+    let Some(fields) = item.as_object_mut() else {
+        return;
+    };
+    if detail == Detail::Full {
+        return;
+    }
+    fields.remove("span");
+    fields.remove("links");
+    if detail != Detail::Search {
+        return;
+    }
+    if let Some(function) = fields
+        .get_mut("inner")
+        .and_then(|inner| inner.get_mut("function"))
+    {
+        let mut slim = Map::new();
+        if let Some(header) = function.get_mut("header") {
+            slim.insert("header".to_string(), header.take());
+        }
+        if let Some(output) = function
+            .get_mut("sig")
+            .and_then(|sig| sig.get_mut("output"))
+        {
+            let output = output.take();
+            slim.insert("sig".to_string(), serde_json::json!({ "output": output }));
+        }
+        *function = Value::Object(slim);
+    }
+}
+
+// The top-level document, with `index` read through `SlimIndex`
+struct SlimDocument(Detail);
+
+impl<'de> DeserializeSeed<'de> for SlimDocument {
+    type Value = Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for SlimDocument {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a rustdoc JSON document")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut document = Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            let value = if key == "index" {
+                map.next_value_seed(SlimIndex(self.0))?
+            } else {
+                map.next_value::<Value>()?
+            };
+            document.insert(key, value);
+        }
+        Ok(Value::Object(document))
+    }
+}
+
+// The `index` map, slimming each item as soon as it's parsed so at most one
+// full item is resident at a time
+struct SlimIndex(Detail);
+
+impl<'de> DeserializeSeed<'de> for SlimIndex {
+    type Value = Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for SlimIndex {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("the rustdoc index map")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut index = Map::new();
+        while let Some((id, mut item)) = map.next_entry::<String, Value>()? {
+            slim_item(&mut item, self.0);
+            index.insert(id, item);
+        }
+        Ok(Value::Object(index))
+    }
+}
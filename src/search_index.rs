@@ -0,0 +1,893 @@
+// Reading the search index rustdoc writes next to its HTML, for
+// `zdoc debug search-index` and as a source of item lists when there's no
+// JSON. Two layouts exist:
+//
+// - `search-index.js` (and `search-index-<suffix>.js`), up to Rust 1.88: one
+//   JSON document per crate, with item kinds, names and module paths in
+//   parallel arrays
+// - `search.index/root.js`, since: a column store. The root lists each column
+//   with its row counts (`N`), a roaring bitmap of its empty rows (`E`) and
+//   the hashes of its shard files (`H`, one per count), which live at
+//   `search.index/<column>/<hash>.js` as length-prefixed strings.
+use anyhow::{Context, Result};
+use base64::Engine;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::table::Table;
+
+// Rustdoc's item type numbering, shared by both layouts
+const ITEM_TYPES: &[&str] = &[
+    "keyword",
+    "primitive",
+    "mod",
+    "externcrate",
+    "import",
+    "struct",
+    "enum",
+    "fn",
+    "type",
+    "static",
+    "trait",
+    "impl",
+    "tymethod",
+    "method",
+    "structfield",
+    "variant",
+    "macro",
+    "associatedtype",
+    "constant",
+    "associatedconstant",
+    "union",
+    "foreigntype",
+    "existential",
+    "attr",
+    "derive",
+    "traitalias",
+    "generic",
+    "attribute",
+];
+
+// How many entries `summary` shows, one per kind
+const SAMPLE_KINDS: usize = 12;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Layout {
+    SearchIndexJs,
+    Stringdex,
+}
+
+impl Layout {
+    fn describe(self) -> &'static str {
+        match self {
+            Layout::SearchIndexJs => "search-index.js",
+            Layout::Stringdex => "search.index",
+        }
+    }
+}
+
+// One searchable item
+pub struct Entry {
+    pub crate_name: String,
+    pub kind: String,
+    // `krate::module`, or empty for a crate root
+    pub module_path: String,
+    // The type or trait an associated item belongs to
+    pub parent: Option<String>,
+    pub name: String,
+}
+
+impl Entry {
+    pub fn path(&self) -> String {
+        [self.module_path.as_str()]
+            .into_iter()
+            .chain(self.parent.as_deref())
+            .chain([self.name.as_str()])
+            .filter(|segment| !segment.is_empty())
+            .collect::<Vec<_>>()
+            .join("::")
+    }
+}
+
+pub struct SearchIndex {
+    pub layout: Layout,
+    pub path: PathBuf,
+    pub crates: Vec<String>,
+    pub entries: Vec<Entry>,
+}
+
+fn item_type(number: u64) -> String {
+    ITEM_TYPES
+        .get(number as usize)
+        .map_or_else(|| format!("unknown({})", number), |name| name.to_string())
+}
+
+// Find the index under a doc directory, or take a path to one of the index
+// files itself
+pub fn locate(path: &Path) -> Result<(Layout, PathBuf)> {
+    if path.is_file() {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        if name == "root.js" {
+            return Ok((Layout::Stringdex, path.to_path_buf()));
+        }
+        if name.starts_with("search-index") && name.ends_with(".js") {
+            return Ok((Layout::SearchIndexJs, path.to_path_buf()));
+        }
+        anyhow::bail!(
+            "{} is not a rustdoc search index (expected search.index/root.js or search-index.js)",
+            path.display()
+        );
+    }
+
+    let root = path.join("search.index").join("root.js");
+    if root.is_file() {
+        return Ok((Layout::Stringdex, root));
+    }
+    let entries =
+        fs::read_dir(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut legacy: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|file| {
+            file.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("search-index") && n.ends_with(".js"))
+        })
+        .collect();
+    legacy.sort();
+    match legacy.pop() {
+        Some(file) => Ok((Layout::SearchIndexJs, file)),
+        None => anyhow::bail!(
+            "No rustdoc search index in {} (run `cargo doc` first)",
+            path.display()
+        ),
+    }
+}
+
+pub fn load(path: &Path) -> Result<SearchIndex> {
+    let (layout, file) = locate(path)?;
+    let (crates, entries) = match layout {
+        Layout::SearchIndexJs => read_search_index_js(&file)?,
+        Layout::Stringdex => read_stringdex(&file)?,
+    };
+    Ok(SearchIndex {
+        layout,
+        path: file,
+        crates,
+        entries,
+    })
+}
+
+// The value of a quoted JavaScript string literal, which `text` starts with
+fn js_string(text: &str) -> Result<String> {
+    let mut chars = text.chars();
+    let quote = chars
+        .next()
+        .filter(|c| matches!(c, '\'' | '"'))
+        .context("Expected a JavaScript string literal")?;
+    let mut out = String::new();
+    while let Some(c) = chars.next() {
+        if c == quote {
+            return Ok(out);
+        }
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        let escaped = chars.next().context("Unterminated escape sequence")?;
+        match escaped {
+            'n' => out.push('\n'),
+            'r' => out.push('\r'),
+            't' => out.push('\t'),
+            '0' => out.push('\0'),
+            // A backslash before a line break continues the string
+            '\n' => {}
+            'x' | 'u' => {
+                let digits: String = chars
+                    .by_ref()
+                    .take(if escaped == 'x' { 2 } else { 4 })
+                    .collect();
+                let code = u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .with_context(|| format!("Invalid escape \\{}{}", escaped, digits))?;
+                out.push(code);
+            }
+            other => out.push(other),
+        }
+    }
+    anyhow::bail!("Unterminated JavaScript string literal")
+}
+
+// The string argument of the call to `function` in a JavaScript file
+fn call_argument(source: &str, function: &str) -> Result<String> {
+    let start = source
+        .find(&format!("{}(", function))
+        .with_context(|| format!("No {}(...) call found", function))?;
+    js_string(&source[start + function.len() + 1..])
+}
+
+fn read_search_index_js(file: &Path) -> Result<(Vec<String>, Vec<Entry>)> {
+    let source =
+        fs::read_to_string(file).with_context(|| format!("Failed to read {}", file.display()))?;
+    let json: Value = serde_json::from_str(&call_argument(&source, "JSON.parse")?)
+        .with_context(|| format!("Failed to parse the index in {}", file.display()))?;
+
+    // `new Map(JSON.parse('[["krate", {...}], ...]'))` since 1.76, a plain
+    // object keyed by crate before
+    let crates: Vec<(String, &Value)> = match &json {
+        Value::Array(pairs) => pairs
+            .iter()
+            .filter_map(|pair| Some((pair.get(0)?.as_str()?.to_string(), pair.get(1)?)))
+            .collect(),
+        Value::Object(map) => map
+            .iter()
+            .map(|(name, data)| (name.clone(), data))
+            .collect(),
+        _ => anyhow::bail!("Unrecognized search index layout in {}", file.display()),
+    };
+
+    let mut entries = Vec::new();
+    for (crate_name, data) in &crates {
+        crate_entries(crate_name, data, &mut entries);
+    }
+    Ok((crates.into_iter().map(|(name, _)| name).collect(), entries))
+}
+
+fn crate_entries(crate_name: &str, data: &Value, entries: &mut Vec<Entry>) {
+    // Kinds are a string of letters (`A` = 0) since 1.69, numbers before
+    let kinds: Vec<u64> = match data.get("t") {
+        Some(Value::String(letters)) => letters
+            .bytes()
+            .map(|b| u64::from(b.saturating_sub(b'A')))
+            .collect(),
+        Some(Value::Array(numbers)) => numbers.iter().filter_map(Value::as_u64).collect(),
+        _ => Vec::new(),
+    };
+    let names: Vec<&str> = data
+        .get("n")
+        .and_then(Value::as_array)
+        .map(|names| names.iter().map(|n| n.as_str().unwrap_or("")).collect())
+        .unwrap_or_default();
+
+    // Module paths apply from their index until the next one: `[[i, path]]`
+    // pairs since 1.69, one string per item (empty = unchanged) before
+    let mut paths: BTreeMap<usize, &str> = BTreeMap::new();
+    if let Some(q) = data.get("q").and_then(Value::as_array) {
+        for (i, path) in q.iter().enumerate() {
+            match path {
+                Value::Array(pair) => {
+                    if let (Some(index), Some(path)) = (
+                        pair.first().and_then(Value::as_u64),
+                        pair.get(1).and_then(Value::as_str),
+                    ) {
+                        paths.insert(index as usize, path);
+                    }
+                }
+                Value::String(path) if !path.is_empty() => {
+                    paths.insert(i, path);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Parents are 1-based indexes into `p` (`[ty, name]` pairs), 0 for
+    // none; later releases pack `i` into a string, which isn't decoded
+    let parent_names: Vec<&str> = data
+        .get("p")
+        .and_then(Value::as_array)
+        .map(|parents| {
+            parents
+                .iter()
+                .map(|p| p.get(1).and_then(Value::as_str).unwrap_or(""))
+                .collect()
+        })
+        .unwrap_or_default();
+    let parents: Vec<u64> = data
+        .get("i")
+        .and_then(Value::as_array)
+        .map(|parents| parents.iter().map(|p| p.as_u64().unwrap_or(0)).collect())
+        .unwrap_or_default();
+
+    for (i, (&kind, name)) in kinds.iter().zip(&names).enumerate() {
+        let module_path = paths
+            .range(..=i)
+            .next_back()
+            .map_or(crate_name, |(_, path)| *path);
+        entries.push(Entry {
+            crate_name: crate_name.to_string(),
+            kind: item_type(kind),
+            module_path: module_path.to_string(),
+            parent: parents
+                .get(i)
+                .and_then(|&parent| parent_names.get((parent as usize).checked_sub(1)?))
+                .map(|parent| parent.to_string()),
+            name: name.to_string(),
+        });
+    }
+}
+
+// A cursor over a byte slice that fails instead of reading past the end
+struct Bytes<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Bytes<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let taken = self
+            .data
+            .get(self.pos..self.pos + len)
+            .context("Search index data ends early")?;
+        self.pos += len;
+        Ok(taken)
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+}
+
+// The members of a serialized roaring bitmap (the standard format, with or
+// without run containers)
+fn roaring_bitmap(data: &[u8]) -> Result<HashSet<u32>> {
+    let mut bytes = Bytes { data, pos: 0 };
+    let cookie = bytes.u16()?;
+    let (size, run_flags) = match cookie {
+        0x303a => {
+            bytes.u16()?;
+            (bytes.u32()? as usize, &[][..])
+        }
+        0x303b => {
+            // The container count, minus one, shares the cookie's word
+            let size = usize::from(bytes.u16()?) + 1;
+            (size, bytes.take(size.div_ceil(8))?)
+        }
+        _ => anyhow::bail!("Unsupported bitmap encoding {:#06x}", cookie),
+    };
+    let mut headers = Vec::with_capacity(size);
+    for _ in 0..size {
+        let key = u32::from(bytes.u16()?);
+        let cardinality = usize::from(bytes.u16()?) + 1;
+        headers.push((key, cardinality));
+    }
+    // Container offsets, which reading in order doesn't need
+    if run_flags.is_empty() || size >= 4 {
+        bytes.take(size * 4)?;
+    }
+
+    let mut members = HashSet::new();
+    for (i, (key, cardinality)) in headers.into_iter().enumerate() {
+        let high = key << 16;
+        let is_run = run_flags
+            .get(i / 8)
+            .is_some_and(|flags| flags & (1 << (i % 8)) != 0);
+        if is_run {
+            for _ in 0..bytes.u16()? {
+                let start = u32::from(bytes.u16()?);
+                let length = u32::from(bytes.u16()?);
+                members.extend((start..=start + length).map(|low| high | low));
+            }
+        } else if cardinality >= 4096 {
+            let bits = bytes.take(8192)?;
+            for (byte_index, byte) in bits.iter().enumerate() {
+                for bit in 0..8 {
+                    if byte & (1 << bit) != 0 {
+                        members.insert(high | (byte_index * 8 + bit) as u32);
+                    }
+                }
+            }
+        } else {
+            for _ in 0..cardinality {
+                members.insert(high | u32::from(bytes.u16()?));
+            }
+        }
+    }
+    Ok(members)
+}
+
+// A number written as hex-like nibbles: characters below `` ` `` continue it,
+// the first one from `` ` `` on ends it. Returns the number and the rest.
+fn nibble_number(data: &[u8]) -> Result<(usize, &[u8])> {
+    let mut n = 0usize;
+    for (i, &c) in data.iter().enumerate() {
+        n = (n << 4) | usize::from(c & 0xf);
+        if c >= 96 {
+            return Ok((n, &data[i + 1..]));
+        }
+    }
+    anyhow::bail!("Search index data ends inside a number")
+}
+
+// The rows of one shard file, without the column's empty rows. Each row is a
+// length then that many bytes, or a digit-like byte (`0`-`?`) repeating one of
+// the 16 most recent distinct rows.
+fn shard_rows(mut data: &[u8]) -> Result<Vec<Vec<u8>>> {
+    let mut rows = Vec::new();
+    let mut recent: Vec<Vec<u8>> = Vec::new();
+    while let Some(&c) = data.first() {
+        if (48..=63).contains(&c) {
+            let row = recent
+                .get(usize::from(c - 48))
+                .context("Search index row refers back too far")?;
+            rows.push(row.clone());
+            data = &data[1..];
+            continue;
+        }
+        let (len, rest) = nibble_number(data)?;
+        let row = rest
+            .get(..len)
+            .context("Search index row runs past the end of its shard")?
+            .to_vec();
+        data = &rest[len..];
+        recent.insert(0, row.clone());
+        recent.truncate(16);
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+// Every row of a column, empty rows included
+fn read_column(dir: &Path, root: &Value, column: &str) -> Result<Vec<Vec<u8>>> {
+    let meta = root
+        .get(column)
+        .with_context(|| format!("The search index has no '{}' column", column))?;
+    let field = |key: &str| {
+        meta.get(key)
+            .and_then(Value::as_str)
+            .with_context(|| format!("The '{}' column has no '{}' field", column, key))
+    };
+    let base64 = |key: &str| -> Result<Vec<u8>> {
+        base64::engine::general_purpose::STANDARD
+            .decode(field(key)?)
+            .with_context(|| format!("Invalid base64 in the '{}' column's '{}'", column, key))
+    };
+
+    let mut counts = Vec::new();
+    let mut text = field("N")?.as_bytes();
+    while !text.is_empty() {
+        let (count, rest) = nibble_number(text)?;
+        counts.push(count);
+        text = rest;
+    }
+    let empty = roaring_bitmap(&base64("E")?)
+        .with_context(|| format!("Invalid empty-row bitmap in the '{}' column", column))?;
+    let hashes = base64("H")?;
+    // Each shard has a count and a hash: 6 bytes in Rust 1.95, 5 in 1.97
+    let hash_len = match hashes.len().checked_div(counts.len()) {
+        Some(len) if len > 0 && len * counts.len() == hashes.len() => len,
+        _ => 6,
+    };
+
+    let mut filled = Vec::new();
+    for (shard, hash) in hashes.chunks(hash_len).enumerate() {
+        let name: String = hash.iter().map(|b| format!("{:02x}", b)).collect();
+        let file = dir.join(column).join(format!("{}.js", name));
+        let source = fs::read_to_string(&file)
+            .with_context(|| format!("Failed to read {}", file.display()))?;
+        // `rd_("...")` holds the bytes as characters, `rb_("...")` as base64
+        let bytes = if source.starts_with("rb_(") {
+            base64::engine::general_purpose::STANDARD
+                .decode(call_argument(&source, "rb_")?)
+                .with_context(|| format!("Invalid base64 in {}", file.display()))?
+        } else {
+            call_argument(&source, "rd_")?
+                .chars()
+                .map(|c| u8::try_from(c).context("Non-byte character in shard data"))
+                .collect::<Result<_>>()
+                .with_context(|| format!("Failed to decode {}", file.display()))?
+        };
+        let rows =
+            shard_rows(&bytes).with_context(|| format!("Failed to decode {}", file.display()))?;
+        if counts.get(shard).is_some_and(|&count| count != rows.len()) {
+            tracing::debug!(
+                "{} has {} rows where the index root says {}",
+                file.display(),
+                rows.len(),
+                counts[shard]
+            );
+        }
+        filled.extend(rows);
+    }
+
+    let total = filled.len() + empty.len();
+    let mut filled = filled.into_iter();
+    Ok((0..total as u32)
+        .map(|row| {
+            if empty.contains(&row) {
+                Vec::new()
+            } else {
+                filled.next().unwrap_or_default()
+            }
+        })
+        .collect())
+}
+
+fn read_stringdex(root_file: &Path) -> Result<(Vec<String>, Vec<Entry>)> {
+    let source = fs::read_to_string(root_file)
+        .with_context(|| format!("Failed to read {}", root_file.display()))?;
+    let root: Value = serde_json::from_str(&call_argument(&source, "rr_")?)
+        .with_context(|| format!("Failed to parse {}", root_file.display()))?;
+    let dir = root_file.parent().unwrap_or(Path::new("."));
+
+    let text = |row: &[u8]| String::from_utf8_lossy(row).into_owned();
+    let crates = read_column(dir, &root, "crateNames")?
+        .iter()
+        .map(|row| text(row))
+        .collect();
+    let names: Vec<String> = read_column(dir, &root, "name")?
+        .iter()
+        .map(|row| text(row))
+        .collect();
+    let paths = read_column(dir, &root, "path")?;
+    let entry_rows = read_column(dir, &root, "entry")?;
+
+    // Row references are stored one higher, so 0 can mean none
+    let name_of = |reference: u64| {
+        reference
+            .checked_sub(1)
+            .and_then(|row| names.get(row as usize))
+    };
+    // `krate::module` for a path row: its module path (`[ty, "krate"]`) plus
+    // its own name
+    let module_path = |reference: u64| -> Option<String> {
+        let name = name_of(reference)?;
+        let row = paths.get(reference as usize - 1)?;
+        let data: Value = serde_json::from_slice(row).ok()?;
+        let parent = data.get(1).and_then(Value::as_str).unwrap_or("");
+        Some(if parent.is_empty() {
+            name.clone()
+        } else {
+            format!("{}::{}", parent, name)
+        })
+    };
+
+    let mut entries = Vec::new();
+    for (row, data) in entry_rows.iter().enumerate() {
+        if data.is_empty() {
+            continue;
+        }
+        // `[krate, ty, module path, exact module path, parent, trait parent,
+        // deprecated, unstable, disambiguator?]`
+        let Ok(Value::Array(fields)) = serde_json::from_slice::<Value>(data) else {
+            tracing::debug!("Skipping undecodable entry row {}", row);
+            continue;
+        };
+        let number = |i: usize| fields.get(i).and_then(Value::as_u64).unwrap_or(0);
+        entries.push(Entry {
+            // The crate is a row number, not a reference
+            crate_name: names.get(number(0) as usize).cloned().unwrap_or_default(),
+            kind: item_type(number(1)),
+            module_path: module_path(number(2)).unwrap_or_default(),
+            parent: name_of(number(4)).cloned(),
+            name: names.get(row).cloned().unwrap_or_default(),
+        });
+    }
+    Ok((crates, entries))
+}
+
+// Item counts by kind, most common first
+fn kind_counts(index: &SearchIndex) -> Vec<(&str, usize)> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for entry in &index.entries {
+        *counts.entry(entry.kind.as_str()).or_default() += 1;
+    }
+    let mut counts: Vec<(&str, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    counts
+}
+
+// The first entry of each kind, in index order
+fn samples(index: &SearchIndex) -> Vec<&Entry> {
+    let mut seen = HashSet::new();
+    index
+        .entries
+        .iter()
+        .filter(|entry| seen.insert(entry.kind.as_str()))
+        .take(SAMPLE_KINDS)
+        .collect()
+}
+
+pub fn summary_json(index: &SearchIndex) -> Value {
+    let counts: serde_json::Map<String, Value> = kind_counts(index)
+        .into_iter()
+        .map(|(kind, count)| (kind.to_string(), count.into()))
+        .collect();
+    let samples: Vec<Value> = samples(index)
+        .into_iter()
+        .map(|entry| {
+            serde_json::json!({
+                "crate": entry.crate_name,
+                "kind": entry.kind,
+                "path": entry.path(),
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "format": index.layout.describe(),
+        "path": index.path.display().to_string(),
+        "crates": index.crates,
+        "entries": index.entries.len(),
+        "kinds": counts,
+        "samples": samples,
+    })
+}
+
+pub fn print_summary(index: &SearchIndex) {
+    println!(
+        "Search index: {} ({} format)",
+        index.path.display(),
+        index.layout.describe()
+    );
+    println!(
+        "Crates ({}): {}",
+        index.crates.len(),
+        index.crates.join(", ")
+    );
+    println!("Entries: {}\n", index.entries.len());
+
+    let mut kinds = Table::new(&["Kind", "Count"]);
+    for (kind, count) in kind_counts(index) {
+        kinds.add_row(vec![kind.to_string(), count.to_string()]);
+    }
+    print!("{}", kinds.render());
+
+    let samples = samples(index);
+    if !samples.is_empty() {
+        println!("\nSample entries:");
+        let mut table = Table::new(&["Kind", "Crate", "Path"]);
+        for entry in samples {
+            table.add_row(vec![
+                entry.kind.clone(),
+                entry.crate_name.clone(),
+                entry.path(),
+            ]);
+        }
+        print!("{}", table.render());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The search index `cargo doc` wrote for the `widgets` fixture crate
+    // under two releases, copied from `target/doc/search.index`. Their
+    // shard hashes differ in length.
+    const RELEASES: [&str; 2] = ["rust-1.95.0", "rust-1.97.0-nightly"];
+
+    fn fixture(release: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/search-index")
+            .join(release)
+    }
+
+    fn paths_by_kind(index: &SearchIndex) -> Vec<(String, String)> {
+        let mut found: Vec<(String, String)> = index
+            .entries
+            .iter()
+            .map(|entry| (entry.kind.clone(), entry.path()))
+            .collect();
+        found.sort();
+        found
+    }
+
+    #[test]
+    fn decodes_both_releases() {
+        let indexes: Vec<SearchIndex> = RELEASES
+            .iter()
+            .map(|release| load(&fixture(release)).unwrap())
+            .collect();
+        for index in &indexes {
+            assert_eq!(index.layout, Layout::Stringdex);
+            assert_eq!(index.crates, ["widgets"]);
+            let own: Vec<(String, String)> = paths_by_kind(index)
+                .into_iter()
+                .filter(|(kind, _)| kind != "method")
+                .collect();
+            let expected = [
+                ("enum", "widgets::Shape"),
+                ("externcrate", "widgets"),
+                ("fn", "widgets::clear"),
+                ("fn", "widgets::draw"),
+                ("macro", "widgets::widget"),
+                ("struct", "widgets::Canvas"),
+                ("struct", "widgets::Widget"),
+                ("structfield", "widgets::Widget::height"),
+                ("structfield", "widgets::Widget::width"),
+                ("trait", "widgets::Draw"),
+                ("tymethod", "widgets::Draw::draw"),
+                ("variant", "widgets::Shape::Circle"),
+                ("variant", "widgets::Shape::Square"),
+            ];
+            let expected: Vec<(String, String)> = expected
+                .iter()
+                .map(|(kind, path)| (kind.to_string(), path.to_string()))
+                .collect();
+            assert_eq!(own, expected, "{}", index.path.display());
+        }
+        assert_eq!(paths_by_kind(&indexes[0]), paths_by_kind(&indexes[1]));
+    }
+
+    // Every item with a page in the rustdoc JSON is in the index, and the
+    // hidden one is in neither
+    #[test]
+    fn agrees_with_rustdoc_json() {
+        let json = crate::fixtures::load("widgets");
+        let documented: Vec<String> = json["paths"]
+            .as_object()
+            .unwrap()
+            .values()
+            .filter(|summary| summary["crate_id"] == 0 && summary["kind"] != "module")
+            .map(|summary| {
+                let segments: Vec<&str> = summary["path"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .collect();
+                segments.join("::")
+            })
+            .collect();
+        assert!(!documented.is_empty());
+        for release in RELEASES {
+            let index = load(&fixture(release)).unwrap();
+            let indexed: HashSet<String> = index.entries.iter().map(Entry::path).collect();
+            for path in &documented {
+                assert!(indexed.contains(path), "{} lacks {}", release, path);
+            }
+            assert!(!indexed.contains("widgets::__internal"));
+        }
+    }
+
+    #[test]
+    fn locates_the_index_from_a_doc_dir_or_a_file() {
+        let root = fixture(RELEASES[0]).join("search.index/root.js");
+        let (layout, file) = locate(&fixture(RELEASES[0])).unwrap();
+        assert_eq!((layout, file), (Layout::Stringdex, root.clone()));
+        let (layout, file) = locate(&root).unwrap();
+        assert_eq!((layout, file), (Layout::Stringdex, root));
+        let name_shard = fs::read_dir(fixture(RELEASES[0]).join("search.index/name"))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        assert!(locate(&name_shard).is_err());
+    }
+
+    // Encoders for the formats the reader decodes, to round-trip through it
+
+    fn nibbles(n: usize) -> Vec<u8> {
+        let digits = format!("{:x}", n);
+        let count = digits.len();
+        digits
+            .chars()
+            .enumerate()
+            .map(|(i, digit)| {
+                let nibble = digit.to_digit(16).unwrap() as u8;
+                // Continuing digits sit below `` ` ``, the last one from it on
+                if i + 1 == count {
+                    96 | nibble
+                } else {
+                    64 | nibble
+                }
+            })
+            .collect()
+    }
+
+    fn shard(rows: &[&[u8]]) -> Vec<u8> {
+        let mut data = Vec::new();
+        let mut recent: Vec<&[u8]> = Vec::new();
+        for &row in rows {
+            if let Some(back) = recent.iter().position(|seen| *seen == row) {
+                data.push(48 + back as u8);
+                continue;
+            }
+            data.extend(nibbles(row.len()));
+            data.extend(row);
+            recent.insert(0, row);
+            recent.truncate(16);
+        }
+        data
+    }
+
+    // A bitmap of one array container per 16-bit key, optionally marking
+    // them all as run containers instead
+    fn bitmap(members: &[u32], runs: bool) -> Vec<u8> {
+        let mut containers: BTreeMap<u16, Vec<u16>> = BTreeMap::new();
+        for member in members {
+            containers
+                .entry((member >> 16) as u16)
+                .or_default()
+                .push(*member as u16);
+        }
+        let size = containers.len();
+        let mut data = Vec::new();
+        if runs {
+            data.extend(0x303bu16.to_le_bytes());
+            data.extend((size as u16 - 1).to_le_bytes());
+            data.extend(vec![0xffu8; size.div_ceil(8)]);
+        } else {
+            data.extend(0x303au16.to_le_bytes());
+            data.extend(0u16.to_le_bytes());
+            data.extend((size as u32).to_le_bytes());
+        }
+        for (key, lows) in &containers {
+            data.extend(key.to_le_bytes());
+            data.extend((lows.len() as u16 - 1).to_le_bytes());
+        }
+        if !runs || size >= 4 {
+            data.extend(vec![0u8; size * 4]);
+        }
+        for lows in containers.values() {
+            if runs {
+                // One run per member
+                data.extend((lows.len() as u16).to_le_bytes());
+                for low in lows {
+                    data.extend(low.to_le_bytes());
+                    data.extend(0u16.to_le_bytes());
+                }
+            } else {
+                for low in lows {
+                    data.extend(low.to_le_bytes());
+                }
+            }
+        }
+        data
+    }
+
+    #[test]
+    fn round_trips_numbers() {
+        for n in [0, 1, 15, 16, 255, 4096, 123_456] {
+            let mut data = nibbles(n);
+            data.push(b'!');
+            assert_eq!(nibble_number(&data).unwrap(), (n, &b"!"[..]));
+        }
+        assert!(nibble_number(&nibbles(300)[..2]).is_err());
+    }
+
+    #[test]
+    fn round_trips_shard_rows() {
+        let long = vec![b'x'; 300];
+        let rows: Vec<&[u8]> = vec![b"Widget", b"", b"new", b"Widget", &long, b"new", b"area"];
+        let decoded = shard_rows(&shard(&rows)).unwrap();
+        assert_eq!(decoded, rows);
+        // A back reference to a row that was never written
+        assert!(shard_rows(b"5").is_err());
+    }
+
+    #[test]
+    fn round_trips_bitmaps() {
+        let members = [0, 3, 7, 65_535, 65_536, 200_000, 1 << 20, (1 << 20) + 1];
+        for runs in [false, true] {
+            let decoded = roaring_bitmap(&bitmap(&members, runs)).unwrap();
+            assert_eq!(decoded, members.into_iter().collect::<HashSet<u32>>());
+        }
+        assert!(roaring_bitmap(&[0x12, 0x34]).is_err());
+        assert!(roaring_bitmap(&bitmap(&members, false)[..12]).is_err());
+    }
+
+    #[test]
+    fn reads_javascript_strings() {
+        assert_eq!(js_string(r#"'it\'s'"#).unwrap(), "it's");
+        assert_eq!(js_string(r#""a\"b" + rest"#).unwrap(), "a\"b");
+        assert_eq!(js_string(r"'\x41é\n\\'").unwrap(), "Aé\n\\");
+        assert_eq!(js_string("'one\\\ntwo'").unwrap(), "onetwo");
+        assert!(js_string("'open").is_err());
+        assert!(js_string("plain").is_err());
+        assert_eq!(
+            call_argument("rr_('{\"a\":1}');", "rr_").unwrap(),
+            "{\"a\":1}"
+        );
+    }
+}
@@ -0,0 +1,595 @@
+// Cargo-style package selection (`-p/--package`, `--workspace`, `--exclude`)
+// shared by the commands that work on workspace crates. With no flags the
+// selection is what cargo would pick: the package whose manifest is in the
+// current directory, else the workspace's default members, else all of them.
+use anyhow::Result;
+use cargo_metadata::{Metadata, Package};
+
+#[derive(clap::Args, Default)]
+pub struct PackageSelection {
+    /// Package to use, as `name` or `name@version` (repeatable)
+    #[arg(short = 'p', long = "package", value_name = "SPEC")]
+    pub packages: Vec<String>,
+    /// Use every workspace member
+    #[arg(long, conflicts_with = "packages")]
+    pub workspace: bool,
+    /// Leave a package out of `--workspace` (repeatable)
+    #[arg(long, value_name = "NAME", requires = "workspace")]
+    pub exclude: Vec<String>,
+}
+
+impl PackageSelection {
+    pub fn is_empty(&self) -> bool {
+        self.packages.is_empty() && !self.workspace
+    }
+
+    // The selected packages. A command's positional crate argument is
+    // shorthand for `-p <name>`.
+    pub fn resolve<'a>(
+        &self,
+        metadata: &'a Metadata,
+        positional: Option<&str>,
+    ) -> Result<Vec<&'a Package>> {
+        if let Some(name) = positional {
+            return Ok(vec![match_spec(metadata, name)?]);
+        }
+        if self.workspace {
+            return Ok(self.workspace_members(metadata));
+        }
+        if self.packages.is_empty() {
+            return Ok(default_packages(metadata));
+        }
+
+        let mut selected: Vec<&Package> = Vec::new();
+        for spec in &self.packages {
+            let package = match_spec(metadata, spec)?;
+            if !selected.iter().any(|p| p.id == package.id) {
+                selected.push(package);
+            }
+        }
+        Ok(selected)
+    }
+
+    // The selected package, for commands that work on exactly one
+    pub fn resolve_one<'a>(
+        &self,
+        metadata: &'a Metadata,
+        positional: Option<&str>,
+        command: &str,
+    ) -> Result<&'a Package> {
+        let packages = self.resolve(metadata, positional)?;
+        match packages.as_slice() {
+            [package] => Ok(package),
+            [] => anyhow::bail!("no packages selected for `zdoc {}`", command),
+            _ => {
+                let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+                anyhow::bail!(
+                    "`zdoc {}` works on one package, but {} were selected: {}\n\
+                     Pick one with `-p <SPEC>`",
+                    command,
+                    packages.len(),
+                    names.join(", ")
+                )
+            }
+        }
+    }
+
+    fn workspace_members<'a>(&self, metadata: &'a Metadata) -> Vec<&'a Package> {
+        let members = metadata.workspace_packages();
+        for excluded in &self.exclude {
+            if !members.iter().any(|p| p.name.as_str() == excluded) {
+                tracing::warn!("excluded package(s) `{}` not found in workspace", excluded);
+            }
+        }
+        members
+            .into_iter()
+            .filter(|p| !self.exclude.iter().any(|e| e == p.name.as_str()))
+            .collect()
+    }
+}
+
+// What cargo builds when no package is named
+fn default_packages(metadata: &Metadata) -> Vec<&Package> {
+    if let Some(root) = metadata.root_package() {
+        return vec![root];
+    }
+    // A virtual manifest: its default members, or every member
+    let defaults = metadata.workspace_default_packages();
+    if defaults.is_empty() {
+        metadata.workspace_packages()
+    } else {
+        defaults
+    }
+}
+
+// Whether `version` (a full version) matches a possibly partial spec
+// version such as `1` or `1.2`
+fn version_matches(version: &cargo_metadata::semver::Version, spec: &str) -> bool {
+    let full = version.to_string();
+    let parts: Vec<&str> = spec.split('.').collect();
+    if parts.len() >= 3 {
+        return full == spec;
+    }
+    let actual = [version.major, version.minor];
+    parts
+        .iter()
+        .zip(actual)
+        .all(|(part, actual)| part.parse::<u64>() == Ok(actual))
+}
+
+// Resolve a `name` or `name@version` spec against every package in the
+// dependency graph, the way `cargo -p` does
+fn match_spec<'a>(metadata: &'a Metadata, spec: &str) -> Result<&'a Package> {
+    let (name, version) = match spec.split_once('@') {
+        Some((name, version)) => (name, Some(version)),
+        None => (spec, None),
+    };
+    let matches: Vec<&Package> = metadata
+        .packages
+        .iter()
+        .filter(|p| p.name.as_str() == name)
+        .filter(|p| version.is_none_or(|v| version_matches(&p.version, v)))
+        .collect();
+
+    match matches.as_slice() {
+        [package] => Ok(package),
+        [] => {
+            anyhow::bail!(
+                "package ID specification `{}` did not match any packages{}",
+                spec,
+                did_you_mean(metadata, name)
+            )
+        }
+        _ => {
+            let mut specs: Vec<String> = matches
+                .iter()
+                .map(|p| format!("  {}@{}", p.name, p.version))
+                .collect();
+            specs.sort();
+            anyhow::bail!(
+                "There are multiple `{}` packages in your project, and the specification `{}` \
+                 is ambiguous.\nPlease re-run this command with one of the following \
+                 specifications:\n{}",
+                name,
+                spec,
+                specs.join("\n")
+            )
+        }
+    }
+}
+
+// How many near names a `Did you mean` hint offers at most
+const MAX_SUGGESTIONS: usize = 3;
+
+// A `Did you mean` hint naming the packages closest to a crate name that
+// wasn't found, or nothing when none is close (or the name exists after all)
+pub fn did_you_mean(metadata: &Metadata, name: &str) -> String {
+    hint(name, metadata.packages.iter().map(|p| p.name.as_str()))
+}
+
+// The same hint for crates looked up on docs.rs or a registry index, which
+// can be any crate: the names to suggest are the ones in the docs cache and
+// in the lockfile of the project zdoc runs in, if any
+pub fn did_you_mean_remote(name: &str) -> String {
+    let mut names = crate::cache::crate_names();
+    names.extend(lockfile_names());
+    hint(name, names.iter().map(String::as_str))
+}
+
+fn hint<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> String {
+    let normalize = |s: &str| s.replace('-', "_");
+    let candidates = candidates.filter(|candidate| normalize(candidate) != normalize(name));
+    let names: Vec<String> = closest_names(name, candidates)
+        .into_iter()
+        .map(|name| format!("`{}`", name))
+        .collect();
+    match names.as_slice() {
+        [] => String::new(),
+        [only] => format!("\n\n\tDid you mean {}?", only),
+        [rest @ .., last] => format!("\n\n\tDid you mean {} or {}?", rest.join(", "), last),
+    }
+}
+
+// Package names in the nearest `Cargo.lock` at or above the current directory
+fn lockfile_names() -> Vec<String> {
+    let Some(text) = std::env::current_dir().ok().and_then(|cwd| {
+        cwd.ancestors()
+            .find_map(|dir| std::fs::read_to_string(dir.join("Cargo.lock")).ok())
+    }) else {
+        return Vec::new();
+    };
+    let Ok(lockfile) = text.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    lockfile
+        .get("package")
+        .and_then(|packages| packages.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|package| package.get("name")?.as_str().map(str::to_string))
+        .collect()
+}
+
+// The candidates nearest to `name` by edit distance, closest first, treating
+// `-` and `_` as the same, among those close enough to be a likely typo
+pub fn closest_names<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> Vec<&'a str> {
+    let normalize = |s: &str| s.to_lowercase().replace('-', "_");
+    let wanted = normalize(name);
+    let max_distance = (wanted.chars().count() / 3).max(1);
+    let mut close: Vec<(usize, &str)> = candidates
+        .map(|candidate| (edit_distance(&wanted, &normalize(candidate)), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    close.sort();
+    close.dedup_by_key(|(_, candidate)| *candidate);
+    close
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+// Levenshtein distance over chars, with swapping two neighbours (`tokoi`
+// for `tokio`) counting as one edit like the others
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    // Rows for the prefixes of `a` one and two characters shorter
+    let mut before: Vec<usize> = Vec::new();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            let mut distance = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+            if i > 0 && j > 0 && *ca == b[j - 1] && a[i - 1] == *cb {
+                distance = distance.min(before[j - 1] + 1);
+            }
+            current.push(distance);
+        }
+        before = std::mem::replace(&mut previous, current);
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+
+    // A package from a `name@version` spec; workspace members live under
+    // `/ws`, everything else comes from crates.io
+    fn package(spec: &str, member: bool) -> Value {
+        let (name, version) = spec.split_once('@').unwrap();
+        let (id, source) = if member {
+            (format!("path+file:///ws/{}#{}", name, version), Value::Null)
+        } else {
+            (
+                format!(
+                    "registry+https://github.com/rust-lang/crates.io-index#{}",
+                    spec
+                ),
+                json!("registry+https://github.com/rust-lang/crates.io-index"),
+            )
+        };
+        json!({
+            "name": name,
+            "version": version,
+            "id": id,
+            "source": source,
+            "dependencies": [],
+            "targets": [],
+            "features": {},
+            "manifest_path": format!("/ws/{}/Cargo.toml", name),
+        })
+    }
+
+    // Metadata for a workspace of `members` depending on `deps`. A `root`
+    // member makes the manifest a package's; without one it's virtual.
+    fn metadata(
+        root: Option<&str>,
+        members: &[&str],
+        defaults: &[&str],
+        deps: &[&str],
+    ) -> Metadata {
+        let packages: Vec<Value> = members
+            .iter()
+            .map(|spec| package(spec, true))
+            .chain(deps.iter().map(|spec| package(spec, false)))
+            .collect();
+        let id = |spec: &str| {
+            packages
+                .iter()
+                .find(|p| {
+                    format!(
+                        "{}@{}",
+                        p["name"].as_str().unwrap(),
+                        p["version"].as_str().unwrap()
+                    ) == spec
+                })
+                .unwrap()["id"]
+                .clone()
+        };
+        let members: Vec<Value> = members.iter().map(|spec| id(spec)).collect();
+        let defaults: Vec<Value> = defaults.iter().map(|spec| id(spec)).collect();
+        let nodes: Vec<Value> = packages
+            .iter()
+            .map(|p| json!({"id": p["id"], "dependencies": []}))
+            .collect();
+        serde_json::from_value(json!({
+            "packages": packages,
+            "workspace_members": members,
+            "workspace_default_members": defaults,
+            "resolve": {"nodes": nodes, "root": root.map(id)},
+            "workspace_root": "/ws",
+            "target_directory": "/ws/target",
+            "version": 1,
+        }))
+        .unwrap()
+    }
+
+    fn names(packages: Vec<&Package>) -> Vec<String> {
+        packages
+            .iter()
+            .map(|p| format!("{}@{}", p.name, p.version))
+            .collect()
+    }
+
+    fn select(packages: &[&str]) -> PackageSelection {
+        PackageSelection {
+            packages: packages.iter().map(|spec| spec.to_string()).collect(),
+            ..PackageSelection::default()
+        }
+    }
+
+    fn workspace(exclude: &[&str]) -> PackageSelection {
+        PackageSelection {
+            workspace: true,
+            exclude: exclude.iter().map(|name| name.to_string()).collect(),
+            ..PackageSelection::default()
+        }
+    }
+
+    fn error(result: Result<Vec<&Package>>) -> String {
+        result.expect_err("the selection should fail").to_string()
+    }
+
+    #[test]
+    fn defaults_to_the_root_package() {
+        let metadata = metadata(
+            Some("app@0.1.0"),
+            &["app@0.1.0", "util@0.1.0"],
+            &["util@0.1.0"],
+            &[],
+        );
+        let selected = PackageSelection::default()
+            .resolve(&metadata, None)
+            .unwrap();
+        assert_eq!(names(selected), ["app@0.1.0"]);
+    }
+
+    #[test]
+    fn virtual_manifest_defaults_to_default_members() {
+        let members = ["app@0.1.0", "cli@0.1.0", "util@0.1.0"];
+        let with_defaults = metadata(None, &members, &["cli@0.1.0"], &[]);
+        let selected = PackageSelection::default()
+            .resolve(&with_defaults, None)
+            .unwrap();
+        assert_eq!(names(selected), ["cli@0.1.0"]);
+
+        // Without `default-members`, every member
+        let without_defaults = metadata(None, &members, &[], &[]);
+        let selected = PackageSelection::default()
+            .resolve(&without_defaults, None)
+            .unwrap();
+        assert_eq!(names(selected), members);
+    }
+
+    #[test]
+    fn workspace_leaves_out_excluded_members() {
+        let members = ["app@0.1.0", "cli@0.1.0", "util@0.1.0"];
+        let metadata = metadata(None, &members, &[], &["serde@1.0.0"]);
+        let selected = workspace(&[]).resolve(&metadata, None).unwrap();
+        assert_eq!(names(selected), members);
+        let selected = workspace(&["cli"]).resolve(&metadata, None).unwrap();
+        assert_eq!(names(selected), ["app@0.1.0", "util@0.1.0"]);
+        // Excluding a package that isn't a member only warns
+        let selected = workspace(&["serde", "nope"])
+            .resolve(&metadata, None)
+            .unwrap();
+        assert_eq!(names(selected), members);
+    }
+
+    #[test]
+    fn matches_name_and_version_specs() {
+        let deps = ["syn@1.0.109", "syn@2.0.48", "serde@1.0.195"];
+        let metadata = metadata(Some("app@0.1.0"), &["app@0.1.0"], &[], &deps);
+        let resolve = |specs: &[&str]| select(specs).resolve(&metadata, None).map(names);
+        assert_eq!(resolve(&["serde"]).unwrap(), ["serde@1.0.195"]);
+        assert_eq!(resolve(&["syn@2"]).unwrap(), ["syn@2.0.48"]);
+        assert_eq!(resolve(&["syn@1.0"]).unwrap(), ["syn@1.0.109"]);
+        assert_eq!(resolve(&["syn@1.0.109"]).unwrap(), ["syn@1.0.109"]);
+        // Specs naming the same package select it once
+        assert_eq!(
+            resolve(&["serde", "app", "serde@1"]).unwrap(),
+            ["serde@1.0.195", "app@0.1.0"]
+        );
+        // The positional crate argument takes the same specs
+        let selected = PackageSelection::default().resolve(&metadata, Some("syn@2.0"));
+        assert_eq!(names(selected.unwrap()), ["syn@2.0.48"]);
+    }
+
+    #[test]
+    fn ambiguous_specs_list_the_candidates() {
+        let metadata = metadata(
+            Some("app@0.1.0"),
+            &["app@0.1.0"],
+            &[],
+            &["syn@1.0.109", "syn@2.0.48"],
+        );
+        let message = error(select(&["syn"]).resolve(&metadata, None));
+        assert!(
+            message.contains("the specification `syn` is ambiguous"),
+            "{}",
+            message
+        );
+        assert!(
+            message.ends_with("  syn@1.0.109\n  syn@2.0.48"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn unmatched_specs_suggest_near_names() {
+        let metadata = metadata(
+            Some("app@0.1.0"),
+            &["app@0.1.0"],
+            &[],
+            &["serde@1.0.195", "serde_json@1.0.111"],
+        );
+        let message = error(select(&["sedre"]).resolve(&metadata, None));
+        assert_eq!(
+            message,
+            "package ID specification `sedre` did not match any packages\n\n\tDid you mean `serde`?"
+        );
+        // A known name at a version that isn't there isn't a typo
+        let message = error(select(&["serde@2"]).resolve(&metadata, None));
+        assert_eq!(
+            message,
+            "package ID specification `serde@2` did not match any packages"
+        );
+        // Nor is a name nothing is close to
+        let message = error(select(&["tokio"]).resolve(&metadata, None));
+        assert!(!message.contains("Did you mean"), "{}", message);
+    }
+
+    #[test]
+    fn resolve_one_wants_exactly_one_package() {
+        let members = ["app@0.1.0", "cli@0.1.0"];
+        let metadata = metadata(None, &members, &[], &[]);
+        let selected = select(&["cli"])
+            .resolve_one(&metadata, None, "list")
+            .unwrap();
+        assert_eq!(selected.name.as_str(), "cli");
+        let message = workspace(&[])
+            .resolve_one(&metadata, None, "list")
+            .err()
+            .unwrap()
+            .to_string();
+        assert_eq!(
+            message,
+            "`zdoc list` works on one package, but 2 were selected: app, cli\n\
+             Pick one with `-p <SPEC>`"
+        );
+        let message = workspace(&["app", "cli"])
+            .resolve_one(&metadata, None, "list")
+            .err()
+            .unwrap()
+            .to_string();
+        assert_eq!(message, "no packages selected for `zdoc list`");
+    }
+
+    const CRATES: &[&str] = &[
+        "reqwest",
+        "request",
+        "regex",
+        "tokio",
+        "tokio-util",
+        "serde",
+        "serde_json",
+        "hyper",
+        "log",
+        "lot",
+        "loom",
+    ];
+
+    fn closest(name: &str) -> Vec<&'static str> {
+        closest_names(name, CRATES.iter().copied())
+    }
+
+    #[test]
+    fn ranks_misspellings_by_distance() {
+        let cases: &[(&str, &[&str])] = &[
+            // An extra letter beats a changed one
+            ("reqwests", &["reqwest", "request"]),
+            ("requets", &["request", "reqwest"]),
+            // Swapped neighbours are one edit
+            ("tokoi", &["tokio"]),
+            ("tokio_utli", &["tokio-util"]),
+            ("hypr", &["hyper"]),
+            ("sedre", &["serde"]),
+            // Case, `-` and `_` don't count
+            ("Serde-JSON", &["serde_json"]),
+            ("regex", &["regex"]),
+            // Ties in name order
+            ("lox", &["log", "lot"]),
+            // Short names allow one edit, so nothing is near `xyz`
+            ("xyz", &[]),
+            ("tk", &[]),
+        ];
+        for (name, expected) in cases {
+            assert_eq!(closest(name), *expected, "suggestions for {}", name);
+        }
+    }
+
+    #[test]
+    fn suggests_at_most_three() {
+        let candidates = ["lag", "leg", "lig", "log", "lug"];
+        assert_eq!(
+            closest_names("lxg", candidates.into_iter()),
+            ["lag", "leg", "lig"]
+        );
+    }
+
+    #[test]
+    fn edit_distances() {
+        let cases = [
+            ("tokio", "tokio", 0),
+            ("tokio", "tokoi", 1),
+            ("tokio", "toki", 1),
+            ("tokio", "ttokio", 1),
+            ("tokio", "takio", 1),
+            ("serde", "sedre", 1),
+            ("serde", "sarda", 2),
+            ("", "log", 3),
+            ("reqwest", "request", 1),
+        ];
+        for (a, b, expected) in cases {
+            assert_eq!(edit_distance(a, b), expected, "{} -> {}", a, b);
+            assert_eq!(edit_distance(b, a), expected, "{} -> {}", b, a);
+        }
+    }
+
+    #[test]
+    fn hints_read_as_a_question() {
+        let hint = |name| hint(name, CRATES.iter().copied());
+        assert_eq!(
+            hint("reqwests"),
+            "\n\n\tDid you mean `reqwest` or `request`?"
+        );
+        assert_eq!(hint("tokoi"), "\n\n\tDid you mean `tokio`?");
+        assert_eq!(hint("loo"), "\n\n\tDid you mean `log`, `loom` or `lot`?");
+        assert_eq!(hint("xyz"), "");
+        // The name itself, spelled differently, isn't a suggestion
+        assert_eq!(hint("serde-json"), "");
+    }
+
+    #[test]
+    fn did_you_mean_suggests_packages() {
+        let metadata = metadata(
+            Some("app@0.1.0"),
+            &["app@0.1.0"],
+            &[],
+            &["reqwest@0.12.0", "tokio@1.40.0"],
+        );
+        assert_eq!(
+            did_you_mean(&metadata, "reqwests"),
+            "\n\n\tDid you mean `reqwest`?"
+        );
+        assert_eq!(
+            did_you_mean(&metadata, "tokoi"),
+            "\n\n\tDid you mean `tokio`?"
+        );
+        assert_eq!(did_you_mean(&metadata, "axum"), "");
+    }
+}
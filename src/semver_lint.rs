@@ -0,0 +1,416 @@
+// `--format semver-lint`: breaking changes reported as named lints, grouped
+// the way cargo-semver-checks prints them, for teams used to that tool
+use colored::Colorize;
+use std::collections::BTreeMap;
+
+use crate::bounds::{group_end, split_top_level};
+use crate::{ApiItem, DiffReport, Severity};
+
+// One breaking change, classified
+struct Finding {
+    lint: String,
+    summary: String,
+    // The item, as `kind path`
+    item: String,
+    // Extra context, e.g. what a parameter count changed from
+    detail: Option<String>,
+}
+
+// `function`, `method` or `trait_method`, the prefix of function lint names
+fn function_prefix(item: &ApiItem) -> &'static str {
+    match item.parent_kind.as_deref() {
+        Some("trait") => "trait_method",
+        Some("impl" | "trait_impl") => "method",
+        _ => "function",
+    }
+}
+
+fn removed(item: &ApiItem) -> (String, String) {
+    let parent = item.parent_kind.as_deref();
+    let (lint, summary) = match (item.kind_family(), parent) {
+        ("function", Some("trait")) => (
+            "trait_method_missing",
+            "pub trait method removed or renamed",
+        ),
+        ("function", Some("impl")) => ("inherent_method_missing", "pub method removed or renamed"),
+        ("function" | "assoc_type" | "assoc_const", Some("trait_impl")) => (
+            "trait_impl_item_missing",
+            "trait impl item removed (the impl was removed)",
+        ),
+        ("function" | "extern fn", _) => ("function_missing", "pub fn removed or renamed"),
+        ("struct", _) => ("struct_missing", "pub struct removed or renamed"),
+        ("enum", _) => ("enum_missing", "pub enum removed or renamed"),
+        ("union", _) => ("union_missing", "pub union removed or renamed"),
+        ("trait", _) => ("trait_missing", "pub trait removed or renamed"),
+        ("module", _) => ("module_missing", "pub module removed or renamed"),
+        ("macro", _) => (
+            "declarative_macro_missing",
+            "macro_rules! removed or renamed",
+        ),
+        ("proc_macro", _) => ("proc_macro_missing", "proc macro removed or renamed"),
+        ("constant", _) => (
+            "pub_module_level_const_missing",
+            "pub const removed or renamed",
+        ),
+        ("static" | "extern static", _) => ("pub_static_missing", "pub static removed or renamed"),
+        ("type_alias", _) => ("type_alias_missing", "pub type alias removed or renamed"),
+        ("variant", _) => ("enum_variant_missing", "enum variant removed or renamed"),
+        ("struct_field", Some("variant")) => (
+            "enum_struct_variant_field_missing",
+            "struct variant field removed or renamed",
+        ),
+        ("struct_field", Some("union")) => {
+            ("union_field_missing", "pub union field removed or renamed")
+        }
+        ("struct_field", _) => (
+            "struct_pub_field_missing",
+            "pub struct field removed or renamed",
+        ),
+        ("assoc_type", Some("trait")) => (
+            "trait_associated_type_missing",
+            "trait associated type removed or renamed",
+        ),
+        ("assoc_const", Some("trait")) => (
+            "trait_associated_const_missing",
+            "trait associated const removed or renamed",
+        ),
+        ("assoc_const", _) => (
+            "inherent_associated_const_missing",
+            "inherent associated const removed or renamed",
+        ),
+        ("impl", _) if item.has_tag("auto") => (
+            "auto_trait_impl_removed",
+            "type no longer implements an auto trait",
+        ),
+        ("impl", _) => ("trait_impl_removed", "trait impl removed"),
+        (kind, _) => {
+            return (
+                format!("{}_missing", kind.replace(' ', "_")),
+                format!("pub {} removed or renamed", kind),
+            );
+        }
+    };
+    (lint.to_string(), summary.to_string())
+}
+
+// Only called for breaking additions: variants of exhaustive enums and
+// required items of unsealed traits
+fn added(item: &ApiItem) -> (String, String) {
+    let (lint, summary) = match item.kind_family() {
+        "variant" => (
+            "enum_variant_added",
+            "enum variant added on exhaustive enum",
+        ),
+        "struct_field" if item.parent_kind.as_deref() == Some("variant") => (
+            "enum_variant_field_added",
+            "field added to a variant of an exhaustive enum",
+        ),
+        "struct_field" => (
+            "constructible_struct_adds_field",
+            "field added to an exhaustive struct without private fields",
+        ),
+        "function" => (
+            "trait_method_added",
+            "non-sealed trait gained a required method",
+        ),
+        "assoc_type" => (
+            "trait_associated_type_added",
+            "non-sealed trait gained an associated type without a default",
+        ),
+        "assoc_const" => (
+            "trait_associated_const_added",
+            "non-sealed trait gained an associated const without a default",
+        ),
+        _ => (
+            "trait_item_added",
+            "non-sealed trait gained a required item",
+        ),
+    };
+    (lint.to_string(), summary.to_string())
+}
+
+// A function signature split into qualifiers, generics, parameters and
+// return type
+struct FunctionParts<'a> {
+    qualifiers: Vec<&'a str>,
+    generics: &'a str,
+    params: Vec<&'a str>,
+    output: &'a str,
+}
+
+fn function_parts(signature: &str) -> Option<FunctionParts<'_>> {
+    let (qualifiers, signature) = crate::signature::split_qualifiers(signature);
+    let (generics, rest) = if signature.starts_with('<') {
+        signature.split_at(group_end(signature)? + 1)
+    } else {
+        ("", signature)
+    };
+    let close = group_end(rest)?;
+    let output = rest[close + 1..].trim();
+    Some(FunctionParts {
+        qualifiers,
+        generics,
+        params: split_top_level(&rest[1..close]),
+        output: output.strip_prefix("-> ").unwrap_or(output),
+    })
+}
+
+// Only called for breaking modifications; checks in the same order as
+// `modified_severity`
+fn modified(old: &ApiItem, new: &ApiItem) -> (String, String, Option<String>) {
+    let was = || Some(format!("was `{}`, now `{}`", old.signature, new.signature));
+
+    if crate::signature_breaking(old, new) {
+        let kind = new.kind_family();
+        if matches!(kind, "function" | "extern fn")
+            && let (Some(before), Some(after)) = (
+                function_parts(&old.signature),
+                function_parts(&new.signature),
+            )
+        {
+            let prefix = function_prefix(new);
+            if before.params.len() != after.params.len() {
+                return (
+                    format!("{}_parameter_count_changed", prefix),
+                    "pub fn parameter count changed".to_string(),
+                    Some(format!(
+                        "now takes {} parameters instead of {}",
+                        after.params.len(),
+                        before.params.len()
+                    )),
+                );
+            }
+            let gained = |qualifier: &str| {
+                !before.qualifiers.contains(&qualifier) && after.qualifiers.contains(&qualifier)
+            };
+            let (lint, summary) = if gained("unsafe") {
+                ("unsafe_added", "pub fn became unsafe")
+            } else if before.qualifiers.contains(&"const") && !after.qualifiers.contains(&"const") {
+                ("const_removed", "pub fn is no longer const")
+            } else if let Some(change) = crate::async_change(old, new) {
+                return (
+                    format!("{}_asyncness_changed", prefix),
+                    "pub fn switched between sync and async".to_string(),
+                    Some(format!("{}: {}", change, was().unwrap_or_default())),
+                );
+            } else if before.qualifiers != after.qualifiers {
+                ("qualifiers_changed", "pub fn qualifiers changed")
+            } else if before.generics != after.generics {
+                ("generics_changed", "pub fn generic parameters changed")
+            } else if before.params != after.params {
+                ("parameter_type_changed", "pub fn parameter type changed")
+            } else if before.output != after.output
+                && let Some(wrapping) = crate::return_wrapping(old, new)
+            {
+                return (
+                    format!("{}_return_type_changed", prefix),
+                    "pub fn return type changed".to_string(),
+                    Some(format!(
+                        "{}: {}",
+                        wrapping.describe(),
+                        was().unwrap_or_default()
+                    )),
+                );
+            } else if before.output != after.output {
+                ("return_type_changed", "pub fn return type changed")
+            } else {
+                ("signature_changed", "pub fn signature changed")
+            };
+            return (format!("{}_{}", prefix, lint), summary.to_string(), was());
+        }
+        let (lint, summary) = match kind {
+            "assoc_type"
+                if crate::without_assoc_default(&old.signature) == Some(new.signature.as_str()) =>
+            {
+                (
+                    "trait_associated_type_default_removed".to_string(),
+                    "associated type lost its default",
+                )
+            }
+            "assoc_type" => (
+                "associated_type_changed".to_string(),
+                "associated type changed",
+            ),
+            "struct_field" => (
+                "struct_field_type_changed".to_string(),
+                "pub field type changed",
+            ),
+            "constant" | "static" | "extern static" | "assoc_const" => (
+                format!("{}_type_changed", kind.replace(' ', "_")),
+                "pub item type changed",
+            ),
+            "trait" if !crate::supertrait_changes(old, new).is_empty() => {
+                let (_, old_bounds) = crate::bounds::split(&old.signature);
+                let (_, new_bounds) = crate::bounds::split(&new.signature);
+                let gained = new_bounds
+                    .difference(&old_bounds)
+                    .any(|(subject, _)| subject == "Self");
+                let lint = if gained {
+                    ("trait_added_supertrait", "pub trait gained a supertrait")
+                } else {
+                    ("trait_removed_supertrait", "pub trait lost a supertrait")
+                };
+                return (
+                    lint.0.to_string(),
+                    lint.1.to_string(),
+                    Some(crate::supertrait_changes(old, new).join("; ")),
+                );
+            }
+            "struct" | "enum" | "union" | "trait" | "type_alias"
+                if crate::bounds::split(&old.signature).0
+                    == crate::bounds::split(&new.signature).0 =>
+            {
+                (
+                    format!("{}_bounds_tightened", kind),
+                    "trait bounds tightened",
+                )
+            }
+            "struct" | "enum" | "union" | "trait" | "type_alias" => (
+                format!("{}_generics_changed", kind),
+                "generic parameters changed",
+            ),
+            "impl" => {
+                let lint = if old.has_tag("auto") || new.has_tag("auto") {
+                    "auto_trait_impl_conditions_tightened"
+                } else {
+                    "trait_impl_conditions_tightened"
+                };
+                return (
+                    lint.to_string(),
+                    "trait impl requires more of its type parameters".to_string(),
+                    Some(crate::impl_condition_changes(old, new).join("; ")),
+                );
+            }
+            _ => (
+                format!("{}_signature_changed", kind.replace(' ', "_")),
+                "signature changed",
+            ),
+        };
+        return (lint, summary.to_string(), was());
+    }
+
+    let lost = crate::lost_paths(old, new);
+    if !lost.is_empty() {
+        let paths: Vec<String> = lost.iter().map(|path| format!("`{}`", path)).collect();
+        return (
+            "reexport_path_missing".to_string(),
+            "item no longer importable by a previous path".to_string(),
+            Some(format!("no longer at {}", paths.join(", "))),
+        );
+    }
+
+    let gained = |tag: &str| !old.has_tag(tag) && new.has_tag(tag);
+    let (lint, summary) = if gained("non_exhaustive") {
+        let kind = match new.item_type.as_str() {
+            "variant" => "enum_variant",
+            kind => kind,
+        };
+        (
+            format!("{}_marked_non_exhaustive", kind),
+            "item marked #[non_exhaustive]",
+        )
+    } else if gained("sealed") {
+        ("trait_newly_sealed".to_string(), "pub trait became sealed")
+    } else if new.kind_family() == "function" {
+        (
+            "trait_method_default_impl_removed".to_string(),
+            "non-sealed trait method lost its default implementation",
+        )
+    } else {
+        (
+            "trait_item_default_removed".to_string(),
+            "non-sealed trait item lost its default",
+        )
+    };
+    (lint, summary.to_string(), None)
+}
+
+fn findings(report: &DiffReport) -> Vec<Finding> {
+    let mut found = Vec::new();
+    for item in &report.removed {
+        let (lint, summary) = removed(item);
+        found.push(Finding {
+            lint,
+            summary,
+            item: item.display_string(),
+            detail: None,
+        });
+    }
+    for item in &report.added {
+        if crate::added_severity(item) == Severity::Breaking {
+            let (lint, summary) = added(item);
+            found.push(Finding {
+                lint,
+                summary,
+                item: item.display_string(),
+                detail: None,
+            });
+        }
+    }
+    for (old_item, new_item) in &report.modified {
+        if crate::modified_severity(old_item, new_item) == Severity::Breaking {
+            let (lint, summary, detail) = modified(old_item, new_item);
+            found.push(Finding {
+                lint,
+                summary,
+                item: old_item.display_string(),
+                detail,
+            });
+        }
+    }
+    found
+}
+
+pub fn render(report: &DiffReport) -> String {
+    let mut out = format!(
+        "{:>12} {} {} -> {}\n",
+        "Checking".green().bold(),
+        report.crate_name,
+        report.ver1,
+        report.ver2
+    );
+
+    let mut by_lint: BTreeMap<String, Vec<Finding>> = BTreeMap::new();
+    for finding in findings(report) {
+        by_lint
+            .entry(finding.lint.clone())
+            .or_default()
+            .push(finding);
+    }
+
+    for (lint, findings) in &by_lint {
+        out.push_str(&format!(
+            "\n{} {}\n\nFailed in:\n",
+            format!("--- failure {}:", lint).red().bold(),
+            format!("{} ---", findings[0].summary).bold()
+        ));
+        for finding in findings {
+            match &finding.detail {
+                Some(detail) => out.push_str(&format!("  {}: {}\n", finding.item, detail)),
+                None => out.push_str(&format!("  {}\n", finding.item)),
+            }
+        }
+    }
+
+    let total = report.added.len() + report.removed.len() + report.modified.len();
+    let breaking: usize = by_lint.values().map(Vec::len).sum();
+    let summary = if breaking > 0 {
+        format!(
+            "semver requires new major version: {} major check{} failed ({} breaking change{})",
+            by_lint.len(),
+            if by_lint.len() == 1 { "" } else { "s" },
+            breaking,
+            if breaking == 1 { "" } else { "s" }
+        )
+    } else if total > 0 {
+        format!(
+            "no breaking changes; {} non-breaking change{}",
+            total,
+            if total == 1 { "" } else { "s" }
+        )
+    } else {
+        "no semver update required".to_string()
+    };
+    out.push_str(&format!("\n{:>12} {}\n", "Summary".bold(), summary));
+    out
+}
@@ -0,0 +1,454 @@
+// `zdoc setup`: settle on a toolchain that writes rustdoc JSON, and check
+// that it does. The toolchain (`--toolchain`, the configured one, or
+// nightly) is looked up among rustup's, installed if it's missing, given the
+// `rust-docs-json` component (std's own JSON docs), used to document a tiny
+// crate in a temporary directory, and saved as `[rustdoc] toolchain` in
+// `zdoc.toml`, which every later `cargo doc` run reads. Installing asks
+// first unless `--yes` is given.
+//
+// Everything outside zdoc (rustup, cargo, the question on the terminal)
+// goes through `Host`, so the flow can run against fakes.
+use anyhow::{Context, Result, bail};
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::Path;
+use std::process::Command;
+use tracing::warn;
+
+use crate::config::Config;
+use crate::doc_source::{self, DocSource};
+use crate::{items, toolchain};
+
+const DEFAULT_TOOLCHAIN: &str = "nightly";
+const DOCS_JSON_COMPONENT: &str = "rust-docs-json";
+
+// The crate documented to check the toolchain
+const PROBE_NAME: &str = "zdoc-probe";
+const PROBE_MANIFEST: &str =
+    "[package]\nname = \"zdoc-probe\"\nversion = \"0.0.0\"\nedition = \"2021\"\n\n[workspace]\n";
+const PROBE_LIB: &str = "//! Documented by `zdoc setup`\n\n/// Present in the JSON when rustdoc works\npub fn probe() {}\n";
+
+pub trait Host {
+    // Installed toolchains as rustup names them (`nightly-x86_64-unknown-linux-gnu`)
+    fn toolchains(&self) -> Result<Vec<String>>;
+    // Components installed for `toolchain` (`rust-docs-json-x86_64-unknown-linux-gnu`)
+    fn components(&self, toolchain: &str) -> Result<Vec<String>>;
+    fn install_toolchain(&self, toolchain: &str) -> Result<()>;
+    fn add_component(&self, toolchain: &str, component: &str) -> Result<()>;
+    // Run `cargo doc` for JSON on the crate at `manifest`
+    fn document(&self, toolchain: &str, manifest: &Path, target_dir: &Path) -> Result<()>;
+    // Ask a yes/no question
+    fn confirm(&self, question: &str) -> Result<bool>;
+}
+
+// The real rustup, cargo and terminal
+pub struct System;
+
+// Run rustup for its output
+fn rustup_output(args: &[&str]) -> Result<String> {
+    let output = Command::new("rustup")
+        .args(args)
+        .output()
+        .context("Failed to run rustup; install it from https://rustup.rs")?;
+    if !output.status.success() {
+        bail!(
+            "`rustup {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// Run rustup with its progress shown
+fn rustup_status(args: &[&str]) -> Result<()> {
+    let status = Command::new("rustup")
+        .args(args)
+        .status()
+        .context("Failed to run rustup")?;
+    if !status.success() {
+        bail!("`rustup {}` failed ({})", args.join(" "), status);
+    }
+    Ok(())
+}
+
+// The first word of each line: rustup follows names with `(default)` and such
+fn names(listing: &str) -> Vec<String> {
+    listing
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .map(String::from)
+        .collect()
+}
+
+impl Host for System {
+    fn toolchains(&self) -> Result<Vec<String>> {
+        Ok(names(&rustup_output(&["toolchain", "list"])?))
+    }
+
+    fn components(&self, toolchain: &str) -> Result<Vec<String>> {
+        let args = ["component", "list", "--installed", "--toolchain", toolchain];
+        Ok(names(&rustup_output(&args)?))
+    }
+
+    fn install_toolchain(&self, toolchain: &str) -> Result<()> {
+        rustup_status(&["toolchain", "install", toolchain, "--profile", "minimal"])
+    }
+
+    fn add_component(&self, toolchain: &str, component: &str) -> Result<()> {
+        rustup_status(&["component", "add", component, "--toolchain", toolchain])
+    }
+
+    fn document(&self, toolchain: &str, manifest: &Path, target_dir: &Path) -> Result<()> {
+        let status = toolchain::cargo_doc_on(Some(toolchain))
+            .arg("--quiet")
+            .arg("--manifest-path")
+            .arg(manifest)
+            .arg("--target-dir")
+            .arg(target_dir)
+            .status()
+            .context("Failed to run `cargo doc`")?;
+        if !status.success() {
+            bail!("`cargo +{} doc` failed ({})", toolchain, status);
+        }
+        Ok(())
+    }
+
+    fn confirm(&self, question: &str) -> Result<bool> {
+        if !std::io::stdin().is_terminal() {
+            bail!("{} (pass --yes to agree without being asked)", question);
+        }
+        eprint!("{} [y/N] ", question);
+        std::io::stderr().flush()?;
+        let mut answer = String::new();
+        std::io::stdin().lock().read_line(&mut answer)?;
+        Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+    }
+}
+
+// Whether rustup's `installed` is `toolchain`: `nightly` is installed as
+// `nightly-<host>`, but `nightly-2025-06-01-<host>` is another toolchain
+fn is_toolchain(installed: &str, toolchain: &str) -> bool {
+    installed == toolchain
+        || installed
+            .strip_prefix(toolchain)
+            .and_then(|rest| rest.strip_prefix('-'))
+            .is_some_and(|host| !host.starts_with(|c: char| c.is_ascii_digit()))
+}
+
+// Document the probe crate and check the JSON it comes out as
+fn verify(host: &dyn Host, toolchain: &str) -> Result<u64> {
+    let dir = tempfile::Builder::new()
+        .prefix("zdoc-setup-")
+        .tempdir()
+        .context("Failed to create a temporary project")?;
+    let manifest = dir.path().join("Cargo.toml");
+    std::fs::create_dir_all(dir.path().join("src")).context("Failed to create src/")?;
+    std::fs::write(&manifest, PROBE_MANIFEST).context("Failed to write Cargo.toml")?;
+    std::fs::write(dir.path().join("src/lib.rs"), PROBE_LIB)
+        .context("Failed to write src/lib.rs")?;
+
+    let target_dir = dir.path().join("target");
+    host.document(toolchain, &manifest, &target_dir)?;
+    let path = doc_source::crate_json_path(&target_dir.join("doc"), PROBE_NAME);
+    let json = doc_source::read_crate_json(PROBE_NAME, DocSource::File { path: &path })
+        .with_context(|| format!("{} didn't write usable rustdoc JSON", toolchain))?
+        .json;
+    if items::resolve_path(&json, &["zdoc_probe", "probe"]).is_empty() {
+        bail!(
+            "The JSON {} wrote is missing the test crate's function",
+            toolchain
+        );
+    }
+    Ok(json
+        .get("format_version")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0))
+}
+
+// Handler for `zdoc setup`; the toolchain is saved in the config in `config_dir`
+pub fn run(host: &dyn Host, toolchain: Option<&str>, yes: bool, config_dir: &Path) -> Result<()> {
+    let toolchain = toolchain
+        .or(toolchain::configured())
+        .unwrap_or(DEFAULT_TOOLCHAIN);
+    let ask = |question: &str| {
+        if yes {
+            Ok(true)
+        } else {
+            host.confirm(question)
+        }
+    };
+
+    let installed = host.toolchains()?;
+    if installed.iter().any(|name| is_toolchain(name, toolchain)) {
+        println!("Toolchain {} is installed.", toolchain);
+    } else {
+        let command = format!("rustup toolchain install {} --profile minimal", toolchain);
+        if !ask(&format!(
+            "{} isn't installed. Run `{}`?",
+            toolchain, command
+        ))? {
+            bail!(
+                "Setup needs the {} toolchain: run `{}`, or name another with --toolchain",
+                toolchain,
+                command
+            );
+        }
+        host.install_toolchain(toolchain)?;
+    }
+
+    // std's JSON docs are optional and only nightly has them, so failing to
+    // add them doesn't stop the setup
+    let components = host.components(toolchain)?;
+    if !toolchain.starts_with("nightly") {
+        println!(
+            "Skipping {}, which only nightly toolchains have.",
+            DOCS_JSON_COMPONENT
+        );
+    } else if components
+        .iter()
+        .any(|name| name.starts_with(DOCS_JSON_COMPONENT))
+    {
+        println!("Component {} is installed.", DOCS_JSON_COMPONENT);
+    } else {
+        let command = format!(
+            "rustup component add {} --toolchain {}",
+            DOCS_JSON_COMPONENT, toolchain
+        );
+        let question = format!("Add std's JSON docs with `{}`?", command);
+        if ask(&question)? {
+            if let Err(e) = host.add_component(toolchain, DOCS_JSON_COMPONENT) {
+                warn!("Continuing without {}: {:#}", DOCS_JSON_COMPONENT, e);
+            }
+        } else {
+            println!("Skipping {}.", DOCS_JSON_COMPONENT);
+        }
+    }
+
+    let format_version = verify(host, toolchain)?;
+    println!(
+        "Documented a test crate with {}: rustdoc JSON format {}.",
+        toolchain, format_version
+    );
+    let path = Config::set_toolchain(config_dir, toolchain)?;
+    println!(
+        "Saved `toolchain = \"{}\"` under [rustdoc] in {}; `cargo doc` runs use it from now on.",
+        toolchain,
+        path.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+
+    const HOST: &str = "x86_64-unknown-linux-gnu";
+
+    // rustup and cargo as seen by `run`: what's installed, the answers to
+    // give, and a log of what was done
+    #[derive(Default)]
+    struct Fake {
+        toolchains: RefCell<Vec<String>>,
+        components: Vec<String>,
+        answers: RefCell<VecDeque<bool>>,
+        // Whether rustdoc leaves out the probe's function, and adding a
+        // component fails
+        broken_rustdoc: bool,
+        broken_component: bool,
+        log: RefCell<Vec<String>>,
+    }
+
+    impl Fake {
+        fn with_toolchains(toolchains: &[&str]) -> Self {
+            Fake {
+                toolchains: RefCell::new(toolchains.iter().map(|name| name.to_string()).collect()),
+                ..Fake::default()
+            }
+        }
+
+        fn answering(self, answers: &[bool]) -> Self {
+            *self.answers.borrow_mut() = answers.iter().copied().collect();
+            self
+        }
+
+        fn did(&self, what: String) {
+            self.log.borrow_mut().push(what);
+        }
+    }
+
+    impl Host for Fake {
+        fn toolchains(&self) -> Result<Vec<String>> {
+            Ok(self.toolchains.borrow().clone())
+        }
+
+        fn components(&self, _toolchain: &str) -> Result<Vec<String>> {
+            Ok(self.components.clone())
+        }
+
+        fn install_toolchain(&self, toolchain: &str) -> Result<()> {
+            self.did(format!("install {}", toolchain));
+            self.toolchains
+                .borrow_mut()
+                .push(format!("{}-{}", toolchain, HOST));
+            Ok(())
+        }
+
+        fn add_component(&self, toolchain: &str, component: &str) -> Result<()> {
+            self.did(format!("add {} to {}", component, toolchain));
+            if self.broken_component {
+                bail!("component '{}' is unavailable", component);
+            }
+            Ok(())
+        }
+
+        // Writes the JSON rustdoc would, without running it
+        fn document(&self, toolchain: &str, manifest: &Path, target_dir: &Path) -> Result<()> {
+            self.did(format!("document with {}", toolchain));
+            assert_eq!(std::fs::read_to_string(manifest)?, PROBE_MANIFEST);
+            let mut json = serde_json::json!({
+                "root": 0,
+                "crate_version": "0.0.0",
+                "includes_private": false,
+                "index": {
+                    "0": {
+                        "id": 0, "crate_id": 0, "name": "zdoc_probe", "visibility": "public",
+                        "docs": null, "links": {}, "attrs": [], "deprecation": null,
+                        "inner": { "module": { "is_crate": true, "items": [1], "is_stripped": false } },
+                    },
+                    "1": {
+                        "id": 1, "crate_id": 0, "name": "probe", "visibility": "public",
+                        "docs": null, "links": {}, "attrs": [], "deprecation": null,
+                        "inner": { "function": {
+                            "sig": { "inputs": [], "output": null, "is_c_variadic": false },
+                            "generics": { "params": [], "where_predicates": [] },
+                            "header": { "is_const": false, "is_unsafe": false, "is_async": false, "abi": "Rust" },
+                            "has_body": true,
+                        }},
+                    },
+                },
+                "paths": {
+                    "0": { "crate_id": 0, "path": ["zdoc_probe"], "kind": "module" },
+                    "1": { "crate_id": 0, "path": ["zdoc_probe", "probe"], "kind": "function" },
+                },
+                "external_crates": {},
+                "format_version": 57,
+            });
+            if self.broken_rustdoc {
+                for table in ["index", "paths"] {
+                    json[table].as_object_mut().unwrap().remove("1");
+                }
+            }
+            let doc_dir = target_dir.join("doc");
+            std::fs::create_dir_all(&doc_dir)?;
+            std::fs::write(doc_dir.join("zdoc_probe.json"), json.to_string())?;
+            Ok(())
+        }
+
+        fn confirm(&self, question: &str) -> Result<bool> {
+            self.did(format!("ask: {}", question));
+            self.answers
+                .borrow_mut()
+                .pop_front()
+                .ok_or_else(|| anyhow!("asked more than expected: {}", question))
+        }
+    }
+
+    // Run the setup for `toolchain`, returning the saved config, if any
+    fn setup(host: &Fake, toolchain: &str, yes: bool) -> Result<Option<String>> {
+        let dir = tempfile::tempdir().unwrap();
+        run(host, Some(toolchain), yes, dir.path())?;
+        Ok(std::fs::read_to_string(dir.path().join(crate::config::CONFIG_FILE)).ok())
+    }
+
+    #[test]
+    fn installs_what_is_missing_once_agreed() {
+        let host =
+            Fake::with_toolchains(&["stable-x86_64-unknown-linux-gnu"]).answering(&[true, true]);
+        let config = setup(&host, "nightly", false).unwrap();
+        assert_eq!(
+            *host.log.borrow(),
+            [
+                "ask: nightly isn't installed. Run `rustup toolchain install nightly --profile minimal`?",
+                "install nightly",
+                "ask: Add std's JSON docs with `rustup component add rust-docs-json --toolchain nightly`?",
+                "add rust-docs-json to nightly",
+                "document with nightly",
+            ]
+        );
+        assert_eq!(
+            config.as_deref(),
+            Some("[rustdoc]\ntoolchain = \"nightly\"\n")
+        );
+    }
+
+    #[test]
+    fn declining_the_install_changes_nothing() {
+        let host = Fake::with_toolchains(&[]).answering(&[false]);
+        let error = setup(&host, "nightly", false).expect_err("declined");
+        assert!(
+            error
+                .to_string()
+                .starts_with("Setup needs the nightly toolchain"),
+            "{}",
+            error
+        );
+        assert_eq!(host.log.borrow().len(), 1);
+    }
+
+    #[test]
+    fn yes_agrees_without_asking() {
+        // A dated nightly isn't the moving one
+        let host = Fake::with_toolchains(&["nightly-2025-06-01-x86_64-unknown-linux-gnu"]);
+        setup(&host, "nightly", true).unwrap();
+        assert_eq!(
+            *host.log.borrow(),
+            [
+                "install nightly",
+                "add rust-docs-json to nightly",
+                "document with nightly",
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_what_is_installed() {
+        let host = Fake {
+            components: vec![format!("rust-docs-json-{}", HOST)],
+            ..Fake::with_toolchains(&["nightly-x86_64-unknown-linux-gnu"])
+        };
+        let config = setup(&host, "nightly", false).unwrap();
+        assert_eq!(*host.log.borrow(), ["document with nightly"]);
+        assert!(config.is_some());
+    }
+
+    #[test]
+    fn std_docs_are_optional() {
+        let host = Fake {
+            broken_component: true,
+            ..Fake::with_toolchains(&["nightly-x86_64-unknown-linux-gnu"])
+        };
+        assert!(setup(&host, "nightly", true).unwrap().is_some());
+        // Stable has no JSON docs of std to add
+        let host = Fake::with_toolchains(&["stable-x86_64-unknown-linux-gnu"]);
+        setup(&host, "stable", false).unwrap();
+        assert_eq!(*host.log.borrow(), ["document with stable"]);
+    }
+
+    #[test]
+    fn unusable_json_isnt_saved() {
+        let host = Fake {
+            broken_rustdoc: true,
+            ..Fake::with_toolchains(&["nightly-x86_64-unknown-linux-gnu"])
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let error = run(&host, Some("nightly"), true, dir.path()).expect_err("no probe function");
+        assert_eq!(
+            error.to_string(),
+            "The JSON nightly wrote is missing the test crate's function"
+        );
+        assert!(!dir.path().join(crate::config::CONFIG_FILE).exists());
+    }
+}
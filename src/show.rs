@@ -0,0 +1,295 @@
+// `zdoc show`: a docs.rs-style page for a crate root or module in the
+// terminal, with rendered docs and a grouped listing of public items, or
+// with `--peek` the source an item is defined by
+use anyhow::Result;
+use colored::Colorize;
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::items::{self, ResolvedItem};
+use crate::markdown;
+use crate::style::{self, Role};
+
+// Listing sections in docs.rs order: (heading, item kinds)
+const GROUPS: &[(&str, &[&str])] = &[
+    ("Modules", &["module"]),
+    ("Macros", &["macro", "proc_macro"]),
+    ("Structs", &["struct"]),
+    ("Enums", &["enum"]),
+    ("Unions", &["union"]),
+    ("Constants", &["constant"]),
+    ("Statics", &["static"]),
+    ("Traits", &["trait", "trait_alias"]),
+    ("Functions", &["function"]),
+    ("Type Aliases", &["type_alias"]),
+];
+
+// One line of a module listing
+struct Entry {
+    name: String,
+    kind: String,
+    summary: String,
+}
+
+// The module's public children, plus items it re-exports from elsewhere
+// under the name they're re-exported as
+fn module_entries(json: &Value, module: &Value) -> (Vec<Entry>, Vec<String>) {
+    let child_ids: Vec<String> = items::item_inner(module)
+        .and_then(|inner| inner.get("items"))
+        .and_then(|v| v.as_array())
+        .map(|ids| ids.iter().filter_map(items::id_key).collect())
+        .unwrap_or_default();
+
+    let mut entries = Vec::new();
+    let mut reexports = Vec::new();
+    for child_id in child_ids {
+        let Some(child) = items::lookup(json, &child_id) else {
+            continue;
+        };
+        if !matches!(items::item_kind(child), Some("use" | "import")) {
+            let Some(name) = items::item_name(child) else {
+                continue;
+            };
+            entries.push(Entry {
+                name: name.to_string(),
+                kind: items::item_kind(child).unwrap_or("unknown").to_string(),
+                summary: items::item_docs(child)
+                    .map(items::summary)
+                    .unwrap_or_default(),
+            });
+            continue;
+        }
+
+        let Some(inner) = items::item_inner(child) else {
+            continue;
+        };
+        let source = inner.get("source").and_then(|v| v.as_str()).unwrap_or("?");
+        let is_glob = inner.get("is_glob").and_then(|v| v.as_bool()) == Some(true);
+        let target = inner
+            .get("id")
+            .and_then(items::id_key)
+            .and_then(|id| items::lookup(json, &id));
+        match (target, is_glob) {
+            // Local items re-exported by name show up like the module's own,
+            // as rustdoc inlines them on the module page
+            (Some(target), false) if items::item_kind(target) != Some("module") => {
+                let name = inner
+                    .get("name")
+                    .and_then(|v| v.as_str())
+                    .or_else(|| items::item_name(target))
+                    .unwrap_or("?");
+                entries.push(Entry {
+                    name: name.to_string(),
+                    kind: items::item_kind(target).unwrap_or("unknown").to_string(),
+                    summary: items::item_docs(target)
+                        .map(items::summary)
+                        .unwrap_or_default(),
+                });
+            }
+            _ => reexports.push(if is_glob {
+                format!("pub use {}::*;", source)
+            } else {
+                format!("pub use {};", source)
+            }),
+        }
+    }
+
+    entries.sort_by_key(|entry| entry.name.to_lowercase());
+    reexports.sort();
+    (entries, reexports)
+}
+
+fn display_module(json: &Value, target: &ResolvedItem) {
+    let is_crate = items::item_inner(target.item)
+        .and_then(|inner| inner.get("is_crate"))
+        .and_then(|v| v.as_bool())
+        == Some(true);
+    let title = if is_crate { "Crate" } else { "Module" };
+    println!("\n{} {}\n", title, target.full_path().bold());
+
+    if let Some(docs) = items::item_docs(target.item) {
+        println!("{}\n", markdown::render(docs));
+    }
+
+    let (entries, reexports) = module_entries(json, target.item);
+    if !reexports.is_empty() {
+        println!("{}", "Re-exports".bold());
+        for reexport in &reexports {
+            println!("  {}", reexport);
+        }
+        println!();
+    }
+
+    let name_width = entries.iter().map(|e| e.name.len()).max().unwrap_or(0);
+    for (heading, kinds) in GROUPS {
+        let group: Vec<&Entry> = entries
+            .iter()
+            .filter(|entry| kinds.contains(&entry.kind.as_str()))
+            .collect();
+        if group.is_empty() {
+            continue;
+        }
+        println!("{}", heading.bold());
+        for entry in group {
+            println!("  {:name_width$}  {}", entry.name, entry.summary);
+        }
+        println!();
+    }
+}
+
+// Anything other than a module gets its declaration and rendered docs
+fn display_item(target: &ResolvedItem) {
+    println!("\n{}\n", target.full_path().bold());
+    println!("{}\n", items::declaration(target.item));
+    if let Some(docs) = items::item_docs(target.item) {
+        println!("{}\n", markdown::render(docs));
+    }
+}
+
+// Hidden items are public only so the crate's own macros or sibling crates
+// can reach them, and change without notice
+const HIDDEN_WARNING: &str = "Warning: this item is #[doc(hidden)], not part of the crate's supported API; it can change or go away in any release";
+
+// What `zdoc show` prints, as JSON: the declaration and docs, and for
+// modules the listing
+pub fn to_json(json: &Value, target: &ResolvedItem) -> Value {
+    let kind = items::item_kind(target.item);
+    let mut page = serde_json::json!({
+        "path": target.full_path(),
+        "kind": kind,
+        "hidden": items::hidden_items(json).contains(&target.id),
+        "declaration": items::declaration(target.item),
+        "docs": items::item_docs(target.item),
+    });
+    if kind == Some("module") {
+        let (entries, reexports) = module_entries(json, target.item);
+        let entries: Vec<Value> = entries
+            .iter()
+            .map(|entry| {
+                serde_json::json!({
+                    "name": entry.name,
+                    "kind": entry.kind,
+                    "summary": entry.summary,
+                })
+            })
+            .collect();
+        page["items"] = entries.into();
+        page["reexports"] = reexports.into();
+    }
+    page
+}
+
+// Where an item's source is: the file and its first and last lines
+fn span(item: &Value) -> Option<(PathBuf, usize, usize)> {
+    let span = item.get("span")?;
+    let line = |key: &str| {
+        span.get(key)
+            .and_then(|pos| pos.get(0))
+            .and_then(|v| v.as_u64())
+            .map(|line| line as usize)
+    };
+    let file = span.get("filename").and_then(|v| v.as_str())?;
+    Some((PathBuf::from(file), line("begin")?, line("end")?))
+}
+
+// The lines of source the item's span covers. Rustdoc writes paths relative
+// to the directory cargo ran it in, the workspace root for workspace
+// members; dependencies' sources have absolute paths.
+fn source(
+    metadata: &cargo_metadata::Metadata,
+    crate_name: &str,
+    item: &Value,
+) -> Option<(String, usize, String)> {
+    let (file, begin, end) = span(item)?;
+    let package_dir = metadata
+        .packages
+        .iter()
+        .find(|package| package.name == crate_name)
+        .and_then(|package| package.manifest_path.parent())
+        .map(|dir| dir.as_std_path().to_path_buf());
+    let path = [
+        Some(metadata.workspace_root.as_std_path().to_path_buf()),
+        package_dir,
+    ]
+    .into_iter()
+    .flatten()
+    .map(|dir| dir.join(&file))
+    .find(|path| path.is_file())?;
+    let text = fs::read_to_string(&path).ok()?;
+    let lines: Vec<&str> = text
+        .lines()
+        .skip(begin.saturating_sub(1))
+        .take(end + 1 - begin.max(1))
+        .collect();
+    if lines.is_empty() {
+        return None;
+    }
+    // Methods and nested items lose the indentation they share
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let lines: Vec<&str> = lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or(line.trim_start()))
+        .collect();
+    Some((file.display().to_string(), begin, lines.join("\n")))
+}
+
+// `--peek`: the item's source definition, cut at the end of the line that
+// reaches `max_chars`, or its declaration when rustdoc recorded no span or
+// the file can't be read
+fn display_source(
+    metadata: &cargo_metadata::Metadata,
+    crate_name: &str,
+    target: &ResolvedItem,
+    max_chars: usize,
+) {
+    println!("\n{}\n", target.full_path().bold());
+    let Some((file, begin, text)) = source(metadata, crate_name, target.item) else {
+        let note = "No source span in the docs; showing the declaration";
+        println!("{}", style::paint(Role::Muted, note));
+        println!("{}\n", items::declaration(target.item));
+        return;
+    };
+    let location = format!("{}:{}", file, begin);
+    println!("{}", style::paint(Role::Path, &location));
+    let mut shown = 0;
+    let lines: Vec<&str> = text.lines().collect();
+    for (i, line) in lines.iter().enumerate() {
+        if shown >= max_chars {
+            let note = format!("... {} more lines", lines.len() - i);
+            println!("{}", style::paint(Role::Muted, &note));
+            break;
+        }
+        println!("{}", line);
+        shown += line.chars().count() + 1;
+    }
+    println!();
+}
+
+// Handler for `zdoc show`. With `peek`, the item's source instead of its
+// docs page.
+pub fn run(
+    metadata: &cargo_metadata::Metadata,
+    item_path: &str,
+    peek: bool,
+    max_chars: usize,
+) -> Result<()> {
+    let located = items::locate(metadata, item_path)?;
+    let target = located.resolved();
+    if items::hidden_items(&located.json).contains(&target.id) {
+        println!("\n{}", style::paint(Role::Removed, HIDDEN_WARNING).bold());
+    }
+    if peek {
+        display_source(metadata, &located.crate_name, &target, max_chars);
+    } else if items::item_kind(target.item) == Some("module") {
+        display_module(&located.json, &target);
+    } else {
+        display_item(&target);
+    }
+    Ok(())
+}
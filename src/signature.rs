@@ -0,0 +1,680 @@
+// `zdoc signature`: an item's declaration written out as Rust, with its
+// visibility, qualifiers, generics and where clause. Unlike the shorthand
+// `extract_signature` produces for diffs, this is meant to be pasted.
+use anyhow::Result;
+use serde_json::Value;
+
+use crate::items;
+
+// `pub `, `pub(crate) `, `pub(in path) `, or nothing for inherited visibility
+fn visibility(item: &Value) -> String {
+    match item.get("visibility") {
+        Some(Value::String(vis)) if vis == "public" => "pub ".to_string(),
+        Some(Value::String(vis)) if vis == "crate" => "pub(crate) ".to_string(),
+        Some(Value::Object(vis)) => vis
+            .get("restricted")
+            .and_then(|restricted| restricted.get("path"))
+            .and_then(|v| v.as_str())
+            .map(|path| format!("pub(in {}) ", path))
+            .unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+// `const async unsafe extern "C" ` from a function header
+pub fn qualifiers(header: Option<&Value>) -> String {
+    let Some(header) = header else {
+        return String::new();
+    };
+    let flag = |key: &str| header.get(key).and_then(|v| v.as_bool()) == Some(true);
+
+    let mut out = String::new();
+    if flag("is_const") {
+        out.push_str("const ");
+    }
+    if flag("is_async") {
+        out.push_str("async ");
+    }
+    if flag("is_unsafe") {
+        out.push_str("unsafe ");
+    }
+    if let Some(abi) = abi(header) {
+        out.push_str(&format!("extern \"{}\" ", abi));
+    }
+    out
+}
+
+// A rendered function signature split into its leading qualifiers
+// (`const`, `async`, `unsafe`, `extern "C"`) and the rest
+pub fn split_qualifiers(signature: &str) -> (Vec<&str>, &str) {
+    let mut found = Vec::new();
+    let mut rest = signature;
+    loop {
+        if let Some(after) = ["const ", "async ", "unsafe "]
+            .iter()
+            .find_map(|keyword| rest.strip_prefix(keyword).map(|after| (keyword, after)))
+        {
+            found.push(after.0.trim_end());
+            rest = after.1;
+        } else if rest.starts_with("extern \"")
+            && let Some(close) = rest[8..].find("\" ")
+        {
+            found.push(&rest[..8 + close + 1]);
+            rest = &rest[8 + close + 2..];
+        } else {
+            return (found, rest);
+        }
+    }
+}
+
+// A rendered function signature's return type, `()` when it has none
+pub fn return_type(signature: &str) -> &str {
+    let Some(arrow) = crate::bounds::find_top_level(signature, " -> ") else {
+        return "()";
+    };
+    let rest = &signature[arrow + 4..];
+    match crate::bounds::find_top_level(rest, " where ") {
+        Some(end) => &rest[..end],
+        None => rest,
+    }
+}
+
+// `Option<T>` or a `Result<T, E>` (any path to them, and aliases such as
+// `io::Result<T>`) split into the wrapper's name and `T`
+pub fn wrapper(ty: &str) -> Option<(&'static str, &str)> {
+    let open = ty.find('<')?;
+    let name = ty[..open].rsplit("::").next()?;
+    let wrapper = ["Option", "Result"].into_iter().find(|w| *w == name)?;
+    let close = open + crate::bounds::group_end(&ty[open..])?;
+    if close + 1 != ty.len() {
+        return None;
+    }
+    let args = &ty[open + 1..close];
+    let first = match crate::bounds::find_top_level(args, ", ") {
+        Some(end) => &args[..end],
+        None => args,
+    };
+    Some((wrapper, first.trim()))
+}
+
+// How a function is asynchronous: declared `async fn`, or a plain `fn`
+// handing back a future for the caller to await
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Asyncness {
+    Async,
+    ReturnsFuture,
+}
+
+impl Asyncness {
+    pub fn label(self) -> &'static str {
+        match self {
+            Asyncness::Async => "async",
+            Asyncness::ReturnsFuture => "returns Future",
+        }
+    }
+
+    pub fn to_json(self) -> &'static str {
+        match self {
+            Asyncness::Async => "async",
+            Asyncness::ReturnsFuture => "returns_future",
+        }
+    }
+}
+
+// Whether a rendered function signature is async. Only return types that
+// are plainly futures count: `impl Future<..>` (with other bounds or not),
+// `Pin<Box<dyn Future<..>>>` and the `BoxFuture` aliases. Named future
+// types (`ResponseFuture`) are left alone, since a name proves nothing.
+pub fn asyncness(signature: &str) -> Option<Asyncness> {
+    let (qualifiers, _) = split_qualifiers(signature);
+    if qualifiers.contains(&"async") {
+        Some(Asyncness::Async)
+    } else if is_future(return_type(signature)) {
+        Some(Asyncness::ReturnsFuture)
+    } else {
+        None
+    }
+}
+
+// The same for a function item's inner JSON, from its header and return type
+pub fn function_asyncness(inner: &Value) -> Option<Asyncness> {
+    let is_async = inner
+        .get("header")
+        .and_then(|header| header.get("is_async"))
+        .and_then(|v| v.as_bool());
+    if is_async == Some(true) {
+        return Some(Asyncness::Async);
+    }
+    let output = inner
+        .get("sig")
+        .or_else(|| inner.get("decl"))
+        .and_then(|sig| sig.get("output"))
+        .filter(|output| !output.is_null())?;
+    is_future(&crate::format_type(output)).then_some(Asyncness::ReturnsFuture)
+}
+
+fn is_future(ty: &str) -> bool {
+    let ty = ty.trim();
+    let (name, args) = match ty.find('<') {
+        Some(open) if ty.ends_with('>') => (&ty[..open], &ty[open + 1..ty.len() - 1]),
+        _ => (ty, ""),
+    };
+    match name.rsplit("::").next().unwrap_or(name) {
+        "Pin" | "Box" => is_future(args),
+        "BoxFuture" | "LocalBoxFuture" => true,
+        _ => {
+            let Some(bounds) = ty.strip_prefix("impl ").or_else(|| ty.strip_prefix("dyn ")) else {
+                return false;
+            };
+            crate::bounds::split_at_top_level(bounds, " + ")
+                .iter()
+                .any(|bound| {
+                    let name = bound.split('<').next().unwrap_or(bound);
+                    name.rsplit("::").next() == Some("Future")
+                })
+        }
+    }
+}
+
+// A function header's ABI (`C`, `C-unwind`, `system`), or None for Rust's
+pub fn abi(header: &Value) -> Option<String> {
+    // The ABI is "Rust", another bare string, or `{"C": {"unwind": bool}}`
+    let abi = match header.get("abi") {
+        Some(Value::String(abi)) => Some(abi.clone()),
+        Some(Value::Object(abi)) => abi.iter().next().map(|(name, details)| {
+            if details.get("unwind").and_then(|v| v.as_bool()) == Some(true) {
+                format!("{}-unwind", name)
+            } else {
+                name.clone()
+            }
+        }),
+        _ => None,
+    };
+    abi.filter(|abi| abi != "Rust")
+}
+
+// A receiver written the way it's declared: `self`, `&self`, `&'a mut self`,
+// or `self: Box<Self>`
+pub fn receiver(ty: &Value) -> String {
+    let is_self = |ty: &Value| ty.get("generic").and_then(|v| v.as_str()) == Some("Self");
+    if is_self(ty) {
+        return "self".to_string();
+    }
+    if let Some(borrowed) = ty.get("borrowed_ref")
+        && borrowed.get("type").is_some_and(is_self)
+    {
+        let lifetime = crate::shown_lifetime(borrowed.get("lifetime"))
+            .map(|lifetime| format!("{} ", lifetime))
+            .unwrap_or_default();
+        let mutability = if borrowed.get("is_mutable").and_then(|v| v.as_bool()) == Some(true) {
+            "mut "
+        } else {
+            ""
+        };
+        return format!("&{}{}self", lifetime, mutability);
+    }
+    format!("self: {}", crate::format_type(ty))
+}
+
+fn function(item: &Value, name: &str, inner: &Value) -> String {
+    let generics = inner.get("generics");
+    let sig = inner.get("sig");
+
+    let mut params: Vec<String> = sig
+        .and_then(|sig| sig.get("inputs"))
+        .and_then(|v| v.as_array())
+        .map(|inputs| {
+            inputs
+                .iter()
+                .filter_map(|input| {
+                    let param = input.get(0)?.as_str()?;
+                    let ty = input.get(1)?;
+                    Some(if param == "self" {
+                        receiver(ty)
+                    } else {
+                        format!("{}: {}", param, crate::format_type(ty))
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    if sig
+        .and_then(|sig| sig.get("is_c_variadic"))
+        .and_then(|v| v.as_bool())
+        == Some(true)
+    {
+        params.push("...".to_string());
+    }
+
+    let output = sig
+        .and_then(|sig| sig.get("output"))
+        .filter(|output| !output.is_null())
+        .map(|output| format!(" -> {}", crate::format_type(output)))
+        .unwrap_or_default();
+
+    format!(
+        "{}{}fn {}{}({}){}{}",
+        visibility(item),
+        qualifiers(inner.get("header")),
+        name,
+        crate::format_generic_params(generics),
+        params.join(", "),
+        output,
+        crate::format_where_clause(generics)
+    )
+}
+
+// `pub name: Type` for named fields, `pub Type` for tuple fields
+fn field(json: &Value, id: &str, named: bool) -> Option<String> {
+    let field = items::lookup(json, id)?;
+    let ty = items::item_inner(field).map(crate::format_type)?;
+    if named {
+        Some(format!(
+            "{}{}: {}",
+            visibility(field),
+            items::item_name(field)?,
+            ty
+        ))
+    } else {
+        Some(format!("{}{}", visibility(field), ty))
+    }
+}
+
+// The part of a struct, union or variant after its name: `;`, `(A, B)` or
+// a braced field list. `indent` is the indentation of the enclosing line.
+fn fields(json: &Value, kind: Option<&Value>, indent: &str) -> String {
+    // Tuple fields hidden from the docs are nulls; show them as `_`
+    if let Some(tuple) = kind
+        .and_then(|kind| kind.get("tuple"))
+        .and_then(|v| v.as_array())
+    {
+        let rendered: Vec<String> = tuple
+            .iter()
+            .map(|id| {
+                items::id_key(id)
+                    .and_then(|id| field(json, &id, false))
+                    .unwrap_or_else(|| "_".to_string())
+            })
+            .collect();
+        return format!("({})", rendered.join(", "));
+    }
+
+    let named = kind.and_then(|kind| kind.get("plain").or_else(|| kind.get("struct")));
+    let Some(named) = named else {
+        return String::new();
+    };
+    let mut lines: Vec<String> = named
+        .get("fields")
+        .and_then(|v| v.as_array())
+        .map(|ids| {
+            ids.iter()
+                .filter_map(items::id_key)
+                .filter_map(|id| field(json, &id, true))
+                .map(|field| format!("{}    {},", indent, field))
+                .collect()
+        })
+        .unwrap_or_default();
+    if named.get("has_stripped_fields").and_then(|v| v.as_bool()) == Some(true) {
+        lines.push(format!("{}    /* private fields */", indent));
+    }
+    if lines.is_empty() {
+        "{}".to_string()
+    } else {
+        format!("{{\n{}\n{}}}", lines.join("\n"), indent)
+    }
+}
+
+fn variant(json: &Value, name: &str, inner: &Value, indent: &str) -> String {
+    let kind = inner.get("kind");
+    let mut out = name.to_string();
+    if kind.and_then(|v| v.as_str()) != Some("plain") {
+        let body = fields(json, kind, indent);
+        if body.starts_with('{') {
+            out.push(' ');
+        }
+        out.push_str(&body);
+    }
+    if let Some(discriminant) = inner.get("discriminant").filter(|d| !d.is_null()) {
+        let expr = discriminant
+            .get("expr")
+            .and_then(|v| v.as_str())
+            .unwrap_or("_");
+        out.push_str(&format!(" = {}", expr));
+    }
+    out
+}
+
+fn bounds_suffix(inner: &Value) -> String {
+    inner
+        .get("bounds")
+        .and_then(|v| v.as_array())
+        .filter(|bounds| !bounds.is_empty())
+        .map(|bounds| format!(": {}", crate::format_bounds(bounds)))
+        .unwrap_or_default()
+}
+
+// Trait members as they appear inside the trait's braces
+fn trait_body(json: &Value, inner: &Value) -> String {
+    let members: Vec<String> = inner
+        .get("items")
+        .and_then(|v| v.as_array())
+        .map(|ids| {
+            ids.iter()
+                .filter_map(items::id_key)
+                .filter_map(|id| items::lookup(json, &id))
+                .map(|member| {
+                    let provided = items::item_kind(member) == Some("function")
+                        && !items::is_required_trait_item(member);
+                    let terminator = if provided { " { ... }" } else { ";" };
+                    format!("    {}{}", render(json, member), terminator)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    if members.is_empty() {
+        "{}".to_string()
+    } else {
+        format!("{{\n{}\n}}", members.join("\n"))
+    }
+}
+
+// A constant's value: the source expression when rustdoc kept it,
+// otherwise the evaluated value
+fn const_value(konst: Option<&Value>) -> Option<String> {
+    let konst = konst?;
+    let expr = konst.get("expr").and_then(|v| v.as_str());
+    let value = konst.get("value").and_then(|v| v.as_str());
+    match (expr, value) {
+        (Some(expr), _) if expr != "_" => Some(expr.to_string()),
+        (_, Some(value)) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+// The item's declaration as Rust source
+pub fn render(json: &Value, item: &Value) -> String {
+    crate::keeping_elided_args(|| render_item(json, item))
+}
+
+fn render_item(json: &Value, item: &Value) -> String {
+    let kind = items::item_kind(item).unwrap_or("unknown");
+    let name = items::item_name(item).unwrap_or("_");
+    let Some(inner) = items::item_inner(item) else {
+        return items::declaration(item);
+    };
+    let vis = visibility(item);
+    let generics = inner.get("generics");
+    let params = crate::format_generic_params(generics);
+    let where_clause = crate::format_where_clause(generics);
+    let ty = |key: &str| inner.get(key).map(crate::format_type);
+
+    match kind {
+        "function" => function(item, name, inner),
+        "struct" | "union" => {
+            let body = match inner.get("kind") {
+                Some(struct_kind) => fields(json, Some(struct_kind), ""),
+                // Unions keep their fields directly in the inner object
+                None => fields(json, Some(&serde_json::json!({ "plain": inner })), ""),
+            };
+            match body.as_str() {
+                "" => format!("{}{} {}{}{};", vis, kind, name, params, where_clause),
+                tuple if tuple.starts_with('(') => {
+                    format!(
+                        "{}{} {}{}{}{};",
+                        vis, kind, name, params, tuple, where_clause
+                    )
+                }
+                braced => format!(
+                    "{}{} {}{}{} {}",
+                    vis, kind, name, params, where_clause, braced
+                ),
+            }
+        }
+        "enum" => {
+            let mut lines: Vec<String> = inner
+                .get("variants")
+                .and_then(|v| v.as_array())
+                .map(|ids| {
+                    ids.iter()
+                        .filter_map(items::id_key)
+                        .filter_map(|id| items::lookup(json, &id))
+                        .filter_map(|v| {
+                            let variant_inner = items::item_inner(v)?;
+                            let variant_name = items::item_name(v)?;
+                            Some(format!(
+                                "    {},",
+                                variant(json, variant_name, variant_inner, "    ")
+                            ))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            if inner.get("has_stripped_variants").and_then(|v| v.as_bool()) == Some(true) {
+                lines.push("    // some variants omitted".to_string());
+            }
+            let body = if lines.is_empty() {
+                "{}".to_string()
+            } else {
+                format!("{{\n{}\n}}", lines.join("\n"))
+            };
+            format!("{}enum {}{}{} {}", vis, name, params, where_clause, body)
+        }
+        "variant" => variant(json, name, inner, ""),
+        "struct_field" => format!("{}{}: {}", vis, name, crate::format_type(inner)),
+        "trait" => {
+            let flag = |key: &str| inner.get(key).and_then(|v| v.as_bool()) == Some(true);
+            let unsafety = if flag("is_unsafe") { "unsafe " } else { "" };
+            let auto = if flag("is_auto") { "auto " } else { "" };
+            format!(
+                "{}{}{}trait {}{}{}{} {}",
+                vis,
+                unsafety,
+                auto,
+                name,
+                params,
+                bounds_suffix(inner),
+                where_clause,
+                trait_body(json, inner)
+            )
+        }
+        "trait_alias" => {
+            let bounds = inner
+                .get("params")
+                .and_then(|v| v.as_array())
+                .map(|bounds| crate::format_bounds(bounds))
+                .unwrap_or_default();
+            format!(
+                "{}trait {}{} = {}{};",
+                vis, name, params, bounds, where_clause
+            )
+        }
+        "type_alias" => format!(
+            "{}type {}{}{} = {};",
+            vis,
+            name,
+            params,
+            where_clause,
+            ty("type").unwrap_or_default()
+        ),
+        "assoc_type" => {
+            let default = inner
+                .get("type")
+                .filter(|ty| !ty.is_null())
+                .map(|ty| format!(" = {}", crate::format_type(ty)))
+                .unwrap_or_default();
+            format!(
+                "{}type {}{}{}{}{}",
+                vis,
+                name,
+                params,
+                bounds_suffix(inner),
+                where_clause,
+                default
+            )
+        }
+        "constant" => {
+            let value = const_value(inner.get("const"))
+                .map(|value| format!(" = {}", value))
+                .unwrap_or_default();
+            format!(
+                "{}const {}: {}{};",
+                vis,
+                name,
+                ty("type").unwrap_or_default(),
+                value
+            )
+        }
+        "assoc_const" => {
+            let value = inner
+                .get("value")
+                .and_then(|v| v.as_str())
+                .map(|value| format!(" = {}", value))
+                .unwrap_or_default();
+            format!(
+                "{}const {}: {}{}",
+                vis,
+                name,
+                ty("type").unwrap_or_default(),
+                value
+            )
+        }
+        "static" => {
+            let mutability = if inner.get("is_mutable").and_then(|v| v.as_bool()) == Some(true) {
+                "mut "
+            } else {
+                ""
+            };
+            let value = inner
+                .get("expr")
+                .and_then(|v| v.as_str())
+                .filter(|expr| *expr != "_")
+                .map(|expr| format!(" = {}", expr))
+                .unwrap_or_default();
+            format!(
+                "{}static {}{}: {}{};",
+                vis,
+                mutability,
+                name,
+                ty("type").unwrap_or_default(),
+                value
+            )
+        }
+        "module" => format!("{}mod {}", vis, name),
+        "macro" => inner.as_str().unwrap_or_default().to_string(),
+        "proc_macro" => match inner.get("kind").and_then(|v| v.as_str()) {
+            Some("derive") => format!("#[derive({})]", name),
+            Some("attr") => format!("#[{}]", name),
+            _ => format!("{}!(...)", name),
+        },
+        _ => items::declaration(item),
+    }
+}
+
+// Handler for `zdoc signature`
+pub fn run(metadata: &cargo_metadata::Metadata, item_path: &str, copy: bool) -> Result<()> {
+    let located = items::locate(metadata, item_path)?;
+    let target = located.resolved();
+    let declaration = render(&located.json, target.item);
+    if copy {
+        crate::clipboard::copy(
+            &declaration,
+            &format!("the declaration of {}", target.full_path()),
+        );
+    } else {
+        println!("{}", declaration);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    fn rendered(json: &Value, path: &str) -> String {
+        let segments: Vec<&str> = path.split("::").collect();
+        let resolved = items::resolve_path(json, &segments);
+        assert_eq!(resolved.len(), 1, "one item at {}", path);
+        render(json, resolved[0].item)
+    }
+
+    // A GAT's arguments can't leave `'_` out
+    #[test]
+    fn elided_lifetime_arguments() {
+        let json = fixtures::load("signatures");
+        assert_eq!(
+            rendered(&json, "signatures::Lender::lend"),
+            "fn lend(&self) -> Self::Loan<'_>"
+        );
+        assert_eq!(
+            rendered(&json, "signatures::Lender::borrowed"),
+            "fn borrowed<'a>(&'a self) -> Self::Loan<'a>"
+        );
+    }
+
+    #[test]
+    fn higher_ranked_bounds() {
+        let json = fixtures::load("signatures");
+        assert_eq!(
+            rendered(&json, "signatures::for_each_line"),
+            "pub fn for_each_line<F>(text: &str, f: F) where F: for<'a> Fn(&'a str) -> &'a str"
+        );
+        assert_eq!(
+            rendered(&json, "signatures::apply"),
+            "pub fn apply(f: &dyn for<'a> Fn(&'a [u8]) -> &'a [u8])"
+        );
+    }
+
+    #[test]
+    fn impl_trait_in_argument_and_return_position() {
+        let json = fixtures::load("signatures");
+        // The argument's synthetic type parameter isn't written out
+        assert_eq!(
+            rendered(&json, "signatures::show"),
+            "pub fn show(value: impl Debug + Send)"
+        );
+        assert_eq!(
+            rendered(&json, "signatures::evens"),
+            "pub fn evens(limit: u32) -> impl Iterator<Item = u32> + Clone"
+        );
+    }
+
+    #[test]
+    fn const_generics() {
+        let json = fixtures::load("signatures");
+        assert_eq!(
+            rendered(&json, "signatures::first"),
+            "pub fn first<const N: usize>(values: [u8; N]) -> Option<u8>"
+        );
+        assert_eq!(
+            rendered(&json, "signatures::Buffer"),
+            "pub struct Buffer<T, const N: usize = 16> {\n    pub items: [T; N],\n}"
+        );
+    }
+
+    #[test]
+    fn function_qualifiers() {
+        let json = fixtures::load("signatures");
+        assert_eq!(
+            rendered(&json, "signatures::Buffer::len"),
+            "pub const fn len(&self) -> usize"
+        );
+        assert_eq!(
+            rendered(&json, "signatures::fetch"),
+            "pub async fn fetch(url: &str) -> Result<Vec<u8>, String>"
+        );
+        assert_eq!(
+            rendered(&json, "signatures::raw"),
+            "pub unsafe extern \"C\" fn raw(ptr: *const u8) -> u8"
+        );
+    }
+
+    #[test]
+    fn splits_rendered_qualifiers() {
+        assert_eq!(
+            split_qualifiers("const unsafe extern \"C\" fn raw()"),
+            (vec!["const", "unsafe", "extern \"C\""], "fn raw()")
+        );
+        assert_eq!(split_qualifiers("(x: u8)"), (vec![], "(x: u8)"));
+    }
+}
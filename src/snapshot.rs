@@ -0,0 +1,119 @@
+// `zdoc snapshot`: a crate's public API as sorted lines of text, to commit
+// next to the code and review with plain `git diff`. Each item is one line,
+// its kind, path and signature, with its compatibility markers at the end;
+// the paths it's also re-exported at follow on indented `= ` lines. Ids,
+// docs and anything else that changes without the API changing are left
+// out, so regenerating an unchanged crate's snapshot changes nothing.
+use anyhow::Result;
+use serde_json::Value;
+
+use crate::ApiItem;
+
+// Items sort by path segment by segment, which keeps members right under
+// the type they belong to (`Config::port` before `Config2`)
+type Entry = (Vec<String>, String, String, Vec<String>);
+
+// One item's line, whitespace inside the signature folded so that an item
+// never spans lines
+fn line(item: &ApiItem) -> String {
+    let signature = item
+        .shown_signature()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut line = item
+        .impl_header()
+        .unwrap_or_else(|| format!("{} {}", item.item_type, item.full_path()));
+    // `Item: Clone` for an associated type's bounds and `Foo: Display` for a
+    // trait's supertraits; `Item = u8` for an associated type's value
+    if signature.starts_with('=') {
+        line.push(' ');
+    } else if !signature.is_empty() && !signature.starts_with(':') {
+        line.push_str(": ");
+    }
+    line.push_str(&signature);
+    if !item.tags.is_empty() {
+        line.push_str(&format!(" [{}]", item.tags.join(", ")));
+    }
+    line
+}
+
+// The snapshot of the crate `json` documents
+pub fn render(crate_name: &str, json: &Value) -> Result<String> {
+    // Blanket impl members (`from`, `borrow`, ...) have no path of their own
+    // to sort or tell them apart by, and say nothing about this crate
+    let mut entries: Vec<Entry> = crate::extract_api_items(json)?
+        .into_iter()
+        .filter(|item| !item.is_pathless_member())
+        .map(|item| {
+            let mut segments = item.path.clone();
+            segments.push(item.name.clone());
+            (
+                segments,
+                item.item_type.clone(),
+                line(&item),
+                item.reexports.clone(),
+            )
+        })
+        .collect();
+    entries.sort();
+    entries.dedup();
+
+    let version = json
+        .get("crate_version")
+        .and_then(|v| v.as_str())
+        .map(|version| format!(" {}", version))
+        .unwrap_or_default();
+    let mut out = format!(
+        "# Public API of {}{}, written by `zdoc snapshot`\n",
+        crate_name, version
+    );
+    for (_, _, line, reexports) in &entries {
+        out.push_str(line);
+        out.push('\n');
+        for path in reexports {
+            out.push_str(&format!("  = {}\n", path));
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    // The same docs with every list of ids (module items, impls, fields,
+    // ...) in the opposite order, as another rustdoc run might write them
+    fn reversed(value: &mut Value) {
+        match value {
+            Value::Array(values) if values.iter().all(Value::is_u64) => values.reverse(),
+            Value::Array(values) => values.iter_mut().for_each(reversed),
+            Value::Object(fields) => fields.values_mut().for_each(reversed),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn leaves_out_blanket_members() {
+        let snapshot = render("widgets", &fixtures::load("widgets")).unwrap();
+        for line in snapshot.lines() {
+            assert!(
+                line.starts_with('#') || line.contains("widgets"),
+                "{}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn output_is_deterministic() {
+        for fixture in ["widgets", "signatures", "tasks-new"] {
+            let mut json = fixtures::load(fixture);
+            let snapshot = render(fixture, &json).unwrap();
+            assert_eq!(render(fixture, &json).unwrap(), snapshot);
+            reversed(&mut json);
+            assert_eq!(render(fixture, &json).unwrap(), snapshot, "{}", fixture);
+        }
+    }
+}
@@ -0,0 +1,518 @@
+// Where a crate's docs and releases come from. Crates on crates.io use
+// docs.rs and the crates.io sparse index; a private registry can have a docs
+// server of its own, laid out like docs.rs (`/crate/{name}/{version}/json.gz`),
+// and its own sparse index. The `[sources]` section of `zdoc.toml` sets them
+// up:
+//
+//     [sources.registries.acme]
+//     index = "sparse+https://cargo.acme.dev/index/"
+//     docs-url = "https://docs.acme.dev"
+//     token-env = "ACME_TOKEN"
+//     crates = ["acme-*"]
+//
+// A crate is served by a registry when the lockfile says it comes from the
+// registry's `index`, or its name matches one of `crates` (for crates
+// outside the project). Every other crate uses the defaults: docs.rs and
+// crates.io, unless `[sources]` `docs-url`/`registry-api`,
+// `ZDOC_DOCS_URL`/`ZDOC_REGISTRY_API` or `--docs-url`/`--registry-api`
+// point elsewhere. Tokens are sent as bearer auth, but never to docs.rs or
+// crates.io.
+use anyhow::Result;
+use cargo_metadata::{Metadata, Package};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use tracing::{debug, warn};
+
+use crate::config::{CONFIG_FILE, Config, SourcesConfig};
+
+const DOCS_RS: &str = "https://docs.rs";
+const CRATES_IO_INDEX: &str = "https://index.crates.io";
+// Where crates.io serves `.crate` files
+const CRATES_IO_STATIC: &str = "https://static.crates.io";
+
+#[derive(Clone, Debug)]
+pub struct Source {
+    docs_url: String,
+    index_url: Option<String>,
+    token: Option<String>,
+}
+
+impl Source {
+    // The rustdoc JSON of a release (or `latest`)
+    pub fn json_url(&self, crate_name: &str, version: &str) -> String {
+        format!(
+            "{}/crate/{}/{}/json.gz",
+            self.docs_url.trim_end_matches('/'),
+            crate_name,
+            version
+        )
+    }
+
+    // The docs server, for messages
+    pub fn docs_host(&self) -> &str {
+        &self.docs_url
+    }
+
+    // The sparse index base URL, if releases can be looked up
+    pub fn index_url(&self) -> Option<&str> {
+        self.index_url
+            .as_deref()
+            .map(|url| url.trim_end_matches('/'))
+    }
+
+    // A GET of `url` with this source's token, unless `url` is public
+    pub fn get(&self, client: &reqwest::Client, url: &str) -> reqwest::RequestBuilder {
+        let request = client.get(url);
+        let public = [DOCS_RS, CRATES_IO_INDEX, CRATES_IO_STATIC]
+            .iter()
+            .any(|host| url.starts_with(&format!("{}/", host)));
+        match &self.token {
+            Some(token) if !public => request.bearer_auth(token),
+            _ => request,
+        }
+    }
+}
+
+struct Registry {
+    name: String,
+    // The index URL without its `sparse+`/`registry+` prefix
+    index: Option<String>,
+    crates: Option<GlobSet>,
+    source: Source,
+}
+
+struct Sources {
+    default: Source,
+    registries: Vec<Registry>,
+}
+
+static SOURCES: OnceLock<Sources> = OnceLock::new();
+// Crate name -> index of its registry, from the project's lockfile
+static LOCKED: Mutex<Option<HashMap<String, usize>>> = Mutex::new(None);
+
+// `sparse+https://x/index/` and `https://x/index` name the same index
+fn normalize_index(index: &str) -> String {
+    let index = index
+        .strip_prefix("sparse+")
+        .or_else(|| index.strip_prefix("registry+"))
+        .unwrap_or(index);
+    index.trim_end_matches('/').to_string()
+}
+
+fn token(token: Option<String>, token_env: Option<&str>, what: &str) -> Option<String> {
+    if let Some(name) = token_env {
+        match std::env::var(name) {
+            Ok(value) if !value.trim().is_empty() => return Some(value.trim().to_string()),
+            _ => warn!("{} is not set; fetching {} without a token", name, what),
+        }
+    }
+    token
+}
+
+fn globs(patterns: &[String], registry: &str) -> Result<Option<GlobSet>> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern).map_err(|e| {
+            anyhow::anyhow!(
+                "Invalid crate pattern '{}' for {}: {}",
+                pattern,
+                registry,
+                e
+            )
+        })?);
+    }
+    Ok(Some(builder.build()?))
+}
+
+impl Sources {
+    fn resolve(
+        config: SourcesConfig,
+        docs_url: Option<String>,
+        registry_api: Option<String>,
+    ) -> Self {
+        let env = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let default = Source {
+            docs_url: docs_url
+                .or_else(|| env("ZDOC_DOCS_URL"))
+                .or(config.docs_url)
+                .unwrap_or_else(|| DOCS_RS.to_string()),
+            index_url: Some(
+                registry_api
+                    .or_else(|| env("ZDOC_REGISTRY_API"))
+                    .or(config.registry_api)
+                    .unwrap_or_else(|| CRATES_IO_INDEX.to_string()),
+            ),
+            token: token(
+                env("ZDOC_TOKEN").or(config.token),
+                config.token_env.as_deref(),
+                "the default docs",
+            ),
+        };
+
+        let mut registries = Vec::new();
+        for (name, registry) in config.registries {
+            if registry.docs_url.is_empty() {
+                warn!(
+                    "Registry '{}' in {} has no docs-url; its crates use the defaults",
+                    name, CONFIG_FILE
+                );
+                continue;
+            }
+            let crates = match globs(&registry.crates, &name) {
+                Ok(crates) => crates,
+                Err(e) => {
+                    warn!("{:#}", e);
+                    None
+                }
+            };
+            let index = registry.index.as_deref().map(normalize_index);
+            let what = format!("registry '{}'", name);
+            registries.push(Registry {
+                source: Source {
+                    docs_url: registry.docs_url,
+                    index_url: registry.registry_api.or_else(|| index.clone()),
+                    token: token(registry.token, registry.token_env.as_deref(), &what),
+                },
+                name,
+                index,
+                crates,
+            });
+        }
+        Sources {
+            default,
+            registries,
+        }
+    }
+}
+
+// Set the sources for the rest of the run from the flags, the environment
+// and the nearest `zdoc.toml`
+pub fn init(docs_url: Option<String>, registry_api: Option<String>) {
+    let config = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| Config::find(&cwd))
+        .map(|root| Config::load(&root))
+        .transpose()
+        .unwrap_or_else(|e| {
+            warn!("Ignoring the sources in {}: {:#}", CONFIG_FILE, e);
+            None
+        });
+    let config = config.map(|config| config.sources).unwrap_or_default();
+    let _ = SOURCES.set(Sources::resolve(config, docs_url, registry_api));
+}
+
+fn sources() -> &'static Sources {
+    SOURCES.get_or_init(|| Sources::resolve(SourcesConfig::default(), None, None))
+}
+
+impl Sources {
+    // The registry `package` was locked from, if one is configured
+    fn registry_of(&self, package: &Package) -> Option<usize> {
+        let source = package.source.as_ref()?;
+        let index = normalize_index(&source.repr);
+        self.registries
+            .iter()
+            .position(|registry| registry.index.as_deref() == Some(index.as_str()))
+    }
+
+    // Crate name -> its registry, for the packages locked from one
+    fn locked(&self, metadata: &Metadata) -> HashMap<String, usize> {
+        metadata
+            .packages
+            .iter()
+            .filter_map(|package| Some((package.name.to_string(), self.registry_of(package)?)))
+            .collect()
+    }
+
+    // Where to fetch `crate_name` from: the registry it's locked from, else
+    // the first whose `crates` match it, else the defaults
+    fn for_crate(&self, crate_name: &str, locked: Option<usize>) -> &Source {
+        let registry = locked.map(|i| &self.registries[i]).or_else(|| {
+            self.registries.iter().find(|registry| {
+                registry
+                    .crates
+                    .as_ref()
+                    .is_some_and(|crates| crates.is_match(crate_name))
+            })
+        });
+        match registry {
+            Some(registry) => {
+                debug!("{} is served by registry '{}'", crate_name, registry.name);
+                &registry.source
+            }
+            None => &self.default,
+        }
+    }
+}
+
+// Learn which registry each of the project's dependencies comes from
+pub fn register(metadata: &Metadata) {
+    let locked = sources().locked(metadata);
+    if !locked.is_empty() {
+        debug!(
+            "{} locked crates come from private registries",
+            locked.len()
+        );
+    }
+    *LOCKED.lock().unwrap_or_else(|e| e.into_inner()) = Some(locked);
+}
+
+// Where to fetch `crate_name` from
+pub fn for_crate(crate_name: &str) -> Source {
+    let locked = LOCKED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .and_then(|locked| locked.get(crate_name).copied());
+    sources().for_crate(crate_name, locked).clone()
+}
+
+// Where a locked package's releases come from: crates.io or a configured
+// registry. Path, git and unconfigured registry packages have none.
+pub fn for_package(package: &Package) -> Option<Source> {
+    let source = package.source.as_ref()?;
+    if source.is_crates_io() {
+        return Some(sources().default.clone());
+    }
+    let sources = sources();
+    sources
+        .registry_of(package)
+        .map(|i| sources.registries[i].source.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::Arc;
+
+    // A request's path and `Authorization` header
+    type Request = (String, Option<String>);
+
+    // A docs server and sparse index on a local port, answering the paths
+    // it was given and 404 otherwise, that keeps each request's path and
+    // `Authorization` header
+    struct MockServer {
+        url: String,
+        requests: Arc<Mutex<Vec<Request>>>,
+    }
+
+    impl MockServer {
+        fn start(routes: &[(&str, &str)]) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            let routes: HashMap<String, String> = routes
+                .iter()
+                .map(|(path, body)| (path.to_string(), body.to_string()))
+                .collect();
+            let requests = Arc::new(Mutex::new(Vec::new()));
+            let seen = Arc::clone(&requests);
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let Ok(mut stream) = stream else { continue };
+                    let mut reader = BufReader::new(&mut stream);
+                    let mut request_line = String::new();
+                    reader.read_line(&mut request_line).unwrap();
+                    let path = request_line
+                        .split_whitespace()
+                        .nth(1)
+                        .unwrap_or_default()
+                        .to_string();
+                    let mut authorization = None;
+                    loop {
+                        let mut line = String::new();
+                        reader.read_line(&mut line).unwrap();
+                        let line = line.trim_end();
+                        if line.is_empty() {
+                            break;
+                        }
+                        if let Some((name, value)) = line.split_once(':')
+                            && name.eq_ignore_ascii_case("authorization")
+                        {
+                            authorization = Some(value.trim().to_string());
+                        }
+                    }
+                    let response = match routes.get(&path) {
+                        Some(body) => format!(
+                            "HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                            body.len(),
+                            body
+                        ),
+                        None => "HTTP/1.1 404 Not Found\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+                            .to_string(),
+                    };
+                    seen.lock().unwrap().push((path, authorization));
+                    let _ = stream.write_all(response.as_bytes());
+                }
+            });
+            MockServer { url, requests }
+        }
+
+        fn requests(&self) -> Vec<Request> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    // The `[sources]` of a `zdoc.toml`
+    fn sources(toml: &str, docs_url: Option<&str>, registry_api: Option<&str>) -> Sources {
+        let config: Config = toml::from_str(toml).unwrap();
+        Sources::resolve(
+            config.sources,
+            docs_url.map(String::from),
+            registry_api.map(String::from),
+        )
+    }
+
+    // A package locked from `source`, as cargo metadata lists it
+    fn package(name: &str, source: &str) -> serde_json::Value {
+        json!({
+            "name": name,
+            "version": "0.1.0",
+            "id": format!("{}#{}@0.1.0", source, name),
+            "source": source,
+            "dependencies": [],
+            "targets": [],
+            "features": {},
+            "manifest_path": format!("/registry/{}/Cargo.toml", name),
+        })
+    }
+
+    // Whether `source` would send its token with a GET of `url`
+    fn sends_token(source: &Source, url: &str) -> bool {
+        let request = source.get(&reqwest::Client::new(), url).build().unwrap();
+        request
+            .headers()
+            .contains_key(reqwest::header::AUTHORIZATION)
+    }
+
+    // A private registry beside the defaults, each on a mock server: public
+    // crates are fetched without the token, private ones with it, whether
+    // they're matched by name or by the lockfile
+    #[tokio::test]
+    async fn fetches_from_both_sources() {
+        let release = r#"{"name":"x","vers":"1.0.0","deps":[],"features":{},"yanked":false}"#;
+        let public = MockServer::start(&[
+            ("/crate/serde/1.0.0/json.gz", "{}"),
+            ("/index/se/rd/serde", release),
+        ]);
+        let private = MockServer::start(&[
+            ("/docs/crate/acme-utils/1.0.0/json.gz", "{}"),
+            ("/docs/crate/ledger/1.0.0/json.gz", "{}"),
+            ("/index/ac/me/acme-utils", release),
+            ("/index/le/dg/ledger", release),
+        ]);
+        let sources = sources(
+            &format!(
+                r#"
+                [sources.registries.acme]
+                index = "sparse+{url}/index/"
+                docs-url = "{url}/docs"
+                token = "s3cret"
+                crates = ["acme-*"]
+                "#,
+                url = private.url
+            ),
+            Some(&public.url),
+            Some(&format!("{}/index", public.url)),
+        );
+        // `ledger` only comes from the registry by way of the lockfile
+        let metadata: Metadata = serde_json::from_value(json!({
+            "packages": [
+                package("ledger", &format!("sparse+{}/index/", private.url)),
+                package("serde", "registry+https://github.com/rust-lang/crates.io-index"),
+            ],
+            "workspace_members": [],
+            "resolve": null,
+            "target_directory": "/ws/target",
+            "version": 1,
+            "workspace_root": "/ws",
+        }))
+        .unwrap();
+        let locked = sources.locked(&metadata);
+        assert_eq!(locked.len(), 1);
+
+        let client = reqwest::Client::builder().no_proxy().build().unwrap();
+        for crate_name in ["serde", "acme-utils", "ledger"] {
+            let source = sources.for_crate(crate_name, locked.get(crate_name).copied());
+            let response = source
+                .get(&client, &source.json_url(crate_name, "1.0.0"))
+                .send()
+                .await
+                .unwrap();
+            assert!(response.status().is_success(), "{}", crate_name);
+            let releases = crate::registry::releases(&client, source, crate_name)
+                .await
+                .unwrap();
+            assert_eq!(releases.len(), 1);
+        }
+
+        let bearer = Some("Bearer s3cret".to_string());
+        assert_eq!(
+            public.requests(),
+            [
+                ("/crate/serde/1.0.0/json.gz".to_string(), None),
+                ("/index/se/rd/serde".to_string(), None),
+            ]
+        );
+        assert_eq!(
+            private.requests(),
+            [
+                (
+                    "/docs/crate/acme-utils/1.0.0/json.gz".to_string(),
+                    bearer.clone()
+                ),
+                ("/index/ac/me/acme-utils".to_string(), bearer.clone()),
+                (
+                    "/docs/crate/ledger/1.0.0/json.gz".to_string(),
+                    bearer.clone()
+                ),
+                ("/index/le/dg/ledger".to_string(), bearer),
+            ]
+        );
+    }
+
+    // However a source with a token is configured, docs.rs and crates.io
+    // never see it
+    #[test]
+    fn tokens_stay_off_public_hosts() {
+        let sources = sources(
+            r#"
+            [sources]
+            token = "default-token"
+
+            [sources.registries.acme]
+            docs-url = "https://docs.acme.dev"
+            token = "s3cret"
+            crates = ["acme-*"]
+            "#,
+            None,
+            None,
+        );
+        let private = sources.for_crate("acme-utils", None);
+        let default = sources.for_crate("serde", None);
+        for source in [private, default] {
+            for url in [
+                "https://docs.rs/crate/serde/latest/json.gz",
+                "https://index.crates.io/se/rd/serde",
+                "https://static.crates.io/crates/serde/serde-1.0.0.crate",
+            ] {
+                assert!(!sends_token(source, url), "{}", url);
+            }
+        }
+        assert!(sends_token(
+            private,
+            "https://docs.acme.dev/crate/acme-utils/latest/json.gz"
+        ));
+        // A host that only starts like docs.rs isn't it
+        assert!(sends_token(
+            private,
+            "https://docs.rs.acme.dev/crate/acme-utils/latest/json.gz"
+        ));
+    }
+}
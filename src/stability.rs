@@ -0,0 +1,71 @@
+// The stability posture of each side of a diff: how many items are
+// deprecated, `#[doc(hidden)]` or behind an unstable feature. These are
+// counted rather than diffed, since deprecating an item doesn't change its
+// API, and shown as a small table of deltas above the changes.
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+use crate::ApiItem;
+use crate::table::Table;
+
+pub const MARKERS: [&str; 3] = ["deprecated", "doc(hidden)", "unstable"];
+
+// The paths carrying each marker in one version, in `MARKERS` order
+#[derive(Default)]
+pub struct Summary([BTreeSet<String>; 3]);
+
+impl Summary {
+    pub fn of(items: &[ApiItem]) -> Self {
+        let mut summary = Summary::default();
+        for item in items {
+            for (i, marker) in MARKERS.iter().enumerate() {
+                if item.stability.contains(marker) {
+                    summary.0[i].insert(item.full_path());
+                }
+            }
+        }
+        summary
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.iter().all(BTreeSet::is_empty)
+    }
+}
+
+// `deprecated: 12 → 19` rows, or None when neither version has any marked
+// items
+pub fn render_table(old: &Summary, new: &Summary) -> Option<String> {
+    if old.is_empty() && new.is_empty() {
+        return None;
+    }
+    let mut table = Table::new(&["Stability", "Before", "After", "Change"]);
+    for (i, marker) in MARKERS.iter().enumerate() {
+        let (before, after) = (old.0[i].len(), new.0[i].len());
+        let change = match after as i64 - before as i64 {
+            0 => "-".to_string(),
+            delta => format!("{:+}", delta),
+        };
+        table.add_row(vec![
+            marker.to_string(),
+            before.to_string(),
+            after.to_string(),
+            change,
+        ]);
+    }
+    Some(table.render())
+}
+
+// Per marker: the items carrying it in each version
+pub fn to_json(old: &Summary, new: &Summary) -> Value {
+    let markers: serde_json::Map<String, Value> = MARKERS
+        .iter()
+        .enumerate()
+        .map(|(i, marker)| {
+            (
+                marker.to_string(),
+                serde_json::json!({ "old": old.0[i], "new": new.0[i] }),
+            )
+        })
+        .collect();
+    Value::Object(markers)
+}
@@ -0,0 +1,86 @@
+// `zdoc diff --stat`: like `git diff --stat`, one line per module with its
+// added/removed/modified counts and a bar, to see where the churn is before
+// reading the full listing
+use std::collections::BTreeMap;
+
+use crate::style::{self, Role};
+use crate::{ApiItem, DiffReport};
+
+// The longest bar; larger counts are scaled down to fit
+const MAX_BAR: usize = 40;
+
+#[derive(Default)]
+struct Counts {
+    added: usize,
+    removed: usize,
+    modified: usize,
+}
+
+impl Counts {
+    fn total(&self) -> usize {
+        self.added + self.removed + self.modified
+    }
+}
+
+// The module an item is in. Members sit under their type (and struct
+// variant fields under their variant), which aren't modules.
+fn module_of(item: &ApiItem) -> String {
+    let depth = match item.parent_kind.as_deref() {
+        None => 0,
+        Some("variant") => 2,
+        Some(_) => 1,
+    };
+    let end = item.path.len().saturating_sub(depth);
+    item.path[..end].join("::")
+}
+
+pub fn print(report: &DiffReport) {
+    let mut modules: BTreeMap<String, Counts> = BTreeMap::new();
+    for item in &report.added {
+        modules.entry(module_of(item)).or_default().added += 1;
+    }
+    for item in &report.removed {
+        modules.entry(module_of(item)).or_default().removed += 1;
+    }
+    for (_, new_item) in &report.modified {
+        modules.entry(module_of(new_item)).or_default().modified += 1;
+    }
+
+    if modules.is_empty() {
+        println!("{}", style::paint(Role::Muted, "No API changes detected."));
+        return;
+    }
+
+    let width = modules.keys().map(|m| m.chars().count()).max().unwrap_or(0);
+    let largest = modules.values().map(Counts::total).max().unwrap_or(0);
+    let count_width = largest.to_string().len();
+    // Scale every bar by the same factor, keeping at least one mark for
+    // each kind of change a module has
+    let scale = |count: usize| match count {
+        0 => 0,
+        _ if largest <= MAX_BAR => count,
+        _ => (count * MAX_BAR / largest).max(1),
+    };
+    for (module, counts) in &modules {
+        println!(
+            " {:<width$} | {:>count_width$} {}{}{}",
+            module,
+            counts.total(),
+            style::paint(Role::Added, &"+".repeat(scale(counts.added))),
+            style::paint(Role::Removed, &"-".repeat(scale(counts.removed))),
+            style::paint(Role::Modified, &"~".repeat(scale(counts.modified))),
+        );
+    }
+    println!(
+        " {} module{} changed, {} added, {} removed, {} modified ({} breaking)",
+        modules.len(),
+        if modules.len() == 1 { "" } else { "s" },
+        report.added.len(),
+        report.removed.len(),
+        report.modified.len(),
+        report.breaking_count()
+    );
+    if let Some(note) = crate::ignore::note(report.ignored_changes, "change") {
+        println!(" {}", style::paint(Role::Muted, &note));
+    }
+}
@@ -0,0 +1,405 @@
+// `zdoc stats`: how big a crate's public API is and how much of it is
+// documented, from its newest release on docs.rs. With `--trend`, the same
+// for each of its last releases, plus the breaking changes since the release
+// before, to tell a dependency that is settling down from one still
+// thrashing. (`diff --stat` lives in stat.rs.)
+//
+// Releases come from the registry index and their docs from the cache or
+// docs.rs, a few at a time. A release without JSON docs (older than May 2025,
+// or a failed build) is a gap in the series rather than an error, and the
+// release after it is compared with the last one that had docs.
+use anyhow::{Context, Result};
+use cargo_metadata::semver::Version;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tracing::{debug, info};
+
+use crate::ignore::Ignore;
+use crate::table::Table;
+use crate::{ApiItem, JsonStyle, SearchFormat, http, registry, signature, sources};
+
+// Releases fetched at once, as with `diff --batch`
+const MAX_CONCURRENT: usize = 4;
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+// Stands in for a release without docs in a sparkline
+const GAP: char = '·';
+
+// What a release's public items add up to, for `stats` and `compare`
+pub struct Tally {
+    pub items: usize,
+    // Items with a doc comment, of those that have their own (trait impl
+    // members show the trait's)
+    pub documented: usize,
+    pub checked: usize,
+    // Item kind (`struct`, `method`, ...) -> count
+    pub by_kind: BTreeMap<String, usize>,
+    // Functions and methods that are `async fn` or return a future
+    pub async_fns: usize,
+    pub unsafe_fns: usize,
+}
+
+impl Tally {
+    pub fn of(items: &[ApiItem]) -> Self {
+        // Trait impls are conditions on types rather than items to count
+        let items: Vec<&ApiItem> = items
+            .iter()
+            .filter(|item| item.item_type != "impl")
+            .collect();
+        let checked: Vec<&ApiItem> = items
+            .iter()
+            .copied()
+            .filter(|item| !item.inherits_docs)
+            .collect();
+        let mut by_kind: BTreeMap<String, usize> = BTreeMap::new();
+        for item in &items {
+            *by_kind.entry(item.item_type.clone()).or_default() += 1;
+        }
+        // `extern "C" fn`s defined in Rust are functions too
+        let functions: Vec<&ApiItem> = items
+            .iter()
+            .copied()
+            .filter(|item| matches!(item.kind_family(), "function" | "extern fn"))
+            .collect();
+        Tally {
+            items: items.len(),
+            documented: checked.iter().filter(|item| item.docs.is_some()).count(),
+            checked: checked.len(),
+            by_kind,
+            async_fns: functions
+                .iter()
+                .filter(|item| signature::asyncness(&item.signature).is_some())
+                .count(),
+            unsafe_fns: functions
+                .iter()
+                .filter(|item| {
+                    signature::split_qualifiers(&item.signature)
+                        .0
+                        .contains(&"unsafe")
+                })
+                .count(),
+        }
+    }
+
+    pub fn documented_percent(&self) -> f64 {
+        if self.checked == 0 {
+            100.0
+        } else {
+            self.documented as f64 * 100.0 / self.checked as f64
+        }
+    }
+}
+
+// What one release's docs add up to
+struct Point {
+    tally: Tally,
+    // Breaking changes since `since`, the previous release with docs
+    breaking: Option<usize>,
+    since: Option<String>,
+}
+
+// A release's public items, hidden and ignored ones left out
+pub async fn public_items(
+    crate_name: &str,
+    version: &str,
+    ignore: &Ignore,
+) -> Result<Vec<ApiItem>> {
+    let source = crate::DocSource::DocsRs { version };
+    let docs = crate::doc_source::load_crate_json(crate_name, source).await?;
+    public_api(&docs.json, ignore)
+}
+
+// The items of a crate's docs that `stats` and `compare` count. Members of
+// blanket impls (`from`, `borrow`, ...) come with every crate, so they
+// aren't counted any more than hidden ones.
+pub fn public_api(json: &Value, ignore: &Ignore) -> Result<Vec<ApiItem>> {
+    let mut items = crate::extract_api_items(json)?;
+    items.retain(|item| !item.stability.contains(&"doc(hidden)") && !item.is_pathless_member());
+    Ok(ignore.partition(items, ApiItem::full_path).0)
+}
+
+// The last `count` releases, oldest first. Pre-releases only count when
+// asked for, or when the crate has nothing else.
+async fn last_releases(crate_name: &str, count: usize, pre: bool) -> Result<Vec<Version>> {
+    let source = sources::for_crate(crate_name);
+    let mut versions = registry::versions(&http::client()?, &source, crate_name)
+        .await
+        .with_context(|| format!("Failed to look up the releases of {}", crate_name))?;
+    if !pre && versions.iter().any(|version| version.pre.is_empty()) {
+        versions.retain(|version| version.pre.is_empty());
+    }
+    if versions.is_empty() {
+        anyhow::bail!("{} has no releases", crate_name);
+    }
+    versions.sort();
+    let skip = versions.len().saturating_sub(count);
+    Ok(versions.split_off(skip))
+}
+
+// `values` as a sparkline, gaps for `None`, scaled from the lowest value to
+// the highest unless `bounds` fixes the scale (percentages)
+fn sparkline(values: &[Option<usize>], bounds: Option<(usize, usize)>) -> String {
+    let known = values.iter().flatten().copied();
+    let Some((min, max)) = bounds.or_else(|| Some((known.clone().min()?, known.max()?))) else {
+        return values.iter().map(|_| GAP).collect();
+    };
+    values
+        .iter()
+        .map(|value| match value {
+            None => GAP,
+            Some(_) if max == min => SPARKS[SPARKS.len() / 2],
+            Some(value) => {
+                SPARKS[(value.clamp(&min, &max) - min) * (SPARKS.len() - 1) / (max - min)]
+            }
+        })
+        .collect()
+}
+
+pub async fn run(
+    crate_name: &str,
+    releases: usize,
+    pre: bool,
+    format: SearchFormat,
+    json_style: JsonStyle,
+) -> Result<()> {
+    let versions = last_releases(crate_name, releases.max(1), pre).await?;
+    if versions.len() > 1 {
+        info!(
+            "Reading the docs of {} releases of {}...",
+            versions.len(),
+            crate_name
+        );
+    }
+
+    let ignore = Arc::new(Ignore::load_nearest()?);
+    let permits = Arc::new(Semaphore::new(MAX_CONCURRENT));
+    let mut tasks = JoinSet::new();
+    for (i, version) in versions.iter().enumerate() {
+        let (ignore, permits) = (ignore.clone(), permits.clone());
+        let (crate_name, version) = (crate_name.to_string(), version.to_string());
+        tasks.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let items = public_items(&crate_name, &version, &ignore)
+                .await
+                .map_err(|e| format!("{:#}", e));
+            (i, items)
+        });
+    }
+    let mut loaded: Vec<Option<Result<Vec<ApiItem>, String>>> =
+        versions.iter().map(|_| None).collect();
+    while let Some(joined) = tasks.join_next().await {
+        let (i, items) = joined.context("A stats task panicked")?;
+        loaded[i] = Some(items);
+    }
+
+    // Walk the series oldest first, comparing each release with the last
+    // one that had docs
+    let mut series: Vec<(String, Result<Point, String>)> = Vec::new();
+    let mut previous: Option<(String, Vec<ApiItem>)> = None;
+    for (version, items) in versions.iter().zip(loaded) {
+        let version = version.to_string();
+        let items = match items.context("A stats task went missing")? {
+            Ok(items) => items,
+            Err(error) => {
+                debug!("No docs for {} v{}: {}", crate_name, version, error);
+                series.push((version, Err(error)));
+                continue;
+            }
+        };
+        let mut point = Point {
+            tally: Tally::of(&items),
+            breaking: None,
+            since: None,
+        };
+        if let Some((since, old)) = previous.take() {
+            let (added, removed, modified) = crate::compare_api_items(old, items.clone());
+            point.breaking = Some(crate::breaking_changes(&added, &removed, &modified));
+            point.since = Some(since);
+        }
+        previous = Some((version.clone(), items));
+        series.push((version, Ok(point)));
+    }
+
+    let gaps: Vec<&str> = series
+        .iter()
+        .filter(|(_, point)| point.is_err())
+        .map(|(version, _)| version.as_str())
+        .collect();
+    if let [(_, Err(error))] = &series[..] {
+        anyhow::bail!("{}", error);
+    }
+    if gaps.len() == series.len() {
+        anyhow::bail!(
+            "None of the {} releases of {} looked at have JSON docs on {}",
+            series.len(),
+            crate_name,
+            sources::for_crate(crate_name).docs_host()
+        );
+    }
+
+    if format == SearchFormat::Json {
+        let releases: Vec<Value> = series
+            .iter()
+            .map(|(version, point)| match point {
+                Ok(point) => serde_json::json!({
+                    "version": version,
+                    "items": point.tally.items,
+                    "documented": point.tally.documented,
+                    "documented_percent": (point.tally.documented_percent() * 10.0).round() / 10.0,
+                    "breaking": point.breaking,
+                    "since": point.since,
+                }),
+                Err(error) => serde_json::json!({
+                    "version": version,
+                    "items": null,
+                    "error": error,
+                }),
+            })
+            .collect();
+        return crate::print_json(
+            &serde_json::json!({ "crate": crate_name, "releases": releases }),
+            json_style,
+        );
+    }
+
+    // Without `--trend`, a single release to sum up
+    if let [(version, Ok(point))] = &series[..] {
+        println!(
+            "{} v{}: {} public items, {:.1}% documented",
+            crate_name,
+            version,
+            point.tally.items,
+            point.tally.documented_percent()
+        );
+        return Ok(());
+    }
+
+    let mut table = Table::new(&["Version", "Items", "Change", "Breaking", "Documented"]);
+    let mut last_items: Option<usize> = None;
+    for (version, point) in &series {
+        let row = match point {
+            Ok(point) => {
+                let change = last_items.map_or(String::new(), |last| {
+                    format!("{:+}", point.tally.items as i64 - last as i64)
+                });
+                last_items = Some(point.tally.items);
+                vec![
+                    version.clone(),
+                    point.tally.items.to_string(),
+                    change,
+                    point.breaking.map_or(String::new(), |n| n.to_string()),
+                    format!("{:.1}%", point.tally.documented_percent()),
+                ]
+            }
+            Err(_) => vec![
+                version.clone(),
+                "—".to_string(),
+                String::new(),
+                String::new(),
+                "—".to_string(),
+            ],
+        };
+        table.add_row(row);
+    }
+    println!(
+        "\nPublic API of {} over its last {} releases:\n",
+        crate_name,
+        series.len()
+    );
+    print!("{}", table.render());
+
+    let column = |value: fn(&Point) -> Option<usize>| -> Vec<Option<usize>> {
+        series
+            .iter()
+            .map(|(_, point)| point.as_ref().ok().and_then(value))
+            .collect()
+    };
+    println!();
+    println!(
+        "  Items       {}",
+        sparkline(&column(|p| Some(p.tally.items)), None)
+    );
+    // No breaking changes is the floor, however churny every release was
+    let breaking = column(|p| p.breaking);
+    let most = breaking.iter().flatten().max().copied().unwrap_or(0);
+    println!(
+        "  Breaking    {}",
+        sparkline(&breaking, Some((0, most.max(1))))
+    );
+    println!(
+        "  Documented  {}",
+        sparkline(
+            &column(|p| Some(p.tally.documented_percent().round() as usize)),
+            Some((0, 100))
+        )
+    );
+    if !gaps.is_empty() {
+        println!(
+            "\n{} of {} releases have no JSON docs ({}), shown as {}",
+            gaps.len(),
+            series.len(),
+            gaps.join(", "),
+            GAP
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    fn tally(fixture: &str) -> Tally {
+        let items = public_api(&fixtures::load(fixture), &Ignore::default()).unwrap();
+        Tally::of(&items)
+    }
+
+    #[test]
+    fn counts_items_by_kind() {
+        let tally = tally("widgets");
+        let by_kind: Vec<(&str, usize)> = tally
+            .by_kind
+            .iter()
+            .map(|(kind, count)| (kind.as_str(), *count))
+            .collect();
+        // No blanket `from`, `into`, ... and no hidden `__internal`
+        assert_eq!(
+            by_kind,
+            [
+                ("enum", 1),
+                ("function", 2),
+                ("macro", 1),
+                ("method", 9),
+                ("module", 1),
+                ("struct", 2),
+                ("struct_field", 3),
+                ("trait", 1),
+                ("trait method", 2),
+                ("variant", 2),
+            ]
+        );
+        assert_eq!(tally.items, 24);
+    }
+
+    #[test]
+    fn documented_share_leaves_out_inherited_docs() {
+        let tally = tally("widgets");
+        // `Canvas::default` shows `Default`'s docs, and fields and variants
+        // go without
+        assert_eq!((tally.documented, tally.checked), (18, 23));
+        assert_eq!(tally.documented_percent().round(), 78.0);
+        assert_eq!(Tally::of(&[]).documented_percent(), 100.0);
+    }
+
+    #[test]
+    fn counts_async_and_unsafe_functions() {
+        let tally = tally("tasks-new");
+        assert_eq!(tally.async_fns, 6);
+        // `unsafe extern "C" fn raw` and the `async fn fetch`
+        let tally = self::tally("signatures");
+        assert_eq!((tally.async_fns, tally.unsafe_fns), (1, 1));
+    }
+}
@@ -0,0 +1,215 @@
+// Terminal colors by role rather than by hue, so they can be themed. A theme
+// maps roles (`added`, `kind.fn`, `muted`, ...) to styles written like
+// `bold green`, `bright black` or `#5f87af`. Two presets are built in,
+// `default` and `light` (for light backgrounds, where yellow and dimmed text
+// are hard to read), and the `[theme]` section of `zdoc.toml` picks one and
+// overrides single roles:
+//
+//     [theme]
+//     preset = "light"
+//     "kind.fn" = "bold blue"
+use colored::{Color, ColoredString, Colorize};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use tracing::warn;
+
+use crate::config::Config;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Role {
+    Added,
+    Removed,
+    Modified,
+    Deprecated,
+    KindFn,
+    KindStruct,
+    KindTrait,
+    Path,
+    DocText,
+    // Secondary text: context lines, notes, "nothing found"
+    Muted,
+}
+
+const ROLES: &[(&str, Role)] = &[
+    ("added", Role::Added),
+    ("removed", Role::Removed),
+    ("modified", Role::Modified),
+    ("deprecated", Role::Deprecated),
+    ("kind.fn", Role::KindFn),
+    ("kind.struct", Role::KindStruct),
+    ("kind.trait", Role::KindTrait),
+    ("path", Role::Path),
+    ("doc-text", Role::DocText),
+    ("muted", Role::Muted),
+];
+
+pub const PRESETS: &[&str] = &["default", "light"];
+
+fn preset(name: &str) -> &'static [(Role, &'static str)] {
+    match name {
+        "light" => &[
+            (Role::Added, "green"),
+            (Role::Removed, "red"),
+            (Role::Modified, "magenta"),
+            (Role::Deprecated, "magenta"),
+            (Role::KindFn, "blue"),
+            (Role::KindStruct, "cyan"),
+            (Role::KindTrait, "magenta"),
+            (Role::Path, "plain"),
+            (Role::DocText, "plain"),
+            (Role::Muted, "bright black"),
+        ],
+        _ => &[
+            (Role::Added, "green"),
+            (Role::Removed, "red"),
+            (Role::Modified, "yellow"),
+            (Role::Deprecated, "yellow"),
+            (Role::KindFn, "blue"),
+            (Role::KindStruct, "cyan"),
+            (Role::KindTrait, "magenta"),
+            (Role::Path, "plain"),
+            (Role::DocText, "plain"),
+            (Role::Muted, "dimmed"),
+        ],
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+struct Style {
+    color: Option<Color>,
+    bold: bool,
+    dimmed: bool,
+    italic: bool,
+    underline: bool,
+}
+
+impl Style {
+    // `[modifiers...] [color]`, e.g. `bold bright red`; `plain` for none
+    fn parse(spec: &str) -> Option<Style> {
+        let mut style = Style::default();
+        let mut color = Vec::new();
+        for word in spec.split_whitespace() {
+            match word.to_ascii_lowercase().as_str() {
+                "bold" => style.bold = true,
+                "dimmed" | "dim" => style.dimmed = true,
+                "italic" => style.italic = true,
+                "underline" => style.underline = true,
+                "plain" | "none" => {}
+                _ => color.push(word),
+            }
+        }
+        if !color.is_empty() {
+            style.color = Some(Color::from_str(&color.join(" ")).ok()?);
+        }
+        Some(style)
+    }
+
+    fn apply(&self, text: &str) -> ColoredString {
+        let mut styled = text.normal();
+        if let Some(color) = self.color {
+            styled = styled.color(color);
+        }
+        if self.bold {
+            styled = styled.bold();
+        }
+        if self.dimmed {
+            styled = styled.dimmed();
+        }
+        if self.italic {
+            styled = styled.italic();
+        }
+        if self.underline {
+            styled = styled.underline();
+        }
+        styled
+    }
+}
+
+struct Theme(BTreeMap<Role, Style>);
+
+impl Theme {
+    // `preset` (`--theme`) over the config's preset, then the config's roles.
+    // Unknown names and styles are warned about and left at the preset's.
+    fn resolve(preset_name: Option<&str>, config: &BTreeMap<String, String>) -> Theme {
+        let name = preset_name
+            .or(config.get("preset").map(String::as_str))
+            .unwrap_or("default");
+        if !PRESETS.contains(&name) {
+            warn!(
+                "Unknown theme '{}' (built in: {}); using the default",
+                name,
+                PRESETS.join(", ")
+            );
+        }
+        let mut styles: BTreeMap<Role, Style> = preset(name)
+            .iter()
+            .map(|(role, spec)| (*role, Style::parse(spec).unwrap_or_default()))
+            .collect();
+        for (key, spec) in config {
+            if key == "preset" {
+                continue;
+            }
+            let Some((_, role)) = ROLES.iter().find(|(name, _)| name == key) else {
+                let known: Vec<&str> = ROLES.iter().map(|(name, _)| *name).collect();
+                warn!(
+                    "Unknown theme role '{}' in {} (known roles: {})",
+                    key,
+                    crate::config::CONFIG_FILE,
+                    known.join(", ")
+                );
+                continue;
+            };
+            match Style::parse(spec) {
+                Some(style) => {
+                    styles.insert(*role, style);
+                }
+                None => warn!(
+                    "Invalid style '{}' for theme role '{}'; keeping the preset's",
+                    spec, key
+                ),
+            }
+        }
+        Theme(styles)
+    }
+}
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+// Pick the theme for the rest of the run: `--theme`, then the nearest
+// `zdoc.toml`. Themes never fail a command; problems are warnings.
+pub fn init(preset_name: Option<&str>) {
+    let config = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| Config::find(&cwd))
+        .map(|root| Config::load(&root))
+        .transpose()
+        .unwrap_or_else(|e| {
+            warn!(
+                "Ignoring the theme in {}: {:#}",
+                crate::config::CONFIG_FILE,
+                e
+            );
+            None
+        });
+    let roles = config.map(|config| config.theme).unwrap_or_default();
+    let _ = THEME.set(Theme::resolve(preset_name, &roles));
+}
+
+pub fn paint(role: Role, text: &str) -> ColoredString {
+    let style = THEME
+        .get()
+        .and_then(|theme| theme.0.get(&role).copied())
+        .unwrap_or_default();
+    style.apply(text)
+}
+
+// The role for an item kind label, if kinds of that sort are colored
+pub fn kind_role(kind: &str) -> Option<Role> {
+    match kind {
+        "function" | "method" | "trait method" | "extern fn" => Some(Role::KindFn),
+        "struct" | "enum" | "union" | "type_alias" => Some(Role::KindStruct),
+        "trait" | "trait_alias" => Some(Role::KindTrait),
+        _ => None,
+    }
+}
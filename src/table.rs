@@ -0,0 +1,163 @@
+// Aligned plain-text tables for listing commands. Columns are sized to their
+// contents and shrunk to fit the layout width (`--width`, else the terminal's,
+// see layout.rs), truncating the widest cells first. Borders are drawn only
+// when stdout is a terminal, so piped output stays easy to cut/awk.
+use std::io::IsTerminal;
+
+// Columns never shrink below this many characters
+const MIN_COLUMN_WIDTH: usize = 6;
+
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(headers: &[&str]) -> Self {
+        Table {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn add_row(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    // Render for stdout: fitted to the layout width, and bordered on a terminal
+    pub fn render(&self) -> String {
+        let borders = std::io::stdout().is_terminal();
+        self.render_with(Some(crate::layout::width()), borders)
+    }
+
+    pub fn render_with(&self, max_width: Option<usize>, borders: bool) -> String {
+        let widths = self.column_widths(max_width, borders);
+        let mut out = String::new();
+
+        let line = |left: &str, mid: &str, right: &str| {
+            let segments: Vec<String> = widths.iter().map(|w| "─".repeat(w + 2)).collect();
+            format!("{}{}{}\n", left, segments.join(mid), right)
+        };
+        let row = |cells: &[String]| {
+            let padded: Vec<String> = widths
+                .iter()
+                .enumerate()
+                .map(|(i, width)| fit(cells.get(i).map_or("", String::as_str), *width))
+                .collect();
+            if borders {
+                format!("│ {} │\n", padded.join(" │ "))
+            } else {
+                format!("{}\n", padded.join("  ").trim_end())
+            }
+        };
+
+        if borders {
+            out.push_str(&line("┌", "┬", "┐"));
+        }
+        out.push_str(&row(&self.headers));
+        if borders {
+            out.push_str(&line("├", "┼", "┤"));
+        }
+        for cells in &self.rows {
+            out.push_str(&row(cells));
+        }
+        if borders {
+            out.push_str(&line("└", "┴", "┘"));
+        }
+        out
+    }
+
+    // Natural widths, then shave the widest column one character at a time
+    // until the table fits
+    fn column_widths(&self, max_width: Option<usize>, borders: bool) -> Vec<usize> {
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.chars().count()).collect();
+        for cells in &self.rows {
+            for (i, cell) in cells.iter().enumerate() {
+                if let Some(width) = widths.get_mut(i) {
+                    *width = (*width).max(cell.chars().count());
+                }
+            }
+        }
+
+        let Some(max_width) = max_width else {
+            return widths;
+        };
+        // Borders take `│ ` + ` │ ` between columns + ` │`; plain tables a two-space gap
+        let overhead = if borders {
+            3 * widths.len() + 1
+        } else {
+            2 * widths.len().saturating_sub(1)
+        };
+        while widths.iter().sum::<usize>() + overhead > max_width {
+            let Some(width) = widths.iter_mut().max_by_key(|w| **w) else {
+                break;
+            };
+            if *width <= MIN_COLUMN_WIDTH {
+                break;
+            }
+            *width -= 1;
+        }
+        widths
+    }
+}
+
+// Pad a cell to `width`, or cut it with an ellipsis when it's too long
+fn fit(cell: &str, width: usize) -> String {
+    let len = cell.chars().count();
+    if len <= width {
+        return format!("{}{}", cell, " ".repeat(width - len));
+    }
+    let mut cut: String = cell.chars().take(width.saturating_sub(1)).collect();
+    cut.push('…');
+    cut
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> Table {
+        let mut table = Table::new(&["Crate", "Version", "Description"]);
+        table.add_row(vec![
+            "serde".to_string(),
+            "1.0.210".to_string(),
+            "A generic serialization/deserialization framework".to_string(),
+        ]);
+        table.add_row(vec![
+            "tokio".to_string(),
+            "1.40.0".to_string(),
+            "An event-driven, non-blocking I/O platform".to_string(),
+        ]);
+        table
+    }
+
+    #[test]
+    fn fits_a_fixed_width() {
+        assert_eq!(
+            table().render_with(Some(40), true),
+            "\
+┌───────┬─────────┬────────────────────┐
+│ Crate │ Version │ Description        │
+├───────┼─────────┼────────────────────┤
+│ serde │ 1.0.210 │ A generic seriali… │
+│ tokio │ 1.40.0  │ An event-driven, … │
+└───────┴─────────┴────────────────────┘
+"
+        );
+        assert_eq!(
+            table().render_with(Some(40), false),
+            "\
+Crate  Version  Description
+serde  1.0.210  A generic serialization…
+tokio  1.40.0   An event-driven, non-bl…
+"
+        );
+    }
+
+    #[test]
+    fn wide_enough_tables_keep_their_cells() {
+        let plain = table().render_with(Some(200), false);
+        assert_eq!(plain, table().render_with(None, false));
+        assert!(plain.contains("A generic serialization/deserialization framework\n"));
+    }
+}
@@ -0,0 +1,78 @@
+// Where `cargo doc` writes the docs zdoc reads. The location is resolved once
+// and passed to `cargo doc` explicitly, so where docs are written and where
+// they're read can't disagree:
+//
+// - the target directory is `--target-dir`, else what cargo metadata reports,
+//   which already follows `CARGO_TARGET_DIR` and `build.target-dir`
+// - with `--target` (or `CARGO_BUILD_TARGET`), docs land in a
+//   `<triple>/doc` subdirectory instead of `doc`
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::{Once, OnceLock};
+use tracing::debug;
+
+struct Overrides {
+    target_dir: Option<PathBuf>,
+    target: Option<String>,
+}
+
+static OVERRIDES: OnceLock<Overrides> = OnceLock::new();
+
+// Set the `--target-dir` and `--target` overrides for the rest of the run
+pub fn set_overrides(target_dir: Option<PathBuf>, target: Option<String>) {
+    // Relative to where zdoc runs, as with cargo's own flag
+    let target_dir = target_dir.map(|dir| match std::env::current_dir() {
+        Ok(cwd) => cwd.join(dir),
+        Err(_) => dir,
+    });
+    let _ = OVERRIDES.set(Overrides { target_dir, target });
+}
+
+// The target triple docs are built for, when one was chosen
+fn target_triple() -> Option<String> {
+    OVERRIDES
+        .get()
+        .and_then(|overrides| overrides.target.clone())
+        .or_else(|| {
+            std::env::var("CARGO_BUILD_TARGET")
+                .ok()
+                .filter(|target| !target.trim().is_empty())
+        })
+}
+
+pub fn target_dir(metadata: &cargo_metadata::Metadata) -> PathBuf {
+    OVERRIDES
+        .get()
+        .and_then(|overrides| overrides.target_dir.clone())
+        .unwrap_or_else(|| metadata.target_directory.clone().into())
+}
+
+// The `doc` directory `cargo doc` writes to under `target_dir`
+pub fn doc_dir_under(target_dir: &Path) -> PathBuf {
+    match target_triple() {
+        Some(triple) => target_dir.join(triple).join("doc"),
+        None => target_dir.join("doc"),
+    }
+}
+
+pub fn doc_dir(metadata: &cargo_metadata::Metadata) -> PathBuf {
+    let doc_dir = doc_dir_under(&target_dir(metadata));
+    static LOGGED: Once = Once::new();
+    LOGGED.call_once(|| debug!("Reading docs from {}", doc_dir.display()));
+    doc_dir
+}
+
+// The arguments that make `cargo doc` write where `doc_dir` reads
+pub fn cargo_args(metadata: &cargo_metadata::Metadata) -> Vec<OsString> {
+    cargo_args_under(&target_dir(metadata))
+}
+
+// The same for builds in another target directory, read with `doc_dir_under`
+pub fn cargo_args_under(target_dir: &Path) -> Vec<OsString> {
+    let mut args = vec!["--target-dir".into(), target_dir.into()];
+    if let Some(triple) = target_triple() {
+        args.push("--target".into());
+        args.push(triple.into());
+    }
+    args
+}
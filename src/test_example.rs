@@ -0,0 +1,241 @@
+// `zdoc test-example`: compile (and run) one doc example against the
+// version of its crate this workspace resolves
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::items::{self, CodeBlock};
+
+// What the example is expected to do, from its info string
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Expectation {
+    Run,
+    // `no_run`: it has to compile, but isn't executed
+    Compile,
+    ShouldPanic,
+    CompileFail,
+}
+
+impl Expectation {
+    fn of(block: &CodeBlock) -> Self {
+        if block.has_attr("compile_fail") {
+            Expectation::CompileFail
+        } else if block.has_attr("no_run") {
+            Expectation::Compile
+        } else if block.has_attr("should_panic") {
+            Expectation::ShouldPanic
+        } else {
+            Expectation::Run
+        }
+    }
+
+    fn cargo_subcommand(self) -> &'static str {
+        match self {
+            Expectation::Run | Expectation::ShouldPanic => "run",
+            Expectation::Compile | Expectation::CompileFail => "check",
+        }
+    }
+}
+
+// The program written to `src/main.rs`, and how many lines precede the
+// example's first line so compiler positions can be mapped back
+struct Program {
+    source: String,
+    offset: usize,
+}
+
+// Wrap the example the way rustdoc does: code without its own `fn main` goes
+// inside one, and examples ending in `Ok(())` get a fallible body so `?` works
+fn wrap_example(code: &str, crate_ident: &str) -> Program {
+    let mut prelude = vec!["#![allow(unused)]".to_string()];
+    if !code.contains(&format!("extern crate {}", crate_ident)) {
+        prelude.push(format!(
+            "#[allow(unused_extern_crates)] extern crate {};",
+            crate_ident
+        ));
+    }
+
+    if code.contains("fn main") {
+        let offset = prelude.len();
+        return Program {
+            source: format!("{}\n{}\n", prelude.join("\n"), code),
+            offset,
+        };
+    }
+
+    let (open, close) = if code.trim_end().ends_with("(())") {
+        (
+            "fn main() { fn _inner() -> Result<(), impl core::fmt::Debug> {",
+            "} _inner().unwrap() }",
+        )
+    } else {
+        ("fn main() {", "}")
+    };
+    prelude.push(open.to_string());
+    let offset = prelude.len();
+    Program {
+        source: format!("{}\n{}\n{}\n", prelude.join("\n"), code, close),
+        offset,
+    }
+}
+
+// The dependency line for the example crate: a path dependency for local
+// packages, otherwise the exact version (or git revision) in the lockfile
+fn dependency_spec(package: &cargo_metadata::Package) -> String {
+    let manifest_dir = package
+        .manifest_path
+        .parent()
+        .map(|dir| dir.to_string())
+        .unwrap_or_default();
+    match package.source.as_ref().map(|source| source.repr.as_str()) {
+        None => format!("{{ path = {:?} }}", manifest_dir),
+        Some(source) if source.starts_with("git+") => {
+            let url = source.trim_start_matches("git+");
+            let (url, rev) = url.split_once('#').unwrap_or((url, ""));
+            let url = url.split('?').next().unwrap_or(url);
+            format!("{{ git = {:?}, rev = {:?} }}", url, rev)
+        }
+        Some(_) => format!("\"={}\"", package.version),
+    }
+}
+
+// Point `src/main.rs:L:C` positions at the example's own lines
+fn map_line(line: &str, pattern: &Regex, offset: usize, len: usize) -> String {
+    pattern
+        .replace_all(line, |caps: &regex::Captures| {
+            let number: usize = caps[1].parse().unwrap_or(0);
+            if number > offset && number <= offset + len {
+                format!("example:{}:{}", number - offset, &caps[2])
+            } else {
+                caps[0].to_string()
+            }
+        })
+        .into_owned()
+}
+
+// Run cargo in the example crate, streaming its stderr with positions
+// mapped back to the example. Returns whether cargo succeeded.
+fn run_cargo(
+    dir: &Path,
+    subcommand: &str,
+    target_dir: &Path,
+    offset: usize,
+    len: usize,
+) -> Result<bool> {
+    let mut child = Command::new("cargo")
+        .arg(subcommand)
+        .arg("--quiet")
+        .current_dir(dir)
+        .env("CARGO_TARGET_DIR", target_dir)
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run cargo for the example")?;
+
+    let pattern = Regex::new(r"src[/\\]main\.rs:(\d+):(\d+)").expect("valid regex");
+    if let Some(stderr) = child.stderr.take() {
+        for line in BufReader::new(stderr).lines() {
+            let line = line.context("Failed to read cargo output")?;
+            eprintln!("{}", map_line(&line, &pattern, offset, len));
+        }
+    }
+
+    let status = child.wait().context("Failed to wait for cargo")?;
+    Ok(status.success())
+}
+
+// Handler for `zdoc test-example`
+pub fn run(metadata: &cargo_metadata::Metadata, item_path: &str, nth: usize) -> Result<()> {
+    let located = items::locate(metadata, item_path)?;
+    let target = located.resolved();
+
+    let (_, blocks) = items::split_docs(items::item_docs(target.item).unwrap_or(""));
+    let examples: Vec<&CodeBlock> = blocks.iter().filter(|block| block.is_rust()).collect();
+    if examples.is_empty() {
+        anyhow::bail!("{} has no Rust examples in its docs", target.full_path());
+    }
+    let Some(block) = nth.checked_sub(1).and_then(|i| examples.get(i)) else {
+        anyhow::bail!(
+            "{} has {} examples; --nth must be between 1 and {}",
+            target.full_path(),
+            examples.len(),
+            examples.len()
+        );
+    };
+
+    let package = metadata
+        .packages
+        .iter()
+        .find(|package| package.name.as_str() == located.crate_name)
+        .with_context(|| format!("Package '{}' not found in metadata", located.crate_name))?;
+    let crate_ident = package
+        .targets
+        .iter()
+        .find(|target| target.is_lib())
+        .map(|target| target.name.replace('-', "_"))
+        .unwrap_or_else(|| package.name.replace('-', "_"));
+
+    let expectation = Expectation::of(block);
+    if block.has_attr("ignore") {
+        tracing::warn!("This example is marked `ignore`; rustdoc would not test it");
+    }
+
+    let code = block.compiled_code();
+    let program = wrap_example(&code, &crate_ident);
+    let manifest = format!(
+        "[package]\nname = \"zdoc-example\"\nversion = \"0.0.0\"\nedition = \"{}\"\n\n\
+         [dependencies]\n{} = {}\n\n[workspace]\n",
+        package.edition,
+        package.name,
+        dependency_spec(package)
+    );
+
+    // The temp project is removed when `dir` is dropped; builds go to the
+    // workspace's target dir so repeated runs reuse compiled dependencies
+    let dir = tempfile::Builder::new()
+        .prefix("zdoc-example-")
+        .tempdir()
+        .context("Failed to create a temporary project")?;
+    fs::create_dir_all(dir.path().join("src")).context("Failed to create src/")?;
+    fs::write(dir.path().join("Cargo.toml"), manifest).context("Failed to write Cargo.toml")?;
+    fs::write(dir.path().join("src/main.rs"), &program.source)
+        .context("Failed to write src/main.rs")?;
+    let target_dir = crate::target_dir::target_dir(metadata).join("zdoc-examples");
+
+    tracing::info!(
+        "Testing example {} of {} for {} (cargo {})...",
+        nth,
+        examples.len(),
+        target.full_path(),
+        expectation.cargo_subcommand()
+    );
+    let succeeded = run_cargo(
+        dir.path(),
+        expectation.cargo_subcommand(),
+        &target_dir,
+        program.offset,
+        code.lines().count(),
+    )?;
+
+    let passed = match expectation {
+        Expectation::Run | Expectation::Compile => succeeded,
+        Expectation::ShouldPanic | Expectation::CompileFail => !succeeded,
+    };
+    if !passed {
+        let reason = match expectation {
+            Expectation::ShouldPanic => "it did not panic",
+            Expectation::CompileFail => "it compiled",
+            _ => "see the output above",
+        };
+        anyhow::bail!(
+            "Example {} of {} failed: {}",
+            nth,
+            target.full_path(),
+            reason
+        );
+    }
+    println!("Example {} of {} passed", nth, target.full_path());
+    Ok(())
+}
@@ -0,0 +1,183 @@
+// Where a run's time goes, for `--timings` and `ZDOC_PROFILE=chrome`. Each
+// major phase (running `cargo doc`, fetching and parsing docs, searching,
+// extracting and comparing items, rendering) is timed by holding a `Phase`
+// for as long as it runs. With timing off, a phase costs an `Instant::now()`.
+//
+// `--timings` prints the phases as a table on stderr once the command is
+// done, nested phases indented under the one they ran in, and JSON output
+// carries them as `timings`. `ZDOC_PROFILE=chrome` writes them to
+// `zdoc-trace.json` as Chrome trace events, for chrome://tracing or Perfetto.
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+use tracing::info;
+
+use crate::table::Table;
+
+const TRACE_FILE: &str = "zdoc-trace.json";
+
+// One finished phase, timed from the start of the run
+struct Record {
+    name: String,
+    start: Duration,
+    duration: Duration,
+    items: Option<usize>,
+}
+
+struct Recorder {
+    started: Instant,
+    // Print the table and add `timings` to JSON output
+    table: bool,
+    // Write the Chrome trace
+    chrome: bool,
+    records: Vec<Record>,
+}
+
+static RECORDER: Mutex<Option<Recorder>> = Mutex::new(None);
+
+fn recorder() -> MutexGuard<'static, Option<Recorder>> {
+    RECORDER.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+// Start recording if `--timings` was given or `ZDOC_PROFILE=chrome` is set
+pub fn init(table: bool) {
+    let chrome = std::env::var("ZDOC_PROFILE").is_ok_and(|profile| profile == "chrome");
+    if table || chrome {
+        *recorder() = Some(Recorder {
+            started: Instant::now(),
+            table,
+            chrome,
+            records: Vec::new(),
+        });
+    }
+}
+
+// A phase being timed; it's recorded when dropped
+pub struct Phase {
+    name: String,
+    started: Instant,
+    items: Option<usize>,
+}
+
+pub fn phase(name: impl Into<String>) -> Phase {
+    Phase {
+        name: name.into(),
+        started: Instant::now(),
+        items: None,
+    }
+}
+
+impl Phase {
+    // How many items the phase handled, for the table's last column
+    pub fn items(&mut self, count: usize) {
+        self.items = Some(count);
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+}
+
+impl Drop for Phase {
+    fn drop(&mut self) {
+        if let Some(recorder) = recorder().as_mut() {
+            recorder.records.push(Record {
+                name: std::mem::take(&mut self.name),
+                start: self.started.saturating_duration_since(recorder.started),
+                duration: self.started.elapsed(),
+                items: self.items,
+            });
+        }
+    }
+}
+
+// The records in the order they started, each with how many others it ran
+// inside of
+fn nested(records: &[Record]) -> Vec<(usize, &Record)> {
+    let mut sorted: Vec<&Record> = records.iter().collect();
+    sorted.sort_by_key(|record| (record.start, std::cmp::Reverse(record.duration)));
+    let mut open: Vec<Duration> = Vec::new();
+    let mut out = Vec::new();
+    for record in sorted {
+        open.retain(|end| *end > record.start);
+        out.push((open.len(), record));
+        open.push(record.start + record.duration);
+    }
+    out
+}
+
+fn millis(duration: Duration) -> f64 {
+    (duration.as_secs_f64() * 10_000.0).round() / 10.0
+}
+
+// The finished phases for JSON output, when `--timings` was given
+pub fn to_json() -> Option<Value> {
+    let guard = recorder();
+    let recorder = guard.as_ref().filter(|recorder| recorder.table)?;
+    let phases: Vec<Value> = nested(&recorder.records)
+        .into_iter()
+        .map(|(depth, record)| {
+            serde_json::json!({
+                "phase": record.name,
+                "depth": depth,
+                "start_ms": millis(record.start),
+                "ms": millis(record.duration),
+                "items": record.items,
+            })
+        })
+        .collect();
+    Some(serde_json::json!({
+        "total_ms": millis(recorder.started.elapsed()),
+        "phases": phases,
+    }))
+}
+
+fn chrome_trace(records: &[Record]) -> Value {
+    let events: Vec<Value> = records
+        .iter()
+        .map(|record| {
+            serde_json::json!({
+                "name": record.name,
+                "cat": "zdoc",
+                "ph": "X",
+                "ts": record.start.as_micros() as u64,
+                "dur": record.duration.as_micros() as u64,
+                "pid": std::process::id(),
+                "tid": 1,
+                "args": { "items": record.items },
+            })
+        })
+        .collect();
+    serde_json::json!({ "traceEvents": events, "displayTimeUnit": "ms" })
+}
+
+// Print the table and write the trace, whichever were asked for
+pub fn finish() -> Result<()> {
+    let Some(recorder) = recorder().take() else {
+        return Ok(());
+    };
+    if recorder.table {
+        let mut table = Table::new(&["Phase", "Time", "Items"]);
+        for (depth, record) in nested(&recorder.records) {
+            table.add_row(vec![
+                format!("{}{}", "  ".repeat(depth), record.name),
+                format!("{:.1} ms", millis(record.duration)),
+                record.items.map(|n| n.to_string()).unwrap_or_default(),
+            ]);
+        }
+        table.add_row(vec![
+            "total".to_string(),
+            format!("{:.1} ms", millis(recorder.started.elapsed())),
+            String::new(),
+        ]);
+        eprint!("\n{}", table.render_with(None, false));
+    }
+    if recorder.chrome {
+        let trace = serde_json::to_string(&chrome_trace(&recorder.records))?;
+        std::fs::write(TRACE_FILE, trace)
+            .with_context(|| format!("Failed to write {}", TRACE_FILE))?;
+        info!("Wrote a Chrome trace to {}", TRACE_FILE);
+    }
+    Ok(())
+}
@@ -0,0 +1,83 @@
+// The toolchain `cargo doc` runs on, from `[rustdoc] toolchain` in
+// `zdoc.toml` (which `zdoc setup` writes). Without one, cargo's default
+// toolchain is used with `RUSTC_BOOTSTRAP=1`, which lets stable rustdoc
+// write JSON but leaves its format to whichever release that is.
+use std::process::Command;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::warn;
+
+use crate::config::Config;
+
+const JSON_FLAGS: &str = "-Z unstable-options --output-format json";
+
+static TOOLCHAIN: OnceLock<Option<String>> = OnceLock::new();
+static HIDDEN: AtomicBool = AtomicBool::new(false);
+
+// Read the configured toolchain, from the config nearest the current directory
+pub fn init() {
+    let toolchain = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| Config::find(&cwd))
+        .map(|root| Config::load(&root))
+        .transpose()
+        .unwrap_or_else(|e| {
+            warn!(
+                "Ignoring the toolchain in {}: {:#}",
+                crate::config::CONFIG_FILE,
+                e
+            );
+            None
+        })
+        .and_then(|config| config.rustdoc.toolchain);
+    let _ = TOOLCHAIN.set(toolchain);
+}
+
+pub fn configured() -> Option<&'static str> {
+    TOOLCHAIN.get().and_then(|toolchain| toolchain.as_deref())
+}
+
+// Document `#[doc(hidden)]` items and the contents of hidden modules, which
+// rustdoc otherwise leaves out (`search --include-hidden`, `show`)
+pub fn set_document_hidden(hidden: bool) {
+    HIDDEN.store(hidden, Ordering::Relaxed);
+}
+
+fn rustdoc_flags() -> String {
+    if HIDDEN.load(Ordering::Relaxed) {
+        format!("{} --document-hidden-items", JSON_FLAGS)
+    } else {
+        JSON_FLAGS.to_string()
+    }
+}
+
+// `cargo doc` writing JSON, on `toolchain` (or cargo's default)
+pub fn cargo_doc_on(toolchain: Option<&str>) -> Command {
+    let mut command = Command::new("cargo");
+    if let Some(toolchain) = toolchain {
+        command.arg(format!("+{}", toolchain));
+    }
+    command
+        .arg("doc")
+        .env("RUSTDOCFLAGS", rustdoc_flags())
+        // Enables unstable features on stable; harmless on nightly
+        .env("RUSTC_BOOTSTRAP", "1");
+    command
+}
+
+// `cargo doc` writing JSON, on the configured toolchain
+pub fn cargo_doc() -> Command {
+    cargo_doc_on(configured())
+}
+
+// How `cargo_doc` invokes cargo, for display
+pub fn describe() -> String {
+    let toolchain = configured()
+        .map(|toolchain| format!(" +{}", toolchain))
+        .unwrap_or_default();
+    format!(
+        "RUSTC_BOOTSTRAP=1 RUSTDOCFLAGS=\"{}\" cargo{} doc",
+        rustdoc_flags(),
+        toolchain
+    )
+}
@@ -0,0 +1,97 @@
+// `zdoc batch` end to end: a scripted conversation with the built `zdoc`
+// in a copy of the `widgets` fixture, whose docs are generated on the first
+// request that needs them (so it needs a nightly toolchain).
+mod common;
+
+use serde_json::Value;
+use std::io::Write;
+use std::process::Stdio;
+
+// The responses `zdoc batch` gives to `requests`, one per line
+fn converse(requests: &[&str]) -> Vec<Value> {
+    let dir = common::workspace("widgets");
+    let mut child = common::zdoc(dir.path(), "batch")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    for request in requests {
+        writeln!(stdin, "{}", request).unwrap();
+    }
+    drop(stdin);
+    let output = child.wait_with_output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[test]
+fn errors_answer_their_own_request_until_shutdown() {
+    let responses = converse(&[
+        r#"{"id": 1, "cmd": "show", "path": "Widget::area"}"#,
+        r#"{"id": 2, "cmd": "frobnicate", "path": "Widget"}"#,
+        r#"{"id": 3, "cmd": "signature", "path": "Widget::volume"}"#,
+        "not json",
+        r#"{"id": 5, "cmd": "signature", "path": "widgets::draw"}"#,
+        r#"{"id": 6, "cmd": "search", "query": "canvas", "limit": 1}"#,
+        r#"{"cmd": "shutdown"}"#,
+        r#"{"id": 8, "cmd": "show", "path": "Canvas"}"#,
+    ]);
+    let ids: Vec<Value> = responses
+        .iter()
+        .map(|response| response["id"].clone())
+        .collect();
+    // Nothing after the shutdown is answered
+    let null = Value::Null;
+    assert_eq!(
+        ids,
+        [
+            1.into(),
+            2.into(),
+            3.into(),
+            null.clone(),
+            5.into(),
+            6.into(),
+            null
+        ]
+    );
+
+    assert_eq!(responses[0]["ok"], true);
+    assert_eq!(responses[0]["result"]["path"], "widgets::Widget::area");
+
+    let error = |i: usize| {
+        assert_eq!(responses[i]["ok"], false);
+        responses[i]["error"].as_str().unwrap()
+    };
+    assert!(
+        error(1).contains("unknown variant `frobnicate`"),
+        "{}",
+        error(1)
+    );
+    assert_eq!(error(2), "No item matching 'Widget::volume'");
+    assert!(error(3).starts_with("Invalid JSON"), "{}", error(3));
+
+    // The session goes on after each of them
+    assert_eq!(
+        responses[4]["result"]["signature"],
+        "pub fn draw(canvas: &Canvas) -> String"
+    );
+    let found = &responses[5]["result"]["results"][0];
+    assert_eq!(
+        (&found["crate"], &found["name"]),
+        (&"widgets".into(), &"Canvas".into())
+    );
+    assert_eq!(
+        responses[6],
+        serde_json::json!({ "ok": true, "result": null })
+    );
+}
@@ -0,0 +1,28 @@
+// Helpers for the tests that run the built `zdoc` on a fixture crate
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+// A copy of a fixture crate, without any build output
+pub fn workspace(fixture: &str) -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(fixture);
+    std::fs::copy(fixture.join("Cargo.toml"), dir.path().join("Cargo.toml")).unwrap();
+    std::fs::create_dir(dir.path().join("src")).unwrap();
+    std::fs::copy(fixture.join("src/lib.rs"), dir.path().join("src/lib.rs")).unwrap();
+    dir
+}
+
+// `zdoc <command>` in the workspace, without the target directory this test
+// itself was built in leaking through
+pub fn zdoc(dir: &Path, command: &str) -> Command {
+    let mut zdoc = Command::new(env!("CARGO_BIN_EXE_zdoc"));
+    zdoc.arg(command)
+        .current_dir(dir)
+        .env_remove("CARGO_TARGET_DIR")
+        .env_remove("CARGO_BUILD_TARGET")
+        .env_remove("CARGO_BUILD_TARGET_DIR");
+    zdoc
+}
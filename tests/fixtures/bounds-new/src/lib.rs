@@ -0,0 +1,42 @@
+//! Declarations whose where clauses change between releases while
+//! everything else stays put.
+use std::fmt::Debug;
+
+/// A value that can be sent to another thread.
+pub struct Wrapper<T>(pub T)
+where
+    T: Send + Sync;
+
+/// One of two values.
+pub enum Either<L, R>
+where
+    L: Send,
+{
+    Left(L),
+    Right(R),
+}
+
+/// Clones and prints `value`.
+pub fn process<T>(value: T) -> T
+where
+    T: Clone,
+{
+    value.clone()
+}
+
+/// Converts `value` to a string.
+pub fn convert<T>(value: T) -> String
+where
+    T: Into<String>,
+{
+    value.into()
+}
+
+/// Something storing values.
+pub trait Store
+where
+    Self: Sized + Send,
+{
+    /// Keeps `value`.
+    fn keep<T>(&self, value: T);
+}
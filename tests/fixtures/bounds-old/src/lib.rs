@@ -0,0 +1,41 @@
+//! Declarations whose where clauses change between releases while
+//! everything else stays put.
+use std::fmt::Debug;
+
+/// A value that can be sent to another thread.
+pub struct Wrapper<T>(pub T)
+where
+    T: Send;
+
+/// One of two values.
+pub enum Either<L, R>
+where
+    L: Send,
+{
+    Left(L),
+    Right(R),
+}
+
+/// Clones and prints `value`.
+pub fn process<T>(value: T) -> T
+where
+    T: Clone + Debug,
+{
+    value.clone()
+}
+
+/// Converts `value` to a string.
+pub fn convert<T: Into<String>>(value: T) -> String {
+    value.into()
+}
+
+/// Something storing values.
+pub trait Store
+where
+    Self: Sized,
+{
+    /// Keeps `value`.
+    fn keep<T>(&self, value: T)
+    where
+        T: Debug;
+}
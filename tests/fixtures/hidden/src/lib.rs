@@ -0,0 +1,49 @@
+//! A crate with a `#[doc(hidden)]` public module, which hides everything
+//! inside it, except what is re-exported from outside. Documented with
+//! `--document-hidden-items` (see `rustdoc-args`), as `zdoc show` does.
+
+/// A handle to something.
+pub struct Handle {
+    pub id: u32,
+}
+
+impl Handle {
+    /// Makes a handle.
+    pub fn new(id: u32) -> Self {
+        Handle { id }
+    }
+
+    #[doc(hidden)]
+    pub fn __raw(&self) -> u32 {
+        self.id
+    }
+}
+
+pub use __private::Exposed;
+
+#[doc(hidden)]
+pub mod __private {
+    /// Support for the crate's macros.
+    pub struct Shim {
+        pub value: u32,
+    }
+
+    impl Shim {
+        pub fn get(&self) -> u32 {
+            self.value
+        }
+    }
+
+    pub trait Helper {
+        fn help(&self);
+    }
+
+    /// Used by macros; re-exported at the crate root.
+    pub struct Exposed;
+
+    pub fn support() {}
+
+    pub mod nested {
+        pub fn deep() {}
+    }
+}
@@ -0,0 +1,3 @@
+pub struct Instant;
+
+pub trait Tick {}
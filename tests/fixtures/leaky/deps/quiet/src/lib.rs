@@ -0,0 +1,5 @@
+pub struct Secret;
+
+pub fn helper() -> u32 {
+    7
+}
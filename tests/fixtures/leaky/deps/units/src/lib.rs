@@ -0,0 +1 @@
+pub struct Meters(pub f64);
@@ -0,0 +1,44 @@
+//! A crate whose public API exposes its dependencies on purpose, in each
+//! position `zdoc public-deps` looks at.
+
+/// A return type.
+pub fn now() -> clock::Instant {
+    clock::Instant
+}
+
+/// A parameter, from a dependency renamed in `Cargo.toml`.
+pub fn walk(_distance: metric::Meters) {}
+
+/// A public field; the private one isn't part of the API.
+pub struct Trip {
+    pub length: metric::Meters,
+    started: clock::Instant,
+}
+
+impl Trip {
+    /// When the trip started.
+    pub fn started(&self) -> &clock::Instant {
+        &self.started
+    }
+}
+
+/// A trait bound.
+pub fn schedule<T: clock::Tick>(_timer: T) {}
+
+/// A trait impl.
+pub struct Timer;
+
+impl clock::Tick for Timer {}
+
+/// A dependency with a wildcard requirement.
+pub fn whatever() -> anything::Anything {
+    anything::Anything
+}
+
+/// Only the body uses `quiet`.
+pub fn count() -> u32 {
+    quiet::helper()
+}
+
+#[doc(hidden)]
+pub fn __internal(_secret: quiet::Secret) {}
@@ -0,0 +1,25 @@
+//! Items only nightly Rust can declare, whose kinds stable crates never
+//! produce.
+#![feature(trait_alias, extern_types, type_alias_impl_trait, never_type)]
+
+/// Something that can be shared between threads and cloned.
+pub trait Shareable = Clone + Send + Sync;
+
+extern "C" {
+    /// An opaque C handle.
+    pub type Handle;
+}
+
+/// The counter `counter` returns.
+pub type Counter = impl Iterator<Item = u32>;
+
+/// Counts up from zero.
+#[define_opaque(Counter)]
+pub fn counter() -> Counter {
+    0..
+}
+
+/// Never returns.
+pub fn halt() -> ! {
+    loop {}
+}
@@ -0,0 +1,44 @@
+// Glob re-exports for `zdoc prelude`: a prelude made of other modules'
+// globs, a nested prelude, an enum glob, an explicit import that shadows a
+// glob, two globs bringing in different items with one name, one item
+// reached through two globs, names that share a spelling across namespaces
+// and a glob of another crate's module.
+#![allow(non_snake_case)]
+
+pub mod shapes {
+    pub struct Circle;
+    pub struct Square;
+    pub fn area() {}
+}
+
+pub mod colors {
+    pub struct Red;
+    // Clashes with `shapes::Square` once both are globbed
+    pub struct Square;
+    pub enum Tone {
+        Light,
+        Dark,
+    }
+    // A type and a function of the same name don't clash
+    pub struct Blue {}
+    pub fn Blue() {}
+}
+
+pub mod nested {
+    pub mod prelude {
+        // Also reachable through `shapes::*`: the same item, not a clash
+        pub use crate::shapes::Circle;
+        pub trait Draw {}
+    }
+}
+
+pub mod prelude {
+    pub use crate::colors::Tone::*;
+    pub use crate::colors::*;
+    pub use crate::nested::prelude::*;
+    pub use crate::shapes::*;
+    pub use core::cmp::*;
+
+    // Shadows `shapes::area`
+    pub fn area() {}
+}
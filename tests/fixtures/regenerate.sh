@@ -0,0 +1,30 @@
+#!/bin/sh
+# Rebuild the rustdoc JSON fixtures from the crates beside this script:
+# `<name>/` documents into `<name>.json` (two versions of one crate can sit
+# in `<crate>-old/` and `<crate>-new/`). `paths` keeps only the ids the
+# crate's own index mentions and `external_crates` only the crates those
+# belong to, so each file stays a few dozen KB instead of carrying all of
+# std's paths; local filesystem paths are dropped. Extra rustdoc arguments
+# for a fixture go in `<name>/rustdoc-args`.
+#
+#     tests/fixtures/regenerate.sh [name...]
+set -e
+cd "$(dirname "$0")"
+names="$*"
+[ -n "$names" ] || names=$(for manifest in */Cargo.toml; do dirname "$manifest"; done)
+for name in $names; do
+    target=$(mktemp -d)
+    args=$(cat "$name/rustdoc-args" 2>/dev/null || true)
+    (cd "$name" && CARGO_TARGET_DIR="$target" cargo +nightly rustdoc --quiet -- \
+        -Z unstable-options --output-format json $args)
+    jq -c '
+        ([.index | .. | objects | .id? | numbers] + [.index | keys[] | tonumber] | unique) as $ids
+        | .paths |= with_entries(select((.key | tonumber) as $id | $ids | index($id)))
+        | ([.paths[].crate_id] + [.index[].crate_id] | unique | map(tostring)) as $crates
+        | .external_crates |= (with_entries(select(.key as $k | $crates | index($k)))
+            | map_values(del(.path)))
+        | .target.target_features = []
+    ' "$target"/doc/*.json > "$name.json"
+    rm -rf "$target"
+    echo "$name.json: $(wc -c < "$name.json") bytes"
+done
@@ -0,0 +1 @@
+rn_("RQBAAAAyOgA8AD0APgBtEwAeACYAJwAoADUAQwAAMjoAPAA9AD4AbR4AJgAnACgAcUAAAAoAEAARABIALAA0ADUANgCnAQCFoAAAAAANoBAAAAANAFgROjAAAAEAAAAAAAcAEAAAAAsAFAAVABYALQA3ADgAOQA=")
\ No newline at end of file
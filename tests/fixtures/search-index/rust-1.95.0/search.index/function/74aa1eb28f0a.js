@@ -0,0 +1 @@
+rd_("Ah[\"{{{Gh{jD`}}Dl}Cb}\",[]]Ae[\"{{{Gh{D`}}}Cj}\",[]]Aa[\"{{AbAb}Dl}\",[]]m[\"{{}D`}\",[]]Ae[\"{{{Gh{Dl}}}Ab}\",[]]Ae[\"{{{Gh{D`}}}Dh}\",[]]Ae[\"{{{Gh{Ad}}}Dh}\",[]]A`[\"{cc{}}\",[\"T\"]]00Af[\"{{{Gh{jDl}}}Cb}\",[]]Aa[\"{{}c{}}\",[\"U\"]]00Am[\"{{{Gh{Ad}}}{{Gh{A`}}}}\",[]]Af[\"{{{Gh{jD`}}}Cb}\",[]]m[\"{{}Dl}\",[]]m[\"{DlAb}\",[]]Ai[\"{Gh{{Gh{c}}}{}}\",[\"T\"]]001;m[\"{GhDj}\",[]]00Ae[\"{{{Gh{D`}}}Al}\",[]]An[\"{c{{Dd{e}}}{}{}}\",[\"U\",\"T\"]]00Ai[\"{{}{{Dd{c}}}{}}\",[\"U\"]]00Ba[\"{{{Gh{j}}}{{Gh{jc}}}{}}\",[\"T\"]]00")
\ No newline at end of file
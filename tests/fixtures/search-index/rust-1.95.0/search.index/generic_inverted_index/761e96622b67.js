@@ -0,0 +1 @@
+rb_("RGIAAxQAAAAVAAAAFgAAADswAAABAAAFAAIAEAACADcAAgA7MAAAAQAABQACACYAAgA0AAIAAAM8AAAAPQAAAD4AAABBYAAAAAM0AAAANQAAADYAAAA=")
\ No newline at end of file
@@ -0,0 +1 @@
+rd_("b()cAnycaddclencmutcnew0cstrcu32dDrawdFromdIntodareadboolddraw0dfrom00dgrowdinto00dnameduniteShapeecleareemptyeusizeewidthfBorrowfCanvasfCirclefResultfSquarefStringfTypeIdfWidgetfborrow00fheightfwidgetgDefaultgTryFromgTryIntogdefaultgtype_id00gwidgetshis_emptyhtry_from00htry_into00iBorrowMutireferencejborrow_mut00")
\ No newline at end of file
@@ -0,0 +1 @@
+rr_('{"normalizedName":{"I":"JQJAAAAzLAA0ADUANgAzLQA3ADgAOQAzJAAvADAAMQBmaXABABsAMwAFAEAAADEkAC8AMAAxAGkZABsCsBAAGwAYZXQxQgAAOgA8AD0APgAUAEJldAAAADIAcyUAKgASgWQCAOaAAAAAHWd08gABAWltADowAAABAAAAAAAJABAAAAAFAAYACQAOAA8AEwAeACYAJwAoAEFAAAAEADoAPAA9AD4AIUEAACEAKwAuAABDaXplAAAcAABCaXQAABgAZgOAAwPjIAAAAAigIAAAACIzbnNhbHRxQAAACwAUABUAFgAtADcAOAA5ADBDZWlkAAAkAC8AMAAxAPIAAQBwANIbAAAAGAQBwAAAM3JvbSwANAA1ADYAM250by0ANwA4ADkAZmkSAAEBoCAAAAAjeWkAAQcAAACyAAIDoAAAAAAdoAAAAAAycnlob3MAOjAAAAEAAAAAAAsAEAAAAAQAGAAhACUAKQAqACsALgA6ADwAPQA+AAQAQXIAAAJuZyMAaQcAAER1YXJlAAAiAABDYXBlAAAZAHIAAwOgQAAAADOgIAAAABygIAAAACFocXRlaXUA0h8AAAATBQHAAAAzLAA0ADUANgAzLQA3ADgAOQBmaTUAQgAAMjoAPAA9AD4AbR4AJgAnACgARQBAAAAyOgA8AD0APgBtEwAeACYAJwAoAHFAAAAKABAAEQASACwANAA1ADYA+wJtdyFBAAAJAA4ADwAAQ3VsdAAAIQAARmVyZW5jZQAAOwAyAAICoAAAAAAMoCAAAAA7ZnNhbgABIgAAADYHAIGGoCAAAAAgoCAAAAAjEAAABUFBANIHAAAAE3FBAAALABQAFQAWAC0ANwA4ADkAAEJtZQAAFwAWAgACBvdwAAUAAaAQAAAAO6AAAAAAI6AQAAAAGKAgAAAAH6AAAAAAAWFlY2dpdHZ5AAEDAAAAAAF0AAABBAAAANQ6AAAAAgEBEgABAqAAAAAAF7AgABsAGHVlcAA6MAAAAQAAAAAABwAQAAAACgAQABEAEgAsADQANQA2AAAAAQHAAAAAAAOgEAAAABpuYQABIAAAABIAAQGAACEAoANldAABDQAAABUAQgAAADIAcyUAKgATAAKgEAAAAB1ndNQkAAAACwEBAEVlbXB0eQAAMwAAAW8AANQLAAAACQEB1C0AAAAKAQESgAEBoAAAAAAjdGdygIKFoCAAAAApoDAAAAAgoAAAAAAYoBAAAAAcACACSACFAERpZ2h0AAApABIAAQKgIAAAABmgAAAAACllYXQAAR0AAAAVAEEAAAAyAHMlACoAAEJvdwAAEwAyAAECoBAAAAApcmVoAAEjAAAAAAJvbQAA1AoAAAAGAQHULAAAAAgBARKAAQKwMAArAAOgUAAAADtyYWUVAEAAAAAyAHMlACoABQBAAAABOwBjAwAFAcAAADAkAC8AMAAxAAIpAGRnBQHAAAATKwAuAAU7AGFlBQBAAAAAGgByDAAAA3B0eQAAARsAAAABMwAAAPYJAIGIoEAAAAA7oDAAAAAhsAAABQABABAAISEXANYXAAAAAgMEAhkgQmF3AAAJAA4ADwAQRWZhdWx0AAArAC4AtAAAAgMAAaAAAAAAAhWgEAAAAB1lcmRndADVAgAAACILAQEAAWUBAOcgAAAAGmEAASAAAAAARHJjbGUAACAAAERudmFzAAAfAHKAAwGgAAAAADthaWxlNABDcm93AAAydXQ6ADwAPQA+AG0eACYAJwAoACqKAgDmwAAAAA1vcgAAAQDAAAAAAAxhAAEiAAAA8gABAGUAARoAAAAAgAEBwAAAAAABoCAAAAAfeXZiAIOF5kAAAAACoBAAAAAXoBAAAAAZoAAAAAAfsCAAKwADgAAJAFABCCABAJAaAAEMAAAAAIASCeKQAAAAAG50gI/eEBFgwaIOSmR4aUyf825FpAkuqnSD/aYnQFgSJZgPlH0WrImN5AZ/lVfvMS+F2/Nls2d1Tt20tjhnUirbVkPa4KLJPjSkEXz/MnzSncz3kTcOzAmqlGD4RaZnH33n5CVvYKAAAAAAAKAAAAAACKAQAAAACBQJxa2a8F4cM6tih6BAAAAAIqAgAAAAHz7Cz5JhqKAQAAAAHChhYmNkZWZnaGlsbW5yc3R1dykyM29wcXZ5eg==","N":"Co","E":"OjAAAAAAAAA=","H":"OGxlgy5N"},"crateNames":{"N":"a","E":"OjAAAAAAAAA=","H":"TgFtc5we"},"name":{"N":"Co","E":"OjAAAAAAAAA=","H":"urpJYpEZ"},"path":{"N":"Ah","E":"OzAAAAEAACYADAACAAEABQABAAwAAAAOAAkAGgABAB0AAAAgAAAAIgAAACYABAAuAAMAMwAGADwAAgA=","H":"E3YEJ4OO"},"entry":{"N":"Bl","E":"OjAAAAEAAAAAABIAEAAAAAAAAQAEAAcACAAKAAsADQAYABwAHgAhACMAJAArACwALQA6ADsA","H":"06Ups15m"},"desc":{"N":"Ah","E":"OzAAAAEAACYACwAAAAEABAAAAAcAAQAKAAEADQAAABgAAAAcAAIAIAAEACYAAwArAAYANAAKAA==","H":"GVP41yL0"},"function":{"N":"Bd","E":"OzAAAAEAABoACgAAAAEABAAAAAcABAANAAAAGAABABwAAAAeAAcAKgADADIAAAA6AAEA","H":"dKoeso8K"},"type":{"N":"n","E":"OzAAAAEAADAACgABAAIABQABAAoAAgAOAAkAGQACAB0AAQAgAAAAIgAAACYAFAA8AAIA","H":"L60mst3V"},"alias":{"N":"`","E":"OzAAAAEAAD4AAQAAAD4A","H":"p2IVDFMs"},"generic_inverted_index":{"N":"b","E":"OjAAAAAAAAA=","H":"dh6WYitn"}}')
\ No newline at end of file
@@ -0,0 +1 @@
+rd_("Ao[\"\",\"AAAAAAITAAAAGgAAAAECAAAA\"]Dg[\"AAAAAAITAAAAGgAAAAQCAAAAPAAAAD0AAAA+AAAA\",\"AAAAAAADPAAAAD0AAAA+AAAA\"]Ac[\"\",\"AAAAAAEXAAAA\"]Co[\"AAAAAQUAAAA=\",\"AAAAAQwAAAAAAAAAAAAAAAAAAAAAAAIdAAAAKQAAAA==\"]Ak[\"AAAAAQ8AAAABFwAAAA==\",\"\"]Ac[\"\",\"AAAAATMAAAA=\"]5Ac[\"\",\"AAAAAQMAAAA=\"]Ck[\"AAAAAwMAAAAOAAAAMwAAAAEaAAAAAQIAAAA=\",\"AAIGAAAALgAAAA==\"]Ca[\"\",\"AAADNwAAADgAAAA5AAAAAzQAAAA1AAAANgAAAA==\",1]Ag[\"\",\"AAAAAg4AAAAPAAAA\"]Ak[\"\",\"AAADLwAAADAAAAAxAAAA\"]Dk[\"AAAAAQwAAAABEwAAAAECAAAAAAAAAAAAAAAAAAAAAh0AAAApAAAA\",\"AAEbAAAAAAEFAAAA\"]Ja[\"AAADLwAAADAAAAAxAAAAOjAAAAEAAAAAAAcAEAAAAAMADAAOAA8AJgAnACgAMwADEwAAABcAAAAaAAAABAIAAAA8AAAAPQAAAD4AAAA=\",\"AAAAAyYAAAAnAAAAKAAAAAEXAAAAAzwAAAA9AAAAPgAAAA==\",1]")
\ No newline at end of file
@@ -0,0 +1 @@
+rn_("VQHAAABCOgA8AD0APgBtEwAeACYAJwAoAEUBwwAAQjoAPAA9AD4AbR4AJgAnACgAAUDjCgAAABAAAAAsAAAANAAAAAICpwEA/QBYEeAAAAANwAAAAA3jCwAAABQAAAAtAAAANwAAAAIC")
\ No newline at end of file
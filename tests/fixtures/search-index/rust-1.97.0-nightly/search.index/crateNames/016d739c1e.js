@@ -0,0 +1 @@
+rd_("gwidgets")
\ No newline at end of file
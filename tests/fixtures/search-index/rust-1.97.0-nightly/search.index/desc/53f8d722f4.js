@@ -0,0 +1 @@
+rd_("BcAdds a widget on top of the others.BbHow many widgets the canvas holds.BaMakes a widget of the given size.A`An empty canvas.AlSomething that can be drawn.AnThe widget\xe2\x80\x99s area in pixels.C`Draws every widget on the canvas, back to front.lThe drawing.AoReturns the argument unchanged.00AlDoubles the widget\xe2\x80\x99s size.BaCalls <code>U::from(self)</code>.00AjA name for error messages.AhThe outline of a widget.AeRemoves every widget.AlMakes a widget with no area.AhWhere widgets are drawn.CgA rectangle that can be drawn on a <code>Canvas</code>.ChMakes a <code>Widget</code>: <code>widget!(3, 4)</code>.DhA small, documented crate: the kind of API <code>zdoc man</code> and \xe2\x80\xa6BdWhether the canvas holds no widgets.")
\ No newline at end of file
@@ -0,0 +1 @@
+rd_("Af[50,13,51,51,32,0,0,0]0Af[50,13,51,51,38,0,0,0]1Ad[50,10,51,0,0,0,0,0]1Ac[50,7,51,0,0,0,0,0]Af[50,12,51,51,10,0,0,0]Ag[50,13,51,51,38,11,0,0]Ag[50,13,51,51,26,11,0,0]Ag[50,13,51,51,32,11,0,0]Af[50,13,51,51,38,0,1,0]Ag[50,13,51,51,38,12,0,0]Ag[50,13,51,51,26,12,0,0]Ag[50,13,51,51,32,12,0,0]Af[50,13,51,51,10,0,0,0]Ac[50,6,51,0,0,0,0,0]:<Af[50,14,51,51,38,0,0,0]Ac[50,5,51,0,0,0,0,0]Af[50,15,51,51,26,0,0,0]01Ag[50,13,51,51,38,31,0,0]Ag[50,13,51,51,26,31,0,0]Ag[50,13,51,51,32,31,0,0]5Ad[50,16,51,0,0,0,0,0]Ag[50,13,51,51,32,44,0,0]Af[50,13,51,51,38,2,0,0]Af[50,13,51,51,26,2,0,0]Af[50,13,51,51,32,2,0,0]Ab[50,3,0,0,0,0,0,0]Af[50,13,51,51,32,0,0,0]Ag[50,13,51,51,38,45,0,0]Ag[50,13,51,51,26,45,0,0]Ag[50,13,51,51,32,45,0,0]Ag[50,13,51,51,38,46,0,0]Ag[50,13,51,51,26,46,0,0]Ag[50,13,51,51,32,46,0,0]Ag[50,13,51,51,38,59,0,0]Ag[50,13,51,51,26,59,0,0]Ag[50,13,51,51,32,59,0,0]")
\ No newline at end of file
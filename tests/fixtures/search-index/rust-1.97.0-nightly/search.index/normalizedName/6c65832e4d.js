@@ -0,0 +1 @@
+rd_("b()canycaddclencmutcnew0cstrcu32ddrawdfromdintodareadbool44333dgrow333dnameduniteshapeecleareemptyeusizeewidthfborrowfcanvasfcirclefresultfsquarefstringftypeidfwidget777fheight1gdefaultgtryfromgtryinto2555gwidgetsgisempty333222iborrowmutireference111")
\ No newline at end of file
@@ -0,0 +1 @@
+rd_("f[1,\"\"]A`[10,\"core::any\"]f[0,\"\"]22Ah[10,\"widgets\",\"widgets\"]Ad[10,\"core::convert\"]044Ag[6,\"widgets\",\"widgets\"]5Ac[10,\"core::borrow\"]Ag[5,\"widgets\",\"widgets\"]Ba[6,\"core::result\",\"core::result\"]Bc[5,\"alloc::string\",\"alloc::string\"]Ak[5,\"core::any\",\"core::any\"]3Ad[10,\"core::default\"]77f[3,\"\"]6<")
\ No newline at end of file
@@ -0,0 +1 @@
+rr_('{"normalizedName":{"I":"NQPAAABDLAA0ADUANgBDLQA3ADgAOQBDJAAvADAAMQBmaXABABsAMwAhwQAAGwAzABWEwAAAQSQALwAwADEAGQD7AmV0QcIAADoAPAA9AD4AJAHCZXQAABAyAHMlACoAEoFkAgCNAAAAHWd08gCEAW0A5QUAAAAJAAAADgAAABMAAAAeAAAAJgAAABAQAlHAAAAEADoAPAA9AD4AMcEAACEAKwAuABDDaXplAAAcABDCaXQAABgAZgOAAwOBAAAACMEAAAAiM25zYWx0AUDjCwAAABQAAAAtAAAANwAAAAICQMNlaWQAACQALwAwADEA8gABAHAA0hsAAAAYBALAAABDcm9tLAA0ADUANgBDbnRvLQA3ADgAOQBmaRIAAYTBAAAAI3kAAQcAAACyAAID4AAAAB3gAAAAMnJ5aG9zAOcEAAAAGAAAACEAAAAlAAAAKQAAAC4AAAA6AAAAPAAAAAAAIAIUhMFyAAASbmcjAAcAEMR1YXJlAAAiABDDYXBlAAAZAHIAwCCQlsMAAAAzwQAAABzBAAAAIQDSHwAAABMFAsAAAEMsADQANQA2AEMtADcAOAA5AGZpRQHCAABCOgA8AD0APgBtHgAmACcAKABVAcAAAEI6ADwAPQA+AG0TAB4AJgAnACgAAUDjCgAAABAAAAAsAAAANAAAAAIC+wJtdzHBAAAJAA4ADwAQw3VsdAAAIQAQxmVyZW5jZQAAOwAyAAIC4AAAAAzBAAAAO2ZzYW4AASIAAAA2BwCC/QVBQcEAAAAgwQAAACMA0gcAAAATAUHjCwAAABQAAAAtAAAANwAAAAICoMF3AAAFABDCbWUAABcANgIAg/4AUQBKwAAAADvgAAAAI8AAAAAYwQAAAB/gAAAAAQABAwAAACHCAAAbADMAAEF0AQQAAADhOgAAADwAAAACUgCQAuAAAAAXZXAA4woAAAAQAAAALAAAADQAAAACAjHAAAAhACsALgAAAAGBoAAAAAPAAAAAGm4AASAAAADyAIIBdAABDQAAACUBwgAAEDIAcyUAKgATAALAAAAAHWd04SQAAAAvAAAAAhDFZW1wdHkAADMAAEFv4QsAAAAUAAAAAuEtAAAANwAAAAISgAEB4AAAACN0Z3KAAtAUoMEAAAApwgAAACDgAAAAGMAAAAAcbnMQxGlnaHQAACkAEgCCAsEAAAAZ4AAAAClhdAABHQAAACUBwQAAEDIAcyUAKgAQwm93AAATADIAAQLAAAAAKXJlaAABIwAAACHDAAArAC4AAEJvbeEKAAAAEAAAAALhLAAAADQAAAACMoABg8QAAAA7ciUBwAAAEDIAcyUAKgAVAcAAABE7AGMDAAUCwAAAQCQALwAwADEAEikAZGcFg8AAACMrAC4AFTsAFQHAAAAQGgByDAAAQ3B0eQEbAAAAATMAAAD2CQAB/SEhF8MAAAA7wgAAACHhAAAABW0A1hcAAAACAwQCGTDCYXcAAAkADgAPACDFZmF1bHQAACsALgC0AAACwBSAAAHgAAAAAhbAAAAAHWVyAOICAAAAJAAAAC8AAAAAIAABZYEAlwAAABoAASAAAAAQxHJjbGUAACAAEMRudmFzAAAfAHKAA4LgAAAAO2FpbEQBw3JvdwAAQnV0OgA8AD0APgBtHgAmACcAKAAqigIAkQAAAA1vcjHAAAAJAA4ADwAhwgAAKwAuAAAAgQCgAAAADAABIgAAAPIAggAAARoAAAAAgAEBoAAAAAHBAAAAH3l2ZgwAwAQk/QCQGokAAAACwAAAABfAAAAAGeAAAAAfAAEMAAAAEMEpAAAAAACA8AAAAAAAAQAAAAAAAP5zvAAJRksx9MBqIrQn3gm7lOVIF9Ax1Co5U53VYR4vRp6OeSKptTIlmp9jlX3Pr2qIWUb0J/gBhuWgVVKHXYQyOMEKpud5vhmjxFtTF5TtGcnwdhMePoQDnlY38s7g4AAAAADgAAAACMAAAAAIJYmQYY0o3Z2pcMMAAAAiwQAAAB9Rlkws3cAAAAAcKTIzb3Bxdnl6","N":"Co","E":"OjAAAAAAAAA=","H":"bGWDLk0="},"crateNames":{"N":"a","E":"OjAAAAAAAAA=","H":"AW1znB4="},"name":{"N":"Co","E":"OjAAAAAAAAA=","H":"uklikRk="},"path":{"N":"Ah","E":"OzAAAAEAACYADAACAAEABQABAAwAAAAOAAkAGgABAB0AAAAgAAAAIgAAACYABAAuAAMAMwAGADwAAgA=","H":"dgQng44="},"entry":{"N":"Bl","E":"OjAAAAEAAAAAABIAEAAAAAAAAQAEAAcACAAKAAsADQAYABwAHgAhACMAJAArACwALQA6ADsA","H":"pSmzXmY="},"desc":{"N":"Ah","E":"OzAAAAEAACYACwAAAAEABAAAAAcAAQAKAAEADQAAABgAAAAcAAIAIAAEACYAAwArAAYANAAKAA==","H":"U/jXIvQ="},"function":{"N":"Bd","E":"OzAAAAEAABoACgAAAAEABAAAAAcABAANAAAAGAABABwAAAAeAAcAKgADADIAAAA6AAEA","H":"qh6yjwo="},"type":{"N":"n","E":"OzAAAAEAADAACgABAAIABQABAAoAAgAOAAkAGQACAB0AAQAgAAAAIgAAACYAFAA8AAIA","H":"rSay3dU="},"alias":{"N":"`","E":"OzAAAAEAAD4AAQAAAD4A","H":"YhUMUyw="},"generic_inverted_index":{"N":"b","E":"OjAAAAAAAAA=","H":"HpZiK2c="}}')
\ No newline at end of file
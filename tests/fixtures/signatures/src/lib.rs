@@ -0,0 +1,55 @@
+// Declarations whose JSON is awkward to render: higher-ranked bounds,
+// `impl Trait` in argument and return position, const generics, qualifiers
+// and generic associated types.
+use std::fmt::Debug;
+
+pub fn for_each_line<F>(text: &str, f: F)
+where
+    F: for<'a> Fn(&'a str) -> &'a str,
+{
+    let _ = (text, f);
+}
+
+pub fn apply(f: &dyn for<'a> Fn(&'a [u8]) -> &'a [u8]) {
+    let _ = f;
+}
+
+pub fn show(value: impl Debug + Send) {
+    let _ = value;
+}
+
+pub fn evens(limit: u32) -> impl Iterator<Item = u32> + Clone {
+    (0..limit).filter(|n| n % 2 == 0)
+}
+
+pub fn first<const N: usize>(values: [u8; N]) -> Option<u8> {
+    values.first().copied()
+}
+
+pub struct Buffer<T, const N: usize = 16> {
+    pub items: [T; N],
+}
+
+impl<T: Copy + Default, const N: usize> Buffer<T, N> {
+    pub const fn len(&self) -> usize {
+        N
+    }
+}
+
+pub async fn fetch(url: &str) -> Result<Vec<u8>, String> {
+    Err(url.to_string())
+}
+
+/// # Safety
+/// Never.
+pub unsafe extern "C" fn raw(ptr: *const u8) -> u8 {
+    unsafe { *ptr }
+}
+
+pub trait Lender {
+    type Loan<'a>
+    where
+        Self: 'a;
+    fn lend(&self) -> Self::Loan<'_>;
+    fn borrowed<'a>(&'a self) -> Self::Loan<'a>;
+}
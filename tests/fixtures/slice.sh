@@ -0,0 +1,43 @@
+#!/bin/sh
+# Cut a fixture out of a big crate's rustdoc JSON: the functions, statics
+# and types named, with the modules and glob imports that lead to them from
+# the crate root and nothing else. Fields stay with their type, but impls
+# are dropped. `paths` and `external_crates` are trimmed as in
+# `regenerate.sh`.
+#
+#     tests/fixtures/slice.sh <crate.json> <name...> > <fixture>.json
+#
+# `libc.json` is libc 0.2.190 documented for x86_64-unknown-linux-gnu by
+# `cargo +nightly rustdoc -- -Z unstable-options --output-format json`,
+# sliced to `strlen printf qsort malloc environ in6addr_any tpacket_req_u`.
+set -e
+json=$1
+shift
+names=$(printf '%s\n' "$@" | jq -R . | jq -s -c .)
+jq -c --argjson names "$names" '
+    . as $doc
+    # Where each item is reached from: its module, or the glob import that
+    # brings in the module holding it
+    | ([.index[] | select(.inner.module) | .id as $m | .inner.module.items[] | {key: tostring, value: $m}]
+        + [.index[] | select(.inner.use.is_glob and .inner.use.id != null)
+            | {key: (.inner.use.id | tostring), value: .id}]
+        | from_entries) as $parent
+    | def up: ., ($parent[tostring] // empty | up);
+    ([.index[] | select(.name as $n | $names | index($n))
+        | select(.inner | has("function") or has("static") or has("union") or has("struct"))
+        | .id] | unique) as $wanted
+    | ([$wanted[] | up] + [$wanted[] | tostring | $doc.index[.].inner | (.union // .struct // {}) | .fields // [] | .[]]
+        | unique | map(tostring)) as $keep
+    | .index |= with_entries(select(.key as $k | $keep | index($k)))
+    | .index |= map_values(
+        if .inner.module then .inner.module.items |= map(select(tostring as $k | $keep | index($k)))
+        elif .inner.union then .inner.union.impls = []
+        elif .inner.struct then .inner.struct.impls = []
+        else . end)
+    | ([.index | .. | objects | .id? | numbers] + [.index | keys[] | tonumber] | unique) as $ids
+    | .paths |= with_entries(select((.key | tonumber) as $id | $ids | index($id)))
+    | ([.paths[].crate_id] + [.index[].crate_id] | unique | map(tostring)) as $crates
+    | .external_crates |= (with_entries(select(.key as $k | $crates | index($k)))
+        | map_values(del(.path)))
+    | .target.target_features = []
+' "$json"
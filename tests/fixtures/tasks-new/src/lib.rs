@@ -0,0 +1,71 @@
+//! Futures in every position a signature can hold one: `async fn`, `impl
+//! Future` arguments and returns, boxed trait objects, higher-ranked closure
+//! bounds and return-position `impl Trait` in traits.
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A boxed future that can be sent between threads.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Waits for `duration`.
+pub async fn sleep(duration: std::time::Duration) {
+    let _ = duration;
+}
+
+/// A future that's ready with `value` right away.
+pub fn ready<T>(value: T) -> impl Future<Output = T> {
+    async move { value }
+}
+
+/// Waits for `future` for at most `ms` milliseconds.
+pub fn timeout<F: Future>(ms: u64, future: F) -> impl Future<Output = Option<F::Output>> {
+    let _ = ms;
+    async move { Some(future.await) }
+}
+
+/// Boxes a future.
+pub fn boxed<'a, T: Send + 'a>(future: impl Future<Output = T> + Send + 'a) -> BoxFuture<'a, T> {
+    Box::pin(future)
+}
+
+/// Runs `future` in the background.
+pub fn spawn<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    drop(future);
+    JoinHandle(None)
+}
+
+/// Calls `f` on every item, awaiting each call in turn.
+pub fn for_each<F>(items: &[String], f: F)
+where
+    F: for<'a> Fn(&'a str) -> Pin<Box<dyn Future<Output = ()> + 'a>>,
+{
+    let _ = (items, f);
+}
+
+/// Polls `task` once.
+pub fn poll_once(task: &mut (dyn Future<Output = ()> + Unpin)) -> bool {
+    let _ = task;
+    false
+}
+
+/// A spawned task's result.
+pub struct JoinHandle<T>(Option<T>);
+
+impl<T: Unpin> Future for JoinHandle<T> {
+    type Output = Option<T>;
+
+    fn poll(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Self::Output> {
+        Poll::Ready(self.0.take())
+    }
+}
+
+/// Something answering requests asynchronously.
+pub trait Service {
+    /// Answers one request.
+    fn call(&self, request: String) -> impl Future<Output = String> + Send;
+}
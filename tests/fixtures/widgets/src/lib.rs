@@ -0,0 +1,123 @@
+//! A small, documented crate: the kind of API `zdoc man` and
+//! `zdoc cheatsheet` summarize, with constructors, methods, free
+//! functions, a trait, a macro, a deprecated method and a hidden function.
+
+/// A rectangle that can be drawn on a [`Canvas`].
+///
+/// Widgets are drawn back to front by [`draw`]. Sizes are in pixels, so
+/// `-1` isn't a valid width.
+///
+/// # Sizing
+///
+/// .A line starting with a dot, and a \ backslash, which roff must escape.
+///
+/// # Examples
+///
+/// ```
+/// use widgets::Widget;
+///
+/// let widget = Widget::new(3, 4);
+/// assert_eq!(widget.area(), 12);
+/// ```
+///
+/// ```text
+/// not Rust, so not an example
+/// ```
+pub struct Widget {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Widget {
+    /// Makes a widget of the given size.
+    pub fn new(width: u32, height: u32) -> Self {
+        Widget { width, height }
+    }
+
+    /// Makes a widget with no area.
+    pub fn empty() -> Widget {
+        Widget::new(0, 0)
+    }
+
+    /// The widget's area in pixels.
+    pub fn area(&self) -> u32 {
+        self.width * self.height
+    }
+
+    /// Doubles the widget's size.
+    #[deprecated(note = "use `Widget::new` with the new size")]
+    pub fn grow(&mut self) {
+        self.width *= 2;
+        self.height *= 2;
+    }
+}
+
+/// Where widgets are drawn.
+#[derive(Default)]
+pub struct Canvas {
+    widgets: Vec<Widget>,
+}
+
+impl Canvas {
+    /// An empty canvas.
+    pub fn new() -> Self {
+        Canvas::default()
+    }
+
+    /// Adds a widget on top of the others.
+    pub fn add(&mut self, widget: Widget) {
+        self.widgets.push(widget);
+    }
+
+    /// How many widgets the canvas holds.
+    pub fn len(&self) -> usize {
+        self.widgets.len()
+    }
+
+    /// Whether the canvas holds no widgets.
+    pub fn is_empty(&self) -> bool {
+        self.widgets.is_empty()
+    }
+}
+
+/// Draws every widget on the canvas, back to front.
+pub fn draw(canvas: &Canvas) -> String {
+    canvas
+        .widgets
+        .iter()
+        .map(|widget| format!("[{}x{}]", widget.width, widget.height))
+        .collect()
+}
+
+/// Removes every widget.
+pub fn clear(canvas: &mut Canvas) {
+    canvas.widgets.clear();
+}
+
+/// Something that can be drawn.
+pub trait Draw {
+    /// The drawing.
+    fn draw(&self) -> String;
+
+    /// A name for error messages.
+    fn name(&self) -> &str {
+        "widget"
+    }
+}
+
+/// The outline of a widget.
+pub enum Shape {
+    Square,
+    Circle(u32),
+}
+
+/// Makes a [`Widget`]: `widget!(3, 4)`.
+#[macro_export]
+macro_rules! widget {
+    ($width:expr, $height:expr) => {
+        $crate::Widget::new($width, $height)
+    };
+}
+
+#[doc(hidden)]
+pub fn __internal() {}
@@ -0,0 +1,59 @@
+// `show` and `search --include-hidden` have rustdoc document `#[doc(hidden)]`
+// items, which it otherwise leaves out, on a copy of the `hidden` fixture
+// (so it needs a nightly toolchain).
+mod common;
+
+use std::process::Output;
+
+fn stdout(output: Output) -> String {
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn show_finds_items_in_hidden_modules() {
+    let dir = common::workspace("hidden");
+    let output = common::zdoc(dir.path(), "show")
+        .arg("__private::nested::deep")
+        .output()
+        .unwrap();
+    let shown = stdout(output);
+    assert!(
+        shown.contains("not part of the crate's supported API"),
+        "{}",
+        shown
+    );
+    assert!(
+        shown.contains("hidden::__private::nested::deep"),
+        "{}",
+        shown
+    );
+}
+
+#[test]
+fn search_includes_hidden_items_when_asked() {
+    let dir = common::workspace("hidden");
+    let search = |include_hidden: bool| {
+        let mut search = common::zdoc(dir.path(), "search");
+        search.args(["deep", "--format", "json"]);
+        if include_hidden {
+            search.arg("--include-hidden");
+        }
+        let results: serde_json::Value =
+            serde_json::from_str(&stdout(search.output().unwrap())).unwrap();
+        results["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|result| (result["name"].clone(), result["hidden"].clone()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(search(false), []);
+    assert_eq!(search(true), [("deep".into(), true.into())]);
+    // Docs with hidden items in them don't bring them back by default
+    assert_eq!(search(false), []);
+}
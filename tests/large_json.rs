@@ -0,0 +1,174 @@
+// How much memory parsing a huge crate's rustdoc JSON takes: reading the
+// whole text into a string and parsing that (as zdoc used to), against
+// streaming it from the file at each level of detail. Peak heap use is
+// counted by a wrapping allocator. The crate is synthetic, shaped like
+// `windows-sys`; point `ZDOC_LARGE_JSON` at a real file to measure that.
+//
+//     cargo test --release --test large_json -- --ignored --nocapture
+use serde_json::{Value, json};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+#[allow(dead_code)]
+#[path = "../src/rustdoc_json.rs"]
+mod rustdoc_json;
+
+use rustdoc_json::Detail;
+
+const ITEMS: usize = 100_000;
+
+// The system allocator, keeping track of the most ever in use at once
+struct Counting;
+
+static IN_USE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            let in_use = IN_USE.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(in_use, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        IN_USE.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+// Write a crate of `ITEMS` functions, each with a span, links and a full
+// signature, one item at a time so the fixture itself is never resident
+fn write_synthetic_crate(path: &Path) {
+    let mut out = BufWriter::new(File::create(path).unwrap());
+    write!(
+        out,
+        r#"{{"root":0,"crate_version":"0.1.0","includes_private":false,"index":{{"#
+    )
+    .unwrap();
+    for i in 0..ITEMS {
+        let param = |name: &str| json!([name, { "resolved_path": { "path": "HANDLE", "id": 1, "args": null } }]);
+        let item = json!({
+            "id": i,
+            "crate_id": 0,
+            "name": format!("CreateThing{}", i),
+            "span": {
+                "filename": "src/Windows/Win32/Foundation/mod.rs",
+                "begin": [i, 0],
+                "end": [i + 3, 1],
+            },
+            "visibility": "public",
+            "docs": format!("Creates thing number {}. See [`CloseThing{}`].", i, i),
+            "links": { format!("`CloseThing{}`", i): i + 1 },
+            "attrs": [],
+            "deprecation": null,
+            "inner": { "function": {
+                "sig": {
+                    "inputs": [param("handle"), param("flags"), param("reserved")],
+                    "output": { "primitive": "u32" },
+                    "is_c_variadic": false,
+                },
+                "generics": { "params": [], "where_predicates": [] },
+                "header": { "is_const": false, "is_unsafe": true, "is_async": false, "abi": "Rust" },
+                "has_body": true,
+            }},
+        });
+        if i > 0 {
+            out.write_all(b",").unwrap();
+        }
+        write!(out, r#""{}":"#, i).unwrap();
+        serde_json::to_writer(&mut out, &item).unwrap();
+    }
+    write!(
+        out,
+        r#"}},"paths":{{}},"external_crates":{{}},"format_version":57}}"#
+    )
+    .unwrap();
+    out.flush().unwrap();
+}
+
+// The peak heap use while parsing, beyond what was in use before, and how
+// long parsing took. The tree is dropped outside the measurement.
+fn measure(parse: impl FnOnce() -> Value) -> (usize, Duration) {
+    let before = IN_USE.load(Ordering::Relaxed);
+    PEAK.store(before, Ordering::Relaxed);
+    let started = Instant::now();
+    let json = parse();
+    let elapsed = started.elapsed();
+    let peak = PEAK.load(Ordering::Relaxed) - before;
+    assert!(
+        json["index"]
+            .as_object()
+            .is_some_and(|index| !index.is_empty())
+    );
+    drop(json);
+    (peak, elapsed)
+}
+
+fn mib(bytes: usize) -> f64 {
+    bytes as f64 / (1 << 20) as f64
+}
+
+#[test]
+#[ignore = "writes 70 MB of JSON and needs over 1 GB to parse it; run with --ignored"]
+fn streaming_keeps_the_text_out_of_memory() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = match std::env::var_os("ZDOC_LARGE_JSON") {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let path = dir.path().join("windows.json");
+            write_synthetic_crate(&path);
+            path
+        }
+    };
+    let size = std::fs::metadata(&path).unwrap().len() as usize;
+
+    let whole = measure(|| {
+        let text = std::fs::read_to_string(&path).unwrap();
+        serde_json::from_str(&text).unwrap()
+    });
+    let streamed = |detail| {
+        measure(|| {
+            let file = File::open(&path).unwrap();
+            rustdoc_json::read_hashed_as(file, detail).unwrap().0
+        })
+    };
+    let full = streamed(Detail::Full);
+    let api = streamed(Detail::Api);
+    let search = streamed(Detail::Search);
+
+    println!("{} ({:.1} MiB of JSON)", path.display(), mib(size));
+    for (name, (peak, elapsed)) in [
+        ("read whole text", whole),
+        ("streamed, full", full),
+        ("streamed, api", api),
+        ("streamed, search", search),
+    ] {
+        println!(
+            "  {:<18} peak {:>8.1} MiB in {:>8.2?}",
+            name,
+            mib(peak),
+            elapsed
+        );
+    }
+
+    // The text is never resident alongside the tree
+    assert!(
+        full.0 + size <= whole.0 + whole.0 / 20,
+        "streaming saved {:.1} MiB of {:.1} MiB of text",
+        mib(whole.0.saturating_sub(full.0)),
+        mib(size)
+    );
+    // Each lower detail keeps less
+    assert!(api.0 < full.0);
+    assert!(search.0 < api.0);
+}
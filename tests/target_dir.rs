@@ -0,0 +1,61 @@
+// Docs are read from wherever cargo writes them: a target directory moved by
+// `CARGO_TARGET_DIR`, by `build.target-dir` in `.cargo/config.toml` or by
+// `--target-dir` is followed, for a copy of the `widgets` fixture in a
+// temporary workspace.
+mod common;
+
+use std::path::Path;
+use std::process::Command;
+
+fn zdoc_list(dir: &Path) -> Command {
+    common::zdoc(dir, "list")
+}
+
+fn assert_listed(command: &mut Command) {
+    let output = command.output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Public API of widgets"), "{}", stdout);
+}
+
+#[test]
+fn follows_cargo_target_dir() {
+    let dir = common::workspace("widgets");
+    let target = dir.path().join("custom");
+    assert_listed(zdoc_list(dir.path()).env("CARGO_TARGET_DIR", &target));
+    assert!(target.join("doc/widgets.json").is_file());
+    assert!(!dir.path().join("target").exists());
+}
+
+#[test]
+fn follows_build_target_dir_in_cargo_config() {
+    let dir = common::workspace("widgets");
+    std::fs::create_dir(dir.path().join(".cargo")).unwrap();
+    std::fs::write(
+        dir.path().join(".cargo/config.toml"),
+        "[build]\ntarget-dir = \"configured\"\n",
+    )
+    .unwrap();
+    assert_listed(&mut zdoc_list(dir.path()));
+    assert!(dir.path().join("configured/doc/widgets.json").is_file());
+    assert!(!dir.path().join("target").exists());
+}
+
+#[test]
+fn target_dir_flag_wins_over_the_environment() {
+    let dir = common::workspace("widgets");
+    let flag = dir.path().join("flag");
+    let env = dir.path().join("env");
+    assert_listed(
+        zdoc_list(dir.path())
+            .arg("--target-dir")
+            .arg(&flag)
+            .env("CARGO_TARGET_DIR", &env),
+    );
+    assert!(flag.join("doc/widgets.json").is_file());
+    assert!(!env.exists());
+}